@@ -0,0 +1,58 @@
+//! This example shows how to call rust functions from javascript running in the webview.
+//!
+//! Functions marked with `#[command]` can be registered with the desktop config or the `use_command` hook. Javascript
+//! then calls them through `window.dioxus.commands`, and gets a promise that resolves with the return value.
+
+use dioxus::desktop::{commands::command, use_command, Config};
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus::LaunchBuilder::desktop()
+        .with_cfg(
+            Config::new()
+                .with_command::<add>()
+                // Write typescript declarations for the commands so editors can check the javascript that calls them
+                .with_command_declarations("target/commands.d.ts"),
+        )
+        .launch(app);
+}
+
+#[command]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[command]
+async fn read_file(path: String) -> Result<String, std::io::Error> {
+    std::fs::read_to_string(path)
+}
+
+fn app() -> Element {
+    // This command is only available while the component is mounted
+    use_command::<read_file>();
+
+    let mut output = use_signal(String::new);
+
+    rsx! {
+        button {
+            onclick: move |_| async move {
+                let result = document::eval(
+                    r#"
+                    const sum = await window.dioxus.commands.add(1, 2);
+                    try {
+                        const manifest = await window.dioxus.commands.read_file("Cargo.toml");
+                        return `1 + 2 = ${sum}, Cargo.toml is ${manifest.length} bytes long`;
+                    } catch (error) {
+                        return `1 + 2 = ${sum}, failed to read Cargo.toml: ${error.message}`;
+                    }
+                    "#,
+                )
+                .join::<String>()
+                .await;
+                output.set(result.unwrap_or_else(|err| err.to_string()));
+            },
+            "Call commands from javascript"
+        }
+        p { "{output}" }
+    }
+}
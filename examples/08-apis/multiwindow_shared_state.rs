@@ -0,0 +1,64 @@
+//! Share state between windows
+//!
+//! Every window runs its own virtualdom, so context and signals from one window are not visible in the others. This
+//! example shares a signal through the app context and sends messages between the windows with a typed channel.
+
+use dioxus::desktop::{use_shared_signal, use_window_channel, window};
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus::launch(app);
+}
+
+/// A message the palette windows send to the main window
+#[derive(Clone)]
+struct Picked(String);
+
+fn app() -> Element {
+    // Writing to the signal in any window rerenders every window that reads it
+    let color = use_shared_signal(|| "red".to_string());
+    let channel = use_window_channel::<Picked>();
+    let mut history = use_signal(Vec::new);
+
+    use_future(move || {
+        let channel = channel.clone();
+        async move {
+            while let Some((from, Picked(color))) = channel.recv().await {
+                history.push(format!("{from:?} picked {color}"));
+            }
+        }
+    });
+
+    rsx! {
+        button {
+            onclick: move |_| {
+                window().new_window(VirtualDom::new(palette), Default::default());
+            },
+            "New palette"
+        }
+        h1 { color: "{color}", "The selected color is {color}" }
+        for entry in history.iter() {
+            p { "{entry}" }
+        }
+    }
+}
+
+fn palette() -> Element {
+    let mut color = use_shared_signal(|| "red".to_string());
+    let channel = use_window_channel::<Picked>();
+
+    rsx! {
+        for option in ["red", "green", "blue"] {
+            button {
+                onclick: {
+                    let channel = channel.clone();
+                    move |_| {
+                        color.set(option.to_string());
+                        channel.broadcast(Picked(option.to_string()));
+                    }
+                },
+                "{option}"
+            }
+        }
+    }
+}
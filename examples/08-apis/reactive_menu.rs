@@ -0,0 +1,48 @@
+//! This example shows how to declare the menu bar and tray menu in rsx with Dioxus desktop.
+//! Menu items update automatically when the signals they read change.
+//! This example is not supported on the mobile or web renderers.
+
+use dioxus::desktop::menu::*;
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus::launch(app);
+}
+
+fn app() -> Element {
+    let mut count = use_signal(|| 0);
+    let mut dark_mode = use_signal(|| false);
+
+    rsx! {
+        WindowMenu {
+            Submenu { text: "Counter",
+                MenuItem {
+                    text: "Increment",
+                    accelerator: "CmdOrCtrl+Up",
+                    onclick: move |_| count += 1,
+                }
+                MenuItem {
+                    text: "Reset",
+                    // The item is disabled while the count is zero
+                    enabled: count() != 0,
+                    onclick: move |_| count.set(0),
+                }
+                MenuSeparator {}
+                CheckMenuItem {
+                    text: "Dark mode",
+                    checked: dark_mode,
+                    onclick: move |checked| dark_mode.set(checked),
+                }
+            }
+        }
+        TrayMenu {
+            MenuItem { text: "Count: {count}", enabled: false }
+            MenuItem { text: "Increment", onclick: move |_| count += 1 }
+        }
+        div {
+            background_color: if dark_mode() { "black" } else { "white" },
+            color: if dark_mode() { "white" } else { "black" },
+            h1 { "Count: {count}" }
+        }
+    }
+}
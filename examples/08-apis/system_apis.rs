@@ -0,0 +1,81 @@
+//! This example shows how to open and save files with native dialogs, copy to the clipboard and show notifications.
+//!
+//! These APIs are async methods on the desktop context. They go through backends that can be replaced in the config,
+//! so tests can pick files and answer notifications without a user.
+
+use dioxus::desktop::{dialog::FileDialog, notification::Notification, window};
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus::LaunchBuilder::desktop().launch(app);
+}
+
+fn app() -> Element {
+    let mut text = use_signal(String::new);
+    let mut status = use_signal(String::new);
+
+    rsx! {
+        textarea {
+            rows: 10,
+            cols: 60,
+            value: "{text}",
+            oninput: move |event| text.set(event.value()),
+        }
+        div {
+            button {
+                onclick: move |_| async move {
+                    let dialog = FileDialog::new()
+                        .with_title("Open a text file")
+                        .with_filter("Text", &["txt", "md", "rs"]);
+                    let Some(path) = window().open_file_dialog(dialog).await.pop() else {
+                        return;
+                    };
+                    match std::fs::read_to_string(&path) {
+                        Ok(contents) => text.set(contents),
+                        Err(err) => status.set(format!("Failed to open {}: {err}", path.display())),
+                    }
+                },
+                "Open"
+            }
+            button {
+                onclick: move |_| async move {
+                    let dialog = FileDialog::new().with_file_name("notes.txt");
+                    let Some(path) = window().save_file_dialog(dialog).await else {
+                        return;
+                    };
+                    if let Err(err) = std::fs::write(&path, text()) {
+                        status.set(format!("Failed to save {}: {err}", path.display()));
+                        return;
+                    }
+
+                    // The action callback runs when the user clicks the button on the notification
+                    let notification = Notification::new("Saved")
+                        .with_body(format!("Saved the text to {}", path.display()))
+                        .with_action("show", "Show the app", || window().set_focus());
+                    _ = window().show_notification(notification).await;
+                },
+                "Save"
+            }
+            button {
+                onclick: move |_| async move {
+                    match window().write_clipboard(text()).await {
+                        Ok(()) => status.set("Copied the text".to_string()),
+                        Err(err) => status.set(err.to_string()),
+                    }
+                },
+                "Copy"
+            }
+            button {
+                onclick: move |_| async move {
+                    match window().read_clipboard_text().await {
+                        Ok(Some(clipboard)) => text.set(clipboard),
+                        Ok(None) => status.set("The clipboard has no text".to_string()),
+                        Err(err) => status.set(err.to_string()),
+                    }
+                },
+                "Paste"
+            }
+        }
+        p { "{status}" }
+    }
+}
@@ -1,11 +1,16 @@
 //! Native specific utilities for resolving assets in a bundle. This module is intended for use in renderers that
 //! need to resolve asset bundles for resources like images, and fonts.
 
-use http::{status::StatusCode, Response};
-use std::path::{Path, PathBuf};
+use http::{status::StatusCode, Request, Response};
+use std::{
+    io::{Read, Seek},
+    path::{Path, PathBuf},
+};
 
 use crate::{AssetPathError, NativeAssetResolveError};
 
+mod range;
+
 /// An error that can occur when serving an asset.
 #[non_exhaustive]
 #[derive(Debug, thiserror::Error)]
@@ -17,6 +22,10 @@ pub enum AssetServeError {
     /// An error occurred while constructing the HTTP response.
     #[error("Failed to construct response: {0}")]
     ResponseError(#[from] http::Error),
+
+    /// An error occurred while reading the asset.
+    #[error("Failed to read asset: {0}")]
+    IoError(#[from] std::io::Error),
 }
 
 /// Try to resolve the path of an asset from a given URI path.
@@ -138,6 +147,63 @@ pub fn serve_asset(path: &str) -> Result<Response<Vec<u8>>, AssetServeError> {
     }
 }
 
+/// Serve an asset like [`serve_asset`], but respond to the caching and range headers of the request.
+///
+/// - Responses include `ETag` and `Last-Modified` headers. If the request has an `If-None-Match` or
+///   `If-Modified-Since` header that matches the asset, the response is an empty `304 Not Modified`.
+/// - If the request has a `Range` header, only the requested bytes are read from the asset. This lets video and audio
+///   elements seek through large files without loading them into memory. Open ended ranges like `bytes=0-` are split
+///   into chunks of a few megabytes and the webview requests the rest of the asset as it plays.
+/// - Requests without a `Range` header get the whole asset with a `200 OK`. Video and audio elements always send a
+///   `Range` header, so they only ever load a few megabytes of a large file at a time.
+pub fn serve_asset_request<T>(request: &Request<T>) -> Result<Response<Vec<u8>>, AssetServeError> {
+    let path = request.uri().path();
+
+    // Attempt to serve from the asset dir on android using its loader
+    #[cfg(target_os = "android")]
+    {
+        if let Some(asset) = to_java_load_asset(path) {
+            let extension = path.rsplit_once('.').map(|(_, ext)| ext);
+            let mime_type = get_mime_from_ext(extension);
+            return serve_reader(request, std::io::Cursor::new(asset), mime_type);
+        }
+    }
+
+    match resolve_asset_path_from_filesystem(path) {
+        Some(path) => serve_file(request, path),
+        None => Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(String::from("Not Found").into_bytes())?),
+    }
+}
+
+/// Serve a file from the filesystem in response to a request. This is useful in custom asset handlers.
+///
+/// Like [`serve_asset_request`], this responds to the caching and range headers of the request and only reads the part
+/// of the file the request asked for.
+pub fn serve_file<T>(
+    request: &Request<T>,
+    path: impl AsRef<Path>,
+) -> Result<Response<Vec<u8>>, AssetServeError> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path)?;
+    let validators = range::Validators::from_metadata(&file.metadata()?);
+    let mime_type = get_mime_from_path(path)?;
+    range::respond(request, file, mime_type, &validators)
+}
+
+/// Serve the contents of a reader in response to a request. This is useful in custom asset handlers that don't read
+/// their data from a file.
+///
+/// If the request has a `Range` header, this seeks to the start of the range and only reads the requested bytes.
+pub fn serve_reader<T>(
+    request: &Request<T>,
+    reader: impl Read + Seek,
+    mime_type: &str,
+) -> Result<Response<Vec<u8>>, AssetServeError> {
+    range::respond(request, reader, mime_type, &Default::default())
+}
+
 /// Get the asset directory, following tauri/cargo-bundles directory discovery approach
 ///
 /// Currently supports:
@@ -0,0 +1,184 @@
+//! Respond to the caching and range headers of requests for assets
+
+use http::{
+    header::*, response::Builder as ResponseBuilder, HeaderMap, Request, Response, StatusCode,
+};
+use http_range::{HttpRange, HttpRangeParseError};
+use std::{
+    io::{Read, Seek, SeekFrom},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::AssetServeError;
+
+/// The most bytes we send in a single range response.
+///
+/// Media elements request open ended ranges like `bytes=0-`. Without a limit we would read the whole file into memory
+/// before the first frame can play. The webview requests the rest of the file as it needs it.
+pub(crate) const MAX_RANGE_LEN: u64 = 4 * 1024 * 1024;
+
+/// The validators the webview can use to check if its cached copy of an asset is still fresh
+#[derive(Debug, Default)]
+pub(crate) struct Validators {
+    etag: Option<String>,
+    last_modified: Option<SystemTime>,
+}
+
+impl Validators {
+    pub(crate) fn from_metadata(metadata: &std::fs::Metadata) -> Self {
+        let last_modified = metadata.modified().ok();
+        let modified_nanos = last_modified
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |time| time.as_nanos());
+
+        Self {
+            etag: Some(format!("\"{:x}-{modified_nanos:x}\"", metadata.len())),
+            last_modified,
+        }
+    }
+
+    fn add_headers(&self, mut response: ResponseBuilder) -> ResponseBuilder {
+        if let Some(etag) = &self.etag {
+            response = response.header(ETAG, etag);
+        }
+        if let Some(last_modified) = self.last_modified {
+            response = response.header(LAST_MODIFIED, httpdate::fmt_http_date(last_modified));
+        }
+        response
+    }
+
+    /// Check if the copy of the asset the webview has cached is still fresh
+    fn not_modified(&self, headers: &HeaderMap) -> bool {
+        // If-None-Match takes precedence over If-Modified-Since
+        if let Some(if_none_match) = header_str(headers, &IF_NONE_MATCH) {
+            return self.etag.as_deref().is_some_and(|etag| {
+                if_none_match
+                    .split(',')
+                    .map(str::trim)
+                    .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+            });
+        }
+
+        let since = header_str(headers, &IF_MODIFIED_SINCE)
+            .and_then(|date| httpdate::parse_http_date(date).ok());
+        match (since, self.last_modified) {
+            (Some(since), Some(modified)) => truncate_to_secs(modified) <= since,
+            _ => false,
+        }
+    }
+
+    /// Check if a range request still applies to the current version of the asset
+    fn range_applies(&self, headers: &HeaderMap) -> bool {
+        let Some(if_range) = header_str(headers, &IF_RANGE) else {
+            return true;
+        };
+
+        // Weak etags never match an If-Range header
+        if if_range.starts_with('"') || if_range.starts_with("W/") {
+            return self.etag.as_deref() == Some(if_range);
+        }
+
+        match (httpdate::parse_http_date(if_range), self.last_modified) {
+            (Ok(date), Some(modified)) => truncate_to_secs(modified) == date,
+            _ => false,
+        }
+    }
+}
+
+/// Respond to a request with the contents of the reader, or the part of it the request asked for.
+pub(crate) fn respond<T>(
+    request: &Request<T>,
+    mut reader: impl Read + Seek,
+    mime_type: &str,
+    validators: &Validators,
+) -> Result<Response<Vec<u8>>, AssetServeError> {
+    let headers = request.headers();
+    let response = Response::builder()
+        .header(CONTENT_TYPE, mime_type)
+        .header(ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header(ACCEPT_RANGES, "bytes");
+    let response = validators.add_headers(response);
+
+    if validators.not_modified(headers) {
+        return Ok(response.status(StatusCode::NOT_MODIFIED).body(Vec::new())?);
+    }
+
+    let len = reader.seek(SeekFrom::End(0))?;
+    let range = match header_str(headers, &RANGE) {
+        Some(range) if validators.range_applies(headers) => HttpRange::parse(range, len),
+        _ => Ok(Vec::new()),
+    };
+
+    match range {
+        // We only send the first range if the request asks for several. The client requests the rest of the asset
+        // after it reads the Content-Range header
+        Ok(ranges) if !ranges.is_empty() => {
+            read_range(response, reader, ranges[0].start, ranges[0].length, len)
+        }
+
+        Err(HttpRangeParseError::NoOverlap) => Ok(response
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(CONTENT_RANGE, format!("bytes */{len}"))
+            .body(Vec::new())?),
+
+        // Requests without a range and invalid range headers get the whole asset. Media elements always send a range,
+        // so large videos are still read in chunks
+        _ => {
+            let body = read_body(reader, 0, len)?;
+            Ok(response.header(CONTENT_LENGTH, body.len()).body(body)?)
+        }
+    }
+}
+
+/// Respond with at most [`MAX_RANGE_LEN`] bytes of the range
+fn read_range(
+    response: ResponseBuilder,
+    reader: impl Read + Seek,
+    start: u64,
+    length: u64,
+    len: u64,
+) -> Result<Response<Vec<u8>>, AssetServeError> {
+    let read_len = length.min(MAX_RANGE_LEN);
+    let end = (start + read_len).saturating_sub(1);
+    let body = read_body(reader, start, read_len)?;
+
+    Ok(response
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+        .header(CONTENT_LENGTH, body.len())
+        .body(body)?)
+}
+
+/// Read `len` bytes starting at `start` straight from the reader into the body.
+///
+/// The webview needs the whole body of a response at once, so this reads into a buffer of the final size in chunks of at
+/// most [`MAX_RANGE_LEN`] bytes instead of growing a buffer. The reader is bounded to the length, so a file that grows
+/// while it is read never makes the body longer than the Content-Length we computed.
+fn read_body(mut reader: impl Read + Seek, start: u64, len: u64) -> std::io::Result<Vec<u8>> {
+    reader.seek(SeekFrom::Start(start))?;
+    let mut reader = reader.take(len);
+    let mut body = vec![0; len as usize];
+    let mut read = 0;
+    while read < body.len() {
+        let chunk_end = body.len().min(read + MAX_RANGE_LEN as usize);
+        match reader.read(&mut body[read..chunk_end])? {
+            // The file shrank while we read it
+            0 => break,
+            n => read += n,
+        }
+    }
+    body.truncate(read);
+    Ok(body)
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &HeaderName) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Http dates only have second precision, so we need to drop the rest before comparing them to file times
+fn truncate_to_secs(time: SystemTime) -> SystemTime {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => UNIX_EPOCH + Duration::from_secs(duration.as_secs()),
+        Err(_) => time,
+    }
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "native")]
+//! Make sure assets respond to range and caching headers
+
+use dioxus_asset_resolver::native::{serve_file, serve_reader};
+use http::{header::*, Request, StatusCode};
+use std::io::Cursor;
+
+fn request(headers: &[(HeaderName, &str)]) -> Request<()> {
+    let mut request = Request::builder().uri("/assets/video.mp4");
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+    request.body(()).unwrap()
+}
+
+#[test]
+fn range_requests_only_read_the_range() {
+    let data: Vec<u8> = (0..100).collect();
+
+    let response = serve_reader(&request(&[]), Cursor::new(&data), "video/mp4").unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[ACCEPT_RANGES], "bytes");
+    assert_eq!(response.body(), &data);
+
+    let response = serve_reader(
+        &request(&[(RANGE, "bytes=10-19")]),
+        Cursor::new(&data),
+        "video/mp4",
+    )
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes 10-19/100");
+    assert_eq!(response.body(), &data[10..20]);
+
+    let response = serve_reader(
+        &request(&[(RANGE, "bytes=-5")]),
+        Cursor::new(&data),
+        "video/mp4",
+    )
+    .unwrap();
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes 95-99/100");
+    assert_eq!(response.body(), &data[95..]);
+
+    let response = serve_reader(
+        &request(&[(RANGE, "bytes=200-")]),
+        Cursor::new(&data),
+        "video/mp4",
+    )
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()[CONTENT_RANGE], "bytes */100");
+}
+
+#[test]
+fn open_ended_ranges_are_chunked() {
+    let data = vec![0u8; 10 * 1024 * 1024];
+    let response = serve_reader(
+        &request(&[(RANGE, "bytes=0-")]),
+        Cursor::new(&data),
+        "video/mp4",
+    )
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert!(response.body().len() < data.len());
+    assert_eq!(
+        response.headers()[CONTENT_RANGE],
+        format!("bytes 0-{}/{}", response.body().len() - 1, data.len())
+    );
+}
+
+#[test]
+fn large_assets_without_a_range_are_sent_whole() {
+    let data: Vec<u8> = (0..10 * 1024 * 1024).map(|i| i as u8).collect();
+
+    // A partial response is only valid as the answer to a range request, even for media
+    for mime_type in ["video/mp4", "application/json"] {
+        let response = serve_reader(&request(&[]), Cursor::new(&data), mime_type).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(CONTENT_RANGE));
+        assert_eq!(response.headers()[CONTENT_LENGTH], data.len().to_string());
+        assert_eq!(response.body(), &data);
+    }
+}
+
+#[test]
+fn cached_files_are_not_modified() {
+    let path = std::env::temp_dir().join(format!("dioxus-serve-test-{}.txt", std::process::id()));
+    std::fs::write(&path, "hello world").unwrap();
+
+    let response = serve_file(&request(&[]), &path).unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[ETAG].to_str().unwrap().to_string();
+    let last_modified = response.headers()[LAST_MODIFIED]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let response = serve_file(&request(&[(IF_NONE_MATCH, &etag)]), &path).unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert!(response.body().is_empty());
+
+    let response = serve_file(&request(&[(IF_MODIFIED_SINCE, &last_modified)]), &path).unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    // A range for an old version of the file is ignored
+    let response = serve_file(
+        &request(&[(RANGE, "bytes=0-4"), (IF_RANGE, "\"outdated\"")]),
+        &path,
+    )
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), b"hello world");
+
+    let response = serve_file(&request(&[(RANGE, "bytes=0-4"), (IF_RANGE, &etag)]), &path).unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.body(), b"hello");
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -65,6 +65,8 @@ pub const ALWAYS_ON_TOP_ENV: &str = "DIOXUS_ALWAYS_ON_TOP";
 pub const ASSET_ROOT_ENV: &str = "DIOXUS_ASSET_ROOT";
 pub const APP_TITLE_ENV: &str = "DIOXUS_APP_TITLE";
 pub const PRODUCT_NAME_ENV: &str = "DIOXUS_PRODUCT_NAME";
+pub const APP_VERSION_ENV: &str = "DIOXUS_APP_VERSION";
+pub const DEVTOOLS_PATH_ENV: &str = "DIOXUS_DEVTOOLS_PATH";
 
 #[deprecated(since = "0.6.0", note = "The CLI currently does not set this.")]
 #[doc(hidden)]
@@ -271,6 +273,20 @@ pub fn web_base_path() -> Option<String> {
     }
 }
 
+/// Get the path the devtools of the devserver are served under in the browser, if it isn't `/_dioxus`.
+///
+/// `dx serve` sets this when multiple apps share one port, so each app has to reach its devserver under its route.
+#[cfg(feature = "web")]
+pub fn web_devtools_path() -> Option<String> {
+    get_meta_contents(DEVTOOLS_PATH_ENV)
+}
+
+/// Format a meta element for the path of the devtools to be used in the output HTML
+#[doc(hidden)]
+pub fn format_devtools_path_meta_element(devtools_path: &str) -> String {
+    format!(r#"<meta name="{DEVTOOLS_PATH_ENV}" content="{devtools_path}">"#,)
+}
+
 /// Format a meta element for the base path to be used in the output HTML
 #[doc(hidden)]
 pub fn format_base_path_meta_element(base_path: &str) -> String {
@@ -334,3 +350,8 @@ pub fn build_id() -> u64 {
 pub fn product_name() -> Option<String> {
     read_env_config!("DIOXUS_PRODUCT_NAME")
 }
+
+/// The version of the bundled application, from the `version` field of its `Cargo.toml`.
+pub fn app_version() -> Option<String> {
+    read_env_config!("DIOXUS_APP_VERSION")
+}
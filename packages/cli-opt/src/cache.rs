@@ -0,0 +1,340 @@
+//! A content-addressed cache of optimized assets that is shared between builds.
+//!
+//! Optimizing assets (transcoding images, minifying css, bundling js) is by far the slowest part of copying assets
+//! into the bundle. The output of an optimization only depends on the contents of the source, the options it was
+//! optimized with and the version of the optimizer, so we key each optimized file by a hash of those and reuse it
+//! across profiles, packages and clean builds.
+//!
+//! Every entry ends with the sha256 of its contents so entries that were truncated or corrupted on disk are detected
+//! and optimized again instead of being copied into the bundle.
+
+use std::{
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{bail, Context};
+use manganis::AssetOptions;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    file::{process_file_to, resolve_asset_options, ResolvedAssetType},
+    hash::hash_file_with_options,
+};
+
+/// An on-disk cache of optimized assets, bounded to a maximum size
+#[derive(Debug, Clone)]
+pub struct AssetCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+/// Entries that are being written are prefixed with this until they are complete
+const PARTIAL_PREFIX: &str = "partial.";
+
+/// Partial entries older than this were left behind by a build that was interrupted, so eviction removes them
+const ABANDONED_AFTER: Duration = Duration::from_secs(60 * 60);
+
+impl AssetCache {
+    /// Create a cache in the given directory that evicts the least recently used entries once it grows past
+    /// `max_size` bytes
+    pub fn new(dir: impl Into<PathBuf>, max_size: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_size,
+        }
+    }
+
+    /// The directory the cache entries are stored in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Process a file like [`process_file_to`], reusing the optimized output of a previous build if the source and
+    /// options haven't changed since. Returns whether the file was already optimized.
+    pub fn process_file_to(
+        &self,
+        options: &AssetOptions,
+        source: &Path,
+        output_path: &Path,
+    ) -> anyhow::Result<bool> {
+        // Hashed assets that already exist in the output directory are already up to date
+        if output_path.exists() && options.hash_suffix() {
+            return Ok(true);
+        }
+
+        // Folders are copied file by file and are cheap to process, so they aren't worth caching
+        if let ResolvedAssetType::Folder(_) = resolve_asset_options(source, options.variant()) {
+            process_file_to(options, source, output_path)?;
+            return Ok(false);
+        }
+
+        let entry = self.dir.join(cache_key(options, source, output_path)?);
+        if entry.is_file() {
+            // Another build may evict the entry while we read it, so any failure falls back to processing the file
+            match self.copy_entry(&entry, output_path) {
+                Ok(()) => return Ok(true),
+                Err(err) => {
+                    tracing::debug!(
+                        "Failed to use cached asset {entry:?}, processing it again: {err}"
+                    );
+                    _ = std::fs::remove_file(&entry);
+                }
+            }
+        }
+
+        process_file_to(options, source, output_path)?;
+
+        // A failure to fill the cache shouldn't fail the build, the asset will just be optimized again next time
+        if let Err(err) = self.insert(&entry, output_path) {
+            tracing::debug!("Failed to cache optimized asset {output_path:?}: {err}");
+        }
+
+        Ok(false)
+    }
+
+    /// Copy the contents of an entry to the output location after checking that it is intact
+    fn copy_entry(&self, entry: &Path, output_path: &Path) -> anyhow::Result<()> {
+        let mut contents = std::fs::read(entry)?;
+        let Some(split) = contents.len().checked_sub(Sha256::output_size()) else {
+            bail!("The entry is too short to be complete");
+        };
+        if Sha256::digest(&contents[..split]).as_slice() != &contents[split..] {
+            bail!("The contents of the entry don't match its checksum");
+        }
+        contents.truncate(split);
+
+        // Hashed outputs that exist are never written again, so an interrupted copy must not leave a truncated one
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create directory")?;
+        }
+        write_atomically(output_path, &contents).with_context(|| {
+            format!(
+                "Failed to copy cached asset to output location: {}",
+                output_path.display()
+            )
+        })?;
+
+        // Mark the entry as recently used so it is evicted last
+        if let Ok(file) = std::fs::File::options().write(true).open(entry) {
+            _ = file.set_modified(SystemTime::now());
+        }
+
+        Ok(())
+    }
+
+    /// Copy an optimized file into the cache. Like processing, this goes through a temporary file so an interrupted
+    /// copy never looks like a complete entry.
+    fn insert(&self, entry: &Path, output_path: &Path) -> anyhow::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let mut contents = std::fs::read(output_path)?;
+        let checksum = Sha256::digest(&contents);
+        contents.extend_from_slice(&checksum);
+        Ok(write_atomically(entry, &contents)?)
+    }
+
+    /// Remove the least recently used entries until the cache fits in its maximum size. Returns the number of bytes
+    /// that were removed.
+    ///
+    /// Entries that are still being written are left alone unless they were abandoned by an interrupted build.
+    pub fn evict(&self) -> anyhow::Result<u64> {
+        let Ok(read_dir) = std::fs::read_dir(&self.dir) else {
+            return Ok(0);
+        };
+
+        let mut entries = vec![];
+        let mut total = 0;
+        for entry in read_dir.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(PARTIAL_PREFIX)
+            {
+                let age = used.elapsed().unwrap_or_default();
+                if age > ABANDONED_AFTER {
+                    _ = std::fs::remove_file(entry.path());
+                }
+                continue;
+            }
+            total += metadata.len();
+            entries.push((used, metadata.len(), entry.path()));
+        }
+
+        entries.sort_by_key(|(used, _, _)| *used);
+
+        let mut removed = 0;
+        for (_, size, path) in entries {
+            if total - removed <= self.max_size {
+                break;
+            }
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to evict cached asset {}", path.display()))?;
+            removed += size;
+        }
+
+        Ok(removed)
+    }
+
+    /// Remove every entry from the cache
+    pub fn clear(&self) -> anyhow::Result<()> {
+        if self.dir.exists() {
+            std::fs::remove_dir_all(&self.dir).with_context(|| {
+                format!("Failed to remove asset cache at {}", self.dir.display())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Write a file through a temporary file next to it that is renamed into place once it is complete. The name of the
+/// temporary file is unique so builds that write the same asset at once don't write to the same file.
+fn write_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+    let temp_path = path.with_file_name(format!(
+        "{PARTIAL_PREFIX}{}.{}.{}",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result =
+        std::fs::write(&temp_path, contents).and_then(|_| std::fs::rename(&temp_path, path));
+    if result.is_err() {
+        _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// The key of an optimized asset. Some outputs depend on their own name (css modules reference it for example), so
+/// the name of the output is part of the key along with the options, the optimizer version and the source contents.
+///
+/// The key is a sha256 since the cache is shared between builds of different versions of dx, where the output of
+/// `DefaultHasher` may change.
+fn cache_key(options: &AssetOptions, source: &Path, output_path: &Path) -> anyhow::Result<String> {
+    let mut hasher = Sha256Hasher(Sha256::new());
+    options.hash(&mut hasher);
+    hasher.write(crate::build_info::version().as_bytes());
+    output_path.file_name().hash(&mut hasher);
+    hash_file_with_options(options, source, &mut hasher, false)?;
+    Ok(format!("{:x}", hasher.0.finalize()))
+}
+
+/// Feeds a [`Hash`] into a sha256 so the stable digest can be used as a key
+struct Sha256Hasher(Sha256);
+
+impl Hasher for Sha256Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        u64::from_le_bytes(digest[..8].try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> AssetOptions {
+        AssetOptions::builder()
+            .with_hash_suffix(false)
+            .into_asset_options()
+    }
+
+    fn entries(cache: &AssetCache) -> Vec<PathBuf> {
+        let mut entries: Vec<_> = std::fs::read_dir(cache.dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn processed_assets_are_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AssetCache::new(dir.path().join("cache"), u64::MAX);
+        let source = dir.path().join("notes.txt");
+        std::fs::write(&source, "hello").unwrap();
+
+        let output = dir.path().join("out/notes.txt");
+        assert!(!cache.process_file_to(&options(), &source, &output).unwrap());
+        assert_eq!(entries(&cache).len(), 1);
+
+        let output = dir.path().join("other/notes.txt");
+        assert!(cache.process_file_to(&options(), &source, &output).unwrap());
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "hello");
+
+        // Cached outputs are renamed into place, so no temporary files are left next to them
+        let outputs: Vec<_> = std::fs::read_dir(dir.path().join("other"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(outputs, ["notes.txt"]);
+
+        // A change to the source is a miss
+        std::fs::write(&source, "goodbye").unwrap();
+        assert!(!cache.process_file_to(&options(), &source, &output).unwrap());
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "goodbye");
+        assert_eq!(entries(&cache).len(), 2);
+    }
+
+    #[test]
+    fn corrupt_entries_are_processed_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AssetCache::new(dir.path().join("cache"), u64::MAX);
+        let source = dir.path().join("notes.txt");
+        let output = dir.path().join("out/notes.txt");
+        std::fs::write(&source, "hello").unwrap();
+        cache.process_file_to(&options(), &source, &output).unwrap();
+
+        let entry = entries(&cache).remove(0);
+        let mut contents = std::fs::read(&entry).unwrap();
+        contents[0] = b'j';
+        std::fs::write(&entry, &contents).unwrap();
+        assert!(!cache.process_file_to(&options(), &source, &output).unwrap());
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "hello");
+
+        // Truncated entries are detected too, and the entry is replaced by an intact one
+        std::fs::write(&entry, "he").unwrap();
+        assert!(!cache.process_file_to(&options(), &source, &output).unwrap());
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "hello");
+        assert!(cache.process_file_to(&options(), &source, &output).unwrap());
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AssetCache::new(dir.path().join("cache"), 100);
+        std::fs::create_dir_all(cache.dir()).unwrap();
+
+        let now = SystemTime::now();
+        let write = |name: &str, size: usize, age: u64| {
+            let path = cache.dir().join(name);
+            std::fs::write(&path, vec![0; size]).unwrap();
+            let file = std::fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - Duration::from_secs(age)).unwrap();
+            path
+        };
+        let oldest = write("oldest", 60, 30);
+        let newest = write("newest", 60, 10);
+        let writing = write("partial.writing.1.0", 60, 20);
+        let abandoned = write("partial.abandoned.1.1", 60, 2 * 60 * 60);
+
+        assert_eq!(cache.evict().unwrap(), 60);
+        assert_eq!(entries(&cache), [newest, writing]);
+        assert!(!oldest.exists());
+        assert!(!abandoned.exists());
+    }
+}
@@ -8,6 +8,7 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
 mod build_info;
+mod cache;
 mod css;
 mod file;
 mod folder;
@@ -16,6 +17,7 @@ mod image;
 mod js;
 mod json;
 
+pub use cache::AssetCache;
 pub use file::process_file_to;
 pub use hash::add_hash_to_asset;
 
@@ -1,6 +1,6 @@
 use crate::{
     serve::WebServer, BuildArtifacts, BuildRequest, BuildStage, BuilderUpdate, BundleFormat,
-    ProgressRx, ProgressTx, Result, RustcArgs, StructuredOutput,
+    ProgressRx, ProgressTx, Result, RustcArgs, StageTimer, StructuredOutput,
 };
 use anyhow::{bail, Context};
 use dioxus_cli_opt::process_file_to;
@@ -377,9 +377,11 @@ impl AppBuilder {
     /// todo(jon): maybe we want to do some logging here? The build/bundle/run screens could be made to
     /// use the TUI output for prettier outputs.
     pub(crate) async fn finish_build(&mut self) -> Result<BuildArtifacts> {
+        let mut stage_timer = StageTimer::default();
         loop {
             match self.wait().await {
                 BuilderUpdate::Progress { stage } => {
+                    stage_timer.record(&stage);
                     match &stage {
                         BuildStage::Compiling {
                             current,
@@ -415,6 +417,15 @@ impl AppBuilder {
                     tracing::debug!(json = %StructuredOutput::BuildFinished {
                         artifacts: bundle.clone().into_structured_output(),
                     });
+
+                    if self.build.timings {
+                        let stages = std::mem::take(&mut stage_timer).finish();
+                        std::fs::write(
+                            self.build.stage_timings_file(),
+                            serde_json::to_string(&stages)?,
+                        )?;
+                    }
+
                     return Ok(bundle);
                 }
                 BuilderUpdate::BuildFailed { err } => {
@@ -451,9 +462,19 @@ impl AppBuilder {
     ) -> Vec<(String, String)> {
         let krate = &self.build;
 
+        // Processes that run on this machine get every variable from the .env files. These go first so the vars
+        // below take precedence.
+        let mut envs: Vec<(String, String)> = match krate.bundle {
+            BundleFormat::Server
+            | BundleFormat::MacOS
+            | BundleFormat::Windows
+            | BundleFormat::Linux => krate.env_file_vars.clone(),
+            _ => vec![],
+        };
+
         // Set the env vars that the clients will expect
         // These need to be stable within a release version (ie 0.6.0)
-        let mut envs: Vec<(String, String)> = vec![
+        envs.extend([
             (
                 dioxus_cli_config::CLI_ENABLED_ENV.into(),
                 "true".to_string(),
@@ -471,7 +492,7 @@ impl AppBuilder {
                 dioxus_cli_config::ALWAYS_ON_TOP_ENV.into(),
                 always_on_top.to_string(),
             ),
-        ];
+        ]);
 
         if let Some(devserver_ip) = devserver_ip {
             envs.push((
@@ -320,17 +320,21 @@
 //! - xbuild: <https://github.com/rust-mobile/xbuild/blob/master/xbuild/src/command/build.rs>
 
 use crate::{
-    AndroidTools, BuildContext, BundleFormat, DioxusConfig, Error, LinkAction, LinkerFlavor,
-    Platform, Renderer, Result, RustcArgs, TargetArgs, TraceSrc, WasmBindgen, WasmOptConfig,
-    Workspace, DX_RUSTC_WRAPPER_ENV_VAR,
+    load_env_files, AndroidTools, BuildContext, BundleFormat, DioxusConfig, Error, LinkAction,
+    LinkerFlavor, Platform, ProfileConfig, Renderer, Result, RustcArgs, TargetArgs, TestTarget,
+    TraceSrc, WasmBindgen, WasmOptConfig, Workspace, DX_RUSTC_TIMINGS_ENV_VAR,
+    DX_RUSTC_WRAPPER_ENV_VAR,
 };
 use anyhow::{bail, Context};
 use cargo_metadata::diagnostic::Diagnostic;
 use cargo_toml::{Profile, Profiles, StripSetting};
 use depinfo::RustcDepInfo;
-use dioxus_cli_config::{format_base_path_meta_element, PRODUCT_NAME_ENV};
+use dioxus_cli_config::{
+    format_base_path_meta_element, format_devtools_path_meta_element, APP_VERSION_ENV,
+    PRODUCT_NAME_ENV,
+};
 use dioxus_cli_config::{APP_TITLE_ENV, ASSET_ROOT_ENV};
-use dioxus_cli_opt::{process_file_to, AssetManifest};
+use dioxus_cli_opt::AssetManifest;
 use itertools::Itertools;
 use krates::{cm::TargetKind, NodeId};
 use manganis::AssetOptions;
@@ -345,16 +349,16 @@ use std::{
     process::Stdio,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        Arc,
+        Arc, Mutex,
     },
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 use target_lexicon::{Architecture, OperatingSystem, Triple};
 use tempfile::TempDir;
 use tokio::{io::AsyncBufReadExt, process::Command};
 use uuid::Uuid;
 
-use super::HotpatchModuleCache;
+use super::{AssetTiming, HotpatchModuleCache};
 
 /// This struct is used to plan the build process.
 ///
@@ -394,10 +398,14 @@ pub(crate) struct BuildRequest {
     pub(crate) inject_loading_scripts: bool,
     pub(crate) custom_linker: Option<PathBuf>,
     pub(crate) base_path: Option<String>,
+    pub(crate) devtools_path: Option<String>,
     pub(crate) using_dioxus_explicitly: bool,
     pub(crate) apple_entitlements: Option<PathBuf>,
     pub(crate) apple_team_id: Option<String>,
     pub(crate) session_cache_dir: PathBuf,
+    pub(crate) env_file_vars: Vec<(String, String)>,
+    pub(crate) test_target: Option<TestTarget>,
+    pub(crate) timings: bool,
 }
 
 /// dx can produce different "modes" of a build. A "regular" build is a "base" build. The Fat and Thin
@@ -478,44 +486,70 @@ impl BuildRequest {
         let crate_package = workspace.find_main_package(args.package.clone())?;
 
         let config = workspace
-            .load_dioxus_config(crate_package)?
+            .load_dioxus_config(crate_package, args.profile.as_deref())?
             .unwrap_or_default();
 
-        let target_kind = match args.example.is_some() {
-            true => TargetKind::Example,
-            false => TargetKind::Bin,
-        };
-
         let main_package = &workspace.krates[crate_package];
 
-        let target_name = args
-            .example
-            .clone()
-            .or(args.bin.clone())
-            .or_else(|| {
-                if let Some(default_run) = &main_package.default_run {
-                    return Some(default_run.to_string());
-                }
+        // `dx test` builds the test harness of the library when no binary or example is selected
+        let is_lib = |target: &krates::cm::Target| {
+            target.kind.iter().any(|kind| {
+                matches!(
+                    kind,
+                    TargetKind::Lib
+                        | TargetKind::RLib
+                        | TargetKind::DyLib
+                        | TargetKind::CDyLib
+                        | TargetKind::StaticLib
+                )
+            })
+        };
+        let test_target = args.test_target.clone().map(|test| match test {
+            TestTarget::Auto if args.bin.is_some() || args.example.is_some() => TestTarget::Bin,
+            TestTarget::Auto if main_package.targets.iter().any(is_lib) => TestTarget::Lib,
+            TestTarget::Auto => TestTarget::Bin,
+            test => test,
+        });
 
-                let bin_count = main_package
-                    .targets
-                    .iter()
-                    .filter(|x| x.kind.contains(&target_kind))
-                    .count();
+        let target_kind = match (&test_target, args.example.is_some()) {
+            (Some(TestTarget::Lib), _) => TargetKind::Lib,
+            (Some(TestTarget::Integration(_)), _) => TargetKind::Test,
+            (_, true) => TargetKind::Example,
+            (_, false) => TargetKind::Bin,
+        };
 
-                if bin_count != 1 {
-                    return None;
-                }
+        let target_name = match &test_target {
+            Some(TestTarget::Integration(name)) => Some(name.clone()),
+            _ => None,
+        }
+        .or(args.example.clone())
+        .or(args.bin.clone())
+        .or_else(|| {
+            if let Some(default_run) = &main_package.default_run {
+                return Some(default_run.to_string());
+            }
 
-                main_package.targets.iter().find_map(|x| {
-                    if x.kind.contains(&target_kind) {
-                        Some(x.name.clone())
-                    } else {
-                        None
-                    }
+            let bin_count = main_package
+                .targets
+                .iter()
+                .filter(|x| {
+                    x.kind.contains(&target_kind) || (target_kind == TargetKind::Lib && is_lib(x))
                 })
+                .count();
+
+            if bin_count != 1 {
+                return None;
+            }
+
+            main_package.targets.iter().find_map(|x| {
+                if x.kind.contains(&target_kind) || (target_kind == TargetKind::Lib && is_lib(x)) {
+                    Some(x.name.clone())
+                } else {
+                    None
+                }
             })
-            .unwrap_or(workspace.krates[crate_package].name.clone());
+        })
+        .unwrap_or(workspace.krates[crate_package].name.clone());
 
         // Use the main_target for the client + server build if it is set, otherwise use the target name for this
         // specific build. This is important for @client @server syntax so we use the client's output directory for the bundle.
@@ -525,7 +559,11 @@ impl BuildRequest {
             .targets
             .iter()
             .find(|target| {
-                target_name == target.name.as_str() && target.kind.contains(&target_kind)
+                target_name == target.name.as_str()
+                    && match target_kind {
+                        TargetKind::Lib => is_lib(target),
+                        _ => target.kind.contains(&target_kind),
+                    }
             })
             .with_context(|| {
                 let target_of_kind = |kind|-> String {
@@ -536,7 +574,12 @@ impl BuildRequest {
                     target.kind.contains(kind).then_some(target.name.as_str())
                 }).collect::<Vec<_>>();
                 filtered_packages.join(", ")};
-                if let Some(example) = &args.example {
+                if let Some(TestTarget::Integration(test)) = &test_target {
+                    let tests = target_of_kind(&TargetKind::Test);
+                    format!("Failed to find test {test}. \nAvailable tests are:\n{tests}")
+                } else if test_target == Some(TestTarget::Lib) {
+                    "The package has no library to test. Pass `--bin` or `--test` to pick the tests to run.".to_string()
+                } else if let Some(example) = &args.example {
                     let examples = target_of_kind(&TargetKind::Example);
                     format!("Failed to find example {example}. \nAvailable examples are:\n{examples}")
                 } else if let Some(bin) = &args.bin {
@@ -550,6 +593,18 @@ impl BuildRequest {
             })?
             .clone();
 
+        // Only tests with their own harness stay alive to be hotpatched, the others are rebuilt on every change
+        if test_target.is_some() {
+            let manifest =
+                cargo_toml::Manifest::from_path(main_package.manifest_path.as_std_path());
+            if manifest.is_ok_and(|manifest| crate::uses_libtest(&manifest, &crate_target)) {
+                tracing::warn!(
+                    "The `{}` tests use the libtest harness, so they are rebuilt and re-run on every change instead of being hotpatched. Set `harness = false` for them and call `dioxus_devtools::run_tests` to hotpatch them.",
+                    crate_target.name
+                );
+            }
+        }
+
         // We usually use the simulator unless --device is passed *or* a device is detected by probing.
         // For now, though, since we don't have probing, it just defaults to false
         // Tools like xcrun/adb can detect devices
@@ -626,7 +681,8 @@ impl BuildRequest {
         // - if the `default =["web"]` or `default = ["dioxus/web"]`
         // - if there's only one non-server platform ie `web = ["dioxus/web"], server = ["dioxus/server"]`
         // Only do this if we're explicitly using dioxus
-        if matches!(platform, Platform::Unknown) && using_dioxus_explicitly {
+        if matches!(platform, Platform::Unknown) && using_dioxus_explicitly && test_target.is_none()
+        {
             let auto = dioxus_direct_renderer
                 .or_else(|| {
                     if enabled_renderers.len() == 1 {
@@ -782,6 +838,12 @@ impl BuildRequest {
             }
         }
 
+        // Tests run on this machine with the features the user picked, without enabling a renderer
+        if test_target.is_some() {
+            bundle_format = bundle_format.or(Some(BundleFormat::Server));
+            triple = triple.or(Some(Triple::host()));
+        }
+
         // If no default features are enabled, we need to add the rendererless features
         if no_default_features {
             features.extend(Self::rendererless_features(main_package));
@@ -816,8 +878,23 @@ impl BuildRequest {
         // Set the profile of the build if it's not already set
         // This is mostly used for isolation of builds (preventing thrashing) but also useful to have multiple performance profiles
         // We might want to move some of these profiles into dioxus.toml and make them "virtual".
+        //
+        // The profile might also be a profile in Dioxus.toml, which only selects a cargo profile if it names one or
+        // cargo knows a profile with the same name.
+        //
+        // Tests always use cargo's test profile since that's what builds the test harness.
         let profile = match args.profile.clone() {
-            Some(profile) => profile,
+            _ if test_target.is_some() => "test".to_string(),
+            Some(profile) => match config.profile.get(&profile) {
+                Some(ProfileConfig {
+                    cargo_profile: Some(cargo_profile),
+                    ..
+                }) => cargo_profile.clone(),
+                Some(_) if !workspace.has_cargo_profile(&profile) => {
+                    bundle.profile_name(args.release)
+                }
+                _ => profile,
+            },
             None => bundle.profile_name(args.release),
         };
 
@@ -905,11 +982,7 @@ impl BuildRequest {
             );
         }
 
-        let target_dir = std::env::var("CARGO_TARGET_DIR")
-            .ok()
-            .map(PathBuf::from)
-            .or_else(|| cargo_config.build.target_dir.clone())
-            .unwrap_or_else(|| workspace.workspace_root().join("target"));
+        let target_dir = workspace.target_dir();
 
         // If the user provided a profile and wasm_split is enabled, we should check that LTO=true and debug=true
         if args.wasm_split {
@@ -937,6 +1010,15 @@ impl BuildRequest {
         let extra_cargo_args = shell_words::split(&args.cargo_args.clone().unwrap_or_default())
             .context("Failed to parse cargo args")?;
 
+        // Load the .env files from the workspace root and then the crate itself
+        let crate_dir = main_package.manifest_path.parent().unwrap().as_std_path();
+        let workspace_root = workspace.workspace_root();
+        let mut env_dirs = vec![workspace_root.as_path()];
+        if crate_dir != env_dirs[0] {
+            env_dirs.push(crate_dir);
+        }
+        let env_file_vars = load_env_files(&env_dirs, args.profile.as_deref())?;
+
         tracing::debug!(
             r#"Target Info:
                 • features: {features:?}
@@ -969,8 +1051,12 @@ impl BuildRequest {
             using_dioxus_explicitly,
             should_codesign,
             session_cache_dir,
+            env_file_vars,
+            test_target,
+            timings: false,
             skip_assets: args.skip_assets,
             base_path: args.base_path.clone(),
+            devtools_path: args.devtools_path.clone(),
             wasm_split: args.wasm_split,
             debug_symbols: args.debug_symbols,
             inject_loading_scripts: args.inject_loading_scripts,
@@ -988,6 +1074,13 @@ impl BuildRequest {
         _ = std::fs::File::create_new(self.link_args_file());
         _ = std::fs::File::create_new(self.windows_command_file());
 
+        // Timings are appended to as the build runs, so start from a clean slate
+        if self.timings {
+            _ = std::fs::remove_file(self.rustc_timings_file());
+            _ = std::fs::remove_file(self.asset_timings_file());
+            _ = std::fs::remove_file(self.stage_timings_file());
+        }
+
         if !matches!(ctx.mode, BuildMode::Thin { .. }) {
             self.prepare_build_dir()?;
         }
@@ -1533,9 +1626,12 @@ impl BuildRequest {
         // Parallel Copy over the assets and keep track of progress with an atomic counter
         let progress = ctx.tx.clone();
         let ws_dir = self.workspace_dir();
+        let cache = self.workspace.asset_cache();
+        let record_timings = self.timings;
+        let timings = Mutex::new(vec![]);
 
         // Optimizing assets is expensive and blocking, so we do it in a tokio spawn blocking task
-        tokio::task::spawn_blocking(move || {
+        let asset_timings = tokio::task::spawn_blocking(move || {
             assets_to_transfer
                 .par_iter()
                 .try_for_each(|(from, to, options)| {
@@ -1545,11 +1641,20 @@ impl BuildRequest {
                         "Starting asset copy {processing}/{asset_count} from {from_:?}"
                     );
 
-                    let res = process_file_to(options, from, to);
+                    let started = Instant::now();
+                    let res = cache.process_file_to(options, from, to);
                     if let Err(err) = res.as_ref() {
                         tracing::error!("Failed to copy asset {from:?}: {err}");
                     }
 
+                    if record_timings {
+                        timings.lock().unwrap().push(AssetTiming {
+                            path: from_.to_path_buf(),
+                            duration: started.elapsed().as_millis() as u64,
+                            cached: res.as_ref().is_ok_and(|cached| *cached),
+                        });
+                    }
+
                     let finished = copied.fetch_add(1, Ordering::SeqCst);
                     BuildContext::status_copied_asset(
                         &progress,
@@ -1559,11 +1664,29 @@ impl BuildRequest {
                     );
 
                     res.map(|_| ())
-                })
+                })?;
+
+            // Keep the shared cache within its size budget now that this build's assets are in it
+            match cache.evict() {
+                Ok(0) => {}
+                Ok(removed) => tracing::debug!("Evicted {removed} bytes from the asset cache"),
+                Err(err) => {
+                    tracing::warn!("Failed to evict old entries from the asset cache: {err}")
+                }
+            }
+
+            anyhow::Ok(timings.into_inner().unwrap())
         })
         .await
         .map_err(|e| anyhow::anyhow!("A task failed while trying to copy assets: {e}"))??;
 
+        if self.timings {
+            std::fs::write(
+                self.asset_timings_file(),
+                serde_json::to_string(&asset_timings)?,
+            )?;
+        }
+
         // Remove the wasm dir if we packaged it to an "asset"-type app
         if self.should_bundle_to_asset() {
             _ = std::fs::remove_dir_all(self.wasm_bindgen_out_dir());
@@ -2448,7 +2571,14 @@ impl BuildRequest {
                     .args(args)
                     .envs(env.iter().map(|(k, v)| (k.as_ref(), v)));
 
-                if matches!(build_mode, BuildMode::Fat | BuildMode::Base { run: true }) {
+                // Profiled builds go through the wrapper too so it can time every rustc invocation
+                if self.timings {
+                    cmd.env(DX_RUSTC_TIMINGS_ENV_VAR, self.rustc_timings_file());
+                }
+
+                if self.timings
+                    || matches!(build_mode, BuildMode::Fat | BuildMode::Base { run: true })
+                {
                     cmd.env(
                         DX_RUSTC_WRAPPER_ENV_VAR,
                         dunce::canonicalize(self.rustc_wrapper_args_file())
@@ -2502,14 +2632,19 @@ impl BuildRequest {
         cargo_args.push(String::from("-p"));
         cargo_args.push(self.package.clone());
 
-        // Set the executable
-        match self.executable_type() {
-            TargetKind::Bin => cargo_args.push("--bin".to_string()),
-            TargetKind::Lib => cargo_args.push("--lib".to_string()),
-            TargetKind::Example => cargo_args.push("--example".to_string()),
-            _ => {}
-        };
-        cargo_args.push(self.executable_name().to_string());
+        // Set the executable. The tests of the library don't name it since a package only has one library.
+        if self.test_target == Some(TestTarget::Lib) {
+            cargo_args.push("--lib".to_string());
+        } else {
+            match self.executable_type() {
+                TargetKind::Bin => cargo_args.push("--bin".to_string()),
+                TargetKind::Lib => cargo_args.push("--lib".to_string()),
+                TargetKind::Example => cargo_args.push("--example".to_string()),
+                TargetKind::Test => cargo_args.push("--test".to_string()),
+                _ => {}
+            };
+            cargo_args.push(self.executable_name().to_string());
+        }
 
         // Set offline/locked/frozen
         let lock_opts = crate::VERBOSITY.get().cloned().unwrap_or_default();
@@ -2674,6 +2809,19 @@ impl BuildRequest {
             env_vars.push((PRODUCT_NAME_ENV.into(), self.bundled_app_name().into()));
         }
 
+        // The version is baked into debug builds too so the updater can be tested before the app is released
+        env_vars.push((
+            APP_VERSION_ENV.into(),
+            self.package().version.to_string().into(),
+        ));
+
+        // Bake the allowlisted variables from the .env files into the app so they can be read with `env!`
+        for (key, value) in self.env_file_vars.iter() {
+            if self.config.env.is_client_var(key) {
+                env_vars.push((key.clone().into(), value.into()));
+            }
+        }
+
         // Assemble the rustflags by peering into the `.cargo/config.toml` file
         let rust_flags = self.rustflags.clone();
 
@@ -3354,6 +3502,18 @@ impl BuildRequest {
         self.session_cache_dir().join("windows_command.txt")
     }
 
+    pub(crate) fn rustc_timings_file(&self) -> PathBuf {
+        self.session_cache_dir().join("rustc_timings.jsonl")
+    }
+
+    pub(crate) fn asset_timings_file(&self) -> PathBuf {
+        self.session_cache_dir().join("asset_timings.json")
+    }
+
+    pub(crate) fn stage_timings_file(&self) -> PathBuf {
+        self.session_cache_dir().join("stage_timings.json")
+    }
+
     /// Get the outdir specified by the Dioxus.toml, relative to the crate directory.
     /// We don't support workspaces yet since that would cause a collision of bundles per project.
     pub(crate) fn crate_out_dir(&self) -> Option<PathBuf> {
@@ -4417,7 +4577,7 @@ __wbg_init({{module_or_path: "/{}/{wasm_path}"}}).then((wasm) => {{
     }
 
     /// Get the path to the wasm bindgen temporary output folder
-    fn wasm_bindgen_out_dir(&self) -> PathBuf {
+    pub(crate) fn wasm_bindgen_out_dir(&self) -> PathBuf {
         self.root_dir().join("wasm")
     }
 
@@ -4748,6 +4908,9 @@ __wbg_init({{module_or_path: "/{}/{wasm_path}"}}).then((wasm) => {{
             if let Some(base_path) = &self.base_path() {
                 head_resources.push_str(&format_base_path_meta_element(base_path));
             }
+            if let Some(devtools_path) = &self.devtools_path {
+                head_resources.push_str(&format_devtools_path_meta_element(devtools_path));
+            }
         }
 
         // Inject any resources from manganis into the head
@@ -5016,13 +5179,15 @@ __wbg_init({{module_or_path: "/{}/{wasm_path}"}}).then((wasm) => {{
     ///
     /// Note how every platform gets its own profile, and each platform has a dev and release profile.
     fn profile_args(&self) -> Vec<String> {
-        // If the user defined the profile in the Cargo.toml, we don't need to add it to our adhoc list
-        if self
-            .workspace
-            .cargo_toml
-            .profile
-            .custom
-            .contains_key(&self.profile)
+        // If the user defined the profile in the Cargo.toml or it's built into cargo, we don't need to add it to our
+        // adhoc list
+        if matches!(self.profile.as_str(), "dev" | "release" | "test" | "bench")
+            || self
+                .workspace
+                .cargo_toml
+                .profile
+                .custom
+                .contains_key(&self.profile)
         {
             return vec![];
         }
@@ -0,0 +1,706 @@
+//! Profile where the time of a build goes with `dx build --timings`.
+//!
+//! The report is put together from three sources that are recorded while the build runs:
+//! - the stages of the build, recorded by the [`AppBuilder`](super::AppBuilder) as the progress updates come in
+//! - every rustc invocation, timed by the rustc wrapper (see [`crate::RustcTiming`])
+//! - every asset, timed while the assets are optimized
+//!
+//! The crates are placed on the dependency graph to find the critical path - the chain of crates that each had to
+//! wait for the previous one to finish, followed by the stages that run after compiling. Speeding up anything off
+//! the critical path won't make the build any faster.
+//!
+//! The report is written as json and html to `target/dx-timings`, and the previous json report is used as a
+//! baseline to compare against unless another one is passed.
+
+use crate::{BuildRequest, BuildStage, Result, RustcTiming};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How long it took to optimize and copy a single asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AssetTiming {
+    /// The source of the asset, relative to the workspace
+    pub(crate) path: PathBuf,
+    /// In milliseconds
+    pub(crate) duration: u64,
+    /// If the optimized asset was reused from the asset cache
+    pub(crate) cached: bool,
+}
+
+/// A stage of the build. Times are in milliseconds, since the unix epoch while recording and since the start of the
+/// build in the report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct StageTiming {
+    pub(crate) stage: String,
+    pub(crate) start: u64,
+    pub(crate) duration: u64,
+}
+
+/// Records when the build moves from one stage to the next
+#[derive(Debug, Default)]
+pub(crate) struct StageTimer {
+    stages: Vec<StageTiming>,
+    current: Option<(&'static str, u64)>,
+}
+
+impl StageTimer {
+    pub(crate) fn record(&mut self, stage: &BuildStage) {
+        self.record_at(stage, now());
+    }
+
+    fn record_at(&mut self, stage: &BuildStage, at: u64) {
+        let name = stage_name(stage);
+        if self.current.map(|(current, _)| current) == name {
+            return;
+        }
+
+        if let Some((current, start)) = self.current.take() {
+            self.stages.push(StageTiming {
+                stage: current.to_string(),
+                start,
+                duration: at.saturating_sub(start),
+            });
+        }
+
+        self.current = name.map(|name| (name, at));
+    }
+
+    pub(crate) fn finish(mut self) -> Vec<StageTiming> {
+        self.record(&BuildStage::Success);
+        self.stages
+    }
+}
+
+/// The name of the stage in the report. Stages that only mark the beginning or end of the build have no name.
+fn stage_name(stage: &BuildStage) -> Option<&'static str> {
+    match stage {
+        BuildStage::InstallingTooling => Some("installing tooling"),
+        BuildStage::Starting { .. } | BuildStage::Compiling { .. } => Some("compiling"),
+        BuildStage::RunningBindgen => Some("wasm-bindgen"),
+        BuildStage::SplittingBundle => Some("splitting"),
+        BuildStage::OptimizingWasm => Some("wasm-opt"),
+        BuildStage::Linking => Some("linking"),
+        BuildStage::Hotpatching => Some("hotpatching"),
+        BuildStage::ExtractingAssets => Some("extracting assets"),
+        BuildStage::CopyingAssets { .. } => Some("copying assets"),
+        BuildStage::CompressingAssets => Some("compressing assets"),
+        BuildStage::Bundling => Some("bundling"),
+        BuildStage::RunningGradle => Some("gradle"),
+        BuildStage::CodeSigning => Some("code signing"),
+        BuildStage::Prerendering => Some("prerendering"),
+        _ => None,
+    }
+}
+
+/// The timings of a `dx build`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TimingReport {
+    /// When the build started, in milliseconds since the unix epoch
+    pub(crate) started: u64,
+    /// In milliseconds
+    pub(crate) duration: u64,
+    /// The client build and the server build of fullstack apps
+    pub(crate) targets: Vec<TargetTimings>,
+}
+
+/// The timings of the build of a single target. Times are in milliseconds since the start of the `dx build`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TargetTimings {
+    /// `client` or `server`
+    pub(crate) name: String,
+    pub(crate) bundle: String,
+    pub(crate) stages: Vec<StageTiming>,
+    pub(crate) crates: Vec<CrateTiming>,
+    pub(crate) assets: Vec<AssetTiming>,
+    pub(crate) critical_path: Vec<CriticalPathStep>,
+}
+
+/// A single rustc invocation. Build scripts are invoked separately from the library of their package.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CrateTiming {
+    pub(crate) package: String,
+    pub(crate) krate: String,
+    pub(crate) start: u64,
+    pub(crate) duration: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CriticalPathStep {
+    /// The name of the package or the stage
+    pub(crate) name: String,
+    pub(crate) duration: u64,
+}
+
+impl TimingReport {
+    /// Put the report together from the timings the builds recorded
+    pub(crate) fn from_builds(started: u64, builds: &[(&str, &BuildRequest)]) -> Result<Self> {
+        let mut targets = vec![];
+        for (name, build) in builds {
+            targets.push(TargetTimings::from_build(name, build, started)?);
+        }
+
+        Ok(Self {
+            started,
+            duration: now().saturating_sub(started),
+            targets,
+        })
+    }
+
+    /// The directory the reports are written to
+    pub(crate) fn dir(target_dir: &Path) -> PathBuf {
+        target_dir.join("dx-timings")
+    }
+
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the timings at {}", path.display()))?;
+        serde_json::from_str(&contents).context("Failed to parse the timings")
+    }
+
+    /// Write the json and html reports, returning the path to the html report
+    pub(crate) fn save(&self, dir: &Path, baseline: Option<&TimingReport>) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        std::fs::write(
+            dir.join("dx-timing.json"),
+            serde_json::to_string_pretty(self)?,
+        )?;
+
+        let html = dir.join("dx-timing.html");
+        std::fs::write(&html, self.to_html(baseline))?;
+        Ok(html)
+    }
+
+    pub(crate) fn print(&self, top: usize) {
+        use crate::styles::*;
+
+        println!(
+            "\n{LINK_STYLE}Build timings{LINK_STYLE:#}: {GLOW_STYLE}{}{GLOW_STYLE:#}",
+            format_duration(self.duration)
+        );
+
+        for target in &self.targets {
+            println!(
+                "\n{LINK_STYLE}{} ({}){LINK_STYLE:#}",
+                target.name, target.bundle
+            );
+
+            println!("  {NOTE_STYLE}Stages{NOTE_STYLE:#}");
+            for stage in &target.stages {
+                println!(
+                    "    {:>10}  {}",
+                    format_duration(stage.duration),
+                    stage.stage
+                );
+            }
+
+            println!("  {NOTE_STYLE}Slowest crates{NOTE_STYLE:#}");
+            for krate in target.slowest_crates().iter().take(top) {
+                println!(
+                    "    {:>10}  {}",
+                    format_duration(krate.duration),
+                    krate.label()
+                );
+            }
+
+            if !target.assets.is_empty() {
+                let cached = target.assets.iter().filter(|a| a.cached).count();
+                println!(
+                    "  {NOTE_STYLE}Slowest assets{NOTE_STYLE:#} ({cached}/{} cached)",
+                    target.assets.len()
+                );
+                let mut assets: Vec<_> = target.assets.iter().collect();
+                assets.sort_by_key(|asset| std::cmp::Reverse(asset.duration));
+                for asset in assets.iter().take(top) {
+                    println!(
+                        "    {:>10}  {}",
+                        format_duration(asset.duration),
+                        asset.path.display()
+                    );
+                }
+            }
+
+            println!(
+                "  {NOTE_STYLE}Critical path{NOTE_STYLE:#} ({})",
+                format_duration(target.critical_path.iter().map(|step| step.duration).sum())
+            );
+            for step in &target.critical_path {
+                println!("    {:>10}  {}", format_duration(step.duration), step.name);
+            }
+        }
+    }
+
+    fn to_html(&self, baseline: Option<&TimingReport>) -> String {
+        let mut html = String::new();
+        html.push_str(concat!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>dx build timings</title><style>",
+            "body{font-family:system-ui,sans-serif;margin:2em;color:#222}",
+            "table{border-collapse:collapse;margin-bottom:1.5em}td,th{padding:2px 12px;text-align:left}",
+            "td.num{text-align:right;font-variant-numeric:tabular-nums}",
+            ".lane{position:relative;height:16px;margin:1px 0;background:#f4f4f4}",
+            ".bar{position:absolute;height:16px;background:#8cb4e8;font-size:11px;white-space:nowrap;overflow:hidden}",
+            ".bar.critical{background:#e89c8c}.worse{color:#b00}.better{color:#070}",
+            "</style></head><body>"
+        ));
+        html.push_str(&format!(
+            "<h1>dx build timings</h1><p>Total: <b>{}</b></p>",
+            format_duration(self.duration)
+        ));
+
+        if let Some(baseline) = baseline {
+            html.push_str(&TimingDiff::new(baseline, self).to_html());
+        }
+
+        for target in &self.targets {
+            html.push_str(&format!(
+                "<h2>{} ({})</h2>",
+                escape(&target.name),
+                escape(&target.bundle)
+            ));
+
+            html.push_str("<h3>Stages</h3><table>");
+            for stage in &target.stages {
+                html.push_str(&format!(
+                    "<tr><td class=num>{}</td><td>{}</td></tr>",
+                    format_duration(stage.duration),
+                    escape(&stage.stage)
+                ));
+            }
+            html.push_str("</table>");
+
+            html.push_str(&format!(
+                "<h3>Critical path ({})</h3><table>",
+                format_duration(target.critical_path.iter().map(|step| step.duration).sum())
+            ));
+            for step in &target.critical_path {
+                html.push_str(&format!(
+                    "<tr><td class=num>{}</td><td>{}</td></tr>",
+                    format_duration(step.duration),
+                    escape(&step.name)
+                ));
+            }
+            html.push_str("</table>");
+
+            // A waterfall of the rustc invocations, with the crates on the critical path highlighted
+            let critical: HashSet<_> = target.critical_path.iter().map(|s| &s.name).collect();
+            let end = target
+                .crates
+                .iter()
+                .map(|krate| krate.start + krate.duration)
+                .max()
+                .unwrap_or_default()
+                .max(1) as f64;
+            html.push_str("<h3>Crates</h3>");
+            let mut crates: Vec<_> = target.crates.iter().collect();
+            crates.sort_by_key(|krate| krate.start);
+            for krate in crates {
+                html.push_str(&format!(
+                    "<div class=lane><div class=\"bar{}\" style=\"left:{:.2}%;width:{:.2}%\" title=\"{} {}\">{} {}</div></div>",
+                    if critical.contains(&krate.package) { " critical" } else { "" },
+                    krate.start as f64 / end * 100.0,
+                    (krate.duration as f64 / end * 100.0).max(0.1),
+                    escape(&krate.label()),
+                    format_duration(krate.duration),
+                    escape(&krate.label()),
+                    format_duration(krate.duration),
+                ));
+            }
+
+            if !target.assets.is_empty() {
+                html.push_str(
+                    "<h3>Assets</h3><table><tr><th>Time</th><th>Asset</th><th>Cached</th></tr>",
+                );
+                let mut assets: Vec<_> = target.assets.iter().collect();
+                assets.sort_by_key(|asset| std::cmp::Reverse(asset.duration));
+                for asset in assets {
+                    html.push_str(&format!(
+                        "<tr><td class=num>{}</td><td>{}</td><td>{}</td></tr>",
+                        format_duration(asset.duration),
+                        escape(&asset.path.display().to_string()),
+                        if asset.cached { "yes" } else { "no" }
+                    ));
+                }
+                html.push_str("</table>");
+            }
+        }
+
+        html.push_str("</body></html>");
+        html
+    }
+}
+
+impl TargetTimings {
+    fn from_build(name: &str, build: &BuildRequest, started: u64) -> Result<Self> {
+        let mut crates: Vec<CrateTiming> = std::fs::read_to_string(build.rustc_timings_file())
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str::<RustcTiming>(line).ok())
+            .map(|timing| CrateTiming {
+                package: timing.package,
+                krate: timing.krate,
+                start: timing.start.saturating_sub(started),
+                duration: timing.duration,
+            })
+            .collect();
+        crates.sort_by_key(|krate| krate.start);
+
+        let mut stages: Vec<StageTiming> = read_json(&build.stage_timings_file())?;
+        for stage in stages.iter_mut() {
+            stage.start = stage.start.saturating_sub(started);
+        }
+
+        let assets = read_json(&build.asset_timings_file())?;
+
+        // Cargo only knows packages, so we walk the dependencies of the package through the workspace graph
+        let krates = &build.workspace.krates;
+        let dependencies = |package: &str| -> Vec<String> {
+            krates
+                .krates_by_name(package)
+                .flat_map(|found| krates.direct_dependencies(found.node_id))
+                .map(|dep| dep.krate.name.clone())
+                .collect()
+        };
+        let critical_path = critical_path(&build.package().name, &crates, &stages, dependencies);
+
+        Ok(Self {
+            name: name.to_string(),
+            bundle: build.bundle.to_string(),
+            stages,
+            crates,
+            assets,
+            critical_path,
+        })
+    }
+
+    fn slowest_crates(&self) -> Vec<&CrateTiming> {
+        let mut crates: Vec<_> = self.crates.iter().collect();
+        crates.sort_by_key(|krate| std::cmp::Reverse(krate.duration));
+        crates
+    }
+}
+
+impl CrateTiming {
+    fn label(&self) -> String {
+        if self.krate.starts_with("build_script_") {
+            format!("{} (build script)", self.package)
+        } else {
+            self.package.clone()
+        }
+    }
+}
+
+/// Find the chain of packages that kept the build waiting, starting from the package being built.
+///
+/// From the package, we step to the dependency that finished compiling last since that is the one it was waiting on.
+/// The stages after compiling always run after the crates, so they are all on the critical path.
+fn critical_path(
+    package: &str,
+    crates: &[CrateTiming],
+    stages: &[StageTiming],
+    dependencies: impl Fn(&str) -> Vec<String>,
+) -> Vec<CriticalPathStep> {
+    // Packages can be compiled more than once, like a build script and a library
+    let mut packages: HashMap<&str, (u64, u64)> = HashMap::new();
+    for krate in crates {
+        let (end, duration) = packages.entry(&krate.package).or_default();
+        *end = (*end).max(krate.start + krate.duration);
+        *duration += krate.duration;
+    }
+
+    let mut path = vec![];
+    let mut visited = HashSet::new();
+    let mut current = Some(package.to_string());
+    while let Some(package) = current.take() {
+        if !visited.insert(package.clone()) {
+            break;
+        }
+        if let Some((_, duration)) = packages.get(package.as_str()) {
+            path.push(CriticalPathStep {
+                name: package.clone(),
+                duration: *duration,
+            });
+        }
+        current = dependencies(&package)
+            .into_iter()
+            .filter(|dep| !visited.contains(dep))
+            .filter_map(|dep| packages.get(dep.as_str()).map(|(end, _)| (*end, dep)))
+            .max()
+            .map(|(_, dep)| dep);
+    }
+    path.reverse();
+
+    path.extend(
+        stages
+            .iter()
+            .skip_while(|stage| stage.stage != "compiling")
+            .skip(1)
+            .map(|stage| CriticalPathStep {
+                name: stage.stage.clone(),
+                duration: stage.duration,
+            }),
+    );
+
+    path
+}
+
+/// The difference between a report and the baseline it is compared against
+#[derive(Debug, PartialEq)]
+pub(crate) struct TimingDiff {
+    pub(crate) before: u64,
+    pub(crate) after: u64,
+    /// Stages and crates with the largest change first, as `(name, before, after)`
+    pub(crate) stages: Vec<(String, u64, u64)>,
+    pub(crate) crates: Vec<(String, u64, u64)>,
+}
+
+impl TimingDiff {
+    pub(crate) fn new(before: &TimingReport, after: &TimingReport) -> Self {
+        let stages = |report: &TimingReport| {
+            let mut stages = BTreeMap::new();
+            for target in &report.targets {
+                for stage in &target.stages {
+                    *stages
+                        .entry(format!("{}: {}", target.name, stage.stage))
+                        .or_default() += stage.duration;
+                }
+            }
+            stages
+        };
+        let crates = |report: &TimingReport| {
+            let mut crates = BTreeMap::new();
+            for target in &report.targets {
+                for krate in &target.crates {
+                    *crates
+                        .entry(format!("{}: {}", target.name, krate.label()))
+                        .or_default() += krate.duration;
+                }
+            }
+            crates
+        };
+
+        Self {
+            before: before.duration,
+            after: after.duration,
+            stages: diff_rows(stages(before), stages(after)),
+            crates: diff_rows(crates(before), crates(after)),
+        }
+    }
+
+    pub(crate) fn print(&self, top: usize) {
+        use crate::styles::*;
+
+        println!(
+            "\n{LINK_STYLE}Compared to the baseline{LINK_STYLE:#}: {} -> {} ({})",
+            format_duration(self.before),
+            format_duration(self.after),
+            format_change(self.before, self.after)
+        );
+        for (title, rows) in [("Stages", &self.stages), ("Crates", &self.crates)] {
+            if rows.is_empty() {
+                continue;
+            }
+            println!("  {NOTE_STYLE}{title}{NOTE_STYLE:#}");
+            for (name, before, after) in rows.iter().take(top) {
+                println!("    {:>18}  {name}", format_change(*before, *after));
+            }
+        }
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = format!(
+            "<h2>Compared to the baseline</h2><p>{} &rarr; {} ({})</p>",
+            format_duration(self.before),
+            format_duration(self.after),
+            format_change(self.before, self.after)
+        );
+        for (title, rows) in [("Stages", &self.stages), ("Crates", &self.crates)] {
+            html.push_str(&format!(
+                "<h3>{title}</h3><table><tr><th>Change</th><th>Before</th><th>After</th><th></th></tr>"
+            ));
+            for (name, before, after) in rows.iter().take(25) {
+                html.push_str(&format!(
+                    "<tr class={}><td class=num>{}</td><td class=num>{}</td><td class=num>{}</td><td>{}</td></tr>",
+                    if after > before { "worse" } else { "better" },
+                    format_change(*before, *after),
+                    format_duration(*before),
+                    format_duration(*after),
+                    escape(name)
+                ));
+            }
+            html.push_str("</table>");
+        }
+        html
+    }
+}
+
+/// Pair up the rows of the baseline and the report, dropping those that didn't change
+fn diff_rows(
+    before: BTreeMap<String, u64>,
+    after: BTreeMap<String, u64>,
+) -> Vec<(String, u64, u64)> {
+    let names: HashSet<_> = before.keys().chain(after.keys()).cloned().collect();
+    let mut rows: Vec<_> = names
+        .into_iter()
+        .map(|name| {
+            let before = before.get(&name).copied().unwrap_or_default();
+            let after = after.get(&name).copied().unwrap_or_default();
+            (name, before, after)
+        })
+        .filter(|(_, before, after)| before != after)
+        .collect();
+    rows.sort_by(|a, b| {
+        b.1.abs_diff(b.2)
+            .cmp(&a.1.abs_diff(a.2))
+            .then(a.0.cmp(&b.0))
+    });
+    rows
+}
+
+/// Read a timings file, which doesn't exist if nothing was recorded
+fn read_json<T: Default + serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse the timings at {}", path.display())),
+        Err(_) => Ok(T::default()),
+    }
+}
+
+/// The current time in milliseconds since the unix epoch
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn format_duration(millis: u64) -> String {
+    match millis {
+        0..1000 => format!("{millis}ms"),
+        1000..60_000 => format!("{:.2}s", millis as f64 / 1000.0),
+        _ => format!(
+            "{}m {:.1}s",
+            millis / 60_000,
+            (millis % 60_000) as f64 / 1000.0
+        ),
+    }
+}
+
+fn format_change(before: u64, after: u64) -> String {
+    let sign = if after >= before { "+" } else { "-" };
+    let change = format!("{sign}{}", format_duration(before.abs_diff(after)));
+    match before {
+        0 => change,
+        _ => format!(
+            "{change}, {sign}{:.1}%",
+            before.abs_diff(after) as f64 / before as f64 * 100.0
+        ),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn krate(package: &str, start: u64, duration: u64) -> CrateTiming {
+        CrateTiming {
+            package: package.to_string(),
+            krate: package.replace('-', "_"),
+            start,
+            duration,
+        }
+    }
+
+    #[test]
+    fn stages_are_merged_until_the_stage_changes() {
+        let mut timer = StageTimer::default();
+        timer.record_at(&BuildStage::Initializing, 0);
+        timer.record_at(
+            &BuildStage::Starting {
+                crate_count: 2,
+                patch: false,
+            },
+            10,
+        );
+        for (current, at) in [(1, 50), (2, 90)] {
+            let stage = BuildStage::Compiling {
+                current,
+                total: 2,
+                krate: "app".to_string(),
+            };
+            timer.record_at(&stage, at);
+        }
+        timer.record_at(&BuildStage::RunningBindgen, 100);
+        timer.record_at(&BuildStage::Success, 130);
+
+        assert_eq!(
+            timer.stages,
+            vec![
+                StageTiming {
+                    stage: "compiling".to_string(),
+                    start: 10,
+                    duration: 90,
+                },
+                StageTiming {
+                    stage: "wasm-bindgen".to_string(),
+                    start: 100,
+                    duration: 30,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn critical_path_follows_the_last_dependency_to_finish() {
+        let crates = [
+            krate("serde", 0, 40),
+            krate("syn", 0, 100),
+            krate("serde-derive", 100, 50),
+            krate("app", 150, 30),
+        ];
+        let stages = [
+            StageTiming {
+                stage: "compiling".to_string(),
+                start: 0,
+                duration: 180,
+            },
+            StageTiming {
+                stage: "wasm-opt".to_string(),
+                start: 180,
+                duration: 20,
+            },
+        ];
+        let dependencies = |package: &str| -> Vec<String> {
+            match package {
+                "app" => vec!["serde".into(), "serde-derive".into()],
+                "serde-derive" => vec!["syn".into()],
+                _ => vec![],
+            }
+        };
+
+        let path: Vec<_> = critical_path("app", &crates, &stages, dependencies)
+            .into_iter()
+            .map(|step| (step.name, step.duration))
+            .collect();
+        assert_eq!(
+            path,
+            vec![
+                ("syn".to_string(), 100),
+                ("serde-derive".to_string(), 50),
+                ("app".to_string(), 30),
+                ("wasm-opt".to_string(), 20),
+            ]
+        );
+    }
+}
@@ -34,6 +34,13 @@ impl From<BundleConfig> for tauri_bundler::BundleSettings {
             short_description: val.short_description,
             long_description: val.long_description,
             external_bin: val.external_bin,
+            deep_link_protocols: val.url_schemes.map(|schemes| {
+                vec![tauri_utils::config::DeepLinkProtocol {
+                    schemes,
+                    name: None,
+                    role: Default::default(),
+                }]
+            }),
             deb: val.deb.map(Into::into).unwrap_or_default(),
             macos: val.macos.map(Into::into).unwrap_or_default(),
             windows: val.windows.map(Into::into).unwrap_or_default(),
@@ -148,19 +155,21 @@ impl From<NSISInstallerMode> for tauri_utils::config::NSISInstallerMode {
     }
 }
 
-impl From<PackageType> for tauri_bundler::PackageType {
-    fn from(value: PackageType) -> Self {
-        match value {
-            PackageType::MacOsBundle => Self::MacOsBundle,
-            PackageType::IosBundle => Self::IosBundle,
-            PackageType::WindowsMsi => Self::WindowsMsi,
-            PackageType::Deb => Self::Deb,
-            PackageType::Rpm => Self::Rpm,
-            PackageType::AppImage => Self::AppImage,
-            PackageType::Dmg => Self::Dmg,
-            PackageType::Updater => Self::Updater,
-            PackageType::Nsis => Self::Nsis,
-        }
+impl PackageType {
+    /// The tauri bundler package type, if this package is bundled by tauri instead of dx
+    pub(crate) fn tauri_package_type(self) -> Option<tauri_bundler::PackageType> {
+        Some(match self {
+            PackageType::MacOsBundle => tauri_bundler::PackageType::MacOsBundle,
+            PackageType::IosBundle => tauri_bundler::PackageType::IosBundle,
+            PackageType::WindowsMsi => tauri_bundler::PackageType::WindowsMsi,
+            PackageType::Deb => tauri_bundler::PackageType::Deb,
+            PackageType::Rpm => tauri_bundler::PackageType::Rpm,
+            PackageType::AppImage => tauri_bundler::PackageType::AppImage,
+            PackageType::Dmg => tauri_bundler::PackageType::Dmg,
+            PackageType::Updater => tauri_bundler::PackageType::Updater,
+            PackageType::Nsis => tauri_bundler::PackageType::Nsis,
+            PackageType::Flatpak => return None,
+        })
     }
 }
 
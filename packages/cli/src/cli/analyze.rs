@@ -0,0 +1,702 @@
+//! Break down the size of a web build.
+//!
+//! The bytes of every `.wasm` file in the build are attributed to the functions in its name section, and the
+//! functions are grouped by crate and module using their demangled names. When the app was linked with
+//! `--emit-relocs`, like builds that use `wasm_split`, the data section is attributed to the statics and functions
+//! that own it as well. When the app uses `wasm_split`, every
+//! chunk the splitter emitted is reported on its own so you can see what moved out of the main module.
+//!
+//! The report can be saved as json and compared against later builds so CI can fail when the bundle grows.
+
+use super::*;
+use crate::{BuildMode, BuildRequest, BundleFormat};
+use anyhow::{bail, Context};
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path, str::FromStr};
+use wasm_split_cli::ModuleSizes;
+
+/// Break down the size of the wasm and assets of a web build and compare it against a baseline.
+#[derive(Clone, Debug, Parser)]
+pub(crate) struct Analyze {
+    /// The number of crates, modules and functions to list
+    #[clap(long, default_value_t = 15)]
+    pub(crate) top: usize,
+
+    /// Save the report to this json file so later builds can be compared against it
+    #[clap(long)]
+    pub(crate) save_baseline: Option<PathBuf>,
+
+    /// Compare the build against a report saved with `--save-baseline`
+    #[clap(long)]
+    pub(crate) baseline: Option<PathBuf>,
+
+    /// Fail if the build grew more than this compared to the baseline. Either a size like `20kb` or a percentage
+    /// like `5%`
+    #[clap(long, requires = "baseline")]
+    pub(crate) max_increase: Option<SizeBudget>,
+
+    /// Information about the target to analyze
+    #[clap(flatten)]
+    pub(crate) build_args: CommandWithPlatformOverrides<BuildArgs>,
+}
+
+impl Analyze {
+    pub(crate) async fn analyze(self) -> Result<StructuredOutput> {
+        let BuildTargets { client, .. } = self.build_args.into_targets().await?;
+        if client.bundle != BundleFormat::Web {
+            bail!("`dx analyze` only supports web builds. Pass `--web` to build for the web.");
+        }
+        if !client.release {
+            tracing::warn!(
+                "Analyzing a debug build. Pass `--release` to see the sizes of the build you ship."
+            );
+        }
+
+        let artifacts = CommandWithPlatformOverrides::<BuildArgs>::build_client_inner(
+            &client,
+            BuildMode::Base { run: false },
+        )
+        .await?;
+
+        let report = SizeReport::from_build(&client, &artifacts.exe)?;
+        if report.wasm.iter().all(|wasm| !wasm.has_names()) {
+            tracing::warn!("The wasm has no name section, so functions can't be attributed to crates. Pass `--debug-symbols` to keep it.");
+        }
+        report.print(self.top);
+
+        if let Some(path) = &self.baseline {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read the baseline at {}", path.display()))?;
+            let baseline: SizeReport =
+                serde_json::from_str(&contents).context("Failed to parse the baseline")?;
+            let diff = SizeDiff::new(&baseline, &report);
+            diff.print(self.top);
+
+            if let Some(budget) = self.max_increase {
+                diff.check(budget)?;
+            }
+        }
+
+        if let Some(path) = &self.save_baseline {
+            std::fs::write(path, serde_json::to_string_pretty(&report)?)
+                .with_context(|| format!("Failed to write the baseline to {}", path.display()))?;
+            tracing::info!("Saved the size report to {}", path.display());
+        }
+
+        Ok(StructuredOutput::Success)
+    }
+}
+
+/// The sizes of the files in a web build
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SizeReport {
+    /// The main wasm module and the modules and chunks emitted by `wasm_split`
+    pub(crate) wasm: Vec<WasmSize>,
+
+    /// The size of every asset by its path in the asset folder, without the wasm files
+    pub(crate) assets: BTreeMap<String, u64>,
+}
+
+/// The sizes of the parts of a wasm file
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct WasmSize {
+    /// `main` for the main module, or the file name of a `wasm_split` module or chunk
+    pub(crate) name: String,
+
+    /// The size of the file
+    pub(crate) total: u64,
+
+    /// The size of every section, with custom sections prefixed with `custom:`
+    pub(crate) sections: BTreeMap<String, u64>,
+
+    /// The size of the body of every function. Functions without a name are named after their index.
+    pub(crate) functions: BTreeMap<String, u64>,
+
+    /// The size of the data owned by every static or function. Only builds linked with `--emit-relocs` have the
+    /// symbols to attribute the data section.
+    #[serde(default)]
+    pub(crate) data: BTreeMap<String, u64>,
+}
+
+impl SizeReport {
+    /// Measure the wasm and assets of a finished web build. The linker output is used to attribute the data section.
+    fn from_build(build: &BuildRequest, linked: &Path) -> Result<Self> {
+        let main = build.wasm_bindgen_wasm_output_file();
+        let mut wasm = vec![WasmSize::read("main", &main)?.with_data_symbols(linked)?];
+
+        // The splitter writes its output next to the main module as `module_{idx}_{name}.wasm` and
+        // `chunk_{idx}_{name}.wasm`. Older files might be left over from previous builds, so only read them when the
+        // build used wasm_split.
+        if build.wasm_split {
+            let mut split = std::fs::read_dir(build.wasm_bindgen_out_dir())?
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    (name.starts_with("module_") || name.starts_with("chunk_"))
+                        && name.ends_with(".wasm")
+                })
+                .collect::<Vec<_>>();
+            split.sort();
+            for path in split {
+                let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                wasm.push(WasmSize::read(&name, &path)?);
+            }
+        }
+
+        let asset_dir = build.asset_dir();
+        let mut assets = BTreeMap::new();
+        for entry in walkdir::WalkDir::new(&asset_dir).into_iter().flatten() {
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension().is_some_and(|ext| ext == "wasm") {
+                continue;
+            }
+            let name = path.strip_prefix(&asset_dir).unwrap_or(path);
+            assets.insert(
+                name.to_string_lossy().replace('\\', "/"),
+                entry.metadata()?.len(),
+            );
+        }
+
+        Ok(Self { wasm, assets })
+    }
+
+    fn wasm_total(&self) -> u64 {
+        self.wasm.iter().map(|wasm| wasm.total).sum()
+    }
+
+    fn assets_total(&self) -> u64 {
+        self.assets.values().sum()
+    }
+
+    fn total(&self) -> u64 {
+        self.wasm_total() + self.assets_total()
+    }
+
+    /// The size of every crate across all wasm files
+    fn crates(&self) -> BTreeMap<String, u64> {
+        let mut crates = BTreeMap::new();
+        for wasm in &self.wasm {
+            for (krate, size) in wasm.group_functions(|path| path.krate) {
+                *crates.entry(krate).or_default() += size;
+            }
+        }
+        crates
+    }
+
+    fn print(&self, top: usize) {
+        use crate::styles::*;
+
+        println!(
+            "\n{LINK_STYLE}Total{LINK_STYLE:#}: {GLOW_STYLE}{}{GLOW_STYLE:#} ({} wasm, {} assets)",
+            format_size(self.total()),
+            format_size(self.wasm_total()),
+            format_size(self.assets_total())
+        );
+
+        for wasm in &self.wasm {
+            println!(
+                "\n{LINK_STYLE}{}{LINK_STYLE:#}: {GLOW_STYLE}{}{GLOW_STYLE:#}",
+                wasm.name,
+                format_size(wasm.total)
+            );
+            print_table("Sections", &wasm.sections, top);
+            print_table("Crates", &wasm.group_functions(|path| path.krate), top);
+            print_table("Modules", &wasm.group_functions(|path| path.module), top);
+            print_table("Functions", &wasm.functions, top);
+            if !wasm.data.is_empty() {
+                print_table("Data", &wasm.data, top);
+            }
+        }
+
+        if !self.assets.is_empty() {
+            println!("\n{LINK_STYLE}Assets{LINK_STYLE:#}");
+            print_table("Files", &self.assets, top);
+        }
+    }
+}
+
+impl WasmSize {
+    fn read(name: &str, path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read the wasm at {}", path.display()))?;
+        Self::parse(name, &bytes)
+    }
+
+    fn parse(name: &str, bytes: &[u8]) -> Result<Self> {
+        let sizes = ModuleSizes::new(bytes)?;
+        let mut functions = BTreeMap::new();
+        for function in sizes.functions {
+            let name = match function.name {
+                Some(name) => demangle(&name),
+                None => format!("func[{}]", function.index),
+            };
+            *functions.entry(name).or_default() += function.size;
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            total: bytes.len() as u64,
+            sections: sizes.sections,
+            functions,
+            data: BTreeMap::new(),
+        })
+    }
+
+    /// Attribute the data section to the symbols of the linker output, if it was linked with `--emit-relocs`
+    fn with_data_symbols(mut self, linked: &Path) -> Result<Self> {
+        let Ok(bytes) = std::fs::read(linked) else {
+            return Ok(self);
+        };
+        let sizes = ModuleSizes::default().with_data_symbols(&bytes)?;
+        for (name, size) in sizes.data {
+            *self.data.entry(demangle(&name)).or_default() += size;
+        }
+        Ok(self)
+    }
+
+    /// Check if the wasm kept its name section, which the functions are attributed with
+    fn has_names(&self) -> bool {
+        self.sections.contains_key("custom:name")
+    }
+
+    /// Add up the function and data sizes by a part of their path. Data that isn't owned by a symbol is counted as
+    /// its own group.
+    fn group_functions(&self, key: impl Fn(SymbolPath) -> String) -> BTreeMap<String, u64> {
+        let mut groups = BTreeMap::new();
+        for (name, size) in self.functions.iter().chain(&self.data) {
+            *groups.entry(key(SymbolPath::new(name))).or_default() += size;
+        }
+
+        // The optimizer packs the data after linking, so the symbols can add up to more than the data section
+        let attributed: u64 = self.data.values().sum();
+        let unattributed = self
+            .sections
+            .get("data")
+            .map_or(0, |data| data.saturating_sub(attributed));
+        if unattributed > 0 {
+            groups.insert("[data]".to_string(), unattributed);
+        }
+        groups
+    }
+}
+
+/// Demangle a rust symbol like `_ZN4core3fmt5write17h0123456789abcdefE` into `core::fmt::write`, without the hash.
+///
+/// Other names, like the functions of the allocator, are returned as they are.
+fn demangle(name: &str) -> String {
+    format!("{:#}", rustc_demangle::demangle(name))
+}
+
+/// The crate and module of a demangled function name
+#[derive(Debug, PartialEq)]
+struct SymbolPath {
+    krate: String,
+    module: String,
+}
+
+impl SymbolPath {
+    /// Functions that are not part of a rust path, like the functions of the allocator or wasm-bindgen shims, are
+    /// grouped in `[other]`
+    fn new(name: &str) -> Self {
+        // Trait impls like `<alloc::vec::Vec<T> as core::ops::Drop>::drop` belong to the type they are implemented on
+        let name = name.trim_start_matches(['<', '&', '*', '(', '[']);
+        let name = ["mut ", "const ", "dyn "]
+            .iter()
+            .fold(name, |name, prefix| name.trim_start_matches(prefix));
+
+        let end = name
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+            .unwrap_or(name.len());
+        let (path, rest) = name.split_at(end);
+
+        // A path that ends in `::` is followed by an impl block or closure, which is part of the last module
+        let ends_in_module = path.ends_with("::");
+        let segments: Vec<&str> = path.split("::").filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() || (segments.len() < 2 && !ends_in_module && rest.is_empty()) {
+            return Self {
+                krate: "[other]".to_string(),
+                module: "[other]".to_string(),
+            };
+        }
+
+        let module = match ends_in_module {
+            true => &segments[..],
+            false => &segments[..segments.len().saturating_sub(1).max(1)],
+        };
+        Self {
+            krate: segments.first().unwrap_or(&"[other]").to_string(),
+            module: module.join("::"),
+        }
+    }
+}
+
+/// The change between a baseline and the current build
+struct SizeDiff {
+    before: u64,
+    after: u64,
+    files: Vec<(String, u64, u64)>,
+    crates: Vec<(String, u64, u64)>,
+    functions: Vec<(String, u64, u64)>,
+}
+
+impl SizeDiff {
+    fn new(baseline: &SizeReport, current: &SizeReport) -> Self {
+        let mut files = BTreeMap::new();
+        let mut functions = BTreeMap::new();
+        for (wasm, is_current) in baseline
+            .wasm
+            .iter()
+            .map(|wasm| (wasm, false))
+            .chain(current.wasm.iter().map(|wasm| (wasm, true)))
+        {
+            add(&mut files, &wasm.name, wasm.total, is_current);
+            for (name, size) in wasm.functions.iter().chain(&wasm.data) {
+                add(&mut functions, name, *size, is_current);
+            }
+        }
+        add(&mut files, "assets", baseline.assets_total(), false);
+        add(&mut files, "assets", current.assets_total(), true);
+
+        let mut crates = BTreeMap::new();
+        for (name, size) in baseline.crates() {
+            add(&mut crates, &name, size, false);
+        }
+        for (name, size) in current.crates() {
+            add(&mut crates, &name, size, true);
+        }
+
+        Self {
+            before: baseline.total(),
+            after: current.total(),
+            files: changes(files),
+            crates: changes(crates),
+            functions: changes(functions),
+        }
+    }
+
+    fn print(&self, top: usize) {
+        use crate::styles::*;
+
+        println!(
+            "\n{LINK_STYLE}Compared to the baseline{LINK_STYLE:#}: {} -> {} ({})",
+            format_size(self.before),
+            format_size(self.after),
+            format_change(self.before, self.after)
+        );
+        for (title, rows) in [
+            ("Files", &self.files),
+            ("Crates", &self.crates),
+            ("Functions", &self.functions),
+        ] {
+            if rows.is_empty() {
+                continue;
+            }
+            println!("  {NOTE_STYLE}{title}{NOTE_STYLE:#}");
+            for (name, before, after) in rows.iter().take(top) {
+                println!("    {:>12}  {name}", format_change(*before, *after));
+            }
+        }
+    }
+
+    /// Fail if the build grew more than the budget allows
+    fn check(&self, budget: SizeBudget) -> Result<()> {
+        let increase = self.after.saturating_sub(self.before);
+        let allowed = match budget {
+            SizeBudget::Bytes(bytes) => bytes,
+            SizeBudget::Percent(percent) => (self.before as f64 * percent / 100.0) as u64,
+        };
+        if increase > allowed {
+            bail!(
+                "The build grew by {} which is more than the allowed {}",
+                format_size(increase),
+                format_size(allowed)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The largest increase the build may have compared to the baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SizeBudget {
+    Bytes(u64),
+    Percent(f64),
+}
+
+impl FromStr for SizeBudget {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        if let Some(percent) = s.strip_suffix('%') {
+            return percent
+                .trim()
+                .parse()
+                .map(SizeBudget::Percent)
+                .map_err(|_| format!("Invalid percentage {s:?}"));
+        }
+
+        let (number, unit) = match s.find(|c: char| !(c.is_ascii_digit() || c == '.')) {
+            Some(idx) => s.split_at(idx),
+            None => (s.as_str(), "b"),
+        };
+        let multiplier = match unit.trim() {
+            "b" => 1.0,
+            "kb" | "k" => 1024.0,
+            "mb" | "m" => 1024.0 * 1024.0,
+            unit => return Err(format!("Unknown size unit {unit:?}, expected b, kb or mb")),
+        };
+        let number: f64 = number.parse().map_err(|_| format!("Invalid size {s:?}"))?;
+        Ok(SizeBudget::Bytes((number * multiplier) as u64))
+    }
+}
+
+fn add(map: &mut BTreeMap<String, (u64, u64)>, name: &str, size: u64, is_current: bool) {
+    let entry = map.entry(name.to_string()).or_default();
+    match is_current {
+        true => entry.1 += size,
+        false => entry.0 += size,
+    }
+}
+
+/// The entries that changed, with the largest change first
+fn changes(map: BTreeMap<String, (u64, u64)>) -> Vec<(String, u64, u64)> {
+    let mut changes: Vec<_> = map
+        .into_iter()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(name, (before, after))| (name, before, after))
+        .collect();
+    changes.sort_by_key(|(_, before, after)| std::cmp::Reverse(before.abs_diff(*after)));
+    changes
+}
+
+fn print_table(title: &str, rows: &BTreeMap<String, u64>, top: usize) {
+    use crate::styles::*;
+
+    let total: u64 = rows.values().sum();
+    let mut rows: Vec<_> = rows.iter().collect();
+    rows.sort_by_key(|(_, size)| std::cmp::Reverse(**size));
+
+    println!("  {NOTE_STYLE}{title}{NOTE_STYLE:#}");
+    for (name, size) in rows.iter().take(top) {
+        let percent = **size as f64 * 100.0 / total.max(1) as f64;
+        println!(
+            "    {:>10} {HINT_STYLE}{percent:>5.1}%{HINT_STYLE:#}  {name}",
+            format_size(**size)
+        );
+    }
+    if rows.len() > top {
+        println!(
+            "    {HINT_STYLE}... and {} more{HINT_STYLE:#}",
+            rows.len() - top
+        );
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.2} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn format_change(before: u64, after: u64) -> String {
+    let sign = if after >= before { "+" } else { "-" };
+    let change = format!("{sign}{}", format_size(before.abs_diff(after)));
+    match before {
+        0 => change,
+        _ => format!(
+            "{change} {sign}{:.1}%",
+            before.abs_diff(after) as f64 * 100.0 / before as f64
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_encoder::{
+        CodeSection, ConstExpr, DataSection, DataSymbolDefinition, EntityType, Function,
+        FunctionSection, ImportSection, Instruction, LinkingSection, MemorySection, MemoryType,
+        Module, NameMap, NameSection, SymbolTable, TypeSection,
+    };
+
+    fn module_with_functions(functions: &[(&str, usize)]) -> Vec<u8> {
+        let mut types = TypeSection::new();
+        types.ty().function([], []);
+
+        let mut imports = ImportSection::new();
+        imports.import("env", "imported", EntityType::Function(0));
+
+        let mut declarations = FunctionSection::new();
+        let mut code = CodeSection::new();
+        let mut names = NameMap::new();
+        names.append(0, "imported");
+        for (idx, (name, nops)) in functions.iter().enumerate() {
+            declarations.function(0);
+            let mut function = Function::new([]);
+            for _ in 0..*nops {
+                function.instruction(&Instruction::Nop);
+            }
+            function.instruction(&Instruction::End);
+            code.function(&function);
+            names.append(idx as u32 + 1, name);
+        }
+
+        let mut name_section = NameSection::new();
+        name_section.functions(&names);
+
+        let mut module = Module::new();
+        module
+            .section(&types)
+            .section(&imports)
+            .section(&declarations)
+            .section(&code)
+            .section(&name_section);
+        module.finish()
+    }
+
+    #[test]
+    fn functions_are_attributed_to_crates() {
+        let bytes = module_with_functions(&[
+            ("_ZN11dioxus_core4diff6create17h0123456789abcdefE", 100),
+            ("_ZN5alloc3vec4push17h0123456789abcdefE", 10),
+            ("__wbindgen_malloc", 1),
+        ]);
+        let wasm = WasmSize::parse("main", &bytes).unwrap();
+
+        assert_eq!(wasm.total, bytes.len() as u64);
+        assert_eq!(wasm.functions.len(), 3);
+        assert_eq!(wasm.functions["dioxus_core::diff::create"], 103);
+        assert!(wasm.sections.contains_key("code"));
+        assert!(wasm.sections.contains_key("custom:name"));
+
+        let crates = wasm.group_functions(|path| path.krate);
+        assert_eq!(crates["dioxus_core"], 103);
+        assert_eq!(crates["alloc"], 13);
+        assert_eq!(crates["[other]"], 4);
+    }
+
+    #[test]
+    fn data_is_attributed_to_symbols() {
+        let mut memory = MemorySection::new();
+        memory.memory(MemoryType {
+            minimum: 1,
+            maximum: None,
+            memory64: false,
+            shared: false,
+            page_size_log2: None,
+        });
+        let mut data = DataSection::new();
+        data.active(0, &ConstExpr::i32_const(0), vec![0; 100]);
+
+        // A static that owns 60 bytes and a string literal that owns 30. The rest isn't owned by a symbol
+        let mut symbols = SymbolTable::new();
+        let definition = |offset, size| DataSymbolDefinition {
+            index: 0,
+            offset,
+            size,
+        };
+        symbols.data(
+            0,
+            "_ZN11dioxus_core6STATIC17h0123456789abcdefE",
+            Some(definition(0, 60)),
+        );
+        symbols.data(0, ".L.str.1", Some(definition(60, 30)));
+        let mut linking = LinkingSection::new();
+        linking.symbol_table(&symbols);
+
+        let mut module = Module::new();
+        module.section(&memory).section(&data).section(&linking);
+        let linked = module.finish();
+        let path = std::env::temp_dir().join(format!("dx-analyze-{}.wasm", std::process::id()));
+        std::fs::write(&path, &linked).unwrap();
+
+        let wasm = WasmSize::parse("main", &module_with_functions(&[]))
+            .unwrap()
+            .with_data_symbols(&path)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(wasm.data["dioxus_core::STATIC"], 60);
+        assert_eq!(wasm.data[".L.str.1"], 30);
+
+        let wasm = WasmSize {
+            sections: BTreeMap::from([("data".to_string(), 110)]),
+            ..wasm
+        };
+        let crates = wasm.group_functions(|path| path.krate);
+        assert_eq!(crates["dioxus_core"], 60);
+        assert_eq!(crates["[data]"], 20);
+    }
+
+    #[test]
+    fn symbols_are_demangled() {
+        assert_eq!(
+            demangle("_ZN11dioxus_core4diff6create17h0123456789abcdefE"),
+            "dioxus_core::diff::create"
+        );
+        assert_eq!(
+            demangle("_ZN66_$LT$alloc..vec..Vec$LT$T$GT$$u20$as$u20$core..ops..drop..Drop$GT$4drop17h0123456789abcdefE"),
+            "<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop"
+        );
+        assert_eq!(
+            demangle("_RNvNtCs1234_11dioxus_core4diff6create"),
+            "dioxus_core::diff::create"
+        );
+        assert_eq!(demangle("__wbindgen_malloc"), "__wbindgen_malloc");
+    }
+
+    #[test]
+    fn symbol_paths() {
+        let path = |name| SymbolPath::new(name);
+        assert_eq!(
+            path("<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop"),
+            SymbolPath {
+                krate: "alloc".to_string(),
+                module: "alloc::vec".to_string()
+            }
+        );
+        assert_eq!(
+            path("dioxus_core::diff::node::<impl dioxus_core::nodes::VNode>::create"),
+            SymbolPath {
+                krate: "dioxus_core".to_string(),
+                module: "dioxus_core::diff::node".to_string()
+            }
+        );
+        assert_eq!(path("memcpy").krate, "[other]");
+    }
+
+    #[test]
+    fn budgets() {
+        assert_eq!("5%".parse(), Ok(SizeBudget::Percent(5.0)));
+        assert_eq!("20kb".parse(), Ok(SizeBudget::Bytes(20 * 1024)));
+        assert_eq!("1.5 MB".parse(), Ok(SizeBudget::Bytes(1_572_864)));
+        assert_eq!("300".parse(), Ok(SizeBudget::Bytes(300)));
+        assert!("10 parsecs".parse::<SizeBudget>().is_err());
+    }
+
+    #[test]
+    fn regressions_fail_the_budget() {
+        let report = |main: u64, asset: u64| SizeReport {
+            wasm: vec![WasmSize {
+                name: "main".to_string(),
+                total: main,
+                functions: BTreeMap::from([("app::main".to_string(), main)]),
+                ..Default::default()
+            }],
+            assets: BTreeMap::from([("style.css".to_string(), asset)]),
+        };
+
+        let diff = SizeDiff::new(&report(1000, 0), &report(1040, 20));
+        assert_eq!(diff.files[0], ("main".to_string(), 1000, 1040));
+        assert_eq!(diff.crates, vec![("app".to_string(), 1000, 1040)]);
+        assert!(diff.check(SizeBudget::Percent(10.0)).is_ok());
+        assert!(diff.check(SizeBudget::Percent(5.0)).is_err());
+        assert!(diff.check(SizeBudget::Bytes(50)).is_err());
+
+        let shrunk = SizeDiff::new(&report(1000, 0), &report(500, 0));
+        assert!(shrunk.check(SizeBudget::Bytes(0)).is_ok());
+    }
+}
@@ -2,8 +2,9 @@ use dioxus_dx_wire_format::StructuredBuildArtifacts;
 
 use crate::{
     cli::*, Anonymized, AppBuilder, BuildArtifacts, BuildMode, BuildRequest, BundleFormat,
-    TargetArgs, Workspace,
+    TargetArgs, TimingDiff, TimingReport, Workspace,
 };
+use std::path::Path;
 
 /// Build the Rust Dioxus app and all of its assets.
 ///
@@ -62,6 +63,108 @@ impl Anonymized for BuildArgs {
     }
 }
 
+/// The arguments of `dx build`, which can also profile the build.
+#[derive(Clone, Debug, Parser)]
+pub struct BuildCommand {
+    /// Profile the build and write an html and json report of where the time went to `target/dx-timings`
+    ///
+    /// The report covers the stages of the build, every rustc invocation, every asset and the critical path. It is
+    /// compared against the previous report, or the one passed with `--timings-baseline`.
+    #[clap(long)]
+    pub(crate) timings: bool,
+
+    /// Compare the timings against this json report instead of the previous one
+    #[clap(long, requires = "timings")]
+    pub(crate) timings_baseline: Option<PathBuf>,
+
+    /// The arguments for the dioxus build
+    #[clap(flatten)]
+    pub(crate) args: CommandWithPlatformOverrides<BuildArgs>,
+}
+
+impl BuildCommand {
+    pub async fn build(self) -> Result<StructuredOutput> {
+        tracing::info!("Building project...");
+
+        let force_sequential = self.args.shared.force_sequential_build();
+        let ssg = self.args.shared.ssg;
+        let timings = self.timings;
+        let timings_baseline = self.timings_baseline.clone();
+        let mode = match self.args.shared.fat_binary {
+            true => BuildMode::Fat,
+            false => BuildMode::Base { run: false },
+        };
+        let mut targets = self.args.into_targets().await?;
+
+        targets.client.timings = timings;
+        if let Some(server) = targets.server.as_mut() {
+            server.timings = timings;
+        }
+        let started = crate::build::now();
+
+        let build_client = CommandWithPlatformOverrides::<BuildArgs>::build_client_inner(
+            &targets.client,
+            mode.clone(),
+        );
+        let build_server = CommandWithPlatformOverrides::<BuildArgs>::build_server_inner(
+            &targets.server,
+            mode.clone(),
+            ssg,
+        );
+
+        let (client, server) = match force_sequential {
+            true => (build_client.await, build_server.await),
+            false => tokio::join!(build_client, build_server),
+        };
+
+        if timings {
+            let mut builds = vec![("client", &targets.client)];
+            builds.extend(targets.server.iter().map(|server| ("server", server)));
+            Self::report_timings(started, &builds, timings_baseline.as_deref())?;
+        }
+
+        Ok(StructuredOutput::BuildsFinished {
+            client: client?.into_structured_output(),
+            server: server?.map(|s| s.into_structured_output()),
+        })
+    }
+
+    /// Print the timings of the builds and save them, comparing them against the baseline
+    fn report_timings(
+        started: u64,
+        builds: &[(&str, &BuildRequest)],
+        baseline: Option<&Path>,
+    ) -> Result<()> {
+        let report = TimingReport::from_builds(started, builds)?;
+        let dir = TimingReport::dir(&builds[0].1.target_dir);
+
+        // Without a baseline, compare against the report of the previous `--timings` build
+        let baseline = match baseline {
+            Some(path) => Some(TimingReport::load(path)?),
+            None => TimingReport::load(&dir.join("dx-timing.json")).ok(),
+        };
+
+        report.print(10);
+        if let Some(baseline) = baseline.as_ref() {
+            TimingDiff::new(baseline, &report).print(10);
+        }
+
+        let html = report.save(&dir, baseline.as_ref())?;
+        tracing::info!("Wrote the timings report to {}", html.display());
+
+        Ok(())
+    }
+}
+
+impl Anonymized for BuildCommand {
+    fn anonymized(&self) -> Value {
+        json! {{
+            "timings": self.timings,
+            "args": self.args.anonymized(),
+        }}
+    }
+}
+
 pub struct BuildTargets {
     pub client: BuildRequest,
     pub server: Option<BuildRequest>,
@@ -107,11 +210,14 @@ impl CommandWithPlatformOverrides<BuildArgs> {
         // Create the client build request
         let client = BuildRequest::new(client_args, workspace.clone()).await?;
 
-        // Create the server build request if needed
+        // Create the server build request if needed.
+        //
+        // Tests are a single binary, so they never have a separate server build.
         let mut server = None;
-        if matches!(self.shared.fullstack, Some(true))
-            || client.fullstack_feature_enabled()
-            || self.server.is_some()
+        if client.test_target.is_none()
+            && (matches!(self.shared.fullstack, Some(true))
+                || client.fullstack_feature_enabled()
+                || self.server.is_some())
         {
             match self.server.as_mut() {
                 Some(server_args) => {
@@ -142,31 +248,6 @@ impl CommandWithPlatformOverrides<BuildArgs> {
         Ok(BuildTargets { client, server })
     }
 
-    pub async fn build(self) -> Result<StructuredOutput> {
-        tracing::info!("Building project...");
-
-        let force_sequential = self.shared.force_sequential_build();
-        let ssg = self.shared.ssg;
-        let mode = match self.shared.fat_binary {
-            true => BuildMode::Fat,
-            false => BuildMode::Base { run: false },
-        };
-        let targets = self.into_targets().await?;
-
-        let build_client = Self::build_client_inner(&targets.client, mode.clone());
-        let build_server = Self::build_server_inner(&targets.server, mode.clone(), ssg);
-
-        let (client, server) = match force_sequential {
-            true => (build_client.await, build_server.await),
-            false => tokio::join!(build_client, build_server),
-        };
-
-        Ok(StructuredOutput::BuildsFinished {
-            client: client?.into_structured_output(),
-            server: server?.map(|s| s.into_structured_output()),
-        })
-    }
-
     pub(crate) async fn build_client_inner(
         request: &BuildRequest,
         mode: BuildMode,
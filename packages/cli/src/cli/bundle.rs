@@ -1,4 +1,7 @@
-use crate::{AppBuilder, BuildArgs, BuildMode, BuildRequest, BundleFormat};
+use crate::{
+    linux_bundle::{write_icons, LinuxApp},
+    AppBuilder, BuildArgs, BuildMode, BuildRequest, BundleFormat, FlatpakSettings,
+};
 use anyhow::{bail, Context};
 use path_absolutize::Absolutize;
 use std::collections::HashMap;
@@ -77,6 +80,11 @@ impl Bundle {
                 for bundle in Self::bundle_desktop(&client, &self.package_types)? {
                     bundles.extend(bundle.bundle_paths);
                 }
+
+                if let Some(flatpak) = Self::flatpak_settings(&client, &self.package_types)? {
+                    tracing::info!("Bundling flatpak...");
+                    bundles.extend(crate::linux_bundle::write_flatpak(&client, &flatpak)?);
+                }
             }
 
             // Web/ios can just use their root_dir
@@ -96,6 +104,18 @@ impl Bundle {
             }
         };
 
+        // Package the server and the web assets into a container image
+        if let Some(container) = client.config.bundle.container.as_ref() {
+            match server.as_ref() {
+                Some(server) => bundles.push(crate::container::write_container_image(
+                    server, &client, container,
+                )?),
+                None => tracing::warn!(
+                    "Skipping the container image since there is no server. Enable the fullstack feature to bundle a server."
+                ),
+            }
+        }
+
         // Copy the bundles to the output directory if one was specified
         let crate_outdir = client.crate_out_dir();
         if let Some(outdir) = self.out_dir.clone().or(crate_outdir) {
@@ -130,6 +150,22 @@ impl Bundle {
             }
         }
 
+        // Sign the installer and add it to the update manifest so the app can update itself
+        if let Some(updater) = client.config.bundle.updater.as_ref() {
+            if matches!(
+                client.bundle,
+                BundleFormat::MacOS | BundleFormat::Linux | BundleFormat::Windows
+            ) {
+                for file in
+                    crate::update_manifest::write_update_manifest(&client, updater, &bundles)?
+                {
+                    if !bundles.contains(&file) {
+                        bundles.push(file);
+                    }
+                }
+            }
+        }
+
         for bundle_path in bundles.iter() {
             tracing::info!(
                 "Bundled app at: {}",
@@ -147,6 +183,38 @@ impl Bundle {
         })
     }
 
+    /// The flatpak settings if a flatpak should be bundled, either because it was asked for or because the
+    /// `[bundle.linux.flatpak]` section is in `Dioxus.toml`
+    fn flatpak_settings(
+        build: &BuildRequest,
+        package_types: &Option<Vec<crate::PackageType>>,
+    ) -> Result<Option<FlatpakSettings>> {
+        let settings = build
+            .config
+            .bundle
+            .linux
+            .as_ref()
+            .and_then(|linux| linux.flatpak.clone());
+
+        let requested = match package_types {
+            Some(packages) => packages.contains(&crate::PackageType::Flatpak),
+            None => settings.is_some(),
+        };
+
+        if !requested {
+            return Ok(None);
+        }
+
+        if build.bundle != BundleFormat::Linux {
+            bail!(
+                "Flatpaks can only be bundled for linux, but the app is bundled for {}",
+                build.bundle
+            );
+        }
+
+        Ok(Some(settings.unwrap_or_default()))
+    }
+
     fn bundle_desktop(
         build: &BuildRequest,
         package_types: &Option<Vec<crate::PackageType>>,
@@ -172,6 +240,20 @@ impl Bundle {
                 .set_src_path(Some(exe.display().to_string())),
         ];
 
+        // Flatpaks are bundled by dx, the rest by tauri
+        let package_types = package_types.as_ref().map(|packages| {
+            packages
+                .iter()
+                .filter_map(|package| package.tauri_package_type())
+                .collect::<Vec<_>>()
+        });
+        if package_types
+            .as_ref()
+            .is_some_and(|packages| packages.is_empty())
+        {
+            return Ok(vec![]);
+        }
+
         let mut bundle_settings: BundleSettings = krate.config.bundle.clone().into();
 
         // Check if required fields are provided instead of failing silently.
@@ -196,6 +278,10 @@ impl Bundle {
             }
         }
 
+        if build.bundle == BundleFormat::Linux {
+            Self::add_linux_desktop_integration(build, &mut bundle_settings)?;
+        }
+
         if bundle_settings.resources_map.is_none() {
             bundle_settings.resources_map = Some(HashMap::new());
         }
@@ -245,8 +331,8 @@ impl Bundle {
             .binaries(binaries)
             .bundle_settings(bundle_settings);
 
-        if let Some(packages) = &package_types {
-            settings = settings.package_types(packages.iter().map(|p| (*p).into()).collect());
+        if let Some(packages) = package_types {
+            settings = settings.package_types(packages);
         }
 
         settings = settings.target(build.triple.to_string());
@@ -268,4 +354,51 @@ impl Bundle {
 
         Ok(bundles)
     }
+
+    /// Replace the basic desktop file tauri writes for the deb, rpm and AppImage bundles with one that opens files
+    /// and links, install the icon at every size and add the AppStream metainfo.
+    fn add_linux_desktop_integration(
+        build: &BuildRequest,
+        bundle_settings: &mut BundleSettings,
+    ) -> Result<()> {
+        let app = LinuxApp::new(build)?;
+        let bundle_dir = build.bundle_dir(build.bundle);
+
+        // The desktop file is a handlebars template, but ours is already rendered
+        if bundle_settings.deb.desktop_template.is_none() {
+            let template = bundle_dir.join("main.desktop.hbs");
+            let entry = app.desktop_entry(&app.exe, &app.exe);
+            std::fs::write(&template, entry.replace("{{", "\\{{"))
+                .context("Failed to write the desktop file template")?;
+            bundle_settings.deb.desktop_template = Some(template.clone());
+            bundle_settings.rpm.desktop_template = Some(template);
+        }
+
+        // tauri installs png icons at the size they are, so we give it one of each size
+        let icons = write_icons(build, &bundle_dir.join("icons"), &app.exe)?;
+        let pngs = icons
+            .iter()
+            .filter(|icon| icon.extension().is_some_and(|ext| ext == "png"))
+            .map(|icon| icon.display().to_string())
+            .collect::<Vec<_>>();
+        if !pngs.is_empty() {
+            bundle_settings.icon = Some(pngs);
+        }
+
+        // tauri names the desktop file after the product
+        let metainfo_name = format!("{}.metainfo.xml", app.id);
+        let metainfo = bundle_dir.join(&metainfo_name);
+        std::fs::write(&metainfo, app.metainfo(&format!("{}.desktop", app.name)))
+            .context("Failed to write the AppStream metainfo")?;
+        let installed = PathBuf::from("usr/share/metainfo").join(metainfo_name);
+        for files in [
+            &mut bundle_settings.deb.files,
+            &mut bundle_settings.rpm.files,
+            &mut bundle_settings.appimage.files,
+        ] {
+            files.insert(installed.clone(), metainfo.clone());
+        }
+
+        Ok(())
+    }
 }
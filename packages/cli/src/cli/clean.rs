@@ -0,0 +1,36 @@
+use super::*;
+use crate::Workspace;
+use anyhow::Context;
+
+/// Remove the build artifacts that `dx` created in the target directory.
+///
+/// This removes `target/dx`, which holds the builds, bundles and the optimized assets cache of every package in the
+/// workspace. Cargo's own build cache is left alone - use `cargo clean` to remove it.
+#[derive(Clone, Debug, Parser)]
+pub(crate) struct Clean {
+    /// Only remove the optimized assets cache that is shared between builds
+    #[clap(long)]
+    pub(crate) assets: bool,
+}
+
+impl Clean {
+    pub(crate) async fn clean(self) -> Result<StructuredOutput> {
+        let workspace = Workspace::current().await?;
+
+        if self.assets {
+            let cache = workspace.asset_cache();
+            cache.clear()?;
+            tracing::info!("Removed the asset cache at {}", cache.dir().display());
+            return Ok(StructuredOutput::Success);
+        }
+
+        let dx_dir = workspace.target_dir().join("dx");
+        if dx_dir.exists() {
+            std::fs::remove_dir_all(&dx_dir)
+                .with_context(|| format!("Failed to remove {}", dx_dir.display()))?;
+        }
+        tracing::info!("Removed {}", dx_dir.display());
+
+        Ok(StructuredOutput::Success)
+    }
+}
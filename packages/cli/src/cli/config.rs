@@ -38,6 +38,8 @@ pub(crate) enum Setting {
     WSLFilePollInterval { value: u16 },
     /// Disable the built-in telemetry for the CLI
     DisableTelemetry { value: BoolValue },
+    /// Set the maximum size of the optimized assets cache, in megabytes.
+    AssetCacheMaxSize { value: u64 },
 }
 
 impl Display for Setting {
@@ -48,6 +50,7 @@ impl Display for Setting {
             Self::AlwaysOnTop { value: _ } => write!(f, "always-on-top"),
             Self::WSLFilePollInterval { value: _ } => write!(f, "wsl-file-poll-interval"),
             Self::DisableTelemetry { value: _ } => write!(f, "disable-telemetry"),
+            Self::AssetCacheMaxSize { value: _ } => write!(f, "asset-cache-max-size"),
         }
     }
 }
@@ -114,6 +117,9 @@ impl Config {
                     Setting::DisableTelemetry { value } => {
                         settings.disable_telemetry = Some(value.into());
                     }
+                    Setting::AssetCacheMaxSize { value } => {
+                        settings.asset_cache_max_size = Some(value)
+                    }
                 })?;
                 tracing::info!(dx_src = ?TraceSrc::Dev, "🚩 CLI setting `{setting}` has been set.");
             }
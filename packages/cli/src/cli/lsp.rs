@@ -0,0 +1,28 @@
+use super::*;
+use crate::lsp::LanguageServer;
+
+/// Run a language server for rsx over stdio.
+///
+/// Point your editor's LSP client at `dx lsp` to format `rsx!` blocks, see hook issues from `dx check` as you type,
+/// and get completions and docs for elements and attributes.
+#[derive(Clone, Debug, Parser)]
+pub(crate) struct Lsp {
+    /// Split attributes in lines or not when formatting
+    #[clap(long, short = 's', default_value = "false")]
+    pub(crate) split_line_attributes: bool,
+}
+
+impl Lsp {
+    pub(crate) async fn lsp(self) -> Result<StructuredOutput> {
+        // The language server is synchronous and speaks over stdin and stdout, so it gets its own thread
+        tokio::task::spawn_blocking(move || {
+            let (connection, io_threads) = lsp_server::Connection::stdio();
+            LanguageServer::run(connection, self.split_line_attributes)?;
+            io_threads.join()?;
+            anyhow::Ok(())
+        })
+        .await??;
+
+        Ok(StructuredOutput::Success)
+    }
+}
@@ -1,19 +1,23 @@
+pub(crate) mod analyze;
 pub(crate) mod autoformat;
 pub(crate) mod build;
 pub(crate) mod build_assets;
 pub(crate) mod bundle;
 pub(crate) mod check;
+pub(crate) mod clean;
 pub(crate) mod config;
 pub(crate) mod create;
 pub(crate) mod doctor;
 pub(crate) mod hotpatch;
 pub(crate) mod init;
 pub(crate) mod link;
+pub(crate) mod lsp;
 pub(crate) mod platform_override;
 pub(crate) mod print;
 pub(crate) mod run;
 pub(crate) mod serve;
 pub(crate) mod target;
+pub(crate) mod test;
 pub(crate) mod translate;
 pub(crate) mod update;
 pub(crate) mod verbosity;
@@ -21,6 +25,7 @@ pub(crate) mod verbosity;
 pub(crate) use build::*;
 pub(crate) use serve::*;
 pub(crate) use target::*;
+pub(crate) use test::*;
 pub(crate) use verbosity::*;
 
 use crate::platform_override::CommandWithPlatformOverrides;
@@ -62,13 +67,17 @@ pub(crate) enum Commands {
     #[clap(name = "serve")]
     Serve(serve::ServeArgs),
 
+    /// Run the tests and re-run them whenever the code changes, hotpatching them when possible.
+    #[clap(name = "test")]
+    Test(test::TestArgs),
+
     /// Bundle the Dioxus app into a shippable object.
     #[clap(name = "bundle")]
     Bundle(bundle::Bundle),
 
     /// Build the Dioxus project and all of its assets.
     #[clap(name = "build")]
-    Build(CommandWithPlatformOverrides<build::BuildArgs>),
+    Build(build::BuildCommand),
 
     /// Run the project without any hotreloading.
     #[clap(name = "run")]
@@ -101,6 +110,18 @@ pub(crate) enum Commands {
     #[clap(name = "check")]
     Check(check::Check),
 
+    /// Run a language server for rsx, for editors without a Dioxus extension.
+    #[clap(name = "lsp")]
+    Lsp(lsp::Lsp),
+
+    /// Break down the size of a web build and compare it against a baseline.
+    #[clap(name = "analyze")]
+    Analyze(analyze::Analyze),
+
+    /// Remove the build artifacts and the optimized assets cache created by dx.
+    #[clap(name = "clean")]
+    Clean(clean::Clean),
+
     /// Dioxus config file controls.
     #[clap(subcommand)]
     #[clap(name = "config")]
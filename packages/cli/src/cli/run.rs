@@ -168,6 +168,7 @@ impl RunArgs {
                 ServeUpdate::RequestRebuild => {}
                 ServeUpdate::ToggleShouldRebuild => {}
                 ServeUpdate::OpenDebugger { .. } => {}
+                ServeUpdate::RequestReactiveGraph => {}
                 ServeUpdate::Redraw => {}
                 ServeUpdate::TracingLog { .. } => {}
             }
@@ -64,6 +64,17 @@ pub(crate) struct ServeArgs {
     #[clap(long, default_missing_value = "true", num_args=0..=1)]
     pub(crate) watch: Option<bool>,
 
+    /// Record the traffic of every `[[web.proxy]]` backend to fixtures, or replay the fixtures without the backends
+    #[clap(long, value_enum)]
+    pub(crate) proxy_mode: Option<crate::ProxyMode>,
+
+    /// Serve several apps at once, like `--app site --app admin`
+    ///
+    /// Each app is either an app in the `[serve.apps]` section of Dioxus.toml or a package in the workspace.
+    /// If this isn't set, every app in `[serve.apps]` is served unless a package is given.
+    #[clap(long = "app", value_name = "APP")]
+    pub(crate) apps: Vec<String>,
+
     /// Exit the CLI after running into an error. This is mainly used to test hot patching internally
     #[clap(long)]
     #[clap(hide = true)]
@@ -122,6 +133,8 @@ impl Anonymized for ServeArgs {
             "interactive": self.interactive,
             "hot_patch": self.hot_patch,
             "watch": self.watch,
+            "proxy_mode": self.proxy_mode,
+            "apps": self.apps.len(),
             "exit_on_error": self.exit_on_error,
             "platform_args": self.platform_args.anonymized(),
         }}
@@ -45,7 +45,10 @@ pub(crate) struct TargetArgs {
     #[clap(long, help_heading = HELP_HEADING)]
     pub(crate) example: Option<String>,
 
-    /// Build the app with custom a profile
+    /// Build the app with a custom profile
+    ///
+    /// This selects the `[profile.<name>]` section of Dioxus.toml and loads the `.env.<name>` file. The cargo
+    /// profile with the same name is used if it exists.
     #[clap(long, help_heading = HELP_HEADING)]
     pub(crate) profile: Option<String>,
 
@@ -153,6 +156,17 @@ pub(crate) struct TargetArgs {
     /// Automatically pass `--features=js_cfg` when building for wasm targets. This is enabled by default.
     #[clap(long, default_value_t = true, help_heading = HELP_HEADING, num_args = 0..=1)]
     pub(crate) wasm_js_cfg: bool,
+
+    /// The path the devtools of the devserver are served under, if it isn't `/_dioxus`. This is set by `dx serve`
+    /// when multiple apps share one port.
+    #[clap(skip)]
+    #[serde(skip)]
+    pub(crate) devtools_path: Option<String>,
+
+    /// The test harness to build instead of the app. This is set by `dx test`.
+    #[clap(skip)]
+    #[serde(skip)]
+    pub(crate) test_target: Option<TestTarget>,
 }
 
 impl Anonymized for TargetArgs {
@@ -0,0 +1,209 @@
+use super::*;
+use crate::TraceController;
+
+/// Run the tests of the package and re-run them whenever the code changes.
+///
+/// The test binary is built once and launched like an app under `dx serve`, with its output shown in the TUI. Tests
+/// that use the `dioxus_devtools::run_tests` harness stay alive between runs, so changes to the tests and the code
+/// they call are hotpatched into the process and the tests re-run in milliseconds. Tests using the default libtest
+/// harness are rebuilt and re-run instead, and `dx test` warns when it picks one of those.
+///
+/// To hotpatch a test target, set `harness = false` for it in Cargo.toml and call `dioxus_devtools::run_tests` from
+/// its `main`.
+///
+/// ```sh
+/// dx test --test components -- --nocapture
+/// ```
+#[derive(Clone, Debug, Parser)]
+pub(crate) struct TestArgs {
+    /// Only run the tests whose names contain this filter
+    pub(crate) filter: Option<String>,
+
+    /// Run the unit tests of the library [default: the library if the package has one, otherwise the binary]
+    #[clap(long, conflicts_with = "test")]
+    pub(crate) lib: bool,
+
+    /// Run the integration test with this name, from the `tests` directory
+    #[clap(long)]
+    pub(crate) test: Option<String>,
+
+    /// Rebuild the tests on every change instead of hotpatching them
+    #[clap(long)]
+    pub(crate) no_hot_patch: bool,
+
+    /// Run in interactive mode
+    #[arg(long, default_missing_value="true", num_args=0..=1, short = 'i')]
+    pub(crate) interactive: Option<bool>,
+
+    /// Arguments passed to the test binary, like `-- --nocapture`
+    #[clap(last = true)]
+    pub(crate) harness_args: Vec<String>,
+
+    /// Information about the tests to build
+    #[clap(flatten)]
+    pub(crate) build_args: BuildArgs,
+}
+
+/// The test harness that `dx test` builds instead of the app
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TestTarget {
+    /// The library if the package has one, otherwise the binary
+    Auto,
+
+    /// The unit tests of the library
+    Lib,
+
+    /// The unit tests of the binary or example
+    Bin,
+
+    /// An integration test from the `tests` directory
+    Integration(String),
+}
+
+impl TestArgs {
+    pub(crate) async fn test(self, tracer: &TraceController) -> Result<StructuredOutput> {
+        self.into_serve_args().serve(tracer).await
+    }
+
+    /// Tests run through the serve machinery so they get the watcher, the hotpatching engine and the TUI
+    fn into_serve_args(self) -> ServeArgs {
+        let mut targets = self.build_args;
+        targets.build_arguments.test_target = Some(match (self.lib, self.test) {
+            (true, _) => TestTarget::Lib,
+            (false, Some(test)) => TestTarget::Integration(test),
+            (false, None) => TestTarget::Auto,
+        });
+
+        let args = self
+            .filter
+            .into_iter()
+            .chain(self.harness_args)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        ServeArgs {
+            open: Some(false),
+            interactive: self.interactive,
+            hot_patch: !self.no_hot_patch,
+            watch: Some(true),
+            platform_args: CommandWithPlatformOverrides {
+                shared: PlatformServeArgs { targets, args },
+                server: None,
+                client: None,
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// Whether a test target uses the default libtest harness, which exits once the tests ran instead of staying alive to
+/// be hotpatched. Targets that aren't listed in the manifest are discovered by cargo and always use libtest.
+pub(crate) fn uses_libtest(manifest: &cargo_toml::Manifest, target: &krates::cm::Target) -> bool {
+    use krates::cm::TargetKind;
+
+    let products: Vec<&cargo_toml::Product> = match target.kind.first() {
+        Some(TargetKind::Test) => manifest.test.iter().collect(),
+        Some(TargetKind::Bin) => manifest.bin.iter().collect(),
+        Some(TargetKind::Example) => manifest.example.iter().collect(),
+        _ => manifest.lib.iter().collect(),
+    };
+
+    let normalize = |name: &str| name.replace('-', "_");
+    products
+        .into_iter()
+        .filter(|product| product.name.as_deref().map(normalize) == Some(normalize(&target.name)))
+        .all(|product| product.harness)
+}
+
+impl Anonymized for TestArgs {
+    fn anonymized(&self) -> Value {
+        json! {{
+            "filter": self.filter.is_some(),
+            "lib": self.lib,
+            "test": self.test.is_some(),
+            "no_hot_patch": self.no_hot_patch,
+            "interactive": self.interactive,
+            "harness_args": !self.harness_args.is_empty(),
+            "build_args": self.build_args.anonymized(),
+        }}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_are_served() {
+        let args = TestArgs::try_parse_from([
+            "test",
+            "renders",
+            "--test",
+            "components",
+            "--",
+            "--nocapture",
+        ])
+        .unwrap();
+        let serve = args.into_serve_args();
+
+        let shared = &serve.platform_args.shared;
+        assert_eq!(
+            shared.targets.build_arguments.test_target,
+            Some(TestTarget::Integration("components".to_string()))
+        );
+        assert_eq!(shared.args, "renders --nocapture");
+        assert!(serve.hot_patch);
+        assert_eq!(serve.open, Some(false));
+
+        let serve = TestArgs::try_parse_from(["test", "--lib", "--no-hot-patch"])
+            .unwrap()
+            .into_serve_args();
+        assert_eq!(
+            serve
+                .platform_args
+                .shared
+                .targets
+                .build_arguments
+                .test_target,
+            Some(TestTarget::Lib)
+        );
+        assert!(!serve.hot_patch);
+    }
+
+    #[test]
+    fn libtest_targets_are_detected() {
+        let manifest = cargo_toml::Manifest::from_str(
+            r#"
+[package]
+name = "my-app"
+version = "0.1.0"
+
+[[test]]
+name = "components"
+harness = false
+
+[[test]]
+name = "routes"
+"#,
+        )
+        .unwrap();
+
+        let target = |name: &str, kind| {
+            serde_json::from_value::<krates::cm::Target>(serde_json::json!({
+                "name": name,
+                "kind": [kind],
+                "crate_types": ["bin"],
+                "src_path": format!("/my-app/tests/{name}.rs"),
+                "edition": "2021",
+                "doctest": false,
+                "test": true,
+            }))
+            .unwrap()
+        };
+
+        assert!(!uses_libtest(&manifest, &target("components", "test")));
+        assert!(uses_libtest(&manifest, &target("routes", "test")));
+        assert!(uses_libtest(&manifest, &target("discovered", "test")));
+        assert!(uses_libtest(&manifest, &target("my_app", "lib")));
+    }
+}
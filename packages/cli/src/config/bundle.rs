@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub(crate) struct BundleConfig {
@@ -22,14 +25,134 @@ pub(crate) struct BundleConfig {
     pub(crate) long_description: Option<String>,
     #[serde(default)]
     pub(crate) external_bin: Option<Vec<String>>,
+    /// Custom URL schemes like `my-app` that open the app when a `my-app://` link is opened.
+    ///
+    /// On Windows and Linux, the link is passed to the app as a command line argument. On macOS, the link is sent to the
+    /// running app, which forwards it to `use_second_instance_handler`.
+    #[serde(default)]
+    pub(crate) url_schemes: Option<Vec<String>>,
     #[serde(default)]
     pub(crate) deb: Option<DebianSettings>,
+    /// Desktop integration for the linux bundles and the Flatpak bundle.
+    #[serde(default)]
+    pub(crate) linux: Option<LinuxSettings>,
     #[serde(default)]
     pub(crate) macos: Option<MacOsSettings>,
     #[serde(default)]
     pub(crate) windows: Option<WindowsSettings>,
     #[serde(default)]
     pub(crate) android: Option<AndroidSettings>,
+    /// Sign the bundles and write an update manifest that bundled desktop apps can update themselves from.
+    #[serde(default)]
+    pub(crate) updater: Option<UpdaterSettings>,
+    /// Write an OCI image with the fullstack server and its `public` folder that can be loaded with `docker load`.
+    #[serde(default)]
+    pub(crate) container: Option<ContainerSettings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct UpdaterSettings {
+    /// The url the manifest and installers are uploaded to, like `https://releases.example.com/my-app/`.
+    ///
+    /// If this is not set, the installers are linked relative to the manifest.
+    #[serde(default)]
+    pub(crate) base_url: Option<String>,
+    /// The path to the private key that signs the updates.
+    ///
+    /// The `DIOXUS_UPDATER_PRIVATE_KEY` environment variable takes precedence over this. If neither is set, a key is
+    /// created in the dioxus config directory the first time the app is bundled.
+    #[serde(default)]
+    pub(crate) private_key: Option<PathBuf>,
+    /// The release notes to include in the manifest.
+    #[serde(default)]
+    pub(crate) notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct ContainerSettings {
+    /// The name of the image, like `ghcr.io/me/my-app`. Defaults to the name of the server executable.
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    /// The tag of the image. Defaults to the version of the crate.
+    #[serde(default)]
+    pub(crate) tag: Option<String>,
+    /// A tarball of a root filesystem to use as the base layer instead of the minimal distroless style base.
+    ///
+    /// The minimal base has no libc, so servers that are not built for a `*-linux-musl` target need a base with glibc.
+    #[serde(default)]
+    pub(crate) base: Option<PathBuf>,
+    /// A PEM bundle of the CA certificates the server trusts for https requests, like `/etc/ssl/certs/ca-certificates.crt`.
+    ///
+    /// The minimal base has no certificates of its own, so this is required unless `base` is set.
+    #[serde(default)]
+    pub(crate) ca_certificates: Option<PathBuf>,
+    /// The port the server listens on. Defaults to 8080.
+    #[serde(default)]
+    pub(crate) port: Option<u16>,
+    /// The id of the user the server runs as. Defaults to 65532, the `nonroot` user of distroless images.
+    #[serde(default)]
+    pub(crate) user: Option<u32>,
+    /// A command that checks if the server is healthy, like `["/app/server", "--health"]`.
+    #[serde(default)]
+    pub(crate) healthcheck: Option<Vec<String>>,
+    /// How often the healthcheck runs in seconds. Defaults to 30.
+    #[serde(default)]
+    pub(crate) healthcheck_interval: Option<u64>,
+    /// Environment variables to set in the image.
+    #[serde(default)]
+    pub(crate) env: Option<BTreeMap<String, String>>,
+    /// Labels to add to the image, like `org.opencontainers.image.source`.
+    #[serde(default)]
+    pub(crate) labels: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct LinuxSettings {
+    /// The MIME types of the files the app can open, like `text/markdown`.
+    ///
+    /// The app is listed in the "Open with" menu of those files, and the opened files are passed to the app as
+    /// command line arguments.
+    ///
+    /// A launcher entry takes either paths or urls. If the app also handles `url_schemes`, it takes urls, so opened
+    /// files may be passed as `file://` urls instead of paths.
+    #[serde(default)]
+    pub(crate) mime_types: Option<Vec<String>>,
+    /// Extra words app launchers match the app by when searching.
+    #[serde(default)]
+    pub(crate) keywords: Option<Vec<String>>,
+    /// The license of the app as an SPDX expression, for the AppStream metainfo. Defaults to the license of the crate.
+    #[serde(default)]
+    pub(crate) license: Option<String>,
+    /// The date of this version for the release in the AppStream metainfo, like `2025-06-01`.
+    ///
+    /// The release is listed without a date if this isn't set, so the bundle doesn't change with the day it was built.
+    #[serde(default)]
+    pub(crate) release_date: Option<String>,
+    /// Urls of screenshots of the app that software centers like GNOME Software and Flathub show.
+    #[serde(default)]
+    pub(crate) screenshots: Option<Vec<String>>,
+    /// Build a Flatpak bundle, and the manifest to publish the app on Flathub with.
+    #[serde(default)]
+    pub(crate) flatpak: Option<FlatpakSettings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct FlatpakSettings {
+    /// The runtime the app runs on. Defaults to `org.gnome.Platform`, which includes the webview desktop apps use.
+    #[serde(default)]
+    pub(crate) runtime: Option<String>,
+    /// The version of the runtime. Defaults to `48`.
+    #[serde(default)]
+    pub(crate) runtime_version: Option<String>,
+    /// The sdk the app is built with. Defaults to `org.gnome.Sdk`.
+    #[serde(default)]
+    pub(crate) sdk: Option<String>,
+    /// Sandbox permissions in addition to access to the display and the gpu, like `--share=network` or
+    /// `--filesystem=xdg-documents`.
+    ///
+    /// See <https://docs.flatpak.org/en/latest/sandbox-permissions.html> for the available permissions.
+    #[serde(default)]
+    pub(crate) permissions: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -288,7 +411,7 @@ pub struct CustomSignCommandSettings {
     pub args: Vec<String>,
 }
 
-#[derive(Clone, Copy, Debug, clap::ValueEnum, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum, Serialize)]
 pub(crate) enum PackageType {
     /// The macOS application bundle (.app).
     #[clap(name = "macos")]
@@ -318,6 +441,10 @@ pub(crate) enum PackageType {
     #[clap(name = "appimage")]
     AppImage,
 
+    /// The Linux Flatpak bundle (.flatpak) and its manifest.
+    #[clap(name = "flatpak")]
+    Flatpak,
+
     /// The macOS DMG bundle (.dmg).
     #[clap(name = "dmg")]
     Dmg,
@@ -339,6 +466,7 @@ impl FromStr for PackageType {
             "deb" => Ok(PackageType::Deb),
             "rpm" => Ok(PackageType::Rpm),
             "appimage" => Ok(PackageType::AppImage),
+            "flatpak" => Ok(PackageType::Flatpak),
             "dmg" => Ok(PackageType::Dmg),
             "updater" => Ok(PackageType::Updater),
             _ => Err(format!("{s} is not a valid package type")),
@@ -1,8 +1,11 @@
 use super::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct DioxusConfig {
+    /// This is optional so a Dioxus.toml in the root of a workspace may only have a `[serve]` section
+    #[serde(default)]
     pub(crate) application: ApplicationConfig,
 
     #[serde(default)]
@@ -10,6 +13,65 @@ pub(crate) struct DioxusConfig {
 
     #[serde(default)]
     pub(crate) bundle: BundleConfig,
+
+    #[serde(default)]
+    pub(crate) env: EnvConfig,
+
+    /// Named profiles like `[profile.staging]` that are selected with `--profile staging`.
+    #[serde(default)]
+    pub(crate) profile: HashMap<String, ProfileConfig>,
+}
+
+/// A profile overrides the `application`, `web`, `bundle` and `env` sections of the config when it is selected.
+///
+/// The `.env.<profile>` file is loaded on top of `.env` when the profile is selected.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct ProfileConfig {
+    /// The cargo profile to build with. If this is not set, the cargo profile with the same name is used if it
+    /// exists and the default profile otherwise.
+    #[serde(default)]
+    pub(crate) cargo_profile: Option<String>,
+
+    /// The sections this profile overrides
+    #[serde(flatten)]
+    pub(crate) overrides: toml::Table,
+}
+
+impl DioxusConfig {
+    /// Parse the config, applying the overrides of the profile if it is defined.
+    pub(crate) fn from_toml(contents: &str, profile: Option<&str>) -> anyhow::Result<Self> {
+        let mut table: toml::Table = toml::from_str(contents)?;
+
+        let overrides =
+            profile.and_then(|profile| table.get("profile")?.get(profile)?.as_table().cloned());
+        if let Some(overrides) = overrides {
+            for section in ["application", "web", "bundle", "env"] {
+                if let Some(value) = overrides.get(section) {
+                    match table.get_mut(section) {
+                        Some(base) => merge_toml(base, value.clone()),
+                        None => _ = table.insert(section.to_string(), value.clone()),
+                    }
+                }
+            }
+        }
+
+        Ok(table.try_into()?)
+    }
+}
+
+/// Merge tables recursively. Any other value in the overrides replaces the base value.
+fn merge_toml(base: &mut toml::Value, overrides: toml::Value) {
+    match (base, overrides) {
+        (toml::Value::Table(base), toml::Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => _ = base.insert(key, value),
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
 }
 
 impl Default for DioxusConfig {
@@ -52,6 +114,67 @@ impl Default for DioxusConfig {
                 wasm_opt: Default::default(),
             },
             bundle: BundleConfig::default(),
+            env: EnvConfig::default(),
+            profile: HashMap::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[application]
+
+[web.app]
+title = "My App"
+base_path = "app"
+
+[env]
+client = ["API_URL"]
+
+[profile.staging]
+cargo_profile = "release"
+
+[profile.staging.web.app]
+title = "My App (staging)"
+
+[profile.staging.env]
+client = ["API_URL", "SENTRY_DSN"]
+"#;
+
+    #[test]
+    fn profiles_override_sections() {
+        let config = DioxusConfig::from_toml(CONFIG, None).unwrap();
+        assert_eq!(config.web.app.title, "My App");
+        assert_eq!(config.env.client, ["API_URL"]);
+
+        let config = DioxusConfig::from_toml(CONFIG, Some("staging")).unwrap();
+        assert_eq!(config.web.app.title, "My App (staging)");
+        assert_eq!(config.web.app.base_path.as_deref(), Some("app"));
+        assert_eq!(config.env.client, ["API_URL", "SENTRY_DSN"]);
+        assert_eq!(
+            config.profile["staging"].cargo_profile.as_deref(),
+            Some("release")
+        );
+
+        // Profiles that aren't defined leave the config alone
+        let config = DioxusConfig::from_toml(CONFIG, Some("production")).unwrap();
+        assert_eq!(config.web.app.title, "My App");
+    }
+
+    #[test]
+    fn serve_only_configs_parse() {
+        let config = DioxusConfig::from_toml(
+            r#"
+[serve.apps.admin]
+route = "/admin"
+"#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.application.out_dir, None);
+        assert_eq!(config.web.app.title, DioxusConfig::default().web.app.title);
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Environment variables loaded from `.env` files.
+///
+/// The variables are passed to the server when it is launched by `dx serve` or `dx run`. Only the variables that
+/// match the `client` allowlist are passed to the compiler, where they can be read with `env!` or `option_env!`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct EnvConfig {
+    /// The variables that are baked into the app at compile time, like `API_URL`.
+    ///
+    /// Names ending with `*` match every variable with that prefix, like `PUBLIC_*`.
+    #[serde(default)]
+    pub(crate) client: Vec<String>,
+}
+
+impl EnvConfig {
+    /// Check if a variable may be baked into the app
+    pub(crate) fn is_client_var(&self, name: &str) -> bool {
+        self.client
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == pattern,
+            })
+    }
+}
+
+/// Load the variables from the `.env` and `.env.<profile>` files in the given directories.
+///
+/// Later files take precedence over earlier ones. Variables that are already set in the environment of `dx` are
+/// skipped since the environment always takes precedence over `.env` files.
+pub(crate) fn load_env_files(
+    dirs: &[&Path],
+    profile: Option<&str>,
+) -> crate::Result<Vec<(String, String)>> {
+    let mut names = vec![".env".to_string()];
+    names.extend(profile.map(|profile| format!(".env.{profile}")));
+
+    let mut vars: Vec<(String, String)> = vec![];
+    for dir in dirs {
+        for name in names.iter() {
+            let path = dir.join(name);
+            if !path.is_file() {
+                continue;
+            }
+
+            tracing::debug!("Loading environment variables from {}", path.display());
+
+            let iter = dotenvy::from_path_iter(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            for item in iter {
+                let (key, value) =
+                    item.with_context(|| format!("Failed to parse {}", path.display()))?;
+                if std::env::var_os(&key).is_some() {
+                    continue;
+                }
+                vars.retain(|(existing, _)| *existing != key);
+                vars.push((key, value));
+            }
+        }
+    }
+
+    Ok(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_env_files_take_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".env"),
+            "DX_TEST_API_URL=http://localhost\nDX_TEST_SECRET=dev\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(".env.staging"),
+            "DX_TEST_API_URL=https://staging.example.com\n",
+        )
+        .unwrap();
+
+        let vars = load_env_files(&[dir.path()], Some("staging")).unwrap();
+        assert_eq!(
+            vars,
+            [
+                ("DX_TEST_SECRET".to_string(), "dev".to_string()),
+                (
+                    "DX_TEST_API_URL".to_string(),
+                    "https://staging.example.com".to_string()
+                ),
+            ]
+        );
+
+        let vars = load_env_files(&[dir.path()], None).unwrap();
+        assert_eq!(vars[0].1, "http://localhost");
+    }
+
+    #[test]
+    fn client_allowlist() {
+        let config = EnvConfig {
+            client: vec!["API_URL".to_string(), "PUBLIC_*".to_string()],
+        };
+        assert!(config.is_client_var("API_URL"));
+        assert!(config.is_client_var("PUBLIC_STRIPE_KEY"));
+        assert!(!config.is_client_var("API_URL_SECRET"));
+        assert!(!config.is_client_var("DATABASE_URL"));
+    }
+}
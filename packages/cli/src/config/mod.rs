@@ -1,11 +1,13 @@
 mod app;
 mod bundle;
 mod dioxus_config;
+mod env;
 mod serve;
 mod web;
 
 pub(crate) use app::*;
 pub(crate) use bundle::*;
 pub(crate) use dioxus_config::*;
+pub(crate) use env::*;
 pub(crate) use serve::*;
 pub(crate) use web::*;
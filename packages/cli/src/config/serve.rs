@@ -1,4 +1,6 @@
 use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// The arguments for the address the server will run on
 #[derive(Clone, Debug, Default, Parser)]
@@ -20,3 +22,34 @@ impl crate::Anonymized for AddressArguments {
         })
     }
 }
+
+/// The `[serve]` section of Dioxus.toml
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct ServeConfig {
+    /// Apps that `dx serve` runs together, keyed by name, like `[serve.apps.admin]`.
+    ///
+    /// All of the apps are served when `dx serve` is run without a package. A subset can be picked with `--app`.
+    #[serde(default)]
+    pub(crate) apps: BTreeMap<String, ServeAppConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct ServeAppConfig {
+    /// The package to serve. Defaults to the name of the app.
+    #[serde(default)]
+    pub(crate) package: Option<String>,
+
+    /// The path prefix the app is served under on the main port, like `/admin`.
+    ///
+    /// This also sets the base path of the app. Use `/` for the app that handles every other path.
+    #[serde(default)]
+    pub(crate) route: Option<String>,
+
+    /// The port of the devserver for this app. Defaults to any free port.
+    #[serde(default)]
+    pub(crate) port: Option<u16>,
+
+    /// Extra arguments for `dx serve`, like `--web --features admin` or `@server --package api`.
+    #[serde(default)]
+    pub(crate) args: Option<String>,
+}
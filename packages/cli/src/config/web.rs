@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct WebConfig {
@@ -117,9 +117,87 @@ impl Default for WebAppConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub(crate) struct WebProxyConfig {
     pub(crate) backend: String,
+
+    /// Whether requests are forwarded to the backend, recorded to fixtures or replayed from fixtures [default: proxy]
+    ///
+    /// This can be overridden for every proxy with `dx serve --proxy-mode`.
+    #[serde(default)]
+    pub(crate) mode: ProxyMode,
+
+    /// The directory of the recorded fixtures, relative to the crate [default: fixtures/proxy/<backend path>]
+    #[serde(default)]
+    pub(crate) fixtures: Option<PathBuf>,
+
+    /// Artificial latency in milliseconds added to every response
+    #[serde(default)]
+    pub(crate) latency: Option<u64>,
+
+    /// The fraction of requests, between 0.0 and 1.0, that fail with a `503 Service Unavailable`
+    ///
+    /// The failures are spread evenly over the requests so the behavior is the same on every run.
+    #[serde(default)]
+    pub(crate) error_rate: Option<f64>,
+
+    /// Mock responses that are served instead of the backend, regardless of the mode
+    #[serde(default)]
+    pub(crate) mock: Vec<ProxyMockConfig>,
+}
+
+/// How the proxy handles requests to the backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ProxyMode {
+    /// Forward requests to the backend
+    #[default]
+    Proxy,
+
+    /// Forward requests to the backend and save the responses and websocket messages as fixtures
+    Record,
+
+    /// Serve the recorded fixtures without connecting to the backend
+    Replay,
+}
+
+/// A mock response for a route of a proxy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ProxyMockConfig {
+    /// The path of the route, like `/api/users`. A trailing `*` matches every path with that prefix.
+    pub(crate) path: String,
+
+    /// The method of the route. Every method is matched if this isn't set.
+    #[serde(default)]
+    pub(crate) method: Option<String>,
+
+    /// The status code of the response [default: 200]
+    #[serde(default = "default_mock_status")]
+    pub(crate) status: u16,
+
+    /// The headers of the response
+    #[serde(default)]
+    pub(crate) headers: BTreeMap<String, String>,
+
+    /// The body of the response
+    #[serde(default)]
+    pub(crate) body: Option<String>,
+
+    /// A file to use as the body of the response, relative to the crate
+    #[serde(default)]
+    pub(crate) file: Option<PathBuf>,
+
+    /// Artificial latency in milliseconds for this route, overriding the latency of the proxy
+    #[serde(default)]
+    pub(crate) latency: Option<u64>,
+
+    /// The fraction of requests to this route that fail, overriding the error rate of the proxy
+    #[serde(default)]
+    pub(crate) error_rate: Option<f64>,
+}
+
+fn default_mock_status() -> u16 {
+    200
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
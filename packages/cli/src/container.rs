@@ -0,0 +1,475 @@
+//! Package a fullstack server and its `public` folder into an OCI image tarball.
+//!
+//! The image is written without a container runtime. The tarball is an OCI image layout that also has the
+//! `manifest.json` docker uses, so it can be loaded with `docker load`, `podman load` or pushed with `skopeo`.
+//!
+//! Builds are reproducible: every file in the layers has the same owner and timestamp, and the entries are sorted. The
+//! timestamp is `SOURCE_DATE_EPOCH` if it is set.
+
+use crate::{BuildRequest, ContainerSettings, Result};
+use anyhow::{bail, Context};
+use flate2::{write::GzEncoder, Compression};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+use target_lexicon::{Architecture, Environment, OperatingSystem};
+use walkdir::WalkDir;
+
+/// The user the server runs as if none is configured. This is the `nonroot` user of distroless images.
+const DEFAULT_USER: u32 = 65532;
+
+/// The port the server listens on if none is configured
+const DEFAULT_PORT: u16 = 8080;
+
+/// Where the server and its `public` folder are placed in the image
+const APP_DIR: &str = "app";
+
+/// The line every certificate in a PEM bundle starts with
+const PEM_CERTIFICATE: &[u8] = b"-----BEGIN CERTIFICATE-----";
+
+/// A layer of the image
+struct Layer {
+    /// The gzipped tarball
+    blob: Vec<u8>,
+    /// The digest of the tarball before it was compressed
+    diff_id: String,
+    /// What the layer contains, for the history of the image
+    created_by: String,
+}
+
+/// Write an OCI image with the server and the web assets of the client.
+///
+/// Returns the path of the tarball.
+pub(crate) fn write_container_image(
+    server: &BuildRequest,
+    client: &BuildRequest,
+    settings: &ContainerSettings,
+) -> Result<PathBuf> {
+    if server.triple.operating_system != OperatingSystem::Linux {
+        bail!(
+            "Container images need a linux server, but the server was built for {}. Build it with `@server --target x86_64-unknown-linux-musl`.",
+            server.triple
+        );
+    }
+    // The minimal base has no libc, so the server has to be statically linked unless a base with glibc is configured
+    if settings.base.is_none() && server.triple.environment != Environment::Musl {
+        bail!(
+            "The server was built for {}, which links against glibc, but the default base image has no libc. Build it with `@server --target {}-unknown-linux-musl` or set `bundle.container.base` to a root filesystem with glibc.",
+            server.triple,
+            server.triple.architecture
+        );
+    }
+    let architecture = match server.triple.architecture {
+        Architecture::X86_64 => "amd64",
+        Architecture::Aarch64(_) => "arm64",
+        arch => bail!("Container images can't be built for the {arch} architecture"),
+    };
+
+    let mtime = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .unwrap_or(0);
+    let user = settings.user.unwrap_or(DEFAULT_USER);
+    let port = settings.port.unwrap_or(DEFAULT_PORT);
+    let exe_name = server
+        .main_exe()
+        .file_name()
+        .context("The server has no executable")?
+        .to_string_lossy()
+        .into_owned();
+
+    let base = match (&settings.base, &settings.ca_certificates) {
+        (Some(base), ca_certificates) => {
+            if ca_certificates.is_some() {
+                tracing::warn!("Ignoring `bundle.container.ca_certificates` since the base layer from `bundle.container.base` has its own certificates");
+            }
+            Layer::from_tarball(base)?
+        }
+        (None, Some(ca_certificates)) => {
+            let certificates = std::fs::read(ca_certificates).with_context(|| {
+                format!(
+                    "Failed to read the CA certificates at {}",
+                    ca_certificates.display()
+                )
+            })?;
+            if !certificates
+                .windows(PEM_CERTIFICATE.len())
+                .any(|window| window == PEM_CERTIFICATE)
+            {
+                bail!(
+                    "{} is not a PEM bundle of CA certificates",
+                    ca_certificates.display()
+                );
+            }
+            Layer::base(user, &certificates, mtime)?
+        }
+        (None, None) => bail!(
+            "The default base image has no CA certificates, so servers couldn't make https requests. Set `bundle.container.ca_certificates` to a PEM bundle like `/etc/ssl/certs/ca-certificates.crt` or set `bundle.container.base` to a root filesystem that has them."
+        ),
+    };
+    let app = Layer::app(&server.main_exe(), &exe_name, &client.root_dir(), mtime)?;
+    let layers = [base, app];
+
+    let name = settings
+        .name
+        .clone()
+        .unwrap_or_else(|| server.executable_name().to_string());
+    let tag = settings
+        .tag
+        .clone()
+        .unwrap_or_else(|| server.package().version.to_string());
+
+    let mut env = vec![
+        "PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin".to_string(),
+        format!("{}=0.0.0.0", dioxus_cli_config::SERVER_IP_ENV),
+        format!("{}={port}", dioxus_cli_config::SERVER_PORT_ENV),
+    ];
+    env.extend(
+        settings
+            .env
+            .iter()
+            .flatten()
+            .map(|(key, value)| format!("{key}={value}")),
+    );
+
+    let mut config = json!({
+        "User": format!("{user}:{user}"),
+        "Env": env,
+        "Entrypoint": [format!("/{APP_DIR}/{exe_name}")],
+        "WorkingDir": format!("/{APP_DIR}"),
+        "ExposedPorts": { format!("{port}/tcp"): {} },
+        "Labels": settings.labels.clone().unwrap_or_default(),
+    });
+    if let Some(command) = &settings.healthcheck {
+        let interval = settings.healthcheck_interval.unwrap_or(30);
+        config["Healthcheck"] = json!({
+            "Test": std::iter::once("CMD".to_string()).chain(command.iter().cloned()).collect::<Vec<_>>(),
+            "Interval": interval * 1_000_000_000,
+            "Timeout": 5_000_000_000u64,
+            "Retries": 3,
+        });
+    }
+
+    let created = chrono::DateTime::from_timestamp(mtime as i64, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let image_config = json!({
+        "created": created,
+        "architecture": architecture,
+        "os": "linux",
+        "config": config,
+        "rootfs": {
+            "type": "layers",
+            "diff_ids": layers.iter().map(|layer| &layer.diff_id).collect::<Vec<_>>(),
+        },
+        "history": layers.iter().map(|layer| json!({ "created": created, "created_by": layer.created_by })).collect::<Vec<_>>(),
+    });
+    let image_config = serde_json::to_vec(&image_config)?;
+
+    let manifest = json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.manifest.v1+json",
+        "config": descriptor("application/vnd.oci.image.config.v1+json", &image_config),
+        "layers": layers
+            .iter()
+            .map(|layer| descriptor("application/vnd.oci.image.layer.v1.tar+gzip", &layer.blob))
+            .collect::<Vec<_>>(),
+    });
+    let manifest = serde_json::to_vec(&manifest)?;
+
+    let reference = format!("{name}:{tag}");
+    let mut manifest_descriptor =
+        descriptor("application/vnd.oci.image.manifest.v1+json", &manifest);
+    manifest_descriptor["annotations"] = json!({
+        "org.opencontainers.image.ref.name": tag,
+        "io.containerd.image.name": reference,
+    });
+    let index = json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.index.v1+json",
+        "manifests": [manifest_descriptor],
+    });
+
+    // Docker before version 25 only loads images with a docker style manifest
+    let docker_manifest = json!([{
+        "Config": blob_path(&image_config),
+        "RepoTags": [reference],
+        "Layers": layers.iter().map(|layer| blob_path(&layer.blob)).collect::<Vec<_>>(),
+    }]);
+
+    let mut files = BTreeMap::new();
+    files.insert(
+        "oci-layout".to_string(),
+        br#"{"imageLayoutVersion":"1.0.0"}"#.to_vec(),
+    );
+    files.insert("index.json".to_string(), serde_json::to_vec(&index)?);
+    files.insert(
+        "manifest.json".to_string(),
+        serde_json::to_vec(&docker_manifest)?,
+    );
+    files.insert(blob_path(&image_config), image_config);
+    files.insert(blob_path(&manifest), manifest);
+    for layer in layers {
+        files.insert(blob_path(&layer.blob), layer.blob);
+    }
+
+    let out_dir = server.bundle_dir(server.bundle);
+    std::fs::create_dir_all(&out_dir)?;
+    let path = out_dir.join(format!(
+        "{}-{tag}-oci.tar",
+        name.rsplit('/').next().unwrap_or(&name)
+    ));
+
+    let mut archive = TarWriter::new(mtime);
+    archive.dir("blobs")?;
+    archive.dir("blobs/sha256")?;
+    for (name, contents) in files {
+        archive.file(&name, &contents, 0o644)?;
+    }
+    std::fs::write(&path, archive.finish()?)
+        .with_context(|| format!("Failed to write the image to {}", path.display()))?;
+
+    tracing::info!(
+        "Wrote the container image {reference} to {}",
+        path.display()
+    );
+
+    Ok(path)
+}
+
+impl Layer {
+    fn new(tar: Vec<u8>, created_by: impl Into<String>) -> Result<Self> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&tar)?;
+        Ok(Self {
+            blob: encoder.finish()?,
+            diff_id: sha256_digest(&tar),
+            created_by: created_by.into(),
+        })
+    }
+
+    /// A distroless style base with a user to run as, `/tmp` and the CA certificates
+    fn base(user: u32, ca_certificates: &[u8], mtime: u64) -> Result<Self> {
+        let mut tar = TarWriter::new(mtime);
+        tar.dir("etc")?;
+        tar.file(
+            "etc/passwd",
+            format!("root:x:0:0:root:/root:/sbin/nologin\nnonroot:x:{user}:{user}:nonroot:/home/nonroot:/sbin/nologin\n").as_bytes(),
+            0o644,
+        )?;
+        tar.file(
+            "etc/group",
+            format!("root:x:0:\nnonroot:x:{user}:\n").as_bytes(),
+            0o644,
+        )?;
+        tar.file("etc/nsswitch.conf", b"hosts: files dns\n", 0o644)?;
+
+        tar.dir("etc/ssl")?;
+        tar.dir("etc/ssl/certs")?;
+        tar.file("etc/ssl/certs/ca-certificates.crt", ca_certificates, 0o644)?;
+
+        tar.dir("home")?;
+        tar.entry("home/nonroot", &[], 0o700, tar::EntryType::Directory, user)?;
+        tar.entry("tmp", &[], 0o1777, tar::EntryType::Directory, 0)?;
+
+        Self::new(tar.finish()?, "dx bundle: base")
+    }
+
+    /// A base layer from a tarball of a root filesystem, which may be gzipped
+    fn from_tarball(path: &Path) -> Result<Self> {
+        let contents = std::fs::read(path)
+            .with_context(|| format!("Failed to read the base layer at {}", path.display()))?;
+        let tar = match contents.starts_with(&[0x1f, 0x8b]) {
+            true => {
+                let mut tar = Vec::new();
+                flate2::read::GzDecoder::new(contents.as_slice()).read_to_end(&mut tar)?;
+                tar
+            }
+            false => contents,
+        };
+        Self::new(tar, format!("dx bundle: base from {}", path.display()))
+    }
+
+    /// The server and the `public` folder next to it
+    fn app(exe: &Path, exe_name: &str, public: &Path, mtime: u64) -> Result<Self> {
+        let mut tar = TarWriter::new(mtime);
+        tar.dir(APP_DIR)?;
+        let contents = std::fs::read(exe)
+            .with_context(|| format!("Failed to read the server at {}", exe.display()))?;
+        tar.file(&format!("{APP_DIR}/{exe_name}"), &contents, 0o755)?;
+
+        tar.dir(&format!("{APP_DIR}/public"))?;
+        let mut entries: Vec<_> = WalkDir::new(public)
+            .min_depth(1)
+            .into_iter()
+            .collect::<Result<_, _>>()?;
+        entries.sort_by(|a, b| a.path().cmp(b.path()));
+        for entry in entries {
+            let relative = entry.path().strip_prefix(public)?;
+            let name = format!(
+                "{APP_DIR}/public/{}",
+                relative.to_string_lossy().replace('\\', "/")
+            );
+            if entry.file_type().is_dir() {
+                tar.dir(&name)?;
+            } else {
+                tar.file(&name, &std::fs::read(entry.path())?, 0o644)?;
+            }
+        }
+
+        Self::new(tar.finish()?, "dx bundle: app")
+    }
+}
+
+/// Writes tarballs with the same owner and timestamp for every entry
+struct TarWriter {
+    builder: tar::Builder<Vec<u8>>,
+    mtime: u64,
+}
+
+impl TarWriter {
+    fn new(mtime: u64) -> Self {
+        Self {
+            builder: tar::Builder::new(Vec::new()),
+            mtime,
+        }
+    }
+
+    fn dir(&mut self, path: &str) -> Result<()> {
+        self.entry(path, &[], 0o755, tar::EntryType::Directory, 0)
+    }
+
+    fn file(&mut self, path: &str, contents: &[u8], mode: u32) -> Result<()> {
+        self.entry(path, contents, mode, tar::EntryType::Regular, 0)
+    }
+
+    fn entry(
+        &mut self,
+        path: &str,
+        contents: &[u8],
+        mode: u32,
+        kind: tar::EntryType,
+        owner: u32,
+    ) -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(kind);
+        header.set_size(contents.len() as u64);
+        header.set_mode(mode);
+        header.set_mtime(self.mtime);
+        header.set_uid(owner as u64);
+        header.set_gid(owner as u64);
+        let path = match kind {
+            tar::EntryType::Directory => format!("{path}/"),
+            _ => path.to_string(),
+        };
+        self.builder.append_data(&mut header, path, contents)?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Vec<u8>> {
+        Ok(self.builder.into_inner()?)
+    }
+}
+
+fn sha256_digest(bytes: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(bytes))
+}
+
+fn blob_path(bytes: &[u8]) -> String {
+    format!("blobs/sha256/{:x}", Sha256::digest(bytes))
+}
+
+fn descriptor(media_type: &str, bytes: &[u8]) -> serde_json::Value {
+    json!({
+        "mediaType": media_type,
+        "digest": sha256_digest(bytes),
+        "size": bytes.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers_are_reproducible() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("server");
+        let public = dir.path().join("public");
+        std::fs::create_dir_all(public.join("assets")).unwrap();
+        std::fs::write(&exe, b"server").unwrap();
+        std::fs::write(public.join("index.html"), b"<html></html>").unwrap();
+        std::fs::write(public.join("assets/app.js"), b"console.log(1)").unwrap();
+
+        let first = Layer::app(&exe, "server", &public, 0).unwrap();
+        let second = Layer::app(&exe, "server", &public, 0).unwrap();
+        assert_eq!(first.blob, second.blob);
+        assert_eq!(first.diff_id, second.diff_id);
+
+        let mut tar = Vec::new();
+        flate2::read::GzDecoder::new(first.blob.as_slice())
+            .read_to_end(&mut tar)
+            .unwrap();
+        assert_eq!(first.diff_id, sha256_digest(&tar));
+
+        let mut archive = tar::Archive::new(tar.as_slice());
+        let entries: Vec<_> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                let header = entry.header();
+                (
+                    entry.path().unwrap().to_string_lossy().into_owned(),
+                    header.mode().unwrap(),
+                    header.uid().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("app/".to_string(), 0o755, 0),
+                ("app/server".to_string(), 0o755, 0),
+                ("app/public/".to_string(), 0o755, 0),
+                ("app/public/assets/".to_string(), 0o755, 0),
+                ("app/public/assets/app.js".to_string(), 0o644, 0),
+                ("app/public/index.html".to_string(), 0o644, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn base_layer_has_a_non_root_user() {
+        let ca_certificates = b"-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n";
+        let base = Layer::base(DEFAULT_USER, ca_certificates, 0).unwrap();
+        let mut tar = Vec::new();
+        flate2::read::GzDecoder::new(base.blob.as_slice())
+            .read_to_end(&mut tar)
+            .unwrap();
+
+        let mut archive = tar::Archive::new(tar.as_slice());
+        let mut passwd = String::new();
+        let mut home_owner = None;
+        let mut certificates = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_string_lossy().into_owned();
+            match path.as_str() {
+                "etc/passwd" => _ = entry.read_to_string(&mut passwd).unwrap(),
+                "home/nonroot/" => home_owner = entry.header().uid().ok(),
+                "etc/ssl/certs/ca-certificates.crt" => {
+                    _ = entry.read_to_end(&mut certificates).unwrap()
+                }
+                _ => {}
+            }
+        }
+        assert!(passwd.contains("nonroot:x:65532:65532"));
+        assert_eq!(home_owner, Some(65532));
+        assert_eq!(certificates, ca_certificates);
+    }
+}
@@ -0,0 +1,500 @@
+//! Desktop integration for linux bundles, and the Flatpak bundle.
+//!
+//! tauri-bundler builds the deb, rpm and AppImage bundles, but only knows how to write a basic desktop file and copies
+//! the icons at the sizes they happen to be. For every linux bundle we write:
+//! - a desktop file with the MIME types and url schemes the app opens, and its search keywords
+//! - AppStream metainfo, which software centers and Flathub show the app's listing from
+//! - the icon at every size of the hicolor icon theme
+//!
+//! Flatpaks are bundled by dx itself. The prebuilt app is laid out like the `/app` prefix of the sandbox next to a
+//! manifest, which `flatpak-builder` turns into a `.flatpak` bundle if it is installed.
+
+use crate::{BuildRequest, FlatpakSettings, Result};
+use anyhow::{bail, Context};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use target_lexicon::Architecture;
+
+/// The sizes of the hicolor icon theme that the app icon is installed at
+const ICON_SIZES: [u32; 8] = [16, 24, 32, 48, 64, 128, 256, 512];
+
+/// The permissions every flatpak gets: access to the display and the gpu
+const DEFAULT_FLATPAK_PERMISSIONS: [&str; 4] = [
+    "--share=ipc",
+    "--socket=fallback-x11",
+    "--socket=wayland",
+    "--device=dri",
+];
+
+/// Everything about the app that the desktop integration files describe
+#[derive(Debug, Default)]
+pub(crate) struct LinuxApp {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) exe: String,
+    pub(crate) version: String,
+    pub(crate) release_date: Option<String>,
+    pub(crate) summary: Option<String>,
+    pub(crate) description: Option<String>,
+    pub(crate) developer: Option<String>,
+    pub(crate) license: Option<String>,
+    pub(crate) homepage: Option<String>,
+    pub(crate) repository: Option<String>,
+    /// The freedesktop categories, separated by semicolons
+    pub(crate) categories: String,
+    pub(crate) keywords: Vec<String>,
+    pub(crate) mime_types: Vec<String>,
+    pub(crate) url_schemes: Vec<String>,
+    pub(crate) screenshots: Vec<String>,
+}
+
+impl LinuxApp {
+    pub(crate) fn new(build: &BuildRequest) -> Result<Self> {
+        let bundle = &build.config.bundle;
+        let linux = bundle.linux.clone().unwrap_or_default();
+        let package = build.package();
+
+        let Some(id) = bundle.identifier.clone() else {
+            bail!("\n\nBundle identifier was not provided in `Dioxus.toml`. Add it as:\n\n[bundle]\nidentifier = \"com.mycompany\"\n\n");
+        };
+
+        let categories = bundle
+            .category
+            .as_deref()
+            .and_then(|category| category.parse::<tauri_bundler::AppCategory>().ok())
+            .map(|category| category.freedesktop_categories().to_string())
+            .unwrap_or_default();
+
+        Ok(Self {
+            id,
+            name: build.bundled_app_name(),
+            exe: build.executable_name().to_string(),
+            version: package.version.to_string(),
+            release_date: linux.release_date.clone(),
+            summary: bundle
+                .short_description
+                .clone()
+                .or_else(|| package.description.clone()),
+            description: bundle.long_description.clone(),
+            developer: bundle.publisher.clone(),
+            license: linux.license.or_else(|| package.license.clone()),
+            homepage: package.homepage.clone(),
+            repository: package.repository.clone(),
+            categories,
+            keywords: linux.keywords.unwrap_or_default(),
+            mime_types: linux.mime_types.unwrap_or_default(),
+            url_schemes: bundle.url_schemes.clone().unwrap_or_default(),
+            screenshots: linux.screenshots.unwrap_or_default(),
+        })
+    }
+
+    /// The `.desktop` file app launchers list the app with
+    pub(crate) fn desktop_entry(&self, exec: &str, icon: &str) -> String {
+        // Links are passed as urls, opened files as paths. An entry only takes one kind of argument, so apps that open
+        // both get urls, with files as `file://` urls or paths depending on the launcher
+        let exec_args = match (self.url_schemes.is_empty(), self.mime_types.is_empty()) {
+            (false, _) => " %u",
+            (true, false) => " %F",
+            (true, true) => "",
+        };
+        let exec = match exec.contains(' ') {
+            true => format!("\"{exec}\""),
+            false => exec.to_string(),
+        };
+
+        let mut entry = String::from("[Desktop Entry]\nType=Application\n");
+        entry.push_str(&format!("Name={}\n", self.name));
+        if let Some(summary) = &self.summary {
+            entry.push_str(&format!("Comment={summary}\n"));
+        }
+        entry.push_str(&format!("Exec={exec}{exec_args}\n"));
+        entry.push_str(&format!("StartupWMClass={}\n", self.exe));
+        entry.push_str(&format!("Icon={icon}\n"));
+        entry.push_str("Terminal=false\n");
+        if !self.categories.is_empty() {
+            entry.push_str(&format!("Categories={}\n", self.categories));
+        }
+        if !self.keywords.is_empty() {
+            entry.push_str(&format!("Keywords={};\n", self.keywords.join(";")));
+        }
+
+        let mime_types = self
+            .mime_types
+            .iter()
+            .cloned()
+            .chain(
+                self.url_schemes
+                    .iter()
+                    .map(|scheme| format!("x-scheme-handler/{scheme}")),
+            )
+            .collect::<Vec<_>>();
+        if !mime_types.is_empty() {
+            entry.push_str(&format!("MimeType={};\n", mime_types.join(";")));
+        }
+
+        entry
+    }
+
+    /// The AppStream metainfo software centers show the listing of the app from.
+    ///
+    /// <https://www.freedesktop.org/software/appstream/docs/chap-Quickstart.html>
+    pub(crate) fn metainfo(&self, desktop_id: &str) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<component type=\"desktop-application\">\n");
+        xml.push_str(&format!("  <id>{}</id>\n", escape_xml(&self.id)));
+        xml.push_str("  <metadata_license>CC0-1.0</metadata_license>\n");
+        if let Some(license) = &self.license {
+            xml.push_str(&format!(
+                "  <project_license>{}</project_license>\n",
+                escape_xml(license)
+            ));
+        }
+        xml.push_str(&format!("  <name>{}</name>\n", escape_xml(&self.name)));
+        if let Some(summary) = &self.summary {
+            xml.push_str(&format!("  <summary>{}</summary>\n", escape_xml(summary)));
+        }
+        if let Some(description) = &self.description {
+            xml.push_str("  <description>\n");
+            for paragraph in description.split("\n\n").map(str::trim) {
+                if !paragraph.is_empty() {
+                    xml.push_str(&format!("    <p>{}</p>\n", escape_xml(paragraph)));
+                }
+            }
+            xml.push_str("  </description>\n");
+        }
+        if let Some(developer) = &self.developer {
+            // The developer id is the reverse domain of the app id, like `com.example` for `com.example.app`
+            let developer_id = self.id.rsplit_once('.').map_or(&*self.id, |(id, _)| id);
+            xml.push_str(&format!(
+                "  <developer id=\"{}\">\n    <name>{}</name>\n  </developer>\n",
+                escape_xml(developer_id),
+                escape_xml(developer)
+            ));
+        }
+        if let Some(homepage) = &self.homepage {
+            xml.push_str(&format!(
+                "  <url type=\"homepage\">{}</url>\n",
+                escape_xml(homepage)
+            ));
+        }
+        if let Some(repository) = &self.repository {
+            xml.push_str(&format!(
+                "  <url type=\"vcs-browser\">{}</url>\n",
+                escape_xml(repository)
+            ));
+        }
+        xml.push_str(&format!(
+            "  <launchable type=\"desktop-id\">{}</launchable>\n",
+            escape_xml(desktop_id)
+        ));
+        xml.push_str(&format!(
+            "  <provides>\n    <binary>{}</binary>\n",
+            escape_xml(&self.exe)
+        ));
+        for mime_type in &self.mime_types {
+            xml.push_str(&format!(
+                "    <mediatype>{}</mediatype>\n",
+                escape_xml(mime_type)
+            ));
+        }
+        xml.push_str("  </provides>\n");
+        if !self.categories.is_empty() {
+            xml.push_str("  <categories>\n");
+            for category in self.categories.split(';').filter(|c| !c.is_empty()) {
+                xml.push_str(&format!(
+                    "    <category>{}</category>\n",
+                    escape_xml(category)
+                ));
+            }
+            xml.push_str("  </categories>\n");
+        }
+        if !self.keywords.is_empty() {
+            xml.push_str("  <keywords>\n");
+            for keyword in &self.keywords {
+                xml.push_str(&format!("    <keyword>{}</keyword>\n", escape_xml(keyword)));
+            }
+            xml.push_str("  </keywords>\n");
+        }
+        if !self.screenshots.is_empty() {
+            xml.push_str("  <screenshots>\n");
+            for (idx, screenshot) in self.screenshots.iter().enumerate() {
+                let kind = if idx == 0 { " type=\"default\"" } else { "" };
+                xml.push_str(&format!(
+                    "    <screenshot{kind}>\n      <image>{}</image>\n    </screenshot>\n",
+                    escape_xml(screenshot)
+                ));
+            }
+            xml.push_str("  </screenshots>\n");
+        }
+        xml.push_str("  <content_rating type=\"oars-1.1\"/>\n");
+        let date = self
+            .release_date
+            .as_ref()
+            .map(|date| format!(" date=\"{}\"", escape_xml(date)))
+            .unwrap_or_default();
+        xml.push_str(&format!(
+            "  <releases>\n    <release version=\"{}\"{date}/>\n  </releases>\n",
+            escape_xml(&self.version),
+        ));
+        xml.push_str("</component>\n");
+        xml
+    }
+}
+
+/// Write the app icon into a hicolor icon theme at every size, named `name`. The largest png icon is scaled down to
+/// each size that is no larger than it, and svg icons are installed as the scalable icon.
+///
+/// Returns the paths of the written icons.
+pub(crate) fn write_icons(
+    build: &BuildRequest,
+    hicolor: &Path,
+    name: &str,
+) -> Result<Vec<PathBuf>> {
+    let icons = build
+        .config
+        .bundle
+        .icon
+        .iter()
+        .flatten()
+        .map(|icon| build.crate_dir().join(icon))
+        .collect::<Vec<_>>();
+
+    let mut written = vec![];
+
+    if let Some(svg) = icons.iter().find(|icon| has_extension(icon, "svg")) {
+        let path = hicolor.join(format!("scalable/apps/{name}.svg"));
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::copy(svg, &path)
+            .with_context(|| format!("Failed to copy icon {}", svg.display()))?;
+        written.push(path);
+    }
+
+    let mut largest: Option<image::DynamicImage> = None;
+    for icon in icons.iter().filter(|icon| has_extension(icon, "png")) {
+        let image =
+            image::open(icon).with_context(|| format!("Failed to read icon {}", icon.display()))?;
+        if largest
+            .as_ref()
+            .map_or(true, |largest| image.width() > largest.width())
+        {
+            largest = Some(image);
+        }
+    }
+
+    let Some(largest) = largest else {
+        return Ok(written);
+    };
+
+    for size in ICON_SIZES
+        .into_iter()
+        .filter(|size| *size <= largest.width())
+    {
+        let path = hicolor.join(format!("{size}x{size}/apps/{name}.png"));
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        largest
+            .resize_exact(size, size, image::imageops::FilterType::Lanczos3)
+            .save(&path)
+            .with_context(|| format!("Failed to write icon {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Bundle the app as a Flatpak.
+///
+/// This always writes a `flatpak` folder with the manifest and the files it installs, which can be published on
+/// Flathub. If `flatpak-builder` is installed, the manifest is also built into a single file `.flatpak` bundle.
+pub(crate) fn write_flatpak(
+    build: &BuildRequest,
+    settings: &FlatpakSettings,
+) -> Result<Vec<PathBuf>> {
+    let app = LinuxApp::new(build)?;
+    let bundle_dir = build.bundle_dir(build.bundle);
+    let flatpak_dir = bundle_dir.join("flatpak");
+    _ = std::fs::remove_dir_all(&flatpak_dir);
+
+    // The files are laid out like they are installed in the `/app` prefix of the sandbox
+    let files = flatpak_dir.join("files");
+    let bin = files.join("bin");
+    std::fs::create_dir_all(&bin)?;
+    std::fs::copy(build.main_exe(), bin.join(&app.exe))
+        .context("Failed to copy the executable into the flatpak")?;
+
+    // Assets are looked up in `lib/$product_name` next to the `bin` folder
+    let asset_dir = build.asset_dir();
+    if asset_dir.exists() {
+        let lib = files.join("lib").join(&app.name);
+        std::fs::create_dir_all(&lib)?;
+        dircpy::CopyBuilder::new(&asset_dir, lib.join("assets"))
+            .overwrite(true)
+            .run()
+            .context("Failed to copy the assets into the flatpak")?;
+    }
+
+    // Flatpak requires the desktop file, metainfo and icons to be named after the app id
+    let share = files.join("share");
+    let desktop_id = format!("{}.desktop", app.id);
+    std::fs::create_dir_all(share.join("applications"))?;
+    std::fs::write(
+        share.join("applications").join(&desktop_id),
+        app.desktop_entry(&app.exe, &app.id),
+    )?;
+    std::fs::create_dir_all(share.join("metainfo"))?;
+    std::fs::write(
+        share
+            .join("metainfo")
+            .join(format!("{}.metainfo.xml", app.id)),
+        app.metainfo(&desktop_id),
+    )?;
+    if write_icons(build, &share.join("icons/hicolor"), &app.id)?.is_empty() {
+        tracing::warn!("Flatpaks need an icon to be listed in app launchers. Add a png or svg icon to `icon` in the `[bundle]` section of `Dioxus.toml`.");
+    }
+
+    let finish_args = DEFAULT_FLATPAK_PERMISSIONS
+        .iter()
+        .map(|permission| permission.to_string())
+        .chain(settings.permissions.iter().flatten().cloned())
+        .collect::<Vec<_>>();
+    let manifest = json!({
+        "id": app.id,
+        "runtime": settings.runtime.as_deref().unwrap_or("org.gnome.Platform"),
+        "runtime-version": settings.runtime_version.as_deref().unwrap_or("48"),
+        "sdk": settings.sdk.as_deref().unwrap_or("org.gnome.Sdk"),
+        "command": app.exe,
+        "finish-args": finish_args,
+        "modules": [{
+            "name": app.exe,
+            "buildsystem": "simple",
+            "build-commands": ["cp -r . /app/"],
+            "sources": [{ "type": "dir", "path": "files" }],
+        }],
+    });
+    let manifest_path = flatpak_dir.join(format!("{}.json", app.id));
+    std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    let mut bundles = vec![flatpak_dir.clone()];
+
+    if which::which("flatpak-builder").is_err() {
+        tracing::warn!(
+            "flatpak-builder is not installed, so only the flatpak manifest was written to {}. Install flatpak-builder to build a .flatpak bundle.",
+            manifest_path.display()
+        );
+        return Ok(bundles);
+    }
+
+    let arch = match build.triple.architecture {
+        Architecture::X86_64 => "x86_64",
+        Architecture::Aarch64(_) => "aarch64",
+        arch => bail!("Flatpaks can't be built for the {arch} architecture"),
+    };
+
+    // The build directory and repo are kept out of the flatpak folder so they aren't copied to the output directory
+    let build_dir = bundle_dir.join("flatpak-build");
+    let repo = bundle_dir.join("flatpak-repo");
+    let status = std::process::Command::new("flatpak-builder")
+        .arg("--force-clean")
+        .arg("--user")
+        .arg("--install-deps-from=flathub")
+        .arg(format!("--arch={arch}"))
+        .arg("--repo")
+        .arg(&repo)
+        .arg(&build_dir)
+        .arg(&manifest_path)
+        .status()
+        .context("Failed to run flatpak-builder")?;
+    if !status.success() {
+        bail!(
+            "flatpak-builder failed to build {}",
+            manifest_path.display()
+        );
+    }
+
+    let flatpak = bundle_dir.join(format!("{}_{}_{arch}.flatpak", app.name, app.version));
+    let status = std::process::Command::new("flatpak")
+        .arg("build-bundle")
+        .arg(format!("--arch={arch}"))
+        .arg(&repo)
+        .arg(&flatpak)
+        .arg(&app.id)
+        .status()
+        .context("Failed to run flatpak build-bundle")?;
+    if !status.success() {
+        bail!("flatpak build-bundle failed to bundle {}", app.id);
+    }
+
+    bundles.push(flatpak);
+    Ok(bundles)
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> LinuxApp {
+        LinuxApp {
+            id: "com.example.Notes".to_string(),
+            name: "Notes".to_string(),
+            exe: "notes".to_string(),
+            version: "1.2.0".to_string(),
+            summary: Some("Take notes".to_string()),
+            description: Some("Write notes.\n\nSync them & share them.".to_string()),
+            developer: Some("Example".to_string()),
+            license: Some("MIT".to_string()),
+            categories: "Office;".to_string(),
+            keywords: vec!["markdown".to_string()],
+            mime_types: vec!["text/markdown".to_string()],
+            url_schemes: vec!["notes".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn desktop_entry_opens_files_and_links() {
+        let entry = app().desktop_entry("notes", "com.example.Notes");
+        assert!(entry.contains("Exec=notes %u\n"));
+        assert!(entry.contains("Icon=com.example.Notes\n"));
+        assert!(entry.contains("Keywords=markdown;\n"));
+        assert!(entry.contains("MimeType=text/markdown;x-scheme-handler/notes;\n"));
+
+        let entry = LinuxApp {
+            url_schemes: vec![],
+            ..app()
+        }
+        .desktop_entry("notes", "notes");
+        assert!(entry.contains("Exec=notes %F\n"));
+    }
+
+    #[test]
+    fn metainfo_describes_the_app() {
+        let metainfo = app().metainfo("com.example.Notes.desktop");
+        assert!(metainfo.contains("<id>com.example.Notes</id>"));
+        assert!(metainfo.contains("<project_license>MIT</project_license>"));
+        assert!(metainfo.contains("<p>Sync them &amp; share them.</p>"));
+        assert!(metainfo.contains("<developer id=\"com.example\">"));
+        assert!(metainfo
+            .contains("<launchable type=\"desktop-id\">com.example.Notes.desktop</launchable>"));
+        assert!(metainfo.contains("<mediatype>text/markdown</mediatype>"));
+        assert!(metainfo.contains("<category>Office</category>"));
+        assert!(metainfo.contains("<release version=\"1.2.0\"/>"));
+
+        let metainfo = LinuxApp {
+            release_date: Some("2025-06-01".to_string()),
+            ..app()
+        }
+        .metainfo("com.example.Notes.desktop");
+        assert!(metainfo.contains("<release version=\"1.2.0\" date=\"2025-06-01\"/>"));
+    }
+}
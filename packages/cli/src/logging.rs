@@ -56,6 +56,7 @@ use tracing_subscriber::{
     fmt::{
         format::{self, Writer},
         time::FormatTime,
+        writer::BoxMakeWriter,
     },
     prelude::*,
     registry::LookupSpan,
@@ -140,7 +141,8 @@ impl TraceController {
     {
         let args = Cli::parse();
         let tui_active = Arc::new(AtomicBool::new(false));
-        let is_serve_cmd = matches!(args.action, Commands::Serve(_));
+        let is_serve_cmd = matches!(args.action, Commands::Serve(_) | Commands::Test(_));
+        let is_lsp_cmd = matches!(args.action, Commands::Lsp(_));
 
         VERBOSITY
             .set(args.verbosity.clone())
@@ -194,6 +196,12 @@ impl TraceController {
             )
             .with_timer(PrettyUptime::default());
 
+        // The language server speaks its protocol over stdout, so its logs need to go to stderr instead
+        let fmt_layer = match is_lsp_cmd {
+            true => fmt_layer.with_writer(BoxMakeWriter::new(std::io::stderr)),
+            false => fmt_layer.with_writer(BoxMakeWriter::new(std::io::stdout)),
+        };
+
         // If json output is enabled, we want to format the output as JSON
         // When running in interactive mode (of which serve is the only one), we don't want to log to console directly
         let fmt_layer = if args.verbosity.json_output {
@@ -815,6 +823,7 @@ impl TraceController {
                 }),
             ),
             Commands::Serve(serve) => ("serve".to_string(), serve.anonymized()),
+            Commands::Test(test) => ("test".to_string(), test.anonymized()),
             Commands::Bundle(bundle) => (
                 "bundle".to_string(),
                 json!({
@@ -864,6 +873,27 @@ impl TraceController {
                     "build_args": cmd.build_args.anonymized(),
                 }),
             ),
+            Commands::Lsp(cmd) => (
+                "lsp".to_string(),
+                json!({
+                    "split_line_attributes": cmd.split_line_attributes,
+                }),
+            ),
+            Commands::Clean(cmd) => (
+                "clean".to_string(),
+                json!({
+                    "assets": cmd.assets,
+                }),
+            ),
+            Commands::Analyze(cmd) => (
+                "analyze".to_string(),
+                json!({
+                    "baseline": cmd.baseline.is_some(),
+                    "save_baseline": cmd.save_baseline.is_some(),
+                    "max_increase": cmd.max_increase.is_some(),
+                    "build_args": cmd.build_args.anonymized(),
+                }),
+            ),
             Commands::Config(config) => match config {
                 Config::Init { force, .. } => (
                     "config init".to_string(),
@@ -881,6 +911,7 @@ impl TraceController {
                         Setting::AlwaysOnTop { value } => json!({ "value": value }),
                         Setting::WSLFilePollInterval { value } => json!({ "value": value }),
                         Setting::DisableTelemetry { value } => json!({ "value": value }),
+                        Setting::AssetCacheMaxSize { value } => json!({ "value": value }),
                     },
                 ),
             },
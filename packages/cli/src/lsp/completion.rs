@@ -0,0 +1,167 @@
+use super::document::{ident_at, position_at, rsx_context, RsxContext};
+use dioxus_html::docs::{AttributeDoc, ElementDoc};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionItemTag, Documentation, Hover, HoverContents,
+    InsertTextFormat, MarkupContent, MarkupKind, Range,
+};
+use std::collections::HashSet;
+
+/// Complete the elements and attributes that are valid at the given offset
+pub(crate) fn completions(text: &str, offset: usize, snippets: bool) -> Vec<CompletionItem> {
+    let mut items = vec![];
+
+    let attributes = match rsx_context(text, offset) {
+        RsxContext::Rust | RsxContext::Value => return items,
+        RsxContext::Children => None,
+        RsxContext::Element(element) => Some(element),
+    };
+
+    if let Some(element) = attributes {
+        let mut seen = HashSet::new();
+        for attribute in element.all_attributes() {
+            if seen.insert(attribute.name) {
+                items.push(attribute_completion(attribute, snippets));
+            }
+        }
+    }
+
+    for element in dioxus_html::docs::elements() {
+        items.push(element_completion(element, snippets));
+    }
+
+    items
+}
+
+fn element_completion(element: &ElementDoc, snippets: bool) -> CompletionItem {
+    let (insert_text, insert_text_format) = match snippets {
+        true => (
+            format!("{} {{ $0 }}", element.name),
+            InsertTextFormat::SNIPPET,
+        ),
+        false => (element.name.to_string(), InsertTextFormat::PLAIN_TEXT),
+    };
+
+    CompletionItem {
+        label: element.name.to_string(),
+        kind: Some(CompletionItemKind::STRUCT),
+        detail: Some(format!("<{}> element", element.name)),
+        documentation: Some(markdown(element.docs())),
+        insert_text: Some(insert_text),
+        insert_text_format: Some(insert_text_format),
+        ..Default::default()
+    }
+}
+
+fn attribute_completion(attribute: &AttributeDoc, snippets: bool) -> CompletionItem {
+    let (insert_text, insert_text_format) = match snippets {
+        true => (format!("{}: $0", attribute.name), InsertTextFormat::SNIPPET),
+        false => (
+            format!("{}: ", attribute.name),
+            InsertTextFormat::PLAIN_TEXT,
+        ),
+    };
+
+    CompletionItem {
+        label: attribute.rsx_name().to_string(),
+        kind: Some(CompletionItemKind::FIELD),
+        detail: Some("attribute".to_string()),
+        documentation: Some(markdown(attribute.docs())),
+        insert_text: Some(insert_text),
+        insert_text_format: Some(insert_text_format),
+        tags: attribute
+            .is_deprecated()
+            .then(|| vec![CompletionItemTag::DEPRECATED]),
+        ..Default::default()
+    }
+}
+
+/// Show the docs of the element or attribute under the cursor
+pub(crate) fn hover(text: &str, offset: usize) -> Option<Hover> {
+    let ident = ident_at(text, offset)?;
+    let name = text[ident.clone()].trim_start_matches("r#");
+    let after = text[ident.end..].trim_start();
+
+    let contents = match rsx_context(text, ident.start) {
+        RsxContext::Rust | RsxContext::Value => return None,
+
+        // An element is followed by its body
+        _ if after.starts_with('{') => {
+            let element = dioxus_html::docs::element(name)?;
+            format!("```rust\n{name} {{}}\n```\n\n{}", element.docs())
+        }
+
+        // An attribute is followed by its value, but not a path
+        RsxContext::Element(element) if after.starts_with(':') && !after.starts_with("::") => {
+            let attribute = element.attribute(name)?;
+            format!(
+                "```rust\n{} {{ {}: }}\n```\n\n{}",
+                element.name,
+                attribute.name,
+                attribute.docs()
+            )
+        }
+
+        _ => return None,
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: contents,
+        }),
+        range: Some(Range::new(
+            position_at(text, ident.start),
+            position_at(text, ident.end),
+        )),
+    })
+}
+
+fn markdown(value: String) -> Documentation {
+    Documentation::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_attributes_of_the_enclosing_element() {
+        let text = "rsx! { input {  } }";
+        let offset = text.find("{  }").unwrap() + 2;
+        let items = completions(text, offset, false);
+        let labels = items
+            .iter()
+            .map(|item| item.label.as_str())
+            .collect::<Vec<_>>();
+
+        // Attributes of the element, global attributes and the elements that can be children
+        assert!(labels.contains(&"type"));
+        assert!(labels.contains(&"class"));
+        assert!(labels.contains(&"div"));
+        assert!(!labels.contains(&"view_box"));
+
+        let r#type = items.iter().find(|item| item.label == "type").unwrap();
+        assert_eq!(r#type.insert_text.as_deref(), Some("r#type: "));
+
+        assert!(completions("fn main() {  }", 12, false).is_empty());
+    }
+
+    #[test]
+    fn hovers_elements_and_attributes() {
+        let text = "rsx! { a { href: \"/\" } }";
+        let element = hover(text, text.find("a {").unwrap()).unwrap();
+        let attribute = hover(text, text.find("href").unwrap() + 1).unwrap();
+
+        let (HoverContents::Markup(element), HoverContents::Markup(attribute)) =
+            (element.contents, attribute.contents)
+        else {
+            panic!("hover should be markdown");
+        };
+        assert!(element.value.contains("Element/a"));
+        assert!(attribute.value.contains("a { href: }"));
+        assert!(hover(text, text.find('/').unwrap()).is_none());
+    }
+}
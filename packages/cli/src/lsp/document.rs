@@ -0,0 +1,314 @@
+use dioxus_html::docs::ElementDoc;
+use lsp_types::Position;
+
+/// Convert an lsp position (utf-16 code units) into a byte offset into the text
+pub(crate) fn offset_at(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(idx) => line_start += idx + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut utf16 = 0;
+    for (idx, c) in text[line_start..].char_indices() {
+        if c == '\n' || utf16 >= position.character as usize {
+            return line_start + idx;
+        }
+        utf16 += c.len_utf16();
+    }
+
+    text.len()
+}
+
+/// Convert a byte offset into the text into an lsp position (utf-16 code units)
+pub(crate) fn position_at(text: &str, offset: usize) -> Position {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].encode_utf16().count() as u32,
+    }
+}
+
+/// Convert a 1-indexed line and a char column, like the ones proc-macro2 spans use, into an lsp position
+pub(crate) fn position_from_line_column(text: &str, line: usize, column: usize) -> Position {
+    let character = text
+        .lines()
+        .nth(line.saturating_sub(1))
+        .map(|line| {
+            line.chars()
+                .take(column)
+                .map(char::len_utf16)
+                .sum::<usize>()
+        })
+        .unwrap_or(column);
+
+    Position {
+        line: line.saturating_sub(1) as u32,
+        character: character as u32,
+    }
+}
+
+/// What the cursor is inside of, as far as rsx is concerned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RsxContext {
+    /// Regular rust code, outside of any rsx or inside an expression in rsx
+    Rust,
+    /// Somewhere children are allowed, like the root of an `rsx!` call or the body of a component
+    Children,
+    /// Inside the body of an element, where both attributes and children are allowed
+    Element(&'static ElementDoc),
+    /// Inside a string, comment or an attribute value
+    Value,
+}
+
+/// The kind of delimiter the cursor is nested in
+#[derive(Debug, Clone, Copy)]
+enum Delimiter {
+    Rsx,
+    Element(&'static ElementDoc),
+    /// Components and control flow don't change whether we're in rsx, they inherit it from their parent
+    Inherit,
+    Rust,
+}
+
+/// Find the rsx context at the given byte offset.
+///
+/// This is a heuristic that only looks at the delimiters around the offset instead of parsing the file, since the
+/// file is almost never valid rust while it is being edited.
+pub(crate) fn rsx_context(text: &str, offset: usize) -> RsxContext {
+    let text = &text[..offset.min(text.len())];
+    let bytes = text.as_bytes();
+    let mut stack = vec![];
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'/' if bytes.get(idx + 1) == Some(&b'/') => match text[idx..].find('\n') {
+                Some(end) => idx += end,
+                None => return RsxContext::Value,
+            },
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => match text[idx + 2..].find("*/") {
+                Some(end) => idx += end + 3,
+                None => return RsxContext::Value,
+            },
+            b'"' => match string_end(text, idx + 1, 0) {
+                Some(end) => idx = end,
+                None => return RsxContext::Value,
+            },
+            b'r' if is_raw_string_start(text, idx) => {
+                let hashes = text[idx + 1..].len() - text[idx + 1..].trim_start_matches('#').len();
+                match string_end(text, idx + hashes + 2, hashes) {
+                    Some(end) => idx = end,
+                    None => return RsxContext::Value,
+                }
+            }
+            // Either a char literal or a lifetime, only char literals need to be skipped
+            b'\'' => {
+                let mut chars = text[idx + 1..].chars();
+                match (chars.next(), chars.next()) {
+                    (Some('\\'), _) => match text[idx + 2..].find('\'') {
+                        Some(end) => idx += end + 2,
+                        None => return RsxContext::Value,
+                    },
+                    (Some(c), Some('\'')) => idx += c.len_utf8() + 1,
+                    _ => {}
+                }
+            }
+            b'{' | b'(' | b'[' => stack.push(delimiter_kind(text, idx)),
+            b'}' | b')' | b']' => _ = stack.pop(),
+            _ => {}
+        }
+        idx += 1;
+    }
+
+    let mut context = RsxContext::Rust;
+    for delimiter in stack {
+        context = match (delimiter, context) {
+            (Delimiter::Rsx, _) => RsxContext::Children,
+            (_, RsxContext::Rust) | (Delimiter::Rust, _) => RsxContext::Rust,
+            (Delimiter::Element(el), _) => RsxContext::Element(el),
+            (Delimiter::Inherit, _) => RsxContext::Children,
+        };
+    }
+
+    // After the colon of an attribute we're in the value of the attribute
+    if matches!(context, RsxContext::Element(_)) {
+        let segment_start = text
+            .rfind(['{', '}', ',', '\n'])
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+        if text[segment_start..].contains(':') {
+            return RsxContext::Value;
+        }
+    }
+
+    context
+}
+
+/// Find the kind of the delimiter that opens at the given index
+fn delimiter_kind(text: &str, idx: usize) -> Delimiter {
+    let before = text[..idx].trim_end();
+
+    if before
+        .strip_suffix('!')
+        .is_some_and(|name| name.trim_end().ends_with("rsx"))
+    {
+        return Delimiter::Rsx;
+    }
+
+    if text.as_bytes()[idx] != b'{' {
+        return Delimiter::Rust;
+    }
+
+    // Control flow in rsx contains more rsx
+    let statement = before[before
+        .rfind(['{', '}', ';', ',', '\n'])
+        .map(|idx| idx + 1)
+        .unwrap_or(0)..]
+        .trim_start();
+    if ["if ", "for ", "else"]
+        .iter()
+        .any(|keyword| statement.starts_with(keyword))
+    {
+        return Delimiter::Inherit;
+    }
+
+    let ident_start = before.len() - trailing_ident_len(before);
+    let ident = &before[ident_start..];
+    let is_path = before[..ident_start].ends_with("::");
+
+    if !is_path {
+        if let Some(element) = dioxus_html::docs::element(ident) {
+            return Delimiter::Element(element);
+        }
+    }
+
+    match ident.chars().next() {
+        Some(c) if c.is_uppercase() => Delimiter::Inherit,
+        _ => Delimiter::Rust,
+    }
+}
+
+/// Is the `r` at this index the start of a raw string literal?
+fn is_raw_string_start(text: &str, idx: usize) -> bool {
+    !text[..idx].ends_with(is_ident_char)
+        && text[idx + 1..].trim_start_matches('#').starts_with('"')
+}
+
+/// Find the index of the closing quote of a string that starts at `start`, followed by `hashes` hashes for raw strings
+fn string_end(text: &str, start: usize, hashes: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut idx = start;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' if hashes == 0 => idx += 1,
+            b'"' if text[idx + 1..].len() >= hashes
+                && text.as_bytes()[idx + 1..idx + 1 + hashes]
+                    .iter()
+                    .all(|b| *b == b'#') =>
+            {
+                return Some(idx + hashes)
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    None
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The length in bytes of the identifier at the end of the text
+fn trailing_ident_len(text: &str) -> usize {
+    text.chars()
+        .rev()
+        .take_while(|c| is_ident_char(*c))
+        .map(char::len_utf8)
+        .sum()
+}
+
+/// Find the identifier at the given byte offset. Returns the range of the identifier, including the `r#` prefix of
+/// raw identifiers.
+pub(crate) fn ident_at(text: &str, offset: usize) -> Option<std::ops::Range<usize>> {
+    let offset = offset.min(text.len());
+    let start = offset - trailing_ident_len(&text[..offset]);
+    let end = text[offset..]
+        .find(|c: char| !is_ident_char(c))
+        .map(|idx| offset + idx)
+        .unwrap_or(text.len());
+
+    if start == end {
+        return None;
+    }
+
+    match text[..start].ends_with("r#") {
+        true => Some(start - 2..end),
+        false => Some(start..end),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context_at_cursor(source: &str) -> RsxContext {
+        let offset = source.find('$').unwrap();
+        rsx_context(&source.replace('$', ""), offset)
+    }
+
+    #[test]
+    fn detects_rsx_context() {
+        assert_eq!(context_at_cursor("fn app() { $ }"), RsxContext::Rust);
+        assert_eq!(
+            context_at_cursor("fn app() { rsx! { $ } }"),
+            RsxContext::Children
+        );
+        assert!(matches!(
+            context_at_cursor("rsx! { div { class: \"a\", $ } }"),
+            RsxContext::Element(el) if el.name == "div"
+        ));
+        assert!(matches!(
+            context_at_cursor("rsx! { svg { \"{x}\" $ } }"),
+            RsxContext::Element(el) if el.is_svg()
+        ));
+        assert_eq!(
+            context_at_cursor("rsx! { div { class: \"$\" } }"),
+            RsxContext::Value
+        );
+        assert_eq!(
+            context_at_cursor("rsx! { div { width: $ } }"),
+            RsxContext::Value
+        );
+        assert_eq!(
+            context_at_cursor("rsx! { div { onclick: move |_| { $ } } }"),
+            RsxContext::Rust
+        );
+        assert_eq!(
+            context_at_cursor("rsx! { div { if show {\n $ } } }"),
+            RsxContext::Children
+        );
+        assert_eq!(
+            context_at_cursor("rsx! { Component { $ } }"),
+            RsxContext::Children
+        );
+        assert_eq!(
+            context_at_cursor("rsx! { div {} }\nlet x = '{'; $"),
+            RsxContext::Rust
+        );
+    }
+
+    #[test]
+    fn converts_positions() {
+        let text = "let a = \"ß\";\nlet 😀 = 1;\n";
+        for offset in [0, 5, 13, 14, 18, 22, text.len()] {
+            assert_eq!(offset_at(text, position_at(text, offset)), offset);
+        }
+        assert_eq!(position_at(text, 22), Position::new(1, 6));
+        assert_eq!(position_from_line_column(text, 2, 5), Position::new(1, 6));
+    }
+}
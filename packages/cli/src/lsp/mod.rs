@@ -0,0 +1,426 @@
+//! A language server for rsx, used by `dx lsp`.
+//!
+//! Editors without a dedicated Dioxus extension can use this to format rsx, see the issues `dx check` finds as you
+//! type, and complete and document elements and attributes.
+
+mod completion;
+mod document;
+
+use anyhow::Context;
+use dioxus_autofmt::{IndentOptions, IndentType};
+use document::{offset_at, position_at, position_from_line_column};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as NotificationTrait, PublishDiagnostics,
+    },
+    request::{Completion, Formatting, HoverRequest, RangeFormatting, Request as RequestTrait},
+    CompletionOptions, CompletionResponse, Diagnostic, DiagnosticSeverity, FormattingOptions,
+    HoverProviderCapability, InitializeParams, OneOf, PublishDiagnosticsParams, Range,
+    ServerCapabilities, ServerInfo, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit,
+    Url,
+};
+use std::collections::HashMap;
+
+pub(crate) struct LanguageServer {
+    connection: Connection,
+    documents: HashMap<Url, String>,
+    split_line_attributes: bool,
+    snippets: bool,
+}
+
+impl LanguageServer {
+    /// Initialize the connection with the editor and answer its requests until it asks us to shut down
+    pub(crate) fn run(connection: Connection, split_line_attributes: bool) -> anyhow::Result<()> {
+        let capabilities = ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            document_formatting_provider: Some(OneOf::Left(true)),
+            document_range_formatting_provider: Some(OneOf::Left(true)),
+            completion_provider: Some(CompletionOptions::default()),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            ..Default::default()
+        };
+
+        let (id, params) = connection.initialize_start()?;
+        let params: InitializeParams = serde_json::from_value(params)?;
+        connection.initialize_finish(
+            id,
+            serde_json::json!({
+                "capabilities": capabilities,
+                "serverInfo": ServerInfo {
+                    name: "dx".to_string(),
+                    version: Some(crate::VERSION.to_string()),
+                },
+            }),
+        )?;
+
+        let snippets = params
+            .capabilities
+            .text_document
+            .and_then(|doc| doc.completion?.completion_item?.snippet_support)
+            .unwrap_or(false);
+
+        let mut server = Self {
+            connection,
+            documents: HashMap::new(),
+            split_line_attributes,
+            snippets,
+        };
+
+        while let Ok(message) = server.connection.receiver.recv() {
+            match message {
+                Message::Request(req) => {
+                    if server.connection.handle_shutdown(&req)? {
+                        break;
+                    }
+                    let response = server.handle_request(req);
+                    server.connection.sender.send(response.into())?;
+                }
+                Message::Notification(not) => server.handle_notification(not)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&self, req: Request) -> Response {
+        match req.method.as_str() {
+            Formatting::METHOD => handle::<Formatting>(req, |params| {
+                self.format(&params.text_document.uri, &params.options, None)
+                    .map(Some)
+            }),
+            RangeFormatting::METHOD => handle::<RangeFormatting>(req, |params| {
+                self.format(
+                    &params.text_document.uri,
+                    &params.options,
+                    Some(params.range),
+                )
+                .map(Some)
+            }),
+            Completion::METHOD => handle::<Completion>(req, |params| {
+                let position = params.text_document_position;
+                let text = self.document(&position.text_document.uri)?;
+                let offset = offset_at(text, position.position);
+                let items = completion::completions(text, offset, self.snippets);
+                Ok(Some(CompletionResponse::Array(items)))
+            }),
+            HoverRequest::METHOD => handle::<HoverRequest>(req, |params| {
+                let position = params.text_document_position_params;
+                let text = self.document(&position.text_document.uri)?;
+                Ok(completion::hover(text, offset_at(text, position.position)))
+            }),
+            _ => Response::new_err(
+                req.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request: {}", req.method),
+            ),
+        }
+    }
+
+    /// Apply a notification from the editor. Notifications have no response, so malformed ones are logged and
+    /// ignored instead of stopping the server.
+    fn handle_notification(&mut self, not: Notification) -> anyhow::Result<()> {
+        match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Some(params) = extract::<DidOpenTextDocument>(not) else {
+                    return Ok(());
+                };
+                let uri = params.text_document.uri;
+                self.documents
+                    .insert(uri.clone(), params.text_document.text);
+                self.publish_diagnostics(uri)?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let Some(params) = extract::<DidChangeTextDocument>(not) else {
+                    return Ok(());
+                };
+                let uri = params.text_document.uri;
+
+                // We only support full syncs, so the last change holds the entire document
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.documents.insert(uri.clone(), change.text);
+                }
+                self.publish_diagnostics(uri)?;
+            }
+            DidCloseTextDocument::METHOD => {
+                let Some(params) = extract::<DidCloseTextDocument>(not) else {
+                    return Ok(());
+                };
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                self.send_diagnostics(uri, vec![])?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn document(&self, uri: &Url) -> anyhow::Result<&str> {
+        self.documents
+            .get(uri)
+            .map(String::as_str)
+            .with_context(|| format!("Document {uri} is not open"))
+    }
+
+    /// Format the rsx blocks in the document, or only the blocks that overlap the range
+    fn format(
+        &self,
+        uri: &Url,
+        options: &FormattingOptions,
+        range: Option<Range>,
+    ) -> anyhow::Result<Vec<TextEdit>> {
+        let text = self.document(uri)?;
+        let indent = IndentOptions::new(
+            match options.insert_spaces {
+                true => IndentType::Spaces,
+                false => IndentType::Tabs,
+            },
+            options.tab_size.max(1) as usize,
+            self.split_line_attributes,
+        );
+
+        let parsed = syn::parse_file(text).context("Failed to parse file")?;
+        let blocks =
+            dioxus_autofmt::try_fmt_file(text, &parsed, indent).context("Failed to format rsx")?;
+
+        let range = range.map(|range| offset_at(text, range.start)..offset_at(text, range.end));
+
+        Ok(blocks
+            .into_iter()
+            .filter(|block| {
+                range.as_ref().map_or(true, |range| {
+                    block.start <= range.end && range.start <= block.end
+                })
+            })
+            .map(|block| TextEdit {
+                range: Range::new(position_at(text, block.start), position_at(text, block.end)),
+                new_text: block.formatted,
+            })
+            .collect())
+    }
+
+    /// Publish the issues `dx check` finds in the document. The issues of a file that doesn't parse can't be found, so
+    /// the previous ones are cleared instead of pointing at code that changed.
+    fn publish_diagnostics(&self, uri: Url) -> anyhow::Result<()> {
+        let text = match self.document(&uri) {
+            Ok(text) => text,
+            Err(err) => {
+                tracing::warn!("Failed to publish diagnostics: {err}");
+                return Ok(());
+            }
+        };
+        if syn::parse_file(text).is_err() {
+            return self.send_diagnostics(uri, vec![]);
+        }
+
+        let path = uri.to_file_path().unwrap_or_default();
+        let report = dioxus_check::check_file(path, text);
+        let diagnostics = report
+            .issues
+            .iter()
+            .map(|issue| {
+                let span = issue.hook_info().name_span;
+                Diagnostic {
+                    range: Range::new(
+                        position_from_line_column(text, span.start.line, span.start.column),
+                        position_from_line_column(text, span.end.line, span.end.column),
+                    ),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some("dx check".to_string()),
+                    message: issue.to_string(),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.send_diagnostics(uri, diagnostics)
+    }
+
+    fn send_diagnostics(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> anyhow::Result<()> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        self.connection
+            .sender
+            .send(Notification::new(PublishDiagnostics::METHOD.to_string(), params).into())?;
+        Ok(())
+    }
+}
+
+/// Deserialize the params of a notification, logging the notifications that are malformed
+fn extract<N: NotificationTrait>(not: Notification) -> Option<N::Params> {
+    not.extract(N::METHOD)
+        .inspect_err(|err| tracing::warn!("Ignoring malformed {} notification: {err}", N::METHOD))
+        .ok()
+}
+
+/// Deserialize the params of a request, run the handler and serialize its result into a response
+fn handle<R: RequestTrait>(
+    req: Request,
+    handler: impl FnOnce(R::Params) -> anyhow::Result<R::Result>,
+) -> Response {
+    let id = req.id.clone();
+    let result = req
+        .extract::<R::Params>(R::METHOD)
+        .map_err(anyhow::Error::from)
+        .and_then(|(_, params)| handler(params));
+
+    match result {
+        Ok(result) => Response::new_ok(id, result),
+        Err(err) => Response::new_err(id, ErrorCode::RequestFailed as i32, err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::{
+        DidChangeTextDocumentParams, DidOpenTextDocumentParams, DocumentFormattingParams,
+        HoverParams, Position, TextDocumentContentChangeEvent, TextDocumentIdentifier,
+        TextDocumentItem, TextDocumentPositionParams, VersionedTextDocumentIdentifier,
+    };
+
+    fn request<R: RequestTrait>(client: &Connection, id: i32, params: R::Params) -> Response {
+        client
+            .sender
+            .send(Request::new(id.into(), R::METHOD.to_string(), params).into())
+            .unwrap();
+        loop {
+            if let Message::Response(response) = client.receiver.recv().unwrap() {
+                return response;
+            }
+        }
+    }
+
+    fn next_diagnostics(client: &Connection) -> PublishDiagnosticsParams {
+        let Message::Notification(diagnostics) = client.receiver.recv().unwrap() else {
+            panic!("expected diagnostics");
+        };
+        diagnostics.extract(PublishDiagnostics::METHOD).unwrap()
+    }
+
+    #[test]
+    fn serves_an_editor() {
+        let (server, client) = Connection::memory();
+        let server = std::thread::spawn(move || LanguageServer::run(server, false));
+
+        let init = request::<lsp_types::request::Initialize>(&client, 1, Default::default());
+        assert!(init.error.is_none());
+        client
+            .sender
+            .send(Notification::new("initialized".to_string(), serde_json::json!({})).into())
+            .unwrap();
+
+        let uri = Url::parse("file:///tmp/app.rs").unwrap();
+        let text = "fn app() -> Element {\n    if cond { let x = use_signal(|| 0); }\n    rsx! { div {     class: \"a\" } }\n}\n";
+        client
+            .sender
+            .send(
+                Notification::new(
+                    DidOpenTextDocument::METHOD.to_string(),
+                    DidOpenTextDocumentParams {
+                        text_document: TextDocumentItem::new(
+                            uri.clone(),
+                            "rust".to_string(),
+                            0,
+                            text.to_string(),
+                        ),
+                    },
+                )
+                .into(),
+            )
+            .unwrap();
+
+        // Opening the document publishes the hook issues
+        let diagnostics = next_diagnostics(&client);
+        assert_eq!(diagnostics.diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics.diagnostics[0].range,
+            Range::new(Position::new(1, 22), Position::new(1, 32))
+        );
+
+        let format = request::<Formatting>(
+            &client,
+            2,
+            DocumentFormattingParams {
+                text_document: TextDocumentIdentifier::new(uri.clone()),
+                options: FormattingOptions {
+                    tab_size: 4,
+                    insert_spaces: true,
+                    ..Default::default()
+                },
+                work_done_progress_params: Default::default(),
+            },
+        );
+        let edits: Vec<TextEdit> = serde_json::from_value(format.result.unwrap()).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert!(edits[0].new_text.contains("div { class: \"a\" }"));
+
+        let hover = request::<HoverRequest>(
+            &client,
+            3,
+            HoverParams {
+                text_document_position_params: TextDocumentPositionParams::new(
+                    TextDocumentIdentifier::new(uri.clone()),
+                    Position::new(2, 23),
+                ),
+                work_done_progress_params: Default::default(),
+            },
+        );
+        assert!(hover.result.unwrap().to_string().contains("class"));
+
+        // Malformed notifications and notifications for documents that aren't open are ignored
+        for (method, params) in [
+            (
+                DidOpenTextDocument::METHOD,
+                serde_json::json!({ "textDocument": 1 }),
+            ),
+            (
+                DidChangeTextDocument::METHOD,
+                serde_json::to_value(DidChangeTextDocumentParams {
+                    text_document: VersionedTextDocumentIdentifier::new(
+                        Url::parse("file:///tmp/other.rs").unwrap(),
+                        1,
+                    ),
+                    content_changes: vec![],
+                })
+                .unwrap(),
+            ),
+        ] {
+            client
+                .sender
+                .send(Notification::new(method.to_string(), params).into())
+                .unwrap();
+        }
+
+        // A file that doesn't parse clears the issues
+        client
+            .sender
+            .send(
+                Notification::new(
+                    DidChangeTextDocument::METHOD.to_string(),
+                    DidChangeTextDocumentParams {
+                        text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 1),
+                        content_changes: vec![TextDocumentContentChangeEvent {
+                            range: None,
+                            range_length: None,
+                            text: "fn app() -> Element {".to_string(),
+                        }],
+                    },
+                )
+                .into(),
+            )
+            .unwrap();
+        let diagnostics = next_diagnostics(&client);
+        assert_eq!(diagnostics.uri, uri);
+        assert!(diagnostics.diagnostics.is_empty());
+
+        request::<lsp_types::request::Shutdown>(&client, 4, ());
+        client
+            .sender
+            .send(Notification::new("exit".to_string(), ()).into())
+            .unwrap();
+        server.join().unwrap().unwrap();
+    }
+}
@@ -9,17 +9,21 @@ mod bundle_utils;
 mod cargo_toml;
 mod cli;
 mod config;
+mod container;
 mod devcfg;
 mod dx_build_info;
 mod error;
 mod fastfs;
+mod linux_bundle;
 mod logging;
+mod lsp;
 mod platform;
 mod rustcwrapper;
 mod serve;
 mod settings;
 mod tailwind;
 mod test_harnesses;
+mod update_manifest;
 mod wasm_bindgen;
 mod wasm_opt;
 mod workspace;
@@ -54,12 +58,16 @@ async fn main() {
     let result = TraceController::main(|args, tracer| async move {
         match args {
             Commands::Serve(opts) => opts.serve(&tracer).await,
+            Commands::Test(opts) => opts.test(&tracer).await,
             Commands::Translate(opts) => opts.translate(),
             Commands::New(opts) => opts.create().await,
             Commands::Init(opts) => opts.init().await,
             Commands::Config(opts) => opts.config().await,
             Commands::Autoformat(opts) => opts.autoformat().await,
             Commands::Check(opts) => opts.check().await,
+            Commands::Lsp(opts) => opts.lsp().await,
+            Commands::Analyze(opts) => opts.analyze().await,
+            Commands::Clean(opts) => opts.clean().await,
             Commands::Build(opts) => opts.build().await,
             Commands::Bundle(opts) => opts.bundle().await,
             Commands::Run(opts) => opts.run().await,
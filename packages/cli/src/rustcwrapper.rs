@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::{
     env::{args, vars},
+    io::Write,
     path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 /// The environment variable indicating where the args file is located.
@@ -9,6 +11,11 @@ use std::{
 /// When `dx-rustc` runs, it writes its arguments to this file.
 pub const DX_RUSTC_WRAPPER_ENV_VAR: &str = "DX_RUSTC";
 
+/// The environment variable pointing to the file `dx-rustc` appends the duration of each rustc invocation to.
+///
+/// This is only set when the build is profiled with `dx build --timings`.
+pub const DX_RUSTC_TIMINGS_ENV_VAR: &str = "DX_RUSTC_TIMINGS";
+
 /// Is `dx` being used as a rustc wrapper?
 ///
 /// This is primarily used to intercept cargo, enabling fast hot-patching by caching the environment
@@ -28,6 +35,19 @@ pub struct RustcArgs {
     pub link_args: Vec<String>, // I don't believe this is used anymore
 }
 
+/// How long a single rustc invocation took, written as a line of json to the timings file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RustcTiming {
+    /// The package the crate belongs to
+    pub package: String,
+    /// The name of the crate, like `build_script_build` for build scripts
+    pub krate: String,
+    /// When rustc started, in milliseconds since the unix epoch
+    pub start: u64,
+    /// How long rustc ran for, in milliseconds
+    pub duration: u64,
+}
+
 /// Check if the arguments indicate a linking step, including those in command files.
 fn has_linking_args() -> bool {
     for arg in std::env::args() {
@@ -89,13 +109,15 @@ pub fn run_rustc() {
 
     // Another terrible hack to avoid caching non-sensical args when
     // a build is completely fresh (rustc is invoked with --crate-name ___)
-    if rustc_args
+    let crate_name = rustc_args
         .args
         .iter()
         .skip_while(|arg| *arg != "--crate-name")
         .nth(1)
-        .is_some_and(|name| name != "___")
-    {
+        .filter(|name| *name != "___")
+        .cloned();
+
+    if crate_name.is_some() {
         let parent_dir = var_file
             .parent()
             .expect("Args file path has no parent directory");
@@ -121,6 +143,30 @@ pub fn run_rustc() {
     cmd.current_dir(std::env::current_dir().expect("Failed to get current dir"));
 
     // Spawn the process and propagate its exit code.
+    let started = SystemTime::now();
     let status = cmd.status().expect("Failed to execute rustc command");
+
+    if let (Some(krate), Ok(timings_file)) = (crate_name, std::env::var(DX_RUSTC_TIMINGS_ENV_VAR)) {
+        let timing = RustcTiming {
+            package: std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| krate.clone()),
+            krate,
+            start: started
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            duration: started.elapsed().unwrap_or_default().as_millis() as u64,
+        };
+
+        // rustc runs in parallel, so every invocation appends a single line in one write
+        let line = format!("{}\n", serde_json::to_string(&timing).unwrap());
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(timings_file)
+        {
+            _ = file.write_all(line.as_bytes());
+        }
+    }
+
     std::process::exit(status.code().unwrap_or(1)); // Exit with 1 if process was killed by signal
 }
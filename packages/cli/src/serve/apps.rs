@@ -0,0 +1,242 @@
+use super::{get_available_port, proxy::forward_to, AppServer, ServeUpdate, WebServer};
+use crate::{AddressArguments, Result, ServeAppConfig, ServeArgs, Workspace};
+use anyhow::{bail, Context};
+use axum::Router;
+use clap::Parser;
+use std::net::SocketAddr;
+
+/// An app served by `dx serve` along with its devserver.
+///
+/// `dx serve` usually serves a single app, but it can also serve several apps of a workspace at once. Those are
+/// picked with `--app` or listed in the `[serve.apps]` section of Dioxus.toml. Every app gets its own builds,
+/// devserver and hot-reload session. Apps with a route are also served under that path prefix on the main port.
+pub(crate) struct ServedApp {
+    /// The name of the app if multiple apps are served
+    pub(crate) name: Option<String>,
+
+    /// The url of the app on the main port if it has a route
+    pub(crate) url: Option<String>,
+
+    pub(crate) builder: AppServer,
+    pub(crate) devserver: WebServer,
+}
+
+impl ServedApp {
+    /// Resolve the apps to serve and start their devservers.
+    ///
+    /// If any of the apps has a route, the router that forwards the main port to the apps is started as well.
+    pub(crate) async fn start_all(args: ServeArgs) -> Result<Vec<Self>> {
+        let workspace = Workspace::current().await?;
+        let config = workspace.load_serve_config()?;
+
+        // Serve every app in Dioxus.toml unless the user picked some apps or a single package
+        let package = &args.platform_args.shared.targets.build_arguments.package;
+        let names: Vec<String> = match args.apps.is_empty() {
+            false => args.apps.clone(),
+            true if package.is_none() => config.apps.keys().cloned().collect(),
+            true => vec![],
+        };
+
+        if names.is_empty() {
+            let builder = AppServer::new(args).await?;
+            let devserver = WebServer::start(&builder)?;
+            return Ok(vec![Self {
+                name: None,
+                url: None,
+                builder,
+                devserver,
+            }]);
+        }
+
+        let bind_ip = args.address.addr.unwrap_or(WebServer::SELF_IP);
+        let mut apps = vec![];
+        let mut routes: Vec<(String, SocketAddr)> = vec![];
+        let mut app_routes = vec![];
+        for name in names {
+            let app = match config.apps.get(&name) {
+                Some(app) => app.clone(),
+                None if workspace.krates.krates().any(|krate| krate.name == name) => {
+                    ServeAppConfig::default()
+                }
+                None => bail!(
+                    "`{name}` is neither an app in the [serve.apps] section of Dioxus.toml nor a package in the workspace"
+                ),
+            };
+
+            let route = app
+                .route
+                .as_deref()
+                .map(|route| format!("/{}", route.trim_matches('/')));
+            if let Some(route) = route.as_ref() {
+                if routes.iter().any(|(existing, _)| existing == route) {
+                    bail!("Multiple apps are served at the route `{route}`");
+                }
+            }
+
+            let port = app.port.or_else(|| get_available_port(bind_ip, None));
+            let app_args = Self::app_args(&args, &name, &app, route.as_deref(), port)?;
+            let builder = AppServer::new(app_args).await?;
+            let devserver = WebServer::start(&builder)?;
+
+            if let Some(route) = route.clone() {
+                routes.push((route, devserver.devserver_address()));
+            }
+            app_routes.push(route);
+
+            apps.push(Self {
+                name: Some(name),
+                url: None,
+                builder,
+                devserver,
+            });
+        }
+
+        if !routes.is_empty() {
+            let port = args
+                .address
+                .port
+                .unwrap_or_else(|| get_available_port(bind_ip, Some(8080)).unwrap_or(8080));
+            let address = SocketAddr::new(bind_ip, port);
+            Self::start_router(address, &routes)?;
+
+            // Show 127.0.0.1 instead of 0.0.0.0 like the devserver does
+            let displayed = match bind_ip.is_unspecified() {
+                true => SocketAddr::new(WebServer::SELF_IP, port),
+                false => address,
+            };
+            for (app, route) in apps.iter_mut().zip(app_routes) {
+                app.url = route
+                    .map(|route| format!("http://{displayed}{}/", route.trim_end_matches('/')));
+            }
+        }
+
+        Ok(apps)
+    }
+
+    /// Wait for an update from any of the apps, returning the index of the app it came from
+    pub(crate) async fn wait_any(apps: &mut [Self]) -> (usize, ServeUpdate) {
+        let updates = apps.iter_mut().enumerate().map(|(idx, app)| {
+            Box::pin(async move {
+                let update = tokio::select! {
+                    update = app.builder.wait() => update,
+                    update = app.devserver.wait() => update,
+                };
+                (idx, update)
+            })
+        });
+
+        futures_util::future::select_all(updates).await.0
+    }
+
+    /// The arguments for a single app.
+    ///
+    /// The build options come from the args of the app in Dioxus.toml while the options of the session, like hot
+    /// reloading or opening the browser, apply to every app.
+    fn app_args(
+        args: &ServeArgs,
+        name: &str,
+        app: &ServeAppConfig,
+        route: Option<&str>,
+        port: Option<u16>,
+    ) -> Result<ServeArgs> {
+        let mut argv = vec!["serve".to_string()];
+        argv.extend(
+            shell_words::split(app.args.as_deref().unwrap_or_default())
+                .with_context(|| format!("Failed to parse the args of the `{name}` app"))?,
+        );
+        let mut app_args = ServeArgs::try_parse_from(argv)
+            .with_context(|| format!("Invalid args for the `{name}` app"))?;
+
+        app_args.address = AddressArguments {
+            addr: args.address.addr,
+            port,
+        };
+        app_args.open = args.open;
+        app_args.hot_reload = args.hot_reload;
+        app_args.always_on_top = args.always_on_top;
+        app_args.cross_origin_policy |= args.cross_origin_policy;
+        app_args.wsl_file_poll_interval = args.wsl_file_poll_interval;
+        app_args.interactive = args.interactive;
+        app_args.hot_patch |= args.hot_patch;
+        app_args.watch = args.watch;
+        app_args.proxy_mode = args.proxy_mode.or(app_args.proxy_mode);
+        app_args.exit_on_error |= args.exit_on_error;
+
+        let package = app.package.clone().unwrap_or_else(|| name.to_string());
+        let session = &args.platform_args.shared.targets.build_arguments;
+        let platforms = &mut app_args.platform_args;
+        for targets in std::iter::once(&mut platforms.shared)
+            .chain(platforms.client.as_mut())
+            .chain(platforms.server.as_mut())
+        {
+            let target = &mut targets.targets.build_arguments;
+            target.package.get_or_insert_with(|| package.clone());
+            target.release |= session.release;
+            if target.profile.is_none() {
+                target.profile = session.profile.clone();
+            }
+            if let Some(route) = route.filter(|route| *route != "/") {
+                target.base_path = Some(route.trim_start_matches('/').to_string());
+                target.devtools_path = Some(format!("{route}/_dioxus"));
+            }
+        }
+
+        Ok(app_args)
+    }
+
+    /// Start the router that forwards requests on the main port to the devservers of the apps by path prefix
+    fn start_router(address: SocketAddr, routes: &[(String, SocketAddr)]) -> Result<()> {
+        let mut router = Router::new();
+        for (route, devserver) in routes {
+            let service = forward_to(format!("http://{devserver}").parse()?);
+            if route == "/" {
+                router = router.fallback_service(service);
+            } else {
+                router = router
+                    .route(route, service.clone())
+                    .route(&format!("{route}/"), service.clone())
+                    .route(&format!("{route}/{{*path}}"), service);
+            }
+        }
+
+        let listener = std::net::TcpListener::bind(address).with_context(|| {
+            format!("Failed to bind the router for the apps to {address}, is there another devserver running?")
+        })?;
+        listener.set_nonblocking(true)?;
+
+        tokio::spawn(async move {
+            let listener = tokio::net::TcpListener::from_std(listener)?;
+            axum::serve(listener, router.into_make_service()).await?;
+            Result::<()>::Ok(())
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn app_args_inherit_the_session() {
+        let session = ServeArgs::try_parse_from(["serve", "--release", "--hot-patch"]).unwrap();
+        let app = ServeAppConfig {
+            package: Some("admin-ui".to_string()),
+            route: Some("/admin/".to_string()),
+            port: None,
+            args: Some("--features \"admin audit\"".to_string()),
+        };
+
+        let args =
+            ServedApp::app_args(&session, "admin", &app, Some("/admin"), Some(8081)).unwrap();
+        let target = &args.platform_args.shared.targets.build_arguments;
+        assert_eq!(target.package.as_deref(), Some("admin-ui"));
+        assert_eq!(target.base_path.as_deref(), Some("admin"));
+        assert_eq!(target.devtools_path.as_deref(), Some("/admin/_dioxus"));
+        assert_eq!(target.features, ["admin audit"]);
+        assert!(target.release);
+        assert!(args.hot_patch);
+        assert_eq!(args.address.port, Some(8081));
+    }
+}
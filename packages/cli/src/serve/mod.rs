@@ -5,14 +5,18 @@ use crate::{
 };
 
 mod ansi_buffer;
+mod apps;
 mod output;
 mod proxy;
+mod proxy_fixtures;
 mod proxy_ws;
+mod reactive_graph;
 mod runner;
 mod server;
 mod update;
 
 use anyhow::bail;
+pub(crate) use apps::*;
 use dioxus_dx_wire_format::BuildStage;
 pub(crate) use output::*;
 pub(crate) use runner::*;
@@ -42,12 +46,12 @@ pub(crate) use update::*;
 pub(crate) async fn serve_all(args: ServeArgs, tracer: &TraceController) -> Result<()> {
     // Load the args into a plan, resolving all tooling, build dirs, arguments, decoding the multi-target, etc
     let exit_on_error = args.exit_on_error;
-    let mut builder = AppServer::new(args).await?;
-    let mut devserver = WebServer::start(&builder)?;
-    let mut screen = Output::start(builder.interactive).await?;
+    let mut apps = ServedApp::start_all(args).await?;
+    let mut screen = Output::start(apps[0].builder.interactive, apps.len()).await?;
 
     // This is our default splash screen. We might want to make this a fancier splash screen in the future
     // Also, these commands might not be the most important, but it's all we've got enabled right now
+    let first = &apps[0].builder;
     tracing::info!(
         r#"-----------------------------------------------------------------
                 Serving your app: {binname}! 🚀
@@ -57,8 +61,15 @@ pub(crate) async fn serve_all(args: ServeArgs, tracer: &TraceController) -> Resu
                 • Press {GLOW_STYLE}`v`{GLOW_STYLE:#} to toggle verbose logging
                 • Press {GLOW_STYLE}`/`{GLOW_STYLE:#} for more commands and shortcuts{extra}
                ----------------------------------------------------------------"#,
-        binname = builder.client.build.executable_name(),
-        extra = if builder.client.build.using_dioxus_explicitly {
+        binname = match apps.len() {
+            1 => first.client.build.executable_name().to_string(),
+            _ => apps
+                .iter()
+                .filter_map(|app| app.name.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        },
+        extra = if first.client.build.using_dioxus_explicitly {
             format!(
                 "\n                Learn more at {LINK_STYLE}https://dioxuslabs.com/learn/0.7/getting_started{LINK_STYLE:#}"
             )
@@ -67,211 +78,95 @@ pub(crate) async fn serve_all(args: ServeArgs, tracer: &TraceController) -> Resu
         }
     );
 
-    builder.initialize();
+    for app in apps.iter().filter(|app| app.url.is_some()) {
+        tracing::info!(
+            "Serving {} at {LINK_STYLE}{}{LINK_STYLE:#}",
+            app.name.as_deref().unwrap_or_default(),
+            app.url.as_deref().unwrap_or_default()
+        );
+    }
+
+    for app in apps.iter_mut() {
+        app.builder.initialize();
+    }
 
     loop {
         // Draw the state of the server to the screen
-        screen.render(&builder, &devserver);
+        screen.render(&apps);
 
         // And then wait for any updates before redrawing
-        let msg = tokio::select! {
-            msg = builder.wait() => msg,
-            msg = devserver.wait() => msg,
-            msg = screen.wait() => msg,
-            msg = tracer.wait() => msg,
+        let (idx, msg) = tokio::select! {
+            (idx, msg) = ServedApp::wait_any(&mut apps) => (Some(idx), msg),
+            msg = screen.wait() => (None, msg),
+            msg = tracer.wait() => (None, msg),
         };
 
-        match msg {
-            ServeUpdate::FilesChanged { files } => {
-                if files.is_empty() || !builder.hot_reload {
-                    continue;
-                }
-
-                builder.handle_file_change(&files, &mut devserver).await;
-            }
+        // Updates from the builds and devservers only concern their own app
+        if let Some(idx) = idx {
+            handle_app_update(&mut apps[idx], msg, &mut screen, exit_on_error).await?;
+            continue;
+        }
 
+        match msg {
             ServeUpdate::RequestRebuild => {
                 // The spacing here is important-ish: we want
                 // `Full rebuild:` to line up with
                 // `Hotreloading:` to keep the alignment during long edit sessions
                 // `Hot-patching:` to keep the alignment during long edit sessions
                 tracing::info!("Full rebuild: triggered manually");
-                builder.full_rebuild().await;
-                devserver.send_reload_start().await;
-                devserver.start_build().await;
-            }
-
-            // Run the server in the background
-            // Waiting for updates here lets us tap into when clients are added/removed
-            ServeUpdate::NewConnection {
-                id,
-                aslr_reference,
-                pid,
-            } => {
-                devserver
-                    .send_hotreload(builder.applied_hot_reload_changes(BuildId::CLIENT))
-                    .await;
-
-                if builder.server.is_some() {
-                    devserver
-                        .send_hotreload(builder.applied_hot_reload_changes(BuildId::SERVER))
-                        .await;
+                for ServedApp {
+                    builder, devserver, ..
+                } in apps.iter_mut()
+                {
+                    builder.full_rebuild().await;
+                    devserver.send_reload_start().await;
+                    devserver.start_build().await;
                 }
-
-                builder.client_connected(id, aslr_reference, pid).await;
             }
 
-            // Received a message from the devtools server - currently we only use this for
-            // logging, so we just forward it the tui
-            ServeUpdate::WsMessage { msg, bundle } => {
-                screen.push_ws_message(bundle, &msg);
+            ServeUpdate::TracingLog { log } => {
+                screen.push_log(log);
             }
 
-            // Wait for logs from the build engine
-            // These will cause us to update the screen
-            // We also can check the status of the builds here in case we have multiple ongoing builds
-            ServeUpdate::BuilderUpdate { id, update } => {
-                let bundle_format = builder.get_build(id).unwrap().build.bundle;
-
-                // Queue any logs to be printed if need be
-                screen.new_build_update(&update);
-
-                // And then update the websocketed clients with the new build status in case they want it
-                devserver.new_build_update(&update).await;
-
-                // Start the SSG build if we need to
-                builder.new_build_update(&update, &devserver).await;
-
-                // And then open the app if it's ready
-                match update {
-                    BuilderUpdate::Progress {
-                        stage: BuildStage::Failed,
-                    } => {
-                        if exit_on_error {
-                            bail!("Build failed for platform: {bundle_format}");
-                        }
-                    }
-                    BuilderUpdate::Progress {
-                        stage: BuildStage::Aborted,
-                    } => {
-                        if exit_on_error {
-                            bail!("Build aborted for platform: {bundle_format}");
-                        }
-                    }
-                    BuilderUpdate::Progress { .. } => {}
-                    BuilderUpdate::CompilerMessage { message } => {
-                        screen.push_cargo_log(message);
-                    }
-                    BuilderUpdate::BuildFailed { err } => {
-                        tracing::error!(
-                            "{ERROR_STYLE}Build failed{ERROR_STYLE:#}: {}",
-                            crate::error::log_stacktrace(&err, 15),
-                            ERROR_STYLE = crate::styles::ERROR_STYLE,
-                        );
-
-                        if exit_on_error {
-                            return Err(err);
-                        }
-                    }
-                    BuilderUpdate::BuildReady { bundle } => match bundle.mode {
-                        BuildMode::Thin { ref cache, .. } => {
-                            let elapsed =
-                                bundle.time_end.duration_since(bundle.time_start).unwrap();
-                            match builder.hotpatch(&bundle, id, cache).await {
-                                Ok(jumptable) => {
-                                    let pid = match id {
-                                        BuildId::CLIENT => builder.client.pid,
-                                        _ => builder.server.as_ref().and_then(|s| s.pid),
-                                    };
-                                    devserver.send_patch(jumptable, elapsed, id, pid).await
-                                }
-                                Err(err) => {
-                                    tracing::error!("Failed to hot-patch app: {err}");
-
-                                    if let Some(_patching) =
-                                        err.downcast_ref::<crate::build::PatchError>()
-                                    {
-                                        tracing::info!("Starting full rebuild: {err}");
-                                        builder.full_rebuild().await;
-                                        devserver.send_reload_start().await;
-                                        devserver.start_build().await;
-                                    }
-                                }
-                            }
-                        }
-                        BuildMode::Base { .. } | BuildMode::Fat => {
-                            _ = builder
-                                .open(&bundle, &mut devserver)
-                                .await
-                                .inspect_err(|e| tracing::error!("Failed to open app: {}", e));
-                        }
-                    },
-                    BuilderUpdate::StdoutReceived { msg } => {
-                        screen.push_stdio(bundle_format, msg, tracing::Level::INFO);
-                    }
-                    BuilderUpdate::StderrReceived { msg } => {
-                        screen.push_stdio(bundle_format, msg, tracing::Level::ERROR);
-                    }
-                    BuilderUpdate::ProcessExited { status } => {
-                        if status.success() {
-                            tracing::info!(
-                                r#"Application [{bundle_format}] exited gracefully.
-               • To restart the app, press `r` to rebuild or `o` to open
-               • To exit the server, press `ctrl+c`"#
+            ServeUpdate::OpenApp => {
+                for ServedApp {
+                    builder, devserver, ..
+                } in apps.iter_mut()
+                {
+                    match builder.use_hotpatch_engine {
+                        true if !matches!(builder.client.build.bundle, BundleFormat::Web) => {
+                            tracing::warn!(
+                                "Opening a native app with hotpatching enabled requires a full rebuild..."
                             );
-                        } else {
-                            tracing::error!(
-                                "Application [{bundle_format}] exited with error: {status}"
-                            );
-                            if exit_on_error {
-                                bail!("Application [{bundle_format}] exited with error: {status}");
-                            }
+                            builder.full_rebuild().await;
+                            devserver.send_reload_start().await;
+                            devserver.start_build().await;
                         }
-                    }
-                    BuilderUpdate::ProcessWaitFailed { err } => {
-                        tracing::warn!(
-                            "Failed to wait for process - maybe it's hung or being debugged?: {err}"
-                        );
-                        if exit_on_error {
-                            return Err(err.into());
+                        _ => {
+                            if let Err(err) = builder.open_all(devserver, true).await {
+                                tracing::error!(
+                                    "Failed to open app: {}",
+                                    crate::error::log_stacktrace(&err, 15)
+                                )
+                            }
                         }
                     }
                 }
             }
 
-            ServeUpdate::TracingLog { log } => {
-                screen.push_log(log);
-            }
-
-            ServeUpdate::OpenApp => match builder.use_hotpatch_engine {
-                true if !matches!(builder.client.build.bundle, BundleFormat::Web) => {
-                    tracing::warn!(
-                        "Opening a native app with hotpatching enabled requires a full rebuild..."
-                    );
-                    builder.full_rebuild().await;
-                    devserver.send_reload_start().await;
-                    devserver.start_build().await;
-                }
-                _ => {
-                    if let Err(err) = builder.open_all(&devserver, true).await {
-                        tracing::error!(
-                            "Failed to open app: {}",
-                            crate::error::log_stacktrace(&err, 15)
-                        )
-                    }
-                }
-            },
-
             ServeUpdate::Redraw => {
                 // simply returning will cause a redraw
             }
 
             ServeUpdate::ToggleShouldRebuild => {
                 use crate::styles::{ERROR, NOTE_STYLE};
-                builder.automatic_rebuilds = !builder.automatic_rebuilds;
+                let automatic_rebuilds = !apps[0].builder.automatic_rebuilds;
+                for app in apps.iter_mut() {
+                    app.builder.automatic_rebuilds = automatic_rebuilds;
+                }
                 tracing::info!(
                     "Automatic rebuilds are currently: {}",
-                    if builder.automatic_rebuilds {
+                    if automatic_rebuilds {
                         format!("{NOTE_STYLE}enabled{NOTE_STYLE:#}")
                     } else {
                         format!("{ERROR}disabled{ERROR:#}")
@@ -279,19 +174,226 @@ pub(crate) async fn serve_all(args: ServeArgs, tracer: &TraceController) -> Resu
                 )
             }
 
+            ServeUpdate::RequestReactiveGraph => {
+                tracing::info!("Requesting a snapshot of the reactive graph from the running apps");
+                for app in apps.iter_mut() {
+                    app.devserver.send_reactive_graph_request().await;
+                }
+            }
+
+            // The debugger is attached to the app that is selected in the tui
             ServeUpdate::OpenDebugger { id } => {
-                builder.open_debugger(&devserver, id).await;
+                let selected = screen.selected_app(apps.len());
+                let ServedApp {
+                    builder, devserver, ..
+                } = &mut apps[selected];
+                builder.open_debugger(devserver, id).await;
             }
 
             ServeUpdate::Exit { error } => {
-                _ = builder.shutdown().await;
-                _ = devserver.shutdown().await;
+                for app in apps.iter_mut() {
+                    _ = app.builder.shutdown().await;
+                    _ = app.devserver.shutdown().await;
+                }
 
                 match error {
                     Some(err) => return Err(err),
                     None => return Ok(()),
                 }
             }
+
+            // The builds and devservers send these, so they are handled by the app they came from above
+            ServeUpdate::FilesChanged { .. }
+            | ServeUpdate::NewConnection { .. }
+            | ServeUpdate::WsMessage { .. }
+            | ServeUpdate::BuilderUpdate { .. } => {}
         }
     }
 }
+
+/// Handle an update from the builds or the devserver of an app
+async fn handle_app_update(
+    app: &mut ServedApp,
+    msg: ServeUpdate,
+    screen: &mut Output,
+    exit_on_error: bool,
+) -> Result<()> {
+    let ServedApp {
+        name,
+        builder,
+        devserver,
+        ..
+    } = app;
+
+    // Tag the output of the app with its name if multiple apps are served
+    let label = |msg: String| match name.as_deref() {
+        Some(name) => format!("[{name}] {msg}"),
+        None => msg,
+    };
+
+    match msg {
+        ServeUpdate::FilesChanged { files } => {
+            if files.is_empty() || !builder.hot_reload {
+                return Ok(());
+            }
+
+            builder.handle_file_change(&files, devserver).await;
+        }
+
+        // Run the server in the background
+        // Waiting for updates here lets us tap into when clients are added/removed
+        ServeUpdate::NewConnection {
+            id,
+            aslr_reference,
+            pid,
+        } => {
+            devserver
+                .send_hotreload(builder.applied_hot_reload_changes(BuildId::CLIENT))
+                .await;
+
+            if builder.server.is_some() {
+                devserver
+                    .send_hotreload(builder.applied_hot_reload_changes(BuildId::SERVER))
+                    .await;
+            }
+
+            builder.client_connected(id, aslr_reference, pid).await;
+        }
+
+        // Received a message from the devtools server - logs are forwarded to the tui and snapshots of the
+        // reactive graph are saved and summarized
+        ServeUpdate::WsMessage { msg, bundle } => {
+            if let Some(graph) = screen.push_ws_message(bundle, &msg) {
+                let file_name = match name.as_deref() {
+                    Some(name) => format!("reactive-graph-{name}.json"),
+                    None => "reactive-graph.json".to_string(),
+                };
+                let path = builder.workspace.target_dir().join("dx").join(file_name);
+                tracing::info!("{}", label(reactive_graph::report(&graph, &path)));
+            }
+        }
+
+        // Wait for logs from the build engine
+        // These will cause us to update the screen
+        // We also can check the status of the builds here in case we have multiple ongoing builds
+        ServeUpdate::BuilderUpdate { id, update } => {
+            let bundle_format = builder.get_build(id).unwrap().build.bundle;
+
+            // Queue any logs to be printed if need be
+            screen.new_build_update(&update);
+
+            // And then update the websocketed clients with the new build status in case they want it
+            devserver.new_build_update(&update).await;
+
+            // Start the SSG build if we need to
+            builder.new_build_update(&update, devserver).await;
+
+            // And then open the app if it's ready
+            match update {
+                BuilderUpdate::Progress {
+                    stage: BuildStage::Failed,
+                } => {
+                    if exit_on_error {
+                        bail!("Build failed for platform: {bundle_format}");
+                    }
+                }
+                BuilderUpdate::Progress {
+                    stage: BuildStage::Aborted,
+                } => {
+                    if exit_on_error {
+                        bail!("Build aborted for platform: {bundle_format}");
+                    }
+                }
+                BuilderUpdate::Progress { .. } => {}
+                BuilderUpdate::CompilerMessage { message } => {
+                    screen.push_cargo_log(message);
+                }
+                BuilderUpdate::BuildFailed { err } => {
+                    tracing::error!(
+                        "{ERROR_STYLE}Build failed{ERROR_STYLE:#}: {}",
+                        crate::error::log_stacktrace(&err, 15),
+                        ERROR_STYLE = crate::styles::ERROR_STYLE,
+                    );
+
+                    if exit_on_error {
+                        return Err(err);
+                    }
+                }
+                BuilderUpdate::BuildReady { bundle } => match bundle.mode {
+                    BuildMode::Thin { ref cache, .. } => {
+                        let elapsed = bundle.time_end.duration_since(bundle.time_start).unwrap();
+                        match builder.hotpatch(&bundle, id, cache).await {
+                            Ok(jumptable) => {
+                                let pid = match id {
+                                    BuildId::CLIENT => builder.client.pid,
+                                    _ => builder.server.as_ref().and_then(|s| s.pid),
+                                };
+                                devserver.send_patch(jumptable, elapsed, id, pid).await
+                            }
+                            Err(err) => {
+                                tracing::error!("Failed to hot-patch app: {err}");
+
+                                if let Some(_patching) =
+                                    err.downcast_ref::<crate::build::PatchError>()
+                                {
+                                    tracing::info!("Starting full rebuild: {err}");
+                                    builder.full_rebuild().await;
+                                    devserver.send_reload_start().await;
+                                    devserver.start_build().await;
+                                }
+                            }
+                        }
+                    }
+                    BuildMode::Base { .. } | BuildMode::Fat => {
+                        _ = builder
+                            .open(&bundle, devserver)
+                            .await
+                            .inspect_err(|e| tracing::error!("Failed to open app: {}", e));
+                    }
+                },
+                BuilderUpdate::StdoutReceived { msg } => {
+                    screen.push_stdio(bundle_format, label(msg), tracing::Level::INFO);
+                }
+                BuilderUpdate::StderrReceived { msg } => {
+                    screen.push_stdio(bundle_format, label(msg), tracing::Level::ERROR);
+                }
+                BuilderUpdate::ProcessExited { status } => {
+                    if status.success() {
+                        tracing::info!(
+                            r#"Application [{bundle_format}] exited gracefully.
+               • To restart the app, press `r` to rebuild or `o` to open
+               • To exit the server, press `ctrl+c`"#
+                        );
+                    } else {
+                        tracing::error!(
+                            "Application [{bundle_format}] exited with error: {status}"
+                        );
+                        if exit_on_error {
+                            bail!("Application [{bundle_format}] exited with error: {status}");
+                        }
+                    }
+                }
+                BuilderUpdate::ProcessWaitFailed { err } => {
+                    tracing::warn!(
+                        "Failed to wait for process - maybe it's hung or being debugged?: {err}"
+                    );
+                    if exit_on_error {
+                        return Err(err.into());
+                    }
+                }
+            }
+        }
+
+        // The screen and the tracer send these, so they are handled for every app in the main loop
+        ServeUpdate::OpenApp
+        | ServeUpdate::RequestRebuild
+        | ServeUpdate::ToggleShouldRebuild
+        | ServeUpdate::OpenDebugger { .. }
+        | ServeUpdate::RequestReactiveGraph
+        | ServeUpdate::Redraw
+        | ServeUpdate::TracingLog { .. }
+        | ServeUpdate::Exit { .. } => {}
+    }
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::Result;
 use crate::{
-    serve::{ansi_buffer::ansi_string_to_line, ServeUpdate, WebServer},
+    serve::{ansi_buffer::ansi_string_to_line, ServeUpdate, ServedApp, WebServer},
     BuildId, BuildStage, BuilderUpdate, BundleFormat, TraceContent, TraceMsg, TraceSrc,
 };
 use anyhow::{anyhow, bail, Context};
@@ -14,6 +14,7 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType},
     ExecutableCommand,
 };
+use dioxus_devtools_types::ReactiveGraphSnapshot;
 use ratatui::{
     prelude::*,
     widgets::{Block, BorderType, Borders, LineGauge, Paragraph},
@@ -33,7 +34,7 @@ use super::AppServer;
 const TICK_RATE_MS: u64 = 100;
 const VIEWPORT_MAX_WIDTH: u16 = 90;
 const VIEWPORT_HEIGHT_SMALL: u16 = 5;
-const VIEWPORT_HEIGHT_BIG: u16 = 13;
+const VIEWPORT_HEIGHT_BIG: u16 = 14;
 
 /// The TUI that drives the console output.
 ///
@@ -67,16 +68,21 @@ pub struct Output {
     // ! needs to be wrapped in an &mut since `render stateful widget` requires &mut... but our
     // "render" method only borrows &self (for no particular reason at all...)
     throbber: RefCell<throbber_widgets_tui::ThrobberState>,
+
+    // The rows for the status of each app if multiple apps are served, and the app whose details are shown
+    app_rows: u16,
+    selected_app: usize,
 }
 
 #[derive(Clone, Copy)]
 struct RenderState<'a> {
     runner: &'a AppServer,
     server: &'a WebServer,
+    apps: &'a [ServedApp],
 }
 
 impl Output {
-    pub(crate) async fn start(interactive: bool) -> crate::Result<Self> {
+    pub(crate) async fn start(interactive: bool, app_count: usize) -> crate::Result<Self> {
         let mut output = Self {
             interactive,
             term: Rc::new(RefCell::new(None)),
@@ -92,6 +98,8 @@ impl Output {
             trace: crate::logging::VERBOSITY.get().unwrap().trace,
             verbose: crate::logging::VERBOSITY.get().unwrap().verbose,
             tick_animation: false,
+            app_rows: if app_count > 1 { app_count as u16 } else { 0 },
+            selected_app: 0,
             tick_interval: {
                 let mut interval = tokio::time::interval(Duration::from_millis(TICK_RATE_MS));
                 interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
@@ -121,7 +129,7 @@ impl Output {
                 Terminal::with_options(
                     CrosstermBackend::new(stdout()),
                     TerminalOptions {
-                        viewport: Viewport::Inline(VIEWPORT_HEIGHT_SMALL),
+                        viewport: Viewport::Inline(self.viewport_current_height()),
                     },
                 )
                 .ok(),
@@ -253,8 +261,10 @@ impl Output {
                 return Ok(Some(ServeUpdate::Exit { error: None }))
             }
             KeyCode::Char('r') => return Ok(Some(ServeUpdate::RequestRebuild)),
+            KeyCode::Tab if self.app_rows > 0 => self.selected_app += 1,
             KeyCode::Char('o') => return Ok(Some(ServeUpdate::OpenApp)),
             KeyCode::Char('p') => return Ok(Some(ServeUpdate::ToggleShouldRebuild)),
+            KeyCode::Char('g') => return Ok(Some(ServeUpdate::RequestReactiveGraph)),
             KeyCode::Char('v') => {
                 self.verbose = !self.verbose;
                 tracing::info!(
@@ -340,13 +350,17 @@ impl Output {
         self.push_log(TraceMsg::text(TraceSrc::App(bundle), level, msg));
     }
 
-    /// Push a message from the websocket to the logs
-    pub fn push_ws_message(&mut self, bundle: BundleFormat, message: &axum::extract::ws::Message) {
+    /// Push a message from the websocket to the logs. Snapshots of the reactive graph are returned to be reported.
+    pub fn push_ws_message(
+        &mut self,
+        bundle: BundleFormat,
+        message: &axum::extract::ws::Message,
+    ) -> Option<ReactiveGraphSnapshot> {
         use dioxus_devtools_types::ClientMsg;
 
         // We can only handle text messages from the websocket...
         let axum::extract::ws::Message::Text(text) = message else {
-            return;
+            return None;
         };
 
         // ...and then decode them into a ClientMsg
@@ -357,12 +371,14 @@ impl Output {
             Ok(msg) => msg,
             Err(err) => {
                 tracing::error!(dx_src = ?TraceSrc::Dev, "Error parsing message from {}: {} -> {:?}", bundle, err, text.as_str());
-                return;
+                return None;
             }
         };
 
-        let ClientMsg::Log { level, messages } = msg else {
-            return;
+        let (level, messages) = match msg {
+            ClientMsg::Log { level, messages } => (level, messages),
+            ClientMsg::ReactiveGraph(graph) => return Some(graph),
+            _ => return None,
         };
 
         // FIXME(jon): why are we pulling only the first message here?
@@ -379,6 +395,7 @@ impl Output {
 
         // We don't care about logging the app's message so we directly push it instead of using tracing.
         self.push_log(TraceMsg::text(TraceSrc::App(bundle), level, content));
+        None
     }
 
     /// Change internal state based on the build engine's update
@@ -400,9 +417,25 @@ impl Output {
         }
     }
 
+    /// The app whose details are shown, which is also the app the debugger is attached to
+    pub(crate) fn selected_app(&self, app_count: usize) -> usize {
+        self.selected_app % app_count.max(1)
+    }
+
     /// Render the current state of everything to the console screen
-    pub fn render(&mut self, runner: &AppServer, server: &WebServer) {
+    pub(crate) fn render(&mut self, apps: &[ServedApp]) {
+        // Without the tui, the output of tests is passed straight through so the results show up in CI and pipes
         if !self.interactive {
+            let testing = apps
+                .iter()
+                .any(|app| app.builder.client.build.test_target.is_some());
+            while let Some(log) = self.pending_logs.pop_back() {
+                if let (true, TraceSrc::App(_), TraceContent::Text(text)) =
+                    (testing, &log.source, &log.content)
+                {
+                    println!("{text}");
+                }
+            }
             return;
         }
 
@@ -419,8 +452,16 @@ impl Output {
         }
 
         // Then, draw the frame, passing along all the state of the TUI so we can render it properly
+        let selected = &apps[self.selected_app(apps.len())];
         _ = term.draw(|frame| {
-            self.render_frame(frame, RenderState { runner, server });
+            self.render_frame(
+                frame,
+                RenderState {
+                    runner: &selected.builder,
+                    server: &selected.devserver,
+                    apps,
+                },
+            );
         });
     }
 
@@ -458,9 +499,10 @@ impl Output {
     }
 
     fn render_body(&self, frame: &mut Frame<'_>, area: Rect, state: RenderState) {
-        let [_title, body, more, _foot] = Layout::vertical([
+        let [_title, body, apps, more, _foot] = Layout::vertical([
             Constraint::Length(0),
             Constraint::Length(VIEWPORT_HEIGHT_SMALL - 2),
+            Constraint::Length(self.app_rows),
             Constraint::Fill(1),
             Constraint::Length(0),
         ])
@@ -474,11 +516,65 @@ impl Output {
         self.render_gauges(frame, col1, state);
         self.render_stats(frame, col2, state);
 
+        if self.app_rows > 0 {
+            self.render_apps(frame, apps, state);
+        }
+
         if self.more_modal_open {
             self.render_more_modal(frame, more, state);
         }
     }
 
+    /// Render a row with the status of each app when multiple apps are served
+    fn render_apps(&self, frame: &mut Frame<'_>, area: Rect, state: RenderState) {
+        let rows = Layout::vertical(state.apps.iter().map(|_| Constraint::Length(1)))
+            .horizontal_margin(1)
+            .split(area);
+        let selected = self.selected_app(state.apps.len());
+
+        for (idx, (app, row)) in state.apps.iter().zip(rows.iter()).enumerate() {
+            let client = app.builder.client();
+            let failed = [&Some(client), &app.builder.server.as_ref()]
+                .into_iter()
+                .flatten()
+                .any(|build| matches!(build.stage, BuildStage::Failed | BuildStage::Aborted));
+            let ready = client.stage == BuildStage::Success
+                && app
+                    .builder
+                    .server
+                    .as_ref()
+                    .map_or(true, |server| server.stage == BuildStage::Success);
+
+            let status = if failed {
+                "failed".red()
+            } else if ready {
+                "ready".green()
+            } else {
+                format!("{:.0}%", client.compile_progress() * 100.0).yellow()
+            };
+
+            let address = match (&app.url, app.devserver.displayed_address()) {
+                (Some(url), _) => url.clone().blue(),
+                (None, Some(address)) => format!("http://{address}").blue(),
+                (None, None) => "no server address".dark_gray(),
+            };
+
+            frame.render_widget(
+                Line::from(vec![
+                    if idx == selected {
+                        "▸ ".yellow()
+                    } else {
+                        "  ".into()
+                    },
+                    format!("{:<16}", app.name.as_deref().unwrap_or_default()).white(),
+                    format!("{status:<8}").into(),
+                    address,
+                ]),
+                *row,
+            );
+        }
+    }
+
     fn render_gauges(&self, frame: &mut Frame<'_>, area: Rect, state: RenderState) {
         let [gauge_area, _margin] =
             Layout::horizontal([Constraint::Fill(1), Constraint::Length(3)]).areas(area);
@@ -815,9 +911,15 @@ impl Output {
             "v: toggle verbose logs",
             "t: toggle tracing logs ",
             "c: clear the screen",
+            "g: snapshot the reactive graph",
             "/: toggle more commands",
+            if self.app_rows > 0 {
+                "tab: show the next app"
+            } else {
+                ""
+            },
         ];
-        let layout: [_; 8] = Layout::vertical(cmds.iter().map(|_| Constraint::Length(1)))
+        let layout: [_; 10] = Layout::vertical(cmds.iter().map(|_| Constraint::Length(1)))
             .horizontal_margin(1)
             .areas(col2);
         for (idx, cmd) in cmds.iter().enumerate() {
@@ -964,10 +1066,12 @@ impl Output {
     }
 
     fn viewport_current_height(&self) -> u16 {
-        match self.more_modal_open {
-            true => VIEWPORT_HEIGHT_BIG,
+        let height = match self.more_modal_open {
+            true => VIEWPORT_HEIGHT_BIG + 1,
             false => VIEWPORT_HEIGHT_SMALL,
-        }
+        };
+
+        height + self.app_rows
     }
 
     fn tracemsg_to_ansi_string(log: TraceMsg) -> Vec<String> {
@@ -1,4 +1,4 @@
-use crate::config::WebProxyConfig;
+use crate::config::{ProxyMode, WebProxyConfig};
 use crate::TraceSrc;
 use crate::{Error, Result};
 
@@ -11,12 +11,16 @@ use axum::{
     routing::{any, MethodRouter},
     Router,
 };
+use futures_util::StreamExt;
 use hyper::header::*;
-use hyper::{Request, Response, Uri};
+use hyper::{Method, Request, Response, Uri};
 use hyper_util::{
     client::legacy::{self, connect::HttpConnector},
     rt::TokioExecutor,
 };
+use std::{path::Path, sync::Arc};
+
+use super::proxy_fixtures::{ProxyAction, ProxyFixtures, WsRecording};
 
 #[derive(Debug, Clone)]
 struct ProxyClient {
@@ -58,7 +62,13 @@ impl ProxyClient {
 /// - the exact path of the proxy config's backend URL, e.g. /api
 /// - the exact path with a trailing slash, e.g. /api/
 /// - any subpath of the backend URL, e.g. /api/foo/bar
-pub(crate) fn add_proxy(mut router: Router, proxy: &WebProxyConfig) -> Result<Router> {
+///
+/// Fixtures and mock files of the proxy are resolved relative to the crate directory.
+pub(crate) fn add_proxy(
+    mut router: Router,
+    proxy: &WebProxyConfig,
+    crate_dir: &Path,
+) -> Result<Router> {
     let url: Uri = proxy.backend.parse()?;
     let path = url.path().to_string();
     let trimmed_path = path.trim_start_matches('/');
@@ -71,7 +81,24 @@ pub(crate) fn add_proxy(mut router: Router, proxy: &WebProxyConfig) -> Result<Ro
         );
     }
 
-    let method_router = proxy_to(url, false, handle_proxy_error);
+    let fixtures = ProxyFixtures::new(proxy, &url, crate_dir);
+    let method_router = match fixtures.is_passthrough() {
+        true => proxy_to(url, false, handle_proxy_error),
+        false => {
+            if fixtures.mode != ProxyMode::Proxy {
+                tracing::info!(
+                    "{} {} using the fixtures in {}",
+                    match fixtures.mode {
+                        ProxyMode::Record => "Recording",
+                        _ => "Replaying",
+                    },
+                    proxy.backend,
+                    fixtures.dir.display()
+                );
+            }
+            proxy_with_fixtures(url, fixtures)
+        }
+    };
 
     // api/*path
     router = router.route(
@@ -158,6 +185,178 @@ pub(crate) fn proxy_to(
     })
 }
 
+/// Proxy requests to a backend while applying the mocks, faults, and recording or replaying of the proxy config.
+fn proxy_with_fixtures(url: Uri, fixtures: ProxyFixtures) -> MethodRouter {
+    let client = ProxyClient::new(url.clone());
+    let fixtures = Arc::new(fixtures);
+
+    any(move |parts: Parts, mut req: Request<MyBody>| async move {
+        let uri = req.uri().clone();
+        let method = req.method().clone();
+
+        if let ProxyAction::Respond(response) = fixtures.apply_mocks(&method, &uri).await {
+            return Ok(response);
+        }
+
+        // Prevent request loops
+        if req.headers().get("x-proxied-by-dioxus").is_some() {
+            return Err(Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::from(
+                    "API is sharing a loopback with the dev server. Try setting a different port on the API config.",
+                ))
+                .unwrap());
+        }
+        req.headers_mut()
+            .insert("x-proxied-by-dioxus", HeaderValue::from_static("true"));
+
+        let upgrade = req.headers().get(UPGRADE);
+        if upgrade.is_some_and(|h| h.as_bytes().eq_ignore_ascii_case(b"websocket")) {
+            let path = fixtures.ws_fixture_path(&uri);
+            return match fixtures.mode {
+                ProxyMode::Proxy => super::proxy_ws::proxy_websocket(parts, req, &url).await,
+                ProxyMode::Record => {
+                    let recording = WsRecording::new(path, &uri);
+                    super::proxy_ws::proxy_websocket_recorded(parts, req, &url, Some(recording))
+                        .await
+                }
+                ProxyMode::Replay => match fixtures.replay_ws(&path) {
+                    Ok(fixture) => super::proxy_ws::replay_websocket(parts, fixture).await,
+                    Err(err) => Err(missing_fixture(&method, &uri, &path, err)),
+                },
+            };
+        }
+
+        let res = match fixtures.mode {
+            ProxyMode::Proxy => client
+                .send(req)
+                .await
+                .map(IntoResponse::into_response)
+                .map_err(handle_proxy_error)?,
+
+            // Read the whole request so its body is part of the key of the fixture
+            ProxyMode::Record => {
+                let (mut req_parts, body) = req.into_parts();
+                let body = axum::body::to_bytes(body, usize::MAX)
+                    .await
+                    .map_err(|err| handle_proxy_error(err.into()))?;
+                let path = fixtures.http_fixture_path(&method, &uri, &body);
+
+                // Ask for an uncompressed response so the fixture stays readable
+                req_parts.headers.remove(ACCEPT_ENCODING);
+
+                let res = client
+                    .send(Request::from_parts(req_parts, MyBody::from(body)))
+                    .await
+                    .map_err(handle_proxy_error)?;
+                let (res_parts, body) = res.into_parts();
+
+                // The response is forwarded as it arrives so streaming responses like server-sent events keep working.
+                // The fixture is written once the whole body went through.
+                let (status, headers, fixtures, uri) = (
+                    res_parts.status,
+                    res_parts.headers.clone(),
+                    fixtures.clone(),
+                    uri.clone(),
+                );
+                let body = tee_body(MyBody::new(body), &res_parts.headers, move |body| {
+                    if let Err(err) =
+                        fixtures.record_http(&path, &method, &uri, status, &headers, &body)
+                    {
+                        tracing::error!(dx_src = ?TraceSrc::Dev, "Failed to record fixture for {uri}: {err:#}");
+                    }
+                });
+
+                Response::from_parts(res_parts, body)
+            }
+
+            ProxyMode::Replay => {
+                let body = axum::body::to_bytes(req.into_body(), usize::MAX)
+                    .await
+                    .map_err(|err| handle_proxy_error(err.into()))?;
+                let path = fixtures.http_fixture_path(&method, &uri, &body);
+                fixtures
+                    .replay_http(&path)
+                    .map_err(|err| missing_fixture(&method, &uri, &path, err))?
+            }
+        };
+
+        tracing::info!(dx_src = ?TraceSrc::App(crate::BundleFormat::Server), "[{}] {}", res.status().as_u16(), uri);
+        Ok(res)
+    })
+}
+
+/// Forward a body chunk by chunk while keeping a copy of it. `on_end` gets the copy once the whole body went
+/// through, which is when the stream ends or the `content-length` of the response is reached.
+///
+/// Bodies that fail or are dropped before they end are not passed to `on_end`.
+fn tee_body(
+    body: Body,
+    headers: &HeaderMap,
+    on_end: impl FnOnce(Vec<u8>) + Send + 'static,
+) -> Body {
+    let length = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok()?.parse::<usize>().ok());
+
+    let stream = body.into_data_stream();
+    let state = (stream, Vec::new(), Some(on_end));
+    Body::from_stream(futures_util::stream::unfold(
+        state,
+        move |(mut stream, mut copy, mut on_end)| async move {
+            match stream.next().await {
+                Some(Ok(chunk)) => {
+                    copy.extend_from_slice(&chunk);
+                    if length.is_some_and(|length| copy.len() >= length) {
+                        if let Some(on_end) = on_end.take() {
+                            on_end(std::mem::take(&mut copy));
+                        }
+                    }
+                    Some((Ok(chunk), (stream, copy, on_end)))
+                }
+                Some(Err(err)) => Some((Err(err), (stream, copy, None))),
+                None => {
+                    if let Some(on_end) = on_end.take() {
+                        on_end(copy);
+                    }
+                    None
+                }
+            }
+        },
+    ))
+}
+
+fn missing_fixture(method: &Method, uri: &Uri, path: &Path, err: Error) -> Response<Body> {
+    tracing::warn!(dx_src = ?TraceSrc::Dev, "No fixture for {method} {uri} at {}: {err}", path.display());
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Body::from(format!(
+            "No fixture was recorded for {method} {uri}. Record it with `dx serve --proxy-mode record`."
+        )))
+        .unwrap()
+}
+
+/// Forward every request to the devserver of another app, including websockets.
+///
+/// Unlike [`proxy_to`], the request isn't marked as proxied since the devserver might proxy it again to a
+/// fullstack server.
+pub(crate) fn forward_to(url: Uri) -> MethodRouter {
+    let client = ProxyClient::new(url.clone());
+
+    any(move |parts: Parts, req: Request<MyBody>| async move {
+        let upgrade = req.headers().get(UPGRADE);
+        if upgrade.is_some_and(|h| h.as_bytes().eq_ignore_ascii_case(b"websocket")) {
+            return super::proxy_ws::proxy_websocket(parts, req, &url).await;
+        }
+
+        client
+            .send(req)
+            .await
+            .map(IntoResponse::into_response)
+            .map_err(handle_proxy_error)
+    })
+}
+
 pub(crate) fn handle_proxy_error(e: Error) -> axum::http::Response<axum::body::Body> {
     tracing::error!(dx_src = ?TraceSrc::Dev, "Proxy error: {}", e);
     axum::http::Response::builder()
@@ -172,10 +371,11 @@ pub(crate) fn handle_proxy_error(e: Error) -> axum::http::Response<axum::body::B
 mod test {
 
     use super::*;
+    use crate::config::ProxyMockConfig;
 
     use axum_server::{Handle, Server};
 
-    async fn setup_servers(mut config: WebProxyConfig) -> String {
+    async fn setup_servers(mut config: WebProxyConfig, crate_dir: &Path) -> String {
         let backend_router =
             Router::new().route(
                 "/{*path}",
@@ -200,7 +400,7 @@ mod test {
         config.backend = format!("http://{}{}", backend_addr, config.backend);
 
         // Now set up our actual filesystem server
-        let router = super::add_proxy(Router::new(), &config);
+        let router = super::add_proxy(Router::new(), &config, crate_dir);
         let server_handle_handle = Handle::new();
         let server_handle_handle_ = server_handle_handle.clone();
         tokio::spawn(async move {
@@ -223,9 +423,11 @@ mod test {
             // path together.
             // So in day to day usage, use `http://localhost:8000/api` instead!
             backend: path,
+            ..Default::default()
         };
 
-        let server_addr = setup_servers(config).await;
+        let dir = tempfile::tempdir().unwrap();
+        let server_addr = setup_servers(config, dir.path()).await;
 
         assert_eq!(
             reqwest::get(format!("http://{server_addr}/api"))
@@ -267,4 +469,134 @@ mod test {
     async fn add_proxy_trailing_slash() {
         test_proxy_requests("/api/".to_string()).await;
     }
+
+    async fn get(url: String) -> (u16, String) {
+        let res = reqwest::get(url).await.unwrap();
+        (res.status().as_u16(), res.text().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn record_and_replay_fixtures() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = WebProxyConfig {
+            backend: "/api".to_string(),
+            mode: ProxyMode::Record,
+            ..Default::default()
+        };
+        let server_addr = setup_servers(config, dir.path()).await;
+        assert_eq!(
+            get(format!("http://{server_addr}/api/users?page=2")).await,
+            (200, "backend: /api/users?page=2".to_string())
+        );
+
+        // The backend is never contacted when replaying
+        let config = WebProxyConfig {
+            backend: "http://127.0.0.1:1/api".to_string(),
+            mode: ProxyMode::Replay,
+            ..Default::default()
+        };
+        let router = super::add_proxy(Router::new(), &config, dir.path()).unwrap();
+        let handle = Handle::new();
+        let handle_ = handle.clone();
+        tokio::spawn(async move {
+            Server::bind("127.0.0.1:0".parse().unwrap())
+                .handle(handle_)
+                .serve(router.into_make_service())
+                .await
+                .unwrap();
+        });
+        let server_addr = handle.listening().await.unwrap();
+
+        assert_eq!(
+            get(format!("http://{server_addr}/api/users?page=2")).await,
+            (200, "backend: /api/users?page=2".to_string())
+        );
+        assert_eq!(
+            get(format!("http://{server_addr}/api/users?page=3"))
+                .await
+                .0,
+            404
+        );
+    }
+
+    #[tokio::test]
+    async fn recorded_bodies_are_streamed() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<&'static str>();
+        let backend = Body::from_stream(futures_util::stream::unfold(rx, |mut rx| async move {
+            let chunk = rx.recv().await?;
+            Some((Ok::<_, std::io::Error>(chunk), rx))
+        }));
+
+        let recorded = Arc::new(std::sync::Mutex::new(None));
+        let recorded_ = recorded.clone();
+        let mut stream = tee_body(backend, &HeaderMap::new(), move |body| {
+            *recorded_.lock().unwrap() = Some(body)
+        })
+        .into_data_stream();
+
+        // The first event is forwarded before the backend is done
+        tx.send("data: 1\n\n").unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap(), "data: 1\n\n");
+        assert_eq!(*recorded.lock().unwrap(), None);
+
+        tx.send("data: 2\n\n").unwrap();
+        drop(tx);
+        assert_eq!(stream.next().await.unwrap().unwrap(), "data: 2\n\n");
+        assert!(stream.next().await.is_none());
+        assert_eq!(
+            recorded.lock().unwrap().as_deref(),
+            Some(b"data: 1\n\ndata: 2\n\n".as_slice())
+        );
+    }
+
+    #[tokio::test]
+    async fn mocks_and_injected_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("users.json"), "[]").unwrap();
+
+        let mock = |path: &str| ProxyMockConfig {
+            path: path.to_string(),
+            method: None,
+            status: 200,
+            headers: Default::default(),
+            body: None,
+            file: None,
+            latency: None,
+            error_rate: None,
+        };
+        let config = WebProxyConfig {
+            backend: "/api".to_string(),
+            mock: vec![
+                ProxyMockConfig {
+                    file: Some("users.json".into()),
+                    ..mock("/api/users")
+                },
+                ProxyMockConfig {
+                    status: 201,
+                    body: Some("created".to_string()),
+                    ..mock("/api/orders/*")
+                },
+                ProxyMockConfig {
+                    error_rate: Some(1.0),
+                    ..mock("/api/flaky")
+                },
+            ],
+            ..Default::default()
+        };
+        let server_addr = setup_servers(config, dir.path()).await;
+
+        assert_eq!(
+            get(format!("http://{server_addr}/api/users")).await,
+            (200, "[]".to_string())
+        );
+        assert_eq!(
+            get(format!("http://{server_addr}/api/orders/1")).await,
+            (201, "created".to_string())
+        );
+        assert_eq!(get(format!("http://{server_addr}/api/flaky")).await.0, 503);
+        assert_eq!(
+            get(format!("http://{server_addr}/api/other")).await,
+            (200, "backend: /api/other".to_string())
+        );
+    }
 }
@@ -0,0 +1,385 @@
+use crate::config::{ProxyMockConfig, ProxyMode, WebProxyConfig};
+use crate::TraceSrc;
+use anyhow::Context;
+use axum::body::Body;
+use axum::http::{HeaderMap, Method, StatusCode};
+use base64::Engine;
+use hyper::header::{CONNECTION, CONTENT_LENGTH, CONTENT_TYPE, TRANSFER_ENCODING};
+use hyper::{Response, Uri};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::protocol::Message as ServerMessage;
+
+/// The fixtures, mocks and injected faults of a `[[web.proxy]]` backend.
+///
+/// In record mode, every response of the backend is saved to a json file in the fixtures directory, keyed by the
+/// method, path, query and body of the request. Websocket connections are saved as the list of messages in both
+/// directions. In replay mode, the backend is never contacted and the fixtures are served instead.
+///
+/// Mocks take precedence over both the backend and the fixtures so single routes can be stubbed out.
+pub(crate) struct ProxyFixtures {
+    pub(crate) mode: ProxyMode,
+    pub(crate) dir: PathBuf,
+    crate_dir: PathBuf,
+    mocks: Vec<ProxyMockConfig>,
+    latency: Option<u64>,
+    error_rate: Option<f64>,
+
+    // The number of requests handled by the proxy and by each mock, used to spread the injected errors
+    requests: Vec<AtomicU64>,
+}
+
+/// What the proxy should do with a request after the mocks and faults are applied
+pub(crate) enum ProxyAction {
+    Respond(Response<Body>),
+    Forward,
+}
+
+impl ProxyFixtures {
+    pub(crate) fn new(proxy: &WebProxyConfig, url: &Uri, crate_dir: &Path) -> Self {
+        let dir = match proxy.fixtures.as_ref() {
+            Some(dir) => crate_dir.join(dir),
+            None => crate_dir
+                .join("fixtures")
+                .join("proxy")
+                .join(slug(url.path()).trim_matches('_')),
+        };
+
+        Self {
+            mode: proxy.mode,
+            dir,
+            crate_dir: crate_dir.to_path_buf(),
+            mocks: proxy.mock.clone(),
+            latency: proxy.latency,
+            error_rate: proxy.error_rate,
+            requests: (0..=proxy.mock.len()).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    /// Check if the proxy simply forwards requests to the backend
+    pub(crate) fn is_passthrough(&self) -> bool {
+        self.mode == ProxyMode::Proxy
+            && self.mocks.is_empty()
+            && self.latency.is_none()
+            && self.error_rate.is_none()
+    }
+
+    /// Apply the latency, the injected errors and the mock of the route to a request
+    pub(crate) async fn apply_mocks(&self, method: &Method, uri: &Uri) -> ProxyAction {
+        let mock = self.mocks.iter().enumerate().find(|(_, mock)| {
+            mock.method
+                .as_deref()
+                .map_or(true, |m| m.eq_ignore_ascii_case(method.as_str()))
+                && match mock.path.strip_suffix('*') {
+                    Some(prefix) => uri.path().starts_with(prefix),
+                    None => uri.path() == mock.path,
+                }
+        });
+
+        let (counter, latency, error_rate) = match mock {
+            Some((idx, mock)) => (
+                &self.requests[idx + 1],
+                mock.latency.or(self.latency),
+                mock.error_rate.or(self.error_rate),
+            ),
+            None => (&self.requests[0], self.latency, self.error_rate),
+        };
+
+        if let Some(latency) = latency {
+            tokio::time::sleep(Duration::from_millis(latency)).await;
+        }
+
+        let request = counter.fetch_add(1, Ordering::Relaxed);
+        if error_rate.is_some_and(|rate| should_fail(request, rate)) {
+            tracing::info!(dx_src = ?TraceSrc::App(crate::BundleFormat::Server), "[503] {uri} (injected error)");
+            return ProxyAction::Respond(
+                Response::builder()
+                    .status(StatusCode::SERVICE_UNAVAILABLE)
+                    .body(Body::from("Error injected by the dx proxy"))
+                    .unwrap(),
+            );
+        }
+
+        let Some((_, mock)) = mock else {
+            return ProxyAction::Forward;
+        };
+
+        let response = self.mock_response(mock).unwrap_or_else(|err| {
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from(format!(
+                    "Failed to load the mock for {uri}: {err:#}"
+                )))
+                .unwrap()
+        });
+        tracing::info!(dx_src = ?TraceSrc::App(crate::BundleFormat::Server), "[{}] {uri} (mock)", response.status().as_u16());
+        ProxyAction::Respond(response)
+    }
+
+    fn mock_response(&self, mock: &ProxyMockConfig) -> crate::Result<Response<Body>> {
+        let mut response = Response::builder().status(mock.status);
+        for (name, value) in mock.headers.iter() {
+            response = response.header(name, value);
+        }
+
+        // The file is read on every request so it can be edited while serving
+        let body = match mock.file.as_ref() {
+            Some(file) => {
+                let is_json = file.extension().is_some_and(|ext| ext == "json");
+                if is_json
+                    && !mock
+                        .headers
+                        .keys()
+                        .any(|h| h.eq_ignore_ascii_case("content-type"))
+                {
+                    response = response.header(CONTENT_TYPE, "application/json");
+                }
+                let path = self.crate_dir.join(file);
+                Body::from(
+                    std::fs::read(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?,
+                )
+            }
+            None => Body::from(mock.body.clone().unwrap_or_default()),
+        };
+
+        Ok(response.body(body)?)
+    }
+
+    /// The path of the fixture of a request
+    pub(crate) fn http_fixture_path(&self, method: &Method, uri: &Uri, body: &[u8]) -> PathBuf {
+        let path_and_query = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_str());
+        hasher.update("\n");
+        hasher.update(path_and_query);
+        hasher.update("\n");
+        hasher.update(body);
+        self.fixture_path(method.as_str(), uri, hasher)
+    }
+
+    /// The path of the fixture of a websocket connection
+    pub(crate) fn ws_fixture_path(&self, uri: &Uri) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(uri.path_and_query().map(|p| p.as_str()).unwrap_or("/"));
+        self.fixture_path("WS", uri, hasher)
+    }
+
+    fn fixture_path(&self, kind: &str, uri: &Uri, hasher: Sha256) -> PathBuf {
+        let hash = format!("{:x}", hasher.finalize());
+        let mut slug = slug(uri.path());
+        slug.truncate(80);
+        self.dir.join(format!("{kind}{slug}-{}.json", &hash[..16]))
+    }
+
+    /// Save a response of the backend as a fixture
+    pub(crate) fn record_http(
+        &self,
+        path: &Path,
+        method: &Method,
+        uri: &Uri,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> crate::Result<()> {
+        let fixture = HttpFixture {
+            method: method.to_string(),
+            uri: uri.to_string(),
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                .filter(|(name, _)| ![TRANSFER_ENCODING, CONNECTION, CONTENT_LENGTH].contains(name))
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body: FixtureData::new(body),
+        };
+
+        write_fixture(path, &fixture)
+    }
+
+    /// Load the recorded response of a request
+    pub(crate) fn replay_http(&self, path: &Path) -> crate::Result<Response<Body>> {
+        let contents = std::fs::read_to_string(path)?;
+        let fixture: HttpFixture = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid fixture {}", path.display()))?;
+
+        let mut response = Response::builder().status(fixture.status);
+        for (name, value) in fixture.headers {
+            response = response.header(name, value);
+        }
+
+        Ok(response.body(Body::from(fixture.body.into_bytes()?))?)
+    }
+
+    /// Load the recorded messages of a websocket connection
+    pub(crate) fn replay_ws(&self, path: &Path) -> crate::Result<WsFixture> {
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid fixture {}", path.display()))
+    }
+}
+
+/// Spread the failures evenly over the requests, so a rate of 0.25 fails every fourth request
+fn should_fail(request: u64, rate: f64) -> bool {
+    let rate = rate.clamp(0.0, 1.0);
+    ((request + 1) as f64 * rate).floor() > (request as f64 * rate).floor()
+}
+
+/// A filesystem-friendly version of a path, like `_api_users` for `/api/users`
+fn slug(path: &str) -> String {
+    path.chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '-' {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
+
+fn write_fixture(path: &Path, fixture: &impl Serialize) -> crate::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(fixture)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HttpFixture {
+    method: String,
+    uri: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    #[serde(flatten)]
+    body: FixtureData,
+}
+
+/// A recorded websocket connection
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct WsFixture {
+    pub(crate) uri: String,
+    pub(crate) messages: Vec<WsFixtureMessage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct WsFixtureMessage {
+    pub(crate) from: WsPeer,
+    #[serde(flatten)]
+    pub(crate) data: FixtureData,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum WsPeer {
+    Client,
+    Server,
+}
+
+/// A body or message, stored as text if it is valid utf-8 so fixtures can be edited by hand
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct FixtureData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) base64: Option<String>,
+}
+
+impl FixtureData {
+    fn new(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self {
+                text: Some(text.to_string()),
+                base64: None,
+            },
+            Err(_) => Self {
+                text: None,
+                base64: Some(base64::engine::general_purpose::STANDARD.encode(bytes)),
+            },
+        }
+    }
+
+    pub(crate) fn into_bytes(self) -> crate::Result<Vec<u8>> {
+        match (self.text, self.base64) {
+            (_, Some(data)) => Ok(base64::engine::general_purpose::STANDARD.decode(data)?),
+            (Some(text), None) => Ok(text.into_bytes()),
+            (None, None) => Ok(vec![]),
+        }
+    }
+
+    pub(crate) fn into_message(self) -> crate::Result<ServerMessage> {
+        Ok(match self.text {
+            Some(text) if self.base64.is_none() => ServerMessage::Text(text.into()),
+            _ => ServerMessage::Binary(self.into_bytes()?.into()),
+        })
+    }
+}
+
+/// The messages of a websocket connection that is being recorded
+pub(crate) struct WsRecording {
+    path: PathBuf,
+    fixture: WsFixture,
+}
+
+impl WsRecording {
+    pub(crate) fn new(path: PathBuf, uri: &Uri) -> Self {
+        Self {
+            path,
+            fixture: WsFixture {
+                uri: uri.to_string(),
+                messages: vec![],
+            },
+        }
+    }
+
+    /// Record a text or binary message. Control messages aren't recorded.
+    pub(crate) fn push(&mut self, from: WsPeer, msg: &ServerMessage) {
+        let data = match msg {
+            ServerMessage::Text(text) => FixtureData::new(text.as_bytes()),
+            ServerMessage::Binary(bytes) => FixtureData::new(bytes),
+            _ => return,
+        };
+        self.fixture.messages.push(WsFixtureMessage { from, data });
+    }
+
+    pub(crate) fn save(self) {
+        match write_fixture(&self.path, &self.fixture) {
+            Ok(()) => {
+                tracing::debug!(dx_src = ?TraceSrc::Dev, "Recorded websocket fixture {}", self.path.display())
+            }
+            Err(err) => {
+                tracing::error!(dx_src = ?TraceSrc::Dev, "Failed to record websocket fixture: {err:#}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_are_spread_evenly() {
+        let failures: Vec<bool> = (0..8).map(|request| should_fail(request, 0.25)).collect();
+        assert_eq!(
+            failures,
+            [false, false, false, true, false, false, false, true]
+        );
+        assert!((0..100).all(|request| !should_fail(request, 0.0)));
+        assert!((0..100).all(|request| should_fail(request, 1.0)));
+    }
+
+    #[test]
+    fn binary_fixtures_roundtrip() {
+        let data = FixtureData::new(&[0xff, 0x00, 0x12]);
+        assert!(data.text.is_none());
+        assert_eq!(data.into_bytes().unwrap(), [0xff, 0x00, 0x12]);
+
+        let data = FixtureData::new(b"{\"id\": 1}");
+        assert_eq!(data.text.as_deref(), Some("{\"id\": 1}"));
+    }
+}
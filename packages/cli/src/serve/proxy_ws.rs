@@ -1,5 +1,6 @@
 use crate::logging::TraceSrc;
 use crate::serve::proxy::handle_proxy_error;
+use crate::serve::proxy_fixtures::{WsFixture, WsPeer, WsRecording};
 use anyhow::Context;
 use axum::body::Body;
 use axum::extract::ws::{CloseFrame as ClientCloseFrame, Message as ClientMessage};
@@ -13,9 +14,19 @@ use tokio_tungstenite::tungstenite::protocol::{
 };
 
 pub(crate) async fn proxy_websocket(
+    parts: Parts,
+    req: Request<Body>,
+    backend_url: &Uri,
+) -> Result<Response<Body>, Response<Body>> {
+    proxy_websocket_recorded(parts, req, backend_url, None).await
+}
+
+/// Proxy a websocket connection, saving the messages in both directions to a fixture when the connection closes
+pub(crate) async fn proxy_websocket_recorded(
     mut parts: Parts,
     req: Request<Body>,
     backend_url: &Uri,
+    recording: Option<WsRecording>,
 ) -> Result<Response<Body>, Response<Body>> {
     let ws = WebSocketUpgrade::from_request_parts(&mut parts, &())
         .await
@@ -26,7 +37,7 @@ pub(crate) async fn proxy_websocket(
     tracing::trace!(dx_src = ?TraceSrc::Dev, "Connection proxied to {proxied_uri}", proxied_uri = proxied_request.uri());
 
     Ok(ws.on_upgrade(move |client_ws| async move {
-        match handle_ws_connection(client_ws, proxied_request).await {
+        match handle_ws_connection(client_ws, proxied_request, recording).await {
             Ok(()) => tracing::trace!(dx_src = ?TraceSrc::Dev, "Websocket connection closed"),
             Err(e) => {
                 tracing::error!(dx_src = ?TraceSrc::Dev, "Error proxying websocket connection: {e}")
@@ -65,31 +76,93 @@ enum WsError {
 async fn handle_ws_connection(
     mut client_ws: axum::extract::ws::WebSocket,
     proxied_request: tokio_tungstenite::tungstenite::handshake::client::Request,
+    mut recording: Option<WsRecording>,
 ) -> Result<(), WsError> {
     let (mut server_ws, _) = tokio_tungstenite::connect_async(proxied_request)
         .await
         .map_err(WsError::Connect)?;
 
     let mut closed = false;
-    while !closed {
-        tokio::select! {
-            Some(server_msg) = server_ws.next() => {
-                closed = matches!(server_msg, Ok(ServerMessage::Close(..)));
-                match server_msg.map_err(WsError::FromServer)?.into_msg() {
-                    Ok(msg) => client_ws.send(msg).await.map_err(WsError::ToClient)?,
-                    Err(UnexpectedRawFrame) => tracing::warn!(dx_src = ?TraceSrc::Dev, "Dropping unexpected raw websocket frame"),
-                }
-            },
-            Some(client_msg) = client_ws.next() => {
-                closed = matches!(client_msg, Ok(ClientMessage::Close(..)));
-                let Ok(msg) = client_msg.map_err(WsError::FromClient)?.into_msg();
-                server_ws.send(msg).await.map_err(WsError::ToServer)?;
-            },
-            else => break,
+    let result = async {
+        while !closed {
+            tokio::select! {
+                Some(server_msg) = server_ws.next() => {
+                    closed = matches!(server_msg, Ok(ServerMessage::Close(..)));
+                    let server_msg = server_msg.map_err(WsError::FromServer)?;
+                    if let Some(recording) = recording.as_mut() {
+                        recording.push(WsPeer::Server, &server_msg);
+                    }
+                    match server_msg.into_msg() {
+                        Ok(msg) => client_ws.send(msg).await.map_err(WsError::ToClient)?,
+                        Err(UnexpectedRawFrame) => tracing::warn!(dx_src = ?TraceSrc::Dev, "Dropping unexpected raw websocket frame"),
+                    }
+                },
+                Some(client_msg) = client_ws.next() => {
+                    closed = matches!(client_msg, Ok(ClientMessage::Close(..)));
+                    let Ok(msg) = client_msg.map_err(WsError::FromClient)?.into_msg();
+                    if let Some(recording) = recording.as_mut() {
+                        recording.push(WsPeer::Client, &msg);
+                    }
+                    server_ws.send(msg).await.map_err(WsError::ToServer)?;
+                },
+                else => break,
+            }
         }
+
+        Ok(())
+    }
+    .await;
+
+    // Save whatever was recorded, even if the connection was dropped
+    if let Some(recording) = recording {
+        recording.save();
     }
 
-    Ok(())
+    result
+}
+
+/// Replay a recorded websocket connection.
+///
+/// The messages of the server are sent in the order they were recorded. Whenever the client sent a message in the
+/// recording, the replay waits for the client to send a message before continuing.
+pub(crate) async fn replay_websocket(
+    mut parts: Parts,
+    fixture: WsFixture,
+) -> Result<Response<Body>, Response<Body>> {
+    let ws = WebSocketUpgrade::from_request_parts(&mut parts, &())
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    Ok(ws.on_upgrade(move |mut client_ws| async move {
+        for message in fixture.messages {
+            match message.from {
+                WsPeer::Client => match client_ws.next().await {
+                    Some(Ok(ClientMessage::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => {}
+                },
+                WsPeer::Server => {
+                    let msg = match message.data.into_message() {
+                        Ok(msg) => msg,
+                        Err(err) => {
+                            tracing::error!(dx_src = ?TraceSrc::Dev, "Invalid websocket fixture message: {err:#}");
+                            return;
+                        }
+                    };
+                    let Ok(msg) = msg.into_msg() else { continue };
+                    if client_ws.send(msg).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+
+        // Keep the connection open like the backend would until the client closes it
+        while let Some(Ok(msg)) = client_ws.next().await {
+            if matches!(msg, ClientMessage::Close(_)) {
+                break;
+            }
+        }
+    }))
 }
 
 trait IntoMsg<T> {
@@ -0,0 +1,138 @@
+//! Reports for the reactive graph snapshots apps send back when `g` is pressed in the tui.
+//!
+//! The log shows the signals and memos that were written to the most along with the components they rerender, which
+//! answers most "why did this rerender" questions. The full snapshot is saved as json next to the build artifacts.
+
+use dioxus_devtools_types::{ReactiveGraphSnapshot, ScopeSnapshot};
+use std::path::Path;
+
+/// The number of nodes listed in the log
+const LISTED_NODES: usize = 10;
+
+/// Save the snapshot to the path and describe it for the log
+pub(crate) fn report(graph: &ReactiveGraphSnapshot, path: &Path) -> String {
+    let saved = serde_json::to_string_pretty(graph)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| {
+            std::fs::create_dir_all(path.parent().unwrap_or(path))?;
+            Ok(std::fs::write(path, json)?)
+        });
+
+    let mut report = summarize(graph);
+    match saved {
+        Ok(()) => report.push_str(&format!("\nSaved the full snapshot to {}", path.display())),
+        Err(err) => report.push_str(&format!("\nFailed to save the snapshot: {err}")),
+    }
+    report
+}
+
+/// Describe the nodes that were written to the most and the components they rerender
+pub(crate) fn summarize(graph: &ReactiveGraphSnapshot) -> String {
+    if graph.nodes.is_empty() {
+        return "The reactive graph is empty. It is only recorded in debug builds with the `reactive-graph` feature of dioxus enabled.".to_string();
+    }
+
+    let mut written = graph
+        .nodes
+        .iter()
+        .filter(|node| node.writes > 0)
+        .collect::<Vec<_>>();
+    written.sort_by(|a, b| b.writes.cmp(&a.writes).then(a.id.cmp(&b.id)));
+
+    let memos = graph.nodes.iter().filter(|node| node.is_memo).count();
+    let mut summary = format!(
+        "Reactive graph: {} signals and {memos} memos, {} written to",
+        graph.nodes.len() - memos,
+        written.len()
+    );
+
+    for node in written.iter().take(LISTED_NODES) {
+        summary.push_str(&format!(
+            "\n  {} {} at {}: {} writes",
+            if node.is_memo { "memo" } else { "signal" },
+            node.type_name,
+            node.created_at,
+            node.writes,
+        ));
+        if let Some(last) = &node.last_written_at {
+            summary.push_str(&format!(", last at {last}"));
+        }
+        if !node.dirtied_scopes.is_empty() {
+            let scopes = node
+                .dirtied_scopes
+                .iter()
+                .map(scope_name)
+                .collect::<Vec<_>>();
+            summary.push_str(&format!(" -> rerenders {}", scopes.join(", ")));
+        }
+    }
+
+    if written.len() > LISTED_NODES {
+        summary.push_str(&format!("\n  ...and {} more", written.len() - LISTED_NODES));
+    }
+
+    summary
+}
+
+fn scope_name(scope: &ScopeSnapshot) -> String {
+    match &scope.name {
+        Some(name) => name.clone(),
+        None => format!("unmounted scope {}", scope.id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus_devtools_types::ReactiveNodeSnapshot;
+
+    fn node(id: u64, writes: u64, dirtied: &[&str]) -> ReactiveNodeSnapshot {
+        ReactiveNodeSnapshot {
+            id,
+            is_memo: false,
+            type_name: "i32".to_string(),
+            created_at: format!("src/main.rs:{id}:1"),
+            origin_scope: ScopeSnapshot {
+                id: 0,
+                name: Some("App".to_string()),
+            },
+            writes,
+            last_written_at: (writes > 0).then(|| "src/main.rs:20:5".to_string()),
+            subscribers: vec![],
+            dirtied_scopes: dirtied
+                .iter()
+                .enumerate()
+                .map(|(idx, name)| ScopeSnapshot {
+                    id: idx + 1,
+                    name: Some(name.to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn summarizes_the_most_written_nodes() {
+        let graph = ReactiveGraphSnapshot {
+            nodes: vec![
+                node(1, 2, &["Counter"]),
+                node(2, 0, &[]),
+                node(3, 7, &["App", "List"]),
+            ],
+        };
+
+        let summary = summarize(&graph);
+        let lines = summary.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "Reactive graph: 3 signals and 0 memos, 2 written to"
+        );
+        assert_eq!(
+            lines[1],
+            "  signal i32 at src/main.rs:3:1: 7 writes, last at src/main.rs:20:5 -> rerenders App, List"
+        );
+        assert!(lines[2].contains("src/main.rs:1:1: 2 writes"));
+        assert_eq!(lines.len(), 3);
+
+        assert!(summarize(&ReactiveGraphSnapshot::default()).contains("`reactive-graph` feature"));
+    }
+}
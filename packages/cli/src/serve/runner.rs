@@ -1,8 +1,8 @@
 use super::{AppBuilder, ServeUpdate, WebServer};
 use crate::{
     platform_override::CommandWithPlatformOverrides, BuildArtifacts, BuildId, BuildMode,
-    BuildTargets, BuilderUpdate, BundleFormat, HotpatchModuleCache, Result, ServeArgs, TailwindCli,
-    TraceSrc, Workspace,
+    BuildTargets, BuilderUpdate, BundleFormat, HotpatchModuleCache, ProxyMode, Result, ServeArgs,
+    TailwindCli, TraceSrc, Workspace,
 };
 use anyhow::{bail, Context};
 use dioxus_core::internal::{
@@ -74,6 +74,7 @@ pub(crate) struct AppServer {
     pub(crate) devserver_bind_ip: IpAddr,
     pub(crate) proxied_port: Option<u16>,
     pub(crate) cross_origin_policy: bool,
+    pub(crate) proxy_mode: Option<ProxyMode>,
 
     // The arguments that should be forwarded to the client app when it is opened
     pub(crate) client_args: Vec<String>,
@@ -99,6 +100,7 @@ impl AppServer {
         let interactive = args.is_interactive_tty();
         let force_sequential = args.platform_args.shared.targets.force_sequential_build();
         let cross_origin_policy = args.cross_origin_policy;
+        let proxy_mode = args.proxy_mode;
 
         // Find the launch args for the client and server
         let split_args = |args: &str| {
@@ -205,6 +207,7 @@ impl AppServer {
             interactive,
             _force_sequential: force_sequential,
             cross_origin_policy,
+            proxy_mode,
             fullstack,
             ssg,
             tw_watcher,
@@ -490,8 +493,11 @@ impl AppServer {
 
         // todo - we need to distinguish between hotpatchable rebuilds and true full rebuilds.
         //        A full rebuild is required when the user modifies static initializers which we haven't wired up yet.
+        // Test harnesses like libtest exit once the tests ran, leaving no process to patch. We rebuild and rerun them.
+        let exited_tests = self.client.build.test_target.is_some() && self.client.child.is_none();
+
         if needs_full_rebuild && self.automatic_rebuilds {
-            if self.use_hotpatch_engine {
+            if self.use_hotpatch_engine && !exited_tests {
                 self.client.patch_rebuild(files.to_vec());
                 if let Some(server) = self.server.as_mut() {
                     server.patch_rebuild(files.to_vec());
@@ -561,12 +567,9 @@ impl AppServer {
         devserver: &mut WebServer,
     ) -> Result<()> {
         // Make sure to save artifacts regardless of if we're opening the app or not
-        match artifacts.bundle {
-            BundleFormat::Server => {
-                if let Some(server) = self.server.as_mut() {
-                    server.artifacts = Some(artifacts.clone());
-                }
-            }
+        // Server-only apps (and tests) build their "client" for the server, so fall back to the client slot
+        match (artifacts.bundle, self.server.as_mut()) {
+            (BundleFormat::Server, Some(server)) => server.artifacts = Some(artifacts.clone()),
             _ => self.client.artifacts = Some(artifacts.clone()),
         }
 
@@ -1127,7 +1130,7 @@ impl AppServer {
 ///
 /// Todo: we might want to do this on every new build in case the OS tries to bind things to this port
 /// and we don't already have something bound to it. There's no great way of "reserving" a port.
-fn get_available_port(address: IpAddr, prefer: Option<u16>) -> Option<u16> {
+pub(crate) fn get_available_port(address: IpAddr, prefer: Option<u16>) -> Option<u16> {
     // First, try to bind to the preferred port
     if let Some(port) = prefer {
         if let Ok(_listener) = TcpListener::bind((address, port)) {
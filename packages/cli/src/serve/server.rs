@@ -343,6 +343,12 @@ impl WebServer {
             .await;
     }
 
+    /// Asks all connected clients for a snapshot of their reactive graph.
+    pub(crate) async fn send_reactive_graph_request(&mut self) {
+        self.send_devserver_message_to_all(DevserverMsg::RequestReactiveGraph)
+            .await;
+    }
+
     /// Send a shutdown message to all connected clients.
     pub(crate) async fn send_shutdown(&mut self) {
         self.send_devserver_message_to_all(DevserverMsg::Shutdown)
@@ -439,10 +445,21 @@ fn build_devserver_router(
 ) -> Result<Router> {
     let mut router = Router::new();
     let build = runner.client();
+    let base_path = format!(
+        "/{}",
+        build
+            .build
+            .base_path()
+            .unwrap_or_default()
+            .trim_matches('/')
+    );
 
     // Setup proxy for the endpoint specified in the config
+    // The mode can be overridden from the command line to record or replay every backend at once
     for proxy_config in build.build.config.web.proxy.iter() {
-        router = super::proxy::add_proxy(router, proxy_config)?;
+        let mut proxy_config = proxy_config.clone();
+        proxy_config.mode = runner.proxy_mode.unwrap_or(proxy_config.mode);
+        router = super::proxy::add_proxy(router, &proxy_config, &build.build.crate_dir())?;
     }
 
     // For fullstack, liveview, and server, forward all requests to the inner server
@@ -465,15 +482,6 @@ fn build_devserver_router(
     } else {
         // Otherwise, just serve the dir ourselves
         // Route file service to output the .wasm and assets if this is a web build
-        let base_path = format!(
-            "/{}",
-            runner
-                .client()
-                .build
-                .base_path()
-                .unwrap_or_default()
-                .trim_matches('/')
-        );
         if base_path == "/" {
             router = router.fallback_service(build_serve_dir(runner));
         } else {
@@ -483,7 +491,7 @@ fn build_devserver_router(
 
     // Setup middleware to intercept html requests if the build status is "Building"
     router = router.layer(middleware::from_fn_with_state(
-        build_status,
+        (build_status, build.build.devtools_path.clone()),
         build_status_middleware,
     ));
 
@@ -495,9 +503,7 @@ fn build_devserver_router(
     }
 
     // Setup websocket endpoint - and pass in the extension layer immediately after
-    router = router.nest(
-        "/_dioxus",
-        Router::new()
+    let devtools_router = Router::new()
             .route(
                 "/",
                 get(
@@ -516,8 +522,13 @@ fn build_devserver_router(
                     },
                 ),
             )
-            .layer(Extension(build_status_sockets)),
-    );
+            .layer(Extension(build_status_sockets));
+
+    // The devtools are also served under the route of the app if multiple apps share one port
+    if let Some(devtools_path) = &build.build.devtools_path {
+        router = router.nest(devtools_path, devtools_router.clone());
+    }
+    router = router.nest("/_dioxus", devtools_router);
 
     // Setup cors
     router = router.layer(
@@ -688,7 +699,7 @@ async fn get_rustls(web_config: &WebHttpsConfig) -> Result<(String, String)> {
 
 /// Middleware that intercepts html requests if the status is "Building" and returns a loading page instead
 async fn build_status_middleware(
-    state: State<SharedStatus>,
+    State((state, devtools_path)): State<(SharedStatus, Option<String>)>,
     request: Request,
     next: Next,
 ) -> axum::response::Response {
@@ -701,7 +712,13 @@ async fn build_status_middleware(
     if let Some(true) = accepts_html {
         let status = state.get();
         if status != Status::Ready {
-            let html = include_str!("../../assets/web/dev.loading.html");
+            let mut html = include_str!("../../assets/web/dev.loading.html").to_string();
+            if let Some(devtools_path) = &devtools_path {
+                html = html.replace(
+                    "/_dioxus/build_status",
+                    &format!("{devtools_path}/build_status"),
+                );
+            }
             return axum::response::Response::builder()
                 .status(StatusCode::OK)
                 // Load the html loader then keep loading forever
@@ -37,6 +37,9 @@ pub(crate) enum ServeUpdate {
         id: BuildId,
     },
 
+    /// Ask the connected apps for a snapshot of their reactive graph
+    RequestReactiveGraph,
+
     Redraw,
 
     TracingLog {
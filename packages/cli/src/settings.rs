@@ -29,6 +29,8 @@ pub(crate) struct CliSettings {
     pub(crate) ignore_version_update: Option<String>,
     /// Disable telemetry
     pub(crate) disable_telemetry: Option<bool>,
+    /// The maximum size of the optimized assets cache in megabytes
+    pub(crate) asset_cache_max_size: Option<u64>,
 }
 
 impl CliSettings {
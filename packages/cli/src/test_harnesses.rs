@@ -421,7 +421,7 @@ fn main() {
                 };
 
                 futures.push(async move {
-                    let targets = build_args.into_targets().await;
+                    let targets = build_args.args.into_targets().await;
                     (case.callback)(targets).await;
                 });
             }
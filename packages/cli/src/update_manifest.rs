@@ -0,0 +1,350 @@
+//! Sign bundles and write the manifest bundled desktop apps check for updates.
+//!
+//! The manifest format is read by `dioxus_desktop::updater`. The manifest and each installer are signed with an ed25519
+//! key. The app only knows the public key, so only someone with the private key can publish an update.
+
+use crate::{BuildRequest, Result, UpdaterSettings};
+use anyhow::{bail, Context};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use ed25519_dalek::{Signer, SigningKey};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use target_lexicon::{Architecture, OperatingSystem, Triple};
+
+/// The environment variable with the base64 encoded private key that signs updates
+const PRIVATE_KEY_ENV: &str = "DIOXUS_UPDATER_PRIVATE_KEY";
+
+/// The name of the manifest the app checks for updates
+const MANIFEST_NAME: &str = "latest.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct UpdateManifest {
+    pub(crate) version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) notes: Option<String>,
+    pub(crate) platforms: BTreeMap<String, UpdateArtifact>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct UpdateArtifact {
+    pub(crate) url: String,
+    pub(crate) signature: String,
+    pub(crate) format: String,
+}
+
+/// Sign the installer of the app and add it to the update manifest next to it.
+///
+/// Returns the files that were written.
+pub(crate) fn write_update_manifest(
+    build: &BuildRequest,
+    settings: &UpdaterSettings,
+    bundles: &[PathBuf],
+) -> Result<Vec<PathBuf>> {
+    let Some((installer, format)) = find_installer(bundles) else {
+        tracing::warn!(
+            "None of the bundles can be installed by the updater. Bundle an AppImage on linux, an NSIS or MSI installer on windows, or an app on macOS."
+        );
+        return Ok(vec![]);
+    };
+    let Some(platform) = platform_key(&build.triple) else {
+        tracing::warn!("The updater doesn't support {}", build.triple);
+        return Ok(vec![]);
+    };
+
+    let key = signing_key(build, settings)?;
+    tracing::info!(
+        "Signing the update with the key for the public key {}",
+        public_key(&key)
+    );
+
+    // The updater needs a single file, so we pack macOS app bundles into a tarball
+    let installer = match format {
+        "app" => archive_app(&installer)?,
+        _ => installer,
+    };
+    let dir = installer.parent().context("Installer has no parent")?;
+    let file_name = installer
+        .file_name()
+        .context("Installer has no file name")?
+        .to_string_lossy()
+        .into_owned();
+
+    let signature = sign(&key, &std::fs::read(&installer)?);
+    let signature_path = dir.join(format!("{file_name}.sig"));
+    std::fs::write(&signature_path, &signature)?;
+
+    let url = match &settings.base_url {
+        Some(base) => format!("{}/{file_name}", base.trim_end_matches('/')),
+        None => file_name,
+    };
+    let artifact = UpdateArtifact {
+        url,
+        signature,
+        format: format.to_string(),
+    };
+
+    let manifest_path = dir.join(MANIFEST_NAME);
+    let manifest = add_to_manifest(
+        std::fs::read(&manifest_path).ok(),
+        build.package().version.to_string(),
+        settings.notes.clone(),
+        platform,
+        artifact,
+    );
+    let manifest = serde_json::to_vec_pretty(&manifest)?;
+    std::fs::write(&manifest_path, &manifest)?;
+
+    let manifest_signature_path = dir.join(format!("{MANIFEST_NAME}.sig"));
+    std::fs::write(&manifest_signature_path, sign(&key, &manifest))?;
+
+    Ok(vec![
+        installer,
+        signature_path,
+        manifest_path,
+        manifest_signature_path,
+    ])
+}
+
+/// Add the installer for a platform to the manifest. Manifests for the same version are merged so bundles for each
+/// platform can be written to the same directory.
+fn add_to_manifest(
+    existing: Option<Vec<u8>>,
+    version: String,
+    notes: Option<String>,
+    platform: String,
+    artifact: UpdateArtifact,
+) -> UpdateManifest {
+    let mut manifest = existing
+        .and_then(|existing| serde_json::from_slice::<UpdateManifest>(&existing).ok())
+        .filter(|existing| existing.version == version)
+        .unwrap_or_else(|| UpdateManifest {
+            version,
+            notes: None,
+            platforms: BTreeMap::new(),
+        });
+
+    if notes.is_some() {
+        manifest.notes = notes;
+    }
+    manifest.platforms.insert(platform, artifact);
+    manifest
+}
+
+/// Find the bundle the updater can install and its format
+fn find_installer(bundles: &[PathBuf]) -> Option<(PathBuf, &'static str)> {
+    const FORMATS: [(&str, &str); 4] = [
+        ("appimage", ".AppImage"),
+        ("nsis", "-setup.exe"),
+        ("msi", ".msi"),
+        ("app", ".app"),
+    ];
+
+    FORMATS.into_iter().find_map(|(format, suffix)| {
+        bundles
+            .iter()
+            .find(|bundle| {
+                bundle
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().ends_with(suffix))
+            })
+            .map(|bundle| (bundle.clone(), format))
+    })
+}
+
+/// The key of the platform in the manifest. This matches `std::env::consts::{OS, ARCH}` of the app.
+fn platform_key(triple: &Triple) -> Option<String> {
+    let os = match triple.operating_system {
+        OperatingSystem::Linux => "linux",
+        OperatingSystem::Windows => "windows",
+        OperatingSystem::Darwin(_) | OperatingSystem::MacOSX(_) => "macos",
+        _ => return None,
+    };
+    let arch = match triple.architecture {
+        Architecture::X86_64 => "x86_64",
+        Architecture::X86_32(_) => "x86",
+        Architecture::Aarch64(_) => "aarch64",
+        Architecture::Arm(_) => "arm",
+        Architecture::Riscv64(_) => "riscv64",
+        _ => return None,
+    };
+    Some(format!("{os}-{arch}"))
+}
+
+/// Pack a macOS app bundle into a gzipped tarball next to it
+fn archive_app(app: &Path) -> Result<PathBuf> {
+    let name = app.file_name().context("App bundle has no file name")?;
+    let archive_path = app.with_file_name(format!("{}.tar.gz", name.to_string_lossy()));
+    let archive = std::fs::File::create(&archive_path)?;
+
+    let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(
+        archive,
+        flate2::Compression::default(),
+    ));
+    tar.follow_symlinks(false);
+    tar.append_dir_all(name, app)
+        .context("Failed to archive the app bundle")?;
+    tar.into_inner()?.finish()?;
+
+    Ok(archive_path)
+}
+
+fn signing_key(build: &BuildRequest, settings: &UpdaterSettings) -> Result<SigningKey> {
+    if let Ok(key) = std::env::var(PRIVATE_KEY_ENV) {
+        return parse_key(&key).with_context(|| format!("Invalid key in {PRIVATE_KEY_ENV}"));
+    }
+
+    let path = match &settings.private_key {
+        Some(path) => build.crate_dir().join(path),
+        None => {
+            let identifier = build
+                .config
+                .bundle
+                .identifier
+                .clone()
+                .unwrap_or_else(|| build.bundled_app_name());
+            dirs::config_dir()
+                .context("Failed to find the config directory for the updater key")?
+                .join("dioxus")
+                .join("updater-keys")
+                .join(format!("{identifier}.key"))
+        }
+    };
+
+    if path.exists() {
+        let key = std::fs::read_to_string(&path)?;
+        return parse_key(&key)
+            .with_context(|| format!("Invalid updater key in {}", path.display()));
+    }
+
+    if settings.private_key.is_some() {
+        bail!(
+            "The updater key {} does not exist. Remove `private_key` from `[bundle.updater]` to create a new key.",
+            path.display()
+        );
+    }
+
+    let key = generate_key()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, BASE64_STANDARD.encode(key.to_bytes()))?;
+    tracing::warn!(
+        "Created a new key to sign updates at {}. Back it up and keep it secret - apps you ship can only be updated with updates signed by this key.\n\nPass the public key to the updater of the app:\n\n    UpdaterConfig::new(\"https://.../{MANIFEST_NAME}\", \"{}\")\n",
+        path.display(),
+        public_key(&key)
+    );
+
+    Ok(key)
+}
+
+fn generate_key() -> Result<SigningKey> {
+    let mut seed = [0u8; 32];
+    getrandom::fill(&mut seed).map_err(|err| anyhow::anyhow!("Failed to generate a key: {err}"))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+fn parse_key(key: &str) -> Result<SigningKey> {
+    let bytes: [u8; 32] = BASE64_STANDARD
+        .decode(key.trim())?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("The key must be 32 bytes long"))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+fn public_key(key: &SigningKey) -> String {
+    BASE64_STANDARD.encode(key.verifying_key().to_bytes())
+}
+
+fn sign(key: &SigningKey, data: &[u8]) -> String {
+    BASE64_STANDARD.encode(key.sign(data).to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier};
+
+    #[test]
+    fn signatures_verify_with_the_public_key() {
+        let key = parse_key(&BASE64_STANDARD.encode([7u8; 32])).unwrap();
+        let signature = sign(&key, b"installer");
+
+        let public_key = ed25519_dalek::VerifyingKey::from_bytes(
+            &BASE64_STANDARD
+                .decode(public_key(&key))
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        )
+        .unwrap();
+        let signature = Signature::from_bytes(
+            &BASE64_STANDARD
+                .decode(signature)
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+        assert!(public_key.verify(b"installer", &signature).is_ok());
+        assert!(public_key.verify(b"tampered", &signature).is_err());
+    }
+
+    #[test]
+    fn manifests_for_the_same_version_are_merged() {
+        let artifact = |url: &str| UpdateArtifact {
+            url: url.to_string(),
+            signature: "sig".to_string(),
+            format: "appimage".to_string(),
+        };
+
+        let linux = add_to_manifest(
+            None,
+            "1.0.0".to_string(),
+            Some("Fixes".to_string()),
+            "linux-x86_64".to_string(),
+            artifact("app.AppImage"),
+        );
+        let both = add_to_manifest(
+            Some(serde_json::to_vec(&linux).unwrap()),
+            "1.0.0".to_string(),
+            None,
+            "windows-x86_64".to_string(),
+            artifact("app-setup.exe"),
+        );
+        assert_eq!(both.platforms.len(), 2);
+        assert_eq!(both.notes.as_deref(), Some("Fixes"));
+
+        let newer = add_to_manifest(
+            Some(serde_json::to_vec(&both).unwrap()),
+            "1.1.0".to_string(),
+            None,
+            "linux-x86_64".to_string(),
+            artifact("app.AppImage"),
+        );
+        assert_eq!(newer.platforms.len(), 1);
+        assert_eq!(newer.notes, None);
+    }
+
+    #[test]
+    fn installers_are_found_by_name() {
+        let bundles = [
+            PathBuf::from("out/app_1.0.0_amd64.deb"),
+            PathBuf::from("out/app_1.0.0_amd64.AppImage"),
+        ];
+        let (installer, format) = find_installer(&bundles).unwrap();
+        assert_eq!(installer, bundles[1]);
+        assert_eq!(format, "appimage");
+
+        assert!(find_installer(&[PathBuf::from("out/app.dmg")]).is_none());
+        assert_eq!(
+            platform_key(&"aarch64-apple-darwin".parse().unwrap()).as_deref(),
+            Some("macos-aarch64")
+        );
+        assert_eq!(
+            platform_key(&"x86_64-pc-windows-msvc".parse().unwrap()).as_deref(),
+            Some("windows-x86_64")
+        );
+    }
+}
@@ -1,8 +1,12 @@
 use crate::styles::GLOW_STYLE;
 use crate::CliSettings;
 use crate::Result;
-use crate::{config::DioxusConfig, AndroidTools};
+use crate::{
+    config::{DioxusConfig, ServeConfig},
+    AndroidTools,
+};
 use anyhow::{bail, Context};
+use dioxus_cli_opt::AssetCache;
 use ignore::gitignore::Gitignore;
 use krates::{semver::Version, KrateDetails, LockOptions};
 use krates::{Cmd, Krates, NodeId};
@@ -12,6 +16,9 @@ use std::{path::PathBuf, time::Duration};
 use target_lexicon::Triple;
 use tokio::process::Command;
 
+/// The default maximum size of the optimized assets cache, in megabytes
+const DEFAULT_ASSET_CACHE_MAX_SIZE_MB: u64 = 1024;
+
 pub struct Workspace {
     pub(crate) krates: Krates,
     pub(crate) settings: CliSettings,
@@ -143,6 +150,12 @@ impl Workspace {
             .context("Android not installed properly. Please set the `ANDROID_NDK_HOME` environment variable to the root of your NDK installation.")
     }
 
+    /// Check if cargo knows the profile, either as a built-in profile or from the Cargo.toml of the workspace
+    pub fn has_cargo_profile(&self, profile: &str) -> bool {
+        matches!(profile, "dev" | "release" | "test" | "bench")
+            || self.cargo_toml.profile.custom.contains_key(profile)
+    }
+
     pub fn is_release_profile(&self, profile: &str) -> bool {
         // If the profile is "release" or ends with "-release" like the default platform release profiles,
         // always put it in the release category.
@@ -343,7 +356,12 @@ impl Workspace {
         Ok(package)
     }
 
-    pub fn load_dioxus_config(&self, package: NodeId) -> Result<Option<DioxusConfig>> {
+    /// Load the Dioxus.toml of the package, applying the overrides of the profile if one is selected.
+    pub fn load_dioxus_config(
+        &self,
+        package: NodeId,
+        profile: Option<&str>,
+    ) -> Result<Option<DioxusConfig>> {
         // Walk up from the cargo.toml to the root of the workspace looking for Dioxus.toml
         let mut current_dir = self.krates[package]
             .manifest_path
@@ -383,13 +401,43 @@ impl Workspace {
             return Ok(None);
         };
 
-        toml::from_str::<DioxusConfig>(&std::fs::read_to_string(&dioxus_conf_file)?)
+        DioxusConfig::from_toml(&std::fs::read_to_string(&dioxus_conf_file)?, profile)
             .map_err(|err| {
                 anyhow::anyhow!("Failed to parse Dioxus.toml at {dioxus_conf_file:?}: {err}")
             })
             .map(Some)
     }
 
+    /// Load the `[serve]` section of the Dioxus.toml closest to the current directory.
+    ///
+    /// This doesn't need a package, so the Dioxus.toml may live in the root of a virtual workspace.
+    pub fn load_serve_config(&self) -> Result<ServeConfig> {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            #[serde(default)]
+            serve: ServeConfig,
+        }
+
+        let workspace_root = self.workspace_root();
+        let mut current_dir = std::env::current_dir()?;
+        loop {
+            for file in ["Dioxus.toml", "dioxus.toml"] {
+                let path = current_dir.join(file);
+                if path.is_file() {
+                    let config: Config =
+                        toml::from_str(&std::fs::read_to_string(&path)?).map_err(|err| {
+                            anyhow::anyhow!("Failed to parse Dioxus.toml at {path:?}: {err}")
+                        })?;
+                    return Ok(config.serve);
+                }
+            }
+
+            if current_dir == workspace_root || !current_dir.pop() {
+                return Ok(ServeConfig::default());
+            }
+        }
+    }
+
     /// Create a new gitignore map for this target crate
     ///
     /// todo(jon): this is a bit expensive to build, so maybe we should cache it?
@@ -435,6 +483,33 @@ impl Workspace {
         self.krates.workspace_root().as_std_path().to_path_buf()
     }
 
+    /// The cargo target dir, respecting `CARGO_TARGET_DIR` and `build.target-dir` in the cargo config
+    pub(crate) fn target_dir(&self) -> PathBuf {
+        std::env::var("CARGO_TARGET_DIR")
+            .ok()
+            .map(PathBuf::from)
+            .or_else(|| {
+                cargo_config2::Config::load()
+                    .ok()
+                    .and_then(|config| config.build.target_dir)
+            })
+            .unwrap_or_else(|| self.workspace_root().join("target"))
+    }
+
+    /// The optimized assets cache, shared by every build of every package in the workspace
+    ///
+    /// target/dx/.asset-cache/
+    pub(crate) fn asset_cache(&self) -> AssetCache {
+        let max_size = self
+            .settings
+            .asset_cache_max_size
+            .unwrap_or(DEFAULT_ASSET_CACHE_MAX_SIZE_MB);
+        AssetCache::new(
+            self.target_dir().join("dx").join(".asset-cache"),
+            max_size * 1024 * 1024,
+        )
+    }
+
     /// Returns the root of the crate that the command is run from, without calling `cargo metadata`
     ///
     /// If the command is run from the workspace root, this will return the top-level Cargo.toml
@@ -86,7 +86,7 @@ impl VirtualDom {
             context.height
         };
 
-        self.dirty_scopes.remove(&ScopeOrder::new(height, id));
+        self.remove_dirty_scope(&ScopeOrder::new(height, id));
 
         // If this scope was a suspense boundary, remove it from the resolved scopes
         self.resolved_scopes.retain(|s| s != &id);
@@ -147,7 +147,7 @@ impl VNode {
         dom.run_and_diff_scope(to, scope_id);
 
         let height = dom.runtime.get_state(scope_id).height;
-        dom.dirty_scopes.remove(&ScopeOrder::new(height, scope_id));
+        dom.remove_dirty_scope(&ScopeOrder::new(height, scope_id));
     }
 
     fn replace_vcomponent(
@@ -26,6 +26,7 @@ mod scope_context;
 mod scopes;
 mod suspense;
 mod tasks;
+mod transition;
 mod virtual_dom;
 
 mod hotreload_utils;
@@ -73,6 +74,7 @@ pub(crate) mod innerlude {
     pub use crate::scopes::*;
     pub use crate::suspense::*;
     pub use crate::tasks::*;
+    pub use crate::transition::*;
     pub use crate::virtual_dom::*;
 
     pub use anyhow::anyhow;
@@ -90,20 +92,20 @@ pub(crate) mod innerlude {
 }
 
 pub use crate::innerlude::{
-    anyhow, consume_context, consume_context_from_scope, current_owner, current_scope_id,
-    fc_to_builder, generation, has_context, needs_update, needs_update_any, parent_scope,
-    provide_context, provide_create_error_boundary, provide_root_context, queue_effect,
-    remove_future, schedule_update, schedule_update_any, spawn, spawn_forever, spawn_isomorphic,
-    suspend, throw_error, try_consume_context, use_after_render, use_before_render, use_drop,
-    use_hook, use_hook_with_cleanup, with_owner, AnyValue, AnyhowContext, Attribute,
-    AttributeValue, Callback, CapturedError, Component, ComponentFunction, DynamicNode, Element,
-    ElementId, Error, ErrorBoundary, ErrorContext, Event, EventHandler, Fragment, HasAttributes,
-    IntoAttributeValue, IntoDynNode, LaunchConfig, ListenerCallback, MarkerWrapper, Mutation,
-    Mutations, NoOpMutations, OptionStringFromMarker, Properties, ReactiveContext, RenderError,
-    Result, Runtime, RuntimeGuard, ScopeId, ScopeState, SpawnIfAsync, SubscriberList, Subscribers,
-    SuperFrom, SuperInto, SuspendedFuture, SuspenseBoundary, SuspenseBoundaryProps,
-    SuspenseContext, Task, Template, TemplateAttribute, TemplateNode, VComponent, VNode,
-    VNodeInner, VPlaceholder, VText, VirtualDom, WriteMutations,
+    after_transitions, anyhow, consume_context, consume_context_from_scope, current_owner,
+    current_scope_id, fc_to_builder, generation, has_context, in_transition, needs_update,
+    needs_update_any, parent_scope, provide_context, provide_create_error_boundary,
+    provide_root_context, queue_effect, remove_future, schedule_update, schedule_update_any, spawn,
+    spawn_forever, spawn_isomorphic, start_transition, suspend, throw_error, try_consume_context,
+    use_after_render, use_before_render, use_drop, use_hook, use_hook_with_cleanup, with_owner,
+    AnyValue, AnyhowContext, Attribute, AttributeValue, Callback, CapturedError, Component,
+    ComponentFunction, DynamicNode, Element, ElementId, Error, ErrorBoundary, ErrorContext, Event,
+    EventHandler, Fragment, HasAttributes, IntoAttributeValue, IntoDynNode, LaunchConfig,
+    ListenerCallback, MarkerWrapper, Mutation, Mutations, NoOpMutations, OptionStringFromMarker,
+    Properties, ReactiveContext, RenderError, Result, Runtime, RuntimeGuard, ScopeId, ScopeState,
+    SpawnIfAsync, SubscriberList, Subscribers, SuperFrom, SuperInto, SuspendedFuture,
+    SuspenseBoundary, SuspenseBoundaryProps, SuspenseContext, Task, Template, TemplateAttribute,
+    TemplateNode, VComponent, VNode, VNodeInner, VPlaceholder, VText, VirtualDom, WriteMutations,
 };
 
 pub use anyhow::Ok;
@@ -104,7 +104,7 @@ impl ReactiveContext {
         let id = scope.id;
         let sender = runtime.sender.clone();
         let update_scope = move || {
-            _ = sender.unbounded_send(SchedulerMsg::Immediate(id));
+            _ = sender.unbounded_send(SchedulerMsg::update_scope(id));
         };
 
         // Otherwise, create a new context at the current scope
@@ -256,6 +256,30 @@ impl ReactiveContext {
     pub fn origin_scope(&self) -> ScopeId {
         self.scope
     }
+
+    /// Get the location this reactive context was created at. This is only available in debug builds.
+    pub fn created_at(&self) -> Option<&'static std::panic::Location<'static>> {
+        #[cfg(debug_assertions)]
+        {
+            if let Ok(read) = self.inner.try_read() {
+                return Some(read.origin);
+            }
+        }
+        None
+    }
+
+    /// Get the scope this reactive context marks dirty directly if it is the reactive context of a component. This is only available in debug builds.
+    ///
+    /// Reactive contexts created for hooks like `use_memo` or `use_effect` return `None` because they run their own callback instead of rerendering a scope.
+    pub fn rerendered_scope(&self) -> Option<ScopeId> {
+        #[cfg(debug_assertions)]
+        {
+            if let Ok(read) = self.inner.try_read() {
+                return read.scope;
+            }
+        }
+        None
+    }
 }
 
 impl Hash for ReactiveContext {
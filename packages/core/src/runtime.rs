@@ -68,6 +68,9 @@ pub struct Runtime {
     // We need to store this information on the virtual dom so that we know what nodes are mounted where when we bubble events
     // Each mount is associated with a whole rsx block. [`VirtualDom::elements`] link to a specific node in the block
     pub(crate) mounts: RefCell<Slab<VNodeMount>>,
+
+    // Callbacks that run once every pending transition has been rendered
+    pub(crate) transition_listeners: RefCell<Vec<Box<dyn FnOnce()>>>,
 }
 
 impl Runtime {
@@ -89,6 +92,7 @@ impl Runtime {
             dirty_tasks: Default::default(),
             elements: RefCell::new(elements),
             mounts: Default::default(),
+            transition_listeners: Default::default(),
         })
     }
 
@@ -543,6 +547,35 @@ fn MyComponent() -> Element {{
         self.get_state(id).height
     }
 
+    /// Get the name of the component that a scope renders, if the scope is still alive
+    pub fn scope_name(&self, id: ScopeId) -> Option<&'static str> {
+        self.try_get_state(id).map(|scope| scope.name)
+    }
+
+    /// Get the names of the components from the current scope up to the root of the app.
+    ///
+    /// This never panics, so it is safe to call from a panic hook. It returns an empty list if the runtime is busy
+    /// creating or removing scopes.
+    pub fn component_stack(&self) -> Vec<&'static str> {
+        let (Ok(stack), Ok(scopes)) = (
+            self.scope_stack.try_borrow(),
+            self.scope_states.try_borrow(),
+        ) else {
+            return Vec::new();
+        };
+
+        let mut components = Vec::new();
+        let mut current = stack.last().copied();
+        while let Some(scope) = current
+            .and_then(|id| scopes.get(id.0))
+            .and_then(Option::as_ref)
+        {
+            components.push(scope.name);
+            current = scope.parent_id();
+        }
+        components
+    }
+
     /// Throw a [`CapturedError`] into a scope. The error will bubble up to the nearest [`ErrorBoundary`](crate::ErrorBoundary) or the root of the app.
     ///
     /// # Examples
@@ -63,7 +63,7 @@
 //!
 //! ## Implementation
 //!
-//! There are three different types of queued work that can be run by the virtualdom:
+//! There are four different types of queued work that can be run by the virtualdom:
 //! 1. Dirty Scopes:
 //!    Description: When a scope is marked dirty, a rerun of the scope will be scheduled. This will cause the scope to rerun and update the DOM if any changes are detected during the diffing phase.
 //!    Priority: These are the highest priority tasks. Dirty scopes will be rerun in order from the scope closest to the root to the scope furthest from the root. We follow this order to ensure that if a higher component reruns and drops a lower component, the lower component will not be run after it should be dropped.
@@ -75,6 +75,10 @@
 //! 3. Effects:
 //!    Description: Effects should always run after all changes to the DOM have been applied.
 //!    Priority: These are the lowest priority tasks in the scheduler. They are run after all other dirty scopes and futures have been resolved. Other tasks may cause components to rerun, which would update the DOM. These effects should only run after the DOM has been updated.
+//!
+//! 4. Transition Scopes:
+//!    Description: Scopes marked dirty inside [`start_transition`](crate::start_transition) are queued separately from other dirty scopes. If a transition scope is marked dirty by an urgent update, it is moved to the dirty scopes and only rerun once.
+//!    Priority: Transition scopes are only rerun by [`VirtualDom::render_immediate`] when there are no dirty scopes or tasks. Rendering them stops as soon as new urgent work is queued, so urgent updates are never blocked behind slow transitions. The scopes an interrupted transition didn't reach are rerun later with the latest state, so a transition that is replaced by a newer one before it finishes is never rendered. If transitions are put off for several renders in a row, they are rendered after the urgent work without yielding so a steady stream of urgent updates can't starve them. Effects queued by transition renders still run after the DOM has been updated.
 
 use crate::innerlude::Effect;
 use crate::ScopeId;
@@ -85,6 +89,9 @@ use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::hash::Hash;
 
+/// How many renders in a row can put off pending transitions before they are rendered without yielding to urgent work
+pub(crate) const MAX_DEFERRED_TRANSITION_RENDERS: u32 = 5;
+
 #[derive(Debug, Clone, Copy, Eq)]
 pub struct ScopeOrder {
     pub(crate) height: u32,
@@ -137,14 +144,68 @@ impl VirtualDom {
 
     /// Queue a scope to be rerendered
     pub(crate) fn queue_scope(&mut self, order: ScopeOrder) {
+        // An urgent rerun replaces any pending transition rerun
+        self.transition_scopes.remove(&order);
         self.dirty_scopes.insert(order);
     }
 
+    /// Queue a scope to be rerendered once there is no more urgent work
+    pub(crate) fn queue_transition_scope(&mut self, order: ScopeOrder) {
+        if !self.dirty_scopes.contains(&order) {
+            self.transition_scopes.insert(order);
+        }
+    }
+
+    /// Take the highest scope that was marked dirty in a transition
+    pub(crate) fn pop_transition_scope(&mut self) -> Option<ScopeOrder> {
+        let scope = self.transition_scopes.pop_first()?;
+
+        // The scope should still exist. Dropped scopes are removed from the transition queue
+        debug_assert!(self.scopes.contains(scope.id.0));
+
+        Some(scope)
+    }
+
+    /// Move all pending transitions to the urgent queue
+    pub(crate) fn promote_transitions(&mut self) {
+        let transitions = std::mem::take(&mut self.transition_scopes);
+        self.dirty_scopes.extend(transitions);
+    }
+
+    /// Check if pending transitions were put off for long enough that they should render even if there is urgent work
+    pub(crate) fn transitions_expired(&self) -> bool {
+        self.deferred_transition_renders >= MAX_DEFERRED_TRANSITION_RENDERS
+    }
+
+    /// Run any callbacks waiting for transitions if every transition has been rendered
+    pub(crate) fn finish_transitions(&mut self) {
+        if self.has_pending_transitions() {
+            self.deferred_transition_renders += 1;
+            return;
+        }
+        self.deferred_transition_renders = 0;
+        let listeners = std::mem::take(&mut *self.runtime.transition_listeners.borrow_mut());
+        for listener in listeners {
+            listener();
+        }
+    }
+
+    /// Remove a scope from every queue of scopes waiting to rerun
+    pub(crate) fn remove_dirty_scope(&mut self, order: &ScopeOrder) {
+        self.dirty_scopes.remove(order);
+        self.transition_scopes.remove(order);
+    }
+
     /// Check if there are any dirty scopes
     pub(crate) fn has_dirty_scopes(&self) -> bool {
         !self.dirty_scopes.is_empty()
     }
 
+    /// Check if there are any dirty scopes or tasks that should be handled before transitions
+    pub(crate) fn has_urgent_work(&self) -> bool {
+        self.has_dirty_scopes() || !self.runtime.dirty_tasks.borrow().is_empty()
+    }
+
     /// Take the top task from the highest scope
     pub(crate) fn pop_task(&mut self) -> Option<Task> {
         let mut dirty_tasks = self.runtime.dirty_tasks.borrow_mut();
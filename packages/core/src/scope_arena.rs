@@ -98,8 +98,9 @@ impl VirtualDom {
             }
 
             // remove this scope from dirty scopes
-            self.dirty_scopes
-                .remove(&ScopeOrder::new(scope_state.height, scope_id));
+            let order = ScopeOrder::new(scope_state.height, scope_id);
+            self.dirty_scopes.remove(&order);
+            self.transition_scopes.remove(&order);
             output
         })
     }
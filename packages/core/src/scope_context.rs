@@ -139,7 +139,7 @@ impl Scope {
     /// Mark this scope as dirty, and schedule a render for it.
     pub(crate) fn needs_update_any(&self, id: ScopeId) {
         self.sender()
-            .unbounded_send(SchedulerMsg::Immediate(id))
+            .unbounded_send(SchedulerMsg::update_scope(id))
             .expect("Scheduler to exist if scope exists");
     }
 
@@ -152,7 +152,7 @@ impl Scope {
     /// [`subscribe`](crate::reactive_context::ReactiveContext::subscribe) to the [`current`](crate::reactive_context::ReactiveContext::current) [`ReactiveContext`](crate::reactive_context::ReactiveContext) instead.
     pub(crate) fn schedule_update(&self) -> Arc<dyn Fn() + Send + Sync + 'static> {
         let (chan, id) = (self.sender(), self.id);
-        Arc::new(move || drop(chan.unbounded_send(SchedulerMsg::Immediate(id))))
+        Arc::new(move || drop(chan.unbounded_send(SchedulerMsg::update_scope(id))))
     }
 
     /// Schedule an update for any component given its [`ScopeId`].
@@ -166,7 +166,7 @@ impl Scope {
     pub(crate) fn schedule_update_any(&self) -> Arc<dyn Fn(ScopeId) + Send + Sync> {
         let chan = self.sender();
         Arc::new(move |id| {
-            _ = chan.unbounded_send(SchedulerMsg::Immediate(id));
+            _ = chan.unbounded_send(SchedulerMsg::update_scope(id));
         })
     }
 
@@ -384,6 +384,9 @@ pub(crate) enum SchedulerMsg {
     /// Immediate updates from Components that mark them as dirty
     Immediate(ScopeId),
 
+    /// Low priority updates from Components that were marked as dirty inside a transition
+    Transition(ScopeId),
+
     /// A task has woken and needs to be progressed
     TaskNotified(slotmap::DefaultKey),
 
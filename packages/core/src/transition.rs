@@ -0,0 +1,84 @@
+//! Transitions let you mark updates as low priority.
+//!
+//! Scopes marked dirty inside [`start_transition`] are queued separately from urgent updates. The scheduler only reruns them
+//! in a later frame once all urgent work (dirty scopes and tasks) has been handled, and it stops rendering them as soon as new
+//! urgent work arrives. Until a transition scope reruns, the renderer keeps showing its previous output.
+
+use crate::{innerlude::SchedulerMsg, Runtime, ScopeId};
+use std::cell::Cell;
+
+thread_local! {
+    static TRANSITION_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Run a closure and mark any components it causes to rerender as a low priority transition.
+///
+/// Urgent updates like input events are always rendered before transitions, even if they arrive while a transition is
+/// rendering. If a transition has not finished rendering when a newer update to the same components arrives, the rest of
+/// the old render is abandoned and the components only rerun once with the latest state. Transitions that keep getting
+/// put off by urgent updates render after a few frames anyway, so they always finish eventually.
+///
+/// Components are rendered one at a time, so while an interrupted transition waits for urgent work some of its components
+/// may already show the new state while others still show the old one.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus_core::start_transition;
+/// fn App() -> Element {
+///     let mut input = use_signal(String::new);
+///     let mut filter = use_signal(String::new);
+///
+///     rsx! {
+///         input {
+///             value: "{input}",
+///             oninput: move |event| {
+///                 // The input updates right away
+///                 input.set(event.value());
+///                 // The table only rerenders once there is no more urgent work
+///                 start_transition(|| filter.set(event.value()));
+///             }
+///         }
+///         Table { filter }
+///     }
+/// }
+///
+/// #[component]
+/// fn Table(filter: ReadSignal<String>) -> Element {
+///     rsx! { "{filter}" }
+/// }
+/// ```
+pub fn start_transition<O>(f: impl FnOnce() -> O) -> O {
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            TRANSITION_DEPTH.with(|depth| depth.set(depth.get() - 1));
+        }
+    }
+
+    TRANSITION_DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let _reset = Reset;
+    f()
+}
+
+/// Check if the current thread is running a [`start_transition`] closure.
+pub fn in_transition() -> bool {
+    TRANSITION_DEPTH.with(|depth| depth.get() > 0)
+}
+
+/// Run a callback once every pending transition has finished rendering.
+///
+/// If no transitions are pending, the callback runs after the next render.
+pub fn after_transitions(f: impl FnOnce() + 'static) {
+    Runtime::with(|rt| rt.transition_listeners.borrow_mut().push(Box::new(f)));
+}
+
+impl SchedulerMsg {
+    /// Create the message that marks a scope as dirty with the priority of the current thread
+    pub(crate) fn update_scope(id: ScopeId) -> Self {
+        if in_transition() {
+            SchedulerMsg::Transition(id)
+        } else {
+            SchedulerMsg::Immediate(id)
+        }
+    }
+}
@@ -207,6 +207,12 @@ pub struct VirtualDom {
 
     pub(crate) dirty_scopes: BTreeSet<ScopeOrder>,
 
+    // Scopes that were marked dirty inside a transition. These are only rerun once there is no more urgent work
+    pub(crate) transition_scopes: BTreeSet<ScopeOrder>,
+
+    // How many renders in a row finished with transitions still pending
+    pub(crate) deferred_transition_renders: u32,
+
     pub(crate) runtime: Rc<Runtime>,
 
     // The scopes that have been resolved since the last render
@@ -313,6 +319,8 @@ impl VirtualDom {
             runtime: Runtime::new(tx),
             scopes: Default::default(),
             dirty_scopes: Default::default(),
+            transition_scopes: Default::default(),
+            deferred_transition_renders: 0,
             resolved_scopes: Default::default(),
         };
 
@@ -403,6 +411,29 @@ impl VirtualDom {
         self.queue_scope(order);
     }
 
+    /// Mark a scope as requiring a low priority re-render. See [`start_transition`](crate::start_transition) for more details.
+    ///
+    /// If the scope is already queued for an urgent re-render, this does nothing.
+    pub fn mark_dirty_in_transition(&mut self, id: ScopeId) {
+        let Some(scope) = self.runtime.try_get_state(id) else {
+            return;
+        };
+
+        tracing::event!(
+            tracing::Level::TRACE,
+            "Marking scope {:?} as dirty in a transition",
+            id
+        );
+        let order = ScopeOrder::new(scope.height(), id);
+        drop(scope);
+        self.queue_transition_scope(order);
+    }
+
+    /// Check if any scopes marked dirty inside a transition are still waiting to rerender
+    pub fn has_pending_transitions(&self) -> bool {
+        !self.transition_scopes.is_empty()
+    }
+
     /// Mark a task as dirty
     fn mark_task_dirty(&mut self, task: Task) {
         let Some(scope) = self.runtime.task_scope(task) else {
@@ -453,6 +484,11 @@ impl VirtualDom {
                 return;
             }
 
+            // Transitions only run once all urgent work is done, so we only need to check them after polling tasks
+            if self.has_pending_transitions() {
+                return;
+            }
+
             // Make sure we set the runtime since we're running user code
             let _runtime = RuntimeGuard::new(self.runtime.clone());
 
@@ -466,6 +502,7 @@ impl VirtualDom {
     async fn wait_for_event(&mut self) {
         match self.rx.next().await.expect("channel should never close") {
             SchedulerMsg::Immediate(id) => self.mark_dirty(id),
+            SchedulerMsg::Transition(id) => self.mark_dirty_in_transition(id),
             SchedulerMsg::TaskNotified(id) => {
                 // Instead of running the task immediately, we insert it into the runtime's task queue.
                 // The task may be marked dirty at the same time as the scope that owns the task is dropped.
@@ -482,6 +519,7 @@ impl VirtualDom {
         while let Ok(Some(msg)) = self.rx.try_next() {
             match msg {
                 SchedulerMsg::Immediate(id) => self.mark_dirty(id),
+                SchedulerMsg::Transition(id) => self.mark_dirty_in_transition(id),
                 SchedulerMsg::TaskNotified(task) => self.mark_task_dirty(Task::from_id(task)),
                 SchedulerMsg::EffectQueued => {}
                 SchedulerMsg::AllDirty => self.mark_all_dirty(),
@@ -595,6 +633,11 @@ impl VirtualDom {
 
     /// Render whatever the VirtualDom has ready as fast as possible without requiring an executor to progress
     /// suspended subtrees.
+    ///
+    /// Scopes marked dirty inside a [transition](crate::start_transition) are only rendered if there is no urgent work
+    /// when this method is called. If urgent work arrives while transitions are rendering, the remaining transitions are
+    /// left for a later call so the renderer can apply the urgent update first. Transitions that have been put off for
+    /// several calls in a row are rendered after the urgent work without yielding, so they can't be starved.
     #[instrument(skip(self, to), level = "trace", name = "VirtualDom::render_immediate")]
     pub fn render_immediate(&mut self, to: &mut impl WriteMutations) {
         // Process any events that might be pending in the queue
@@ -605,7 +648,9 @@ impl VirtualDom {
         // Next, diff any dirty scopes
         // We choose not to poll the deadline since we complete pretty quickly anyways
         let _runtime = RuntimeGuard::new(self.runtime.clone());
+        let mut rendered_urgent_work = false;
         while let Some(work) = self.pop_work() {
+            rendered_urgent_work = true;
             match work {
                 Work::PollTask(task) => {
                     _ = self.runtime.handle_task_wakeup(task);
@@ -621,7 +666,24 @@ impl VirtualDom {
             }
         }
 
+        // Transitions get their own frame so the urgent updates can be applied before we start on the slow work
+        let expired = self.transitions_expired();
+        if !rendered_urgent_work || expired {
+            while let Some(scope) = self.pop_transition_scope() {
+                self.runtime.clone().while_rendering(|| {
+                    self.run_and_diff_scope(Some(to), scope.id);
+                });
+
+                // Stop rendering transitions as soon as there is urgent work
+                self.queue_events();
+                if !expired && self.has_urgent_work() {
+                    break;
+                }
+            }
+        }
+
         self.runtime.finish_render();
+        self.finish_transitions();
     }
 
     /// [`Self::render_immediate`] to a vector of mutations for testing purposes
@@ -663,6 +725,9 @@ impl VirtualDom {
             // Sometimes when wakers fire we get a slew of updates at once, so its important that we drain this completely
             self.queue_events();
 
+            // Suspense doesn't render progressively, so transitions are just as urgent as any other update
+            self.promote_transitions();
+
             // Now that we have collected all queued work, we should check if we have any dirty scopes. If there are not, then we can poll any queued futures
             if self.has_dirty_scopes() {
                 break;
@@ -701,6 +766,7 @@ impl VirtualDom {
     pub async fn render_suspense_immediate(&mut self) -> Vec<ScopeId> {
         // Queue any new events before we start working
         self.queue_events();
+        self.promote_transitions();
 
         // Render whatever work needs to be rendered, unlocking new futures and suspense leaves
         let _runtime = RuntimeGuard::new(self.runtime.clone());
@@ -738,6 +804,7 @@ impl VirtualDom {
             }
             // Queue any new events
             self.queue_events();
+            self.promote_transitions();
             work_done += 1;
             // Once we have polled a few tasks, we manually yield to the scheduler to give it a chance to run other pending work
             if work_done > 32 {
@@ -746,6 +813,8 @@ impl VirtualDom {
             }
         }
 
+        self.finish_transitions();
+
         self.resolved_scopes
             .sort_by_key(|&id| self.runtime.get_state(id).height);
         std::mem::take(&mut self.resolved_scopes)
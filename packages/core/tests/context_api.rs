@@ -1,6 +1,6 @@
 use dioxus::dioxus_core::{ElementId, Mutation::*};
 use dioxus::prelude::*;
-use dioxus_core::{consume_context_from_scope, generation};
+use dioxus_core::{consume_context_from_scope, generation, Runtime};
 
 #[test]
 fn state_shares() {
@@ -54,3 +54,24 @@ fn state_shares() {
         [SetText { value: "Value is 3".to_string(), id: ElementId(1,) },]
     );
 }
+
+#[test]
+fn component_stack_lists_the_parents() {
+    fn app() -> Element {
+        rsx!(Parent {})
+    }
+
+    #[component]
+    fn Parent() -> Element {
+        rsx!(Child {})
+    }
+
+    #[component]
+    fn Child() -> Element {
+        let stack = Runtime::current().component_stack();
+        assert_eq!(stack, ["Child", "Parent", "app", "RootScopeWrapper"]);
+        rsx!("child")
+    }
+
+    VirtualDom::new(app).rebuild_in_place();
+}
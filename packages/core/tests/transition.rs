@@ -0,0 +1,188 @@
+//! Verify that transitions render after urgent work and are replaced when superseded
+
+use dioxus::prelude::*;
+use dioxus_core::{current_scope_id, needs_update_any, start_transition, NoOpMutations};
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static INPUT_RENDERS: RefCell<Vec<i32>> = const { RefCell::new(Vec::new()) };
+    static TABLE_RENDERS: RefCell<Vec<i32>> = const { RefCell::new(Vec::new()) };
+    static SIGNALS: Cell<Option<(Signal<i32>, Signal<i32>)>> = const { Cell::new(None) };
+}
+
+fn app() -> Element {
+    let input = use_signal(|| 0);
+    let filter = use_signal(|| 0);
+    use_hook(|| SIGNALS.with(|signals| signals.set(Some((input, filter)))));
+
+    rsx! {
+        Input { input }
+        Table { filter }
+    }
+}
+
+#[component]
+fn Input(input: ReadSignal<i32>) -> Element {
+    INPUT_RENDERS.with(|renders| renders.borrow_mut().push(input()));
+    rsx! { "{input}" }
+}
+
+#[component]
+fn Table(filter: ReadSignal<i32>) -> Element {
+    TABLE_RENDERS.with(|renders| renders.borrow_mut().push(filter()));
+    rsx! { "{filter}" }
+}
+
+#[test]
+fn transitions_render_after_urgent_work() {
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    let (input, mut filter) = SIGNALS.with(|signals| signals.get().unwrap());
+
+    let update = move |dom: &mut VirtualDom, value: i32| {
+        let (mut input, mut filter) = (input, filter);
+        dom.in_runtime(|| {
+            input.set(value);
+            start_transition(|| filter.set(value));
+        });
+    };
+
+    // The urgent update renders first and the table keeps its old output
+    update(&mut dom, 1);
+    dom.render_immediate(&mut NoOpMutations);
+    assert_eq!(INPUT_RENDERS.with(|r| r.borrow().clone()), [0, 1]);
+    assert_eq!(TABLE_RENDERS.with(|r| r.borrow().clone()), [0]);
+    assert!(dom.has_pending_transitions());
+
+    // A newer update replaces the pending transition instead of rendering it twice
+    update(&mut dom, 2);
+    dom.render_immediate(&mut NoOpMutations);
+    assert_eq!(INPUT_RENDERS.with(|r| r.borrow().clone()), [0, 1, 2]);
+    assert_eq!(TABLE_RENDERS.with(|r| r.borrow().clone()), [0]);
+
+    // Once there is no urgent work left, the transition renders with the latest state
+    dom.render_immediate(&mut NoOpMutations);
+    assert_eq!(TABLE_RENDERS.with(|r| r.borrow().clone()), [0, 2]);
+    assert!(!dom.has_pending_transitions());
+
+    // An urgent update to a scope waiting on a transition rerenders it right away
+    dom.in_runtime(|| {
+        start_transition(|| filter.set(3));
+        filter.set(4);
+    });
+    dom.render_immediate(&mut NoOpMutations);
+    assert_eq!(TABLE_RENDERS.with(|r| r.borrow().clone()), [0, 2, 4]);
+    assert!(!dom.has_pending_transitions());
+}
+
+#[test]
+fn pending_transitions_render_together() {
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    let (mut input, mut filter) = SIGNALS.with(|signals| signals.get().unwrap());
+
+    // Without urgent work, every scope in a transition renders in the same frame
+    dom.in_runtime(|| {
+        start_transition(|| {
+            input.set(5);
+            filter.set(5);
+        })
+    });
+    dom.render_immediate(&mut NoOpMutations);
+    assert_eq!(INPUT_RENDERS.with(|r| r.borrow().clone()), [0, 5]);
+    assert_eq!(TABLE_RENDERS.with(|r| r.borrow().clone()), [0, 5]);
+    assert!(!dom.has_pending_transitions());
+}
+
+#[test]
+fn transitions_yield_to_urgent_work_and_are_replaced() {
+    thread_local! {
+        static ROW_RENDERS: RefCell<Vec<(usize, i32)>> = const { RefCell::new(Vec::new()) };
+        static STATUS_RENDERS: Cell<usize> = const { Cell::new(0) };
+        static STATUS: Cell<Option<ScopeId>> = const { Cell::new(None) };
+        static FILTER: Cell<Option<Signal<i32>>> = const { Cell::new(None) };
+    }
+
+    fn app() -> Element {
+        let filter = use_signal(|| 0);
+        use_hook(|| FILTER.with(|cell| cell.set(Some(filter))));
+
+        rsx! {
+            Status {}
+            Row { index: 0, filter }
+            Row { index: 1, filter }
+        }
+    }
+
+    #[component]
+    fn Status() -> Element {
+        use_hook(|| STATUS.with(|cell| cell.set(Some(current_scope_id()))));
+        STATUS_RENDERS.with(|renders| renders.set(renders.get() + 1));
+        rsx! { "status" }
+    }
+
+    #[component]
+    fn Row(index: usize, filter: ReadSignal<i32>) -> Element {
+        ROW_RENDERS.with(|renders| renders.borrow_mut().push((index, filter())));
+        // An input event arrives while the first row of the transition renders
+        if index == 0 && filter() == 1 {
+            needs_update_any(STATUS.with(|cell| cell.get().unwrap()));
+        }
+        rsx! { "{filter}" }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    let mut filter = FILTER.with(|cell| cell.get().unwrap());
+
+    // The transition stops rendering as soon as there is urgent work
+    dom.in_runtime(|| start_transition(|| filter.set(1)));
+    dom.render_immediate(&mut NoOpMutations);
+    assert_eq!(
+        ROW_RENDERS.with(|r| r.borrow().clone()),
+        [(0, 0), (1, 0), (0, 1)]
+    );
+    assert_eq!(STATUS_RENDERS.with(Cell::get), 1);
+    assert!(dom.has_pending_transitions());
+
+    // A newer transition replaces the interrupted one and the urgent work renders first
+    dom.in_runtime(|| start_transition(|| filter.set(2)));
+    dom.render_immediate(&mut NoOpMutations);
+    assert_eq!(STATUS_RENDERS.with(Cell::get), 2);
+    assert_eq!(ROW_RENDERS.with(|r| r.borrow().len()), 3);
+
+    // The second row never renders the state of the replaced transition
+    dom.render_immediate(&mut NoOpMutations);
+    assert_eq!(
+        ROW_RENDERS.with(|r| r.borrow().clone()),
+        [(0, 0), (1, 0), (0, 1), (0, 2), (1, 2)]
+    );
+    assert!(!dom.has_pending_transitions());
+}
+
+#[test]
+fn transitions_are_not_starved_by_urgent_work() {
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    let (mut input, mut filter) = SIGNALS.with(|signals| signals.get().unwrap());
+
+    // Every frame has a new urgent update and a new transition, like typing quickly into a filter box
+    let mut frames = 0;
+    while TABLE_RENDERS.with(|r| r.borrow().len()) == 1 {
+        frames += 1;
+        assert!(frames <= 10, "the transition never rendered");
+        dom.in_runtime(|| {
+            input.set(frames);
+            start_transition(|| filter.set(frames));
+        });
+        dom.render_immediate(&mut NoOpMutations);
+    }
+
+    // The urgent update of every frame rendered, and the transition rendered with the latest state
+    assert_eq!(
+        INPUT_RENDERS.with(|r| r.borrow().len()),
+        frames as usize + 1
+    );
+    assert_eq!(TABLE_RENDERS.with(|r| r.borrow().clone()), [0, frames]);
+    assert!(!dom.has_pending_transitions());
+}
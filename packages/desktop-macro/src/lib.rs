@@ -0,0 +1,209 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, FnArg, GenericArgument, ItemFn, Pat, PathArguments,
+    ReturnType, Type,
+};
+
+/// # `#[command]`
+///
+/// Expose a function to javascript running in the webview. The function can be sync or async. Its arguments must
+/// implement `Deserialize` and its return value must implement `Serialize`. If the function returns a `Result`, the
+/// error is converted to a string and the promise in javascript is rejected with it.
+///
+/// ## Expansion
+///
+/// The function is kept as is. The macro adds a struct with the same name that implements the `Command` trait, which
+/// you can register with `Config::with_command::<name>()` or `use_command::<name>()`.
+///
+/// The generated code refers to `dioxus_desktop`, which is in scope if you import `dioxus::prelude::*` or depend on
+/// `dioxus-desktop` directly.
+///
+/// ```rust, ignore
+/// #[command]
+/// async fn add(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+///
+/// // Javascript can now call the command with `await dioxus.commands.add(1, 2)`
+/// ```
+#[proc_macro_attribute]
+pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            TokenStream2::from(attr).span(),
+            "#[command] does not take any arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let function = parse_macro_input!(item as ItemFn);
+    match command_impl(function) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn command_impl(function: ItemFn) -> syn::Result<TokenStream2> {
+    let sig = &function.sig;
+    let vis = &function.vis;
+    let name = &sig.ident;
+    let name_str = name.to_string();
+
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "commands cannot be generic",
+        ));
+    }
+
+    let mut arg_names = Vec::new();
+    let mut arg_types = Vec::new();
+    let mut ts_args = Vec::new();
+    for input in &sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            return Err(syn::Error::new(input.span(), "commands cannot take `self`"));
+        };
+        let Pat::Ident(pat) = &*arg.pat else {
+            return Err(syn::Error::new(
+                arg.pat.span(),
+                "command arguments must be simple identifiers",
+            ));
+        };
+        if matches!(&*arg.ty, Type::Reference(_)) {
+            return Err(syn::Error::new(
+                arg.ty.span(),
+                "command arguments must be owned types",
+            ));
+        }
+
+        let ident = format_ident!("{}", pat.ident);
+        ts_args.push(format!("{}: {}", ident, ts_type(&arg.ty)));
+        arg_names.push(ident);
+        arg_types.push(&*arg.ty);
+    }
+
+    let (returns_result, ts_return) = match &sig.output {
+        ReturnType::Default => (false, "void".to_string()),
+        ReturnType::Type(_, ty) => match result_ok_type(ty) {
+            Some(ok) => (true, ok.map_or_else(|| "unknown".to_string(), ts_type)),
+            None => (false, ts_type(ty)),
+        },
+    };
+    let typescript = format!("{name_str}({}): Promise<{ts_return}>", ts_args.join(", "));
+
+    // `dioxus_desktop` is in scope through `dioxus::prelude` or as a direct dependency
+    let helpers = quote! { dioxus_desktop::commands::macro_helpers };
+    let deserialize = if arg_names.is_empty() {
+        quote! { let _ = args; }
+    } else {
+        quote! {
+            let (#(#arg_names,)*): (#(#arg_types,)*) = #helpers::deserialize_args(args)?;
+        }
+    };
+    let call = match sig.asyncness {
+        Some(_) => quote! { #name(#(#arg_names),*).await },
+        None => quote! { #name(#(#arg_names),*) },
+    };
+    let serialize = match returns_result {
+        true => quote! { #helpers::serialize_result(result) },
+        false => quote! { #helpers::serialize(&result) },
+    };
+    let doc = format!(
+        "The command for [`{name_str}()`]. Register it to let javascript call the function."
+    );
+
+    Ok(quote! {
+        #function
+
+        #[doc = #doc]
+        #[allow(non_camel_case_types, dead_code)]
+        #vis struct #name {}
+
+        impl #helpers::Command for #name {
+            const NAME: &'static str = #name_str;
+            const TYPESCRIPT: &'static str = #typescript;
+
+            fn call(args: #helpers::Value) -> #helpers::CommandFuture {
+                Box::pin(async move {
+                    #deserialize
+                    let result = #call;
+                    #serialize
+                })
+            }
+        }
+    })
+}
+
+/// If the type is a `Result`, returns the type of the ok value if it is known
+fn result_ok_type(ty: &Type) -> Option<Option<&Type>> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    Some(generic_args(&segment.arguments).into_iter().next())
+}
+
+fn generic_args(arguments: &PathArguments) -> Vec<&Type> {
+    let PathArguments::AngleBracketed(arguments) = arguments else {
+        return Vec::new();
+    };
+    arguments
+        .args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Map a rust type to the typescript type of its json representation. Types we don't know are `unknown`.
+fn ts_type(ty: &Type) -> String {
+    match ty {
+        Type::Tuple(tuple) if tuple.elems.is_empty() => "null".to_string(),
+        Type::Tuple(tuple) => {
+            let elems: Vec<_> = tuple.elems.iter().map(ts_type).collect();
+            format!("[{}]", elems.join(", "))
+        }
+        Type::Array(array) => format!("{}[]", ts_type(&array.elem)),
+        Type::Slice(slice) => format!("{}[]", ts_type(&slice.elem)),
+        Type::Reference(reference) => ts_type(&reference.elem),
+        Type::Paren(paren) => ts_type(&paren.elem),
+        Type::Group(group) => ts_type(&group.elem),
+        Type::Path(path) if path.qself.is_none() => {
+            let Some(segment) = path.path.segments.last() else {
+                return "unknown".to_string();
+            };
+            let args = generic_args(&segment.arguments);
+            match (segment.ident.to_string().as_str(), args.as_slice()) {
+                (
+                    "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64"
+                    | "u128" | "usize" | "f32" | "f64",
+                    [],
+                ) => "number".to_string(),
+                ("bool", []) => "boolean".to_string(),
+                ("String" | "str" | "char" | "PathBuf" | "Path", []) => "string".to_string(),
+                ("Option", [inner]) => format!("{} | null", ts_type(inner)),
+                ("Box" | "Rc" | "Arc" | "Cow", [inner, ..]) => ts_type(inner),
+                ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [inner, ..]) => {
+                    let inner = ts_type(inner);
+                    match inner.contains(' ') {
+                        true => format!("({inner})[]"),
+                        false => format!("{inner}[]"),
+                    }
+                }
+                ("HashMap" | "BTreeMap", [_, value, ..]) => {
+                    format!("Record<string, {}>", ts_type(value))
+                }
+                _ => "unknown".to_string(),
+            }
+        }
+        _ => "unknown".to_string(),
+    }
+}
@@ -1,14 +1,18 @@
 use crate::{
+    commands::{resolve_script, CommandCall, CommandRegistry},
     config::{Config, WindowCloseBehaviour},
     edits::EditWebsocket,
     event_handlers::WindowEventHandlers,
     file_upload::{DesktopFileUploadForm, FileDialogRequest},
     ipc::{IpcMessage, UserWindowEvent},
+    navigation::BlockedNavigation,
     query::QueryResult,
+    shared_state::SharedState,
     shortcut::ShortcutRegistry,
     webview::{PendingWebview, WebviewInstance},
+    window_state::WindowBounds,
 };
-use dioxus_core::{consume_context, ElementId, ScopeId, VirtualDom};
+use dioxus_core::{consume_context, spawn, ElementId, ScopeId, VirtualDom};
 use dioxus_history::History;
 use dioxus_html::PlatformEventData;
 use std::{
@@ -37,9 +41,22 @@ pub(crate) struct App {
     pub(crate) is_visible_before_start: bool,
     pub(crate) exit_on_last_window_close: bool,
     pub(crate) webviews: HashMap<WindowId, WebviewInstance>,
+    pub(crate) main_window: Option<WindowId>,
     pub(crate) float_all: bool,
     pub(crate) show_devtools: bool,
 
+    /// The crash reporter and the folder it writes to, until the previous crashes are handed to the main window
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub(crate) crash_reporter: Option<(crate::crash_report::CrashReporter, std::path::PathBuf)>,
+
+    /// The socket other instances forward their launches to in single instance mode
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub(crate) single_instance: Option<crate::single_instance::SingleInstance>,
+
+    /// Launches that arrived before the first window rendered, so no handlers were registered for them yet
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub(crate) pending_launches: Option<Vec<crate::SecondInstance>>,
+
     /// This single blob of state is shared between all the windows so they have access to the runtime state
     ///
     /// This includes stuff like the event handlers, shortcuts, etc as well as ways to modify *other* windows
@@ -54,6 +71,15 @@ pub(crate) struct SharedContext {
     pub(crate) proxy: EventLoopProxy<UserWindowEvent>,
     pub(crate) target: EventLoopWindowTarget<UserWindowEvent>,
     pub(crate) websocket: EditWebsocket,
+    pub(crate) commands: CommandRegistry,
+    pub(crate) state: SharedState,
+    #[cfg(all(
+        feature = "updater",
+        any(target_os = "windows", target_os = "linux", target_os = "macos")
+    ))]
+    pub(crate) updater: Option<crate::updater::UpdaterConfig>,
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub(crate) backends: crate::config::SystemBackends,
 }
 
 impl App {
@@ -63,14 +89,65 @@ impl App {
             .take()
             .unwrap_or_else(|| EventLoopBuilder::<UserWindowEvent>::with_user_event().build());
 
+        // If another instance of the app is already running, this hands the launch over to it and exits
+        #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+        let single_instance = cfg
+            .single_instance
+            .as_deref()
+            .and_then(|id| crate::single_instance::acquire(id, event_loop.create_proxy()));
+
+        // Links that opened the first instance are handled the same way as links forwarded by later launches
+        #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+        let pending_launches = {
+            let launch = crate::SecondInstance::current();
+            let opened_with_link = cfg.single_instance.is_some() && launch.urls().next().is_some();
+            Some(opened_with_link.then_some(launch).into_iter().collect())
+        };
+
+        // Install an update a previous run downloaded before any windows open
+        #[cfg(all(
+            feature = "updater",
+            any(target_os = "windows", target_os = "linux", target_os = "macos")
+        ))]
+        let updater = {
+            if let Some(updater) = &cfg.updater {
+                crate::updater::install_staged_update(updater);
+            }
+            cfg.updater.clone()
+        };
+
+        // Write a report for every panic from here on
+        #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+        let crash_reporter = cfg.crash_reporter.take().and_then(|reporter| {
+            let directory = reporter.directory(cfg.data_dir.as_deref())?;
+            reporter.install(directory.clone());
+            Some((reporter, directory))
+        });
+
+        let commands = CommandRegistry::default();
+        for register in cfg.commands.drain(..) {
+            register(&commands);
+        }
+        commands.set_declarations_path(cfg.command_declarations.take());
+
+        #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+        let backends = std::mem::take(&mut cfg.backends);
+
         let app = Self {
             exit_on_last_window_close: cfg.exit_on_last_window_close,
             is_visible_before_start: true,
             webviews: HashMap::new(),
+            main_window: None,
             control_flow: ControlFlow::Wait,
             unmounted_dom: Cell::new(Some(virtual_dom)),
             float_all: false,
             show_devtools: false,
+            #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+            crash_reporter,
+            #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+            single_instance,
+            #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+            pending_launches,
             cfg: Cell::new(Some(cfg)),
             shared: Rc::new(SharedContext {
                 event_handlers: WindowEventHandlers::default(),
@@ -79,6 +156,15 @@ impl App {
                 proxy: event_loop.create_proxy(),
                 target: event_loop.clone(),
                 websocket: EditWebsocket::start(),
+                commands,
+                state: SharedState::default(),
+                #[cfg(all(
+                    feature = "updater",
+                    any(target_os = "windows", target_os = "linux", target_os = "macos")
+                ))]
+                updater,
+                #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+                backends,
             }),
         };
 
@@ -151,6 +237,48 @@ impl App {
         _ = event;
     }
 
+    /// Bring the main window to the front when the app is launched again in single instance mode
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn handle_second_instance(&self) {
+        let webview = self
+            .main_window
+            .and_then(|id| self.webviews.get(&id))
+            .or_else(|| self.webviews.values().next());
+
+        if let Some(webview) = webview {
+            let window = &webview.desktop_context.window;
+            window.set_minimized(false);
+            window.set_visible(true);
+            window.set_focus();
+        }
+    }
+
+    /// Forward links the operating system asked the running app to open to the second instance handlers
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn handle_opened_urls(&mut self, urls: Vec<String>) {
+        let launch = crate::SecondInstance::from_urls(urls);
+        match &mut self.pending_launches {
+            Some(pending) => pending.push(launch),
+            None => {
+                _ = self
+                    .shared
+                    .proxy
+                    .send_event(UserWindowEvent::SecondInstance(launch))
+            }
+        }
+    }
+
+    /// Send the launches that arrived before the first window rendered now that its handlers are registered
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    fn flush_pending_launches(&mut self) {
+        for launch in self.pending_launches.take().unwrap_or_default() {
+            _ = self
+                .shared
+                .proxy
+                .send_event(UserWindowEvent::SecondInstance(launch));
+        }
+    }
+
     #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
     pub fn handle_tray_icon_event(&mut self, event: tray_icon::TrayIconEvent) {
         if let tray_icon::TrayIconEvent::Click {
@@ -193,6 +321,11 @@ impl App {
             return;
         };
 
+        if let Some(window_state) = &window.window_state {
+            window_state.update(&window.desktop_context.window);
+            window_state.save();
+        }
+
         match window.desktop_context.close_behaviour.get() {
             // If the window is just set to hide when closed, we can just hide it
             WindowCloseBehaviour::WindowHides => {
@@ -239,6 +372,14 @@ impl App {
         }
     }
 
+    pub fn update_window_state(&self, id: WindowId) {
+        if let Some(webview) = self.webviews.get(&id) {
+            if let Some(window_state) = &webview.window_state {
+                window_state.update(&webview.desktop_context.window);
+            }
+        }
+    }
+
     pub fn handle_start_cause_init(&mut self) {
         let virtual_dom = self
             .unmounted_dom
@@ -256,10 +397,16 @@ impl App {
 
         let webview = WebviewInstance::new(cfg, virtual_dom, self.shared.clone());
 
+        #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+        if let Some((reporter, directory)) = self.crash_reporter.take() {
+            reporter.handle_previous_crashes(&directory, &webview.dom);
+        }
+
         // And then attempt to resume from state
         self.resume_from_state(&webview, explicit_window_size, explicit_window_position);
 
         let id = webview.desktop_context.window.id();
+        self.main_window = Some(id);
         self.webviews.insert(id, webview);
     }
 
@@ -292,6 +439,9 @@ impl App {
             .set_visible(self.is_visible_before_start);
 
         _ = self.shared.proxy.send_event(UserWindowEvent::Poll(id));
+
+        #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+        self.flush_pending_launches();
     }
 
     /// Todo: maybe we should poll the virtualdom asking if it has any final actions to apply before closing the webview
@@ -316,6 +466,39 @@ impl App {
         view.desktop_context.query.send(result);
     }
 
+    /// Run a command javascript called and send the result back to the webview once it finishes
+    pub fn handle_command_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let Some(view) = self.webviews.get(&id) else {
+            return;
+        };
+
+        let call = match CommandCall::parse(msg.params()) {
+            Ok(call) => call,
+            Err((Some(call_id), err)) => {
+                let script = resolve_script(call_id, Err(err));
+                _ = view.desktop_context.webview.evaluate_script(&script);
+                return;
+            }
+            Err((None, err)) => {
+                tracing::warn!("Ignoring a command call without an id: {err}");
+                return;
+            }
+        };
+
+        // Commands run on the virtualdom of the window that called them, so they can use the context of the app
+        let future = self.shared.commands.call(id, &call.command, call.args);
+        let desktop_context = Rc::downgrade(&view.desktop_context);
+        view.dom.in_scope(ScopeId::ROOT, || {
+            spawn(async move {
+                let script = resolve_script(call.id, future.await);
+                if let Some(context) = desktop_context.upgrade() {
+                    _ = context.webview.evaluate_script(&script);
+                }
+            });
+        });
+        _ = self.shared.proxy.send_event(UserWindowEvent::Poll(id));
+    }
+
     #[cfg(all(feature = "devtools", debug_assertions))]
     pub fn handle_hot_reload_msg(&mut self, msg: dioxus_devtools::DevserverMsg) {
         use std::time::Duration;
@@ -391,6 +574,13 @@ impl App {
             DevserverMsg::Shutdown => {
                 self.control_flow = ControlFlow::Exit;
             }
+            DevserverMsg::RequestReactiveGraph => {
+                for webview in self.webviews.values() {
+                    dioxus_devtools::send(dioxus_devtools::ClientMsg::ReactiveGraph(
+                        dioxus_devtools::reactive_graph(&webview.dom),
+                    ));
+                }
+            }
             _ => {}
         }
     }
@@ -409,6 +599,20 @@ impl App {
         }
     }
 
+    pub fn handle_log_msg(&self, msg: IpcMessage) {
+        #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+        crate::crash_report::record_console_msg(msg.params());
+
+        #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+        let _ = msg;
+    }
+
+    pub fn handle_blocked_navigation(&self, id: WindowId, navigation: BlockedNavigation) {
+        if let Some(view) = self.webviews.get(&id) {
+            view.navigation.handle_blocked(&view.dom, navigation);
+        }
+    }
+
     pub fn handle_file_dialog_msg(&mut self, msg: IpcMessage, window: WindowId) {
         let Ok(file_dialog) = serde_json::from_value::<FileDialogRequest>(msg.params()) else {
             return;
@@ -506,6 +710,19 @@ impl App {
     /// closing. This way, when the app is restarted, it can attempt to restore the window to the same
     /// position and size it was in before, making a better DX.
     pub(crate) fn handle_loop_destroyed(&self) {
+        // The event loop exits the process without dropping the windows, so we need to flush their state here
+        for webview in self.webviews.values() {
+            if let Some(window_state) = &webview.window_state {
+                window_state.save();
+            }
+        }
+
+        // The process exits without dropping the app, so remove the socket other instances connect to here
+        #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+        if let Some(single_instance) = &self.single_instance {
+            single_instance.release();
+        }
+
         #[cfg(debug_assertions)]
         self.persist_window_state();
     }
@@ -522,29 +739,9 @@ impl App {
                 return;
             };
 
-            let Ok(position) = window.outer_position() else {
+            let Some(bounds) = WindowBounds::from_window(window) else {
                 return;
             };
-            let (x, y) = if cfg!(target_os = "macos") {
-                let position = position.to_logical::<i32>(window.scale_factor());
-                (position.x, position.y)
-            } else {
-                (position.x, position.y)
-            };
-
-            let (width, height) = if cfg!(target_os = "macos") {
-                let size = window.outer_size();
-                let size = size.to_logical::<u32>(window.scale_factor());
-                // This is to work around a bug in how tao handles inner_size on macOS
-                // We *want* to use inner_size, but that's currently broken, so we use outer_size instead and then an adjustment
-                //
-                // https://github.com/tauri-apps/tao/issues/889
-                let adjustment = if window.is_decorated() { 28 } else { 0 };
-                (size.width, size.height.saturating_sub(adjustment))
-            } else {
-                let size = window.inner_size();
-                (size.width, size.height)
-            };
 
             let Some(monitor_name) = monitor.name() else {
                 return;
@@ -555,10 +752,10 @@ impl App {
             });
 
             let state = PreservedWindowState {
-                x,
-                y,
-                width: width.max(200),
-                height: height.max(200),
+                x: bounds.x,
+                y: bounds.y,
+                width: bounds.width.max(200),
+                height: bounds.height.max(200),
                 monitor: monitor_name.to_string(),
                 url: Some(url),
             };
@@ -590,29 +787,24 @@ impl App {
         if let Ok(state) = std::fs::read_to_string(restore_file()) {
             if let Ok(state) = serde_json::from_str::<PreservedWindowState>(&state) {
                 let window = &webview.desktop_context.window;
-                let position = (state.x, state.y);
-                let size = (state.width, state.height);
+                let bounds = WindowBounds {
+                    x: state.x,
+                    y: state.y,
+                    width: state.width,
+                    height: state.height,
+                };
+
+                // Windows that persist their own state were already restored when they were created
+                let restores_window_state = webview.window_state.is_some();
 
                 // Only set the outer position if it wasn't explicitly set
-                if explicit_window_position.is_none() {
-                    if cfg!(target_os = "macos") {
-                        window.set_outer_position(tao::dpi::LogicalPosition::new(
-                            position.0, position.1,
-                        ));
-                    } else {
-                        window.set_outer_position(tao::dpi::PhysicalPosition::new(
-                            position.0, position.1,
-                        ));
-                    }
+                if explicit_window_position.is_none() && !restores_window_state {
+                    window.set_outer_position(bounds.position());
                 }
 
                 // Only set the inner size if it wasn't explicitly set
-                if explicit_inner_size.is_none() {
-                    if cfg!(target_os = "macos") {
-                        window.set_inner_size(tao::dpi::LogicalSize::new(size.0, size.1));
-                    } else {
-                        window.set_inner_size(tao::dpi::PhysicalSize::new(size.0, size.1));
-                    }
+                if explicit_inner_size.is_none() && !restores_window_state {
+                    window.set_inner_size(bounds.size());
                 }
 
                 // Set the url if it exists
@@ -0,0 +1,253 @@
+//! Read and write the clipboard of the OS.
+//!
+//! Use the clipboard with [`read_clipboard`] and [`write_clipboard`], or the methods of the same name on
+//! [`DesktopService`](crate::DesktopService). The clipboard is accessed through a [`ClipboardBackend`], which you can
+//! replace with [`Config::with_clipboard_backend`](crate::Config::with_clipboard_backend) or
+//! [`SystemBackends`](crate::SystemBackends) to keep tests from touching the real clipboard.
+
+use std::{cell::RefCell, future::Future, pin::Pin};
+
+/// The future returned by a [`ClipboardBackend`]
+pub type ClipboardFuture<T> = Pin<Box<dyn Future<Output = Result<T, ClipboardError>>>>;
+
+/// Reads and writes the clipboard of the app.
+///
+/// The default backend uses the clipboard of the OS.
+///
+/// ```rust, no_run
+/// use dioxus::desktop::clipboard::{ClipboardBackend, ClipboardContent, ClipboardFormat, ClipboardFuture};
+/// use std::cell::RefCell;
+///
+/// /// A clipboard that only lives as long as the app
+/// #[derive(Default)]
+/// struct MemoryClipboard(RefCell<Option<ClipboardContent>>);
+///
+/// impl ClipboardBackend for MemoryClipboard {
+///     fn read(&self, format: ClipboardFormat) -> ClipboardFuture<Option<ClipboardContent>> {
+///         let content = self.0.borrow().clone().filter(|content| content.format() == format);
+///         Box::pin(async move { Ok(content) })
+///     }
+///
+///     fn write(&self, content: ClipboardContent) -> ClipboardFuture<()> {
+///         self.0.replace(Some(content));
+///         Box::pin(async { Ok(()) })
+///     }
+/// }
+/// ```
+pub trait ClipboardBackend {
+    /// Read the content of the clipboard in a format. Returns `None` if the clipboard is empty or has no content in
+    /// that format.
+    fn read(&self, format: ClipboardFormat) -> ClipboardFuture<Option<ClipboardContent>>;
+
+    /// Replace the content of the clipboard
+    fn write(&self, content: ClipboardContent) -> ClipboardFuture<()>;
+}
+
+/// Read the content of the clipboard in a format with the clipboard backend of the current window.
+///
+/// Returns `None` if the clipboard is empty or has no content in that format.
+pub async fn read_clipboard(
+    format: ClipboardFormat,
+) -> Result<Option<ClipboardContent>, ClipboardError> {
+    let backend = crate::SystemBackends::current().clipboard;
+    backend.read(format).await
+}
+
+/// Read the text on the clipboard with the clipboard backend of the current window.
+///
+/// Returns `None` if the clipboard is empty or has no text.
+pub async fn read_clipboard_text() -> Result<Option<String>, ClipboardError> {
+    Ok(match read_clipboard(ClipboardFormat::Text).await? {
+        Some(ClipboardContent::Text(text)) => Some(text),
+        _ => None,
+    })
+}
+
+/// Replace the content of the clipboard with the clipboard backend of the current window.
+pub async fn write_clipboard(content: impl Into<ClipboardContent>) -> Result<(), ClipboardError> {
+    let backend = crate::SystemBackends::current().clipboard;
+    backend.write(content.into()).await
+}
+
+/// The formats content can be read from the clipboard in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipboardFormat {
+    /// Plain text
+    Text,
+
+    /// HTML
+    Html,
+
+    /// An image
+    Image,
+}
+
+/// Content on the clipboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardContent {
+    /// Plain text
+    Text(String),
+
+    /// HTML, with the text apps that can't paste HTML use instead
+    Html {
+        /// The HTML
+        html: String,
+
+        /// The plain text version of the HTML
+        alt_text: Option<String>,
+    },
+
+    /// An image
+    Image(ClipboardImage),
+}
+
+impl ClipboardContent {
+    /// The format of the content
+    pub fn format(&self) -> ClipboardFormat {
+        match self {
+            ClipboardContent::Text(_) => ClipboardFormat::Text,
+            ClipboardContent::Html { .. } => ClipboardFormat::Html,
+            ClipboardContent::Image(_) => ClipboardFormat::Image,
+        }
+    }
+}
+
+impl From<String> for ClipboardContent {
+    fn from(text: String) -> Self {
+        ClipboardContent::Text(text)
+    }
+}
+
+impl From<&str> for ClipboardContent {
+    fn from(text: &str) -> Self {
+        ClipboardContent::Text(text.to_string())
+    }
+}
+
+impl From<ClipboardImage> for ClipboardContent {
+    fn from(image: ClipboardImage) -> Self {
+        ClipboardContent::Image(image)
+    }
+}
+
+/// An image on the clipboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+    /// The width of the image in pixels
+    pub width: usize,
+
+    /// The height of the image in pixels
+    pub height: usize,
+
+    /// The pixels of the image, row by row, with four bytes per pixel in RGBA order
+    pub rgba: Vec<u8>,
+}
+
+/// An error that occurred while accessing the clipboard
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ClipboardError {
+    /// The clipboard is not available, for example because no display server is running
+    #[error("The clipboard is not available: {0}")]
+    Unavailable(String),
+
+    /// The clipboard can't hold content in this format on this platform
+    #[error("The clipboard doesn't support {0:?} content on this platform")]
+    Unsupported(ClipboardFormat),
+
+    /// The image doesn't have four bytes for each pixel
+    #[error("The image is {width}x{height} but has {len} bytes instead of {}", width * height * 4)]
+    InvalidImage {
+        /// The width of the image
+        width: usize,
+        /// The height of the image
+        height: usize,
+        /// The number of bytes the image has
+        len: usize,
+    },
+
+    /// The OS failed to read or write the clipboard
+    #[error("Failed to access the clipboard: {0}")]
+    Failed(String),
+}
+
+/// Uses the clipboard of the OS
+#[derive(Default)]
+pub(crate) struct SystemClipboard {
+    // Some platforms drop the content we wrote when the clipboard handle is dropped, so we keep it for the whole app
+    clipboard: RefCell<Option<arboard::Clipboard>>,
+}
+
+impl SystemClipboard {
+    fn with_clipboard<T>(
+        &self,
+        format: ClipboardFormat,
+        f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+    ) -> Result<T, ClipboardError> {
+        let mut clipboard = self.clipboard.borrow_mut();
+        if clipboard.is_none() {
+            *clipboard = Some(
+                arboard::Clipboard::new()
+                    .map_err(|err| ClipboardError::Unavailable(err.to_string()))?,
+            );
+        }
+        f(clipboard.as_mut().expect("the clipboard was just created")).map_err(|err| match err {
+            arboard::Error::ClipboardNotSupported => ClipboardError::Unsupported(format),
+            err => ClipboardError::Failed(err.to_string()),
+        })
+    }
+}
+
+impl ClipboardBackend for SystemClipboard {
+    fn read(&self, format: ClipboardFormat) -> ClipboardFuture<Option<ClipboardContent>> {
+        let content = self.with_clipboard(format, |clipboard| {
+            let content = match format {
+                ClipboardFormat::Text => clipboard.get_text().map(ClipboardContent::Text),
+                ClipboardFormat::Html => {
+                    clipboard.get().html().map(|html| ClipboardContent::Html {
+                        html,
+                        alt_text: None,
+                    })
+                }
+                ClipboardFormat::Image => clipboard.get_image().map(|image| {
+                    ClipboardContent::Image(ClipboardImage {
+                        width: image.width,
+                        height: image.height,
+                        rgba: image.bytes.into_owned(),
+                    })
+                }),
+            };
+
+            // An empty clipboard isn't an error
+            match content {
+                Ok(content) => Ok(Some(content)),
+                Err(arboard::Error::ContentNotAvailable) => Ok(None),
+                Err(err) => Err(err),
+            }
+        });
+        Box::pin(std::future::ready(content))
+    }
+
+    fn write(&self, content: ClipboardContent) -> ClipboardFuture<()> {
+        if let ClipboardContent::Image(image) = &content {
+            if image.rgba.len() != image.width * image.height * 4 {
+                return Box::pin(std::future::ready(Err(ClipboardError::InvalidImage {
+                    width: image.width,
+                    height: image.height,
+                    len: image.rgba.len(),
+                })));
+            }
+        }
+
+        let result = self.with_clipboard(content.format(), |clipboard| match content {
+            ClipboardContent::Text(text) => clipboard.set_text(text),
+            ClipboardContent::Html { html, alt_text } => clipboard.set_html(html, alt_text),
+            ClipboardContent::Image(image) => clipboard.set_image(arboard::ImageData {
+                width: image.width,
+                height: image.height,
+                bytes: image.rgba.into(),
+            }),
+        });
+        Box::pin(std::future::ready(result))
+    }
+}
@@ -0,0 +1,458 @@
+//! Typed commands that javascript in the webview can call.
+//!
+//! Mark a function with [`#[command]`](command) and register it with
+//! [`Config::with_command`](crate::Config::with_command) or [`use_command`](crate::use_command). Javascript can then
+//! call it through `window.dioxus.commands`:
+//!
+//! ```rust, no_run
+//! use dioxus::prelude::*;
+//! use dioxus::desktop::{commands::command, Config};
+//!
+//! #[command]
+//! async fn read_config(name: String) -> Result<String, std::io::Error> {
+//!     std::fs::read_to_string(name)
+//! }
+//!
+//! fn main() {
+//!     dioxus::LaunchBuilder::desktop()
+//!         .with_cfg(Config::new().with_command::<read_config>())
+//!         .launch(app);
+//! }
+//!
+//! fn app() -> Element {
+//!     rsx! {
+//!         // `await window.dioxus.commands.read_config("settings.toml")` now works in the webview
+//!     }
+//! }
+//! ```
+//!
+//! Arguments are passed as json and the promise resolves with the json representation of the return value. If the
+//! command returns an error, the promise is rejected with the error message.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    rc::Rc,
+};
+
+use serde_json::Value;
+use tao::window::WindowId;
+
+pub use dioxus_desktop_macro::command;
+
+/// The future returned by a [`Command`].
+pub type CommandFuture = Pin<Box<dyn Future<Output = Result<Value, CommandError>>>>;
+
+/// A function that javascript in the webview can call. Implement this with the [`#[command]`](command) macro.
+pub trait Command: 'static {
+    /// The name javascript uses to call the command.
+    const NAME: &'static str;
+
+    /// The typescript signature of the command, like `add(a: number, b: number): Promise<number>`.
+    const TYPESCRIPT: &'static str;
+
+    /// Run the command with the json array of arguments javascript passed to it.
+    fn call(args: Value) -> CommandFuture;
+}
+
+/// An error that rejects the promise of a command call in javascript.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum CommandError {
+    /// No command with this name is registered.
+    #[error("No command named `{0}` is registered")]
+    NotFound(String),
+
+    /// The arguments javascript passed don't match the arguments of the command.
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(serde_json::Error),
+
+    /// The message javascript sent is not a valid command call.
+    #[error("Invalid command call: {0}")]
+    InvalidCall(serde_json::Error),
+
+    /// The command returned an error.
+    #[error("{0}")]
+    Failed(String),
+}
+
+#[doc(hidden)]
+pub mod macro_helpers {
+    pub use super::{Command, CommandError, CommandFuture};
+    use serde::{de::DeserializeOwned, Serialize};
+    pub use serde_json::Value;
+
+    pub fn deserialize_args<T: DeserializeOwned>(args: Value) -> Result<T, CommandError> {
+        serde_json::from_value(args).map_err(CommandError::InvalidArguments)
+    }
+
+    pub fn serialize<T: Serialize>(value: &T) -> Result<Value, CommandError> {
+        serde_json::to_value(value).map_err(|err| CommandError::Failed(err.to_string()))
+    }
+
+    pub fn serialize_result<T: Serialize, E: std::fmt::Display>(
+        result: Result<T, E>,
+    ) -> Result<Value, CommandError> {
+        match result {
+            Ok(value) => serialize(&value),
+            Err(err) => Err(CommandError::Failed(err.to_string())),
+        }
+    }
+}
+
+/// A call to a command from javascript
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct CommandCall {
+    pub id: u64,
+    pub command: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+impl CommandCall {
+    /// Parse a call from the params of an ipc message. If the call is invalid, the error contains the id of the call
+    /// if it has one so the promise can still be rejected.
+    pub(crate) fn parse(params: Value) -> Result<Self, (Option<u64>, CommandError)> {
+        let id = params.get("id").and_then(Value::as_u64);
+        serde_json::from_value(params).map_err(|err| (id, CommandError::InvalidCall(err)))
+    }
+}
+
+/// Settle the promise javascript is waiting on for a command call
+pub(crate) fn resolve_script(id: u64, result: Result<Value, CommandError>) -> String {
+    let (ok, value) = match result {
+        Ok(value) => (true, value),
+        Err(err) => (false, Value::String(err.to_string())),
+    };
+    format!("window.dioxus.__resolveCommand({id}, {ok}, {value});")
+}
+
+#[derive(Clone, Copy)]
+struct RegisteredCommand {
+    call: fn(Value) -> CommandFuture,
+    typescript: &'static str,
+}
+
+impl RegisteredCommand {
+    fn new<C: Command>() -> Self {
+        Self {
+            call: C::call,
+            typescript: C::TYPESCRIPT,
+        }
+    }
+}
+
+/// A command registered for a single window, with the number of components that registered it
+struct ScopedCommand {
+    command: RegisteredCommand,
+    users: usize,
+}
+
+/// The commands that are registered for all windows of the app, and the commands that are only registered for one window.
+#[derive(Clone, Default)]
+pub(crate) struct CommandRegistry {
+    commands: Rc<RefCell<BTreeMap<&'static str, RegisteredCommand>>>,
+    scoped: Rc<RefCell<HashMap<WindowId, BTreeMap<&'static str, ScopedCommand>>>>,
+    declarations: Rc<RefCell<Option<PathBuf>>>,
+}
+
+impl CommandRegistry {
+    pub(crate) fn register<C: Command>(&self) {
+        self.commands
+            .borrow_mut()
+            .insert(C::NAME, RegisteredCommand::new::<C>());
+        self.write_declarations();
+    }
+
+    pub(crate) fn remove(&self, name: &str) -> bool {
+        let removed = self.commands.borrow_mut().remove(name).is_some();
+        if removed {
+            self.write_declarations();
+        }
+        removed
+    }
+
+    /// Register a command that only the window can call. The command stays registered until every registration for
+    /// the window is [released](Self::release_in).
+    pub(crate) fn register_in<C: Command>(&self, window: WindowId) {
+        self.scoped
+            .borrow_mut()
+            .entry(window)
+            .or_default()
+            .entry(C::NAME)
+            .or_insert(ScopedCommand {
+                command: RegisteredCommand::new::<C>(),
+                users: 0,
+            })
+            .users += 1;
+        self.write_declarations();
+    }
+
+    /// Release one registration of a command for the window
+    pub(crate) fn release_in(&self, window: WindowId, name: &str) {
+        let mut scoped = self.scoped.borrow_mut();
+        let Some(commands) = scoped.get_mut(&window) else {
+            return;
+        };
+        let Some(command) = commands.get_mut(name) else {
+            return;
+        };
+        command.users -= 1;
+        if command.users == 0 {
+            commands.remove(name);
+            if commands.is_empty() {
+                scoped.remove(&window);
+            }
+            drop(scoped);
+            self.write_declarations();
+        }
+    }
+
+    /// Call a command from the window. Commands registered for the window take precedence over commands registered for
+    /// the whole app.
+    pub(crate) fn call(&self, window: WindowId, name: &str, args: Value) -> CommandFuture {
+        let command = self
+            .scoped
+            .borrow()
+            .get(&window)
+            .and_then(|commands| commands.get(name))
+            .map(|scoped| scoped.command)
+            .or_else(|| self.commands.borrow().get(name).copied());
+
+        match command {
+            Some(command) => (command.call)(args),
+            None => {
+                let name = name.to_string();
+                Box::pin(async move { Err(CommandError::NotFound(name)) })
+            }
+        }
+    }
+
+    /// Keep a typescript declaration file for the registered commands up to date while developing the app
+    pub(crate) fn set_declarations_path(&self, path: Option<PathBuf>) {
+        *self.declarations.borrow_mut() = path;
+        self.write_declarations();
+    }
+
+    /// The typescript declarations for the commands registered for the app or any window
+    pub(crate) fn declarations(&self) -> String {
+        let mut declarations = String::from(
+            "// This file is generated by dioxus-desktop. Do not edit it by hand.\n\n\
+             interface DioxusCommands {\n",
+        );
+        let mut typescript = BTreeMap::new();
+        for (name, command) in self.commands.borrow().iter() {
+            typescript.insert(*name, command.typescript);
+        }
+        for commands in self.scoped.borrow().values() {
+            for (name, scoped) in commands {
+                typescript.entry(*name).or_insert(scoped.command.typescript);
+            }
+        }
+        for typescript in typescript.values() {
+            declarations.push_str("  ");
+            declarations.push_str(typescript);
+            declarations.push_str(";\n");
+        }
+        declarations.push_str(
+            "}\n\n\
+             interface Window {\n  \
+             dioxus: {\n    \
+             commands: DioxusCommands;\n    \
+             invoke<K extends keyof DioxusCommands>(\n      \
+             command: K,\n      \
+             ...args: Parameters<DioxusCommands[K]>\n    \
+             ): ReturnType<DioxusCommands[K]>;\n  \
+             };\n\
+             }\n",
+        );
+        declarations
+    }
+
+    fn write_declarations(&self) {
+        // Release builds don't ship with their sources, so there is nothing to keep in sync
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        if let Some(path) = self.declarations.borrow().as_deref() {
+            if let Err(err) = write_if_changed(path, &self.declarations()) {
+                tracing::warn!(
+                    "Failed to write command declarations to {}: {err}",
+                    path.display()
+                );
+            }
+        }
+    }
+}
+
+fn write_if_changed(path: &Path, contents: &str) -> std::io::Result<()> {
+    if std::fs::read_to_string(path).is_ok_and(|old| old == contents) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)
+}
+
+/// The script that adds `window.dioxus.commands` and `window.dioxus.invoke` to the webview
+pub(crate) const COMMANDS_SCRIPT: &str = r#"
+(function () {
+    const dioxus = (window.dioxus = window.dioxus || {});
+    const pending = new Map();
+    let nextId = 0;
+
+    dioxus.invoke = function (command, ...args) {
+        return new Promise((resolve, reject) => {
+            const id = nextId++;
+            pending.set(id, { resolve, reject });
+            window.ipc.postMessage(
+                JSON.stringify({ method: "command", params: { id, command, args } })
+            );
+        });
+    };
+
+    dioxus.__resolveCommand = function (id, ok, value) {
+        const call = pending.get(id);
+        if (call === undefined) return;
+        pending.delete(id);
+        if (ok) {
+            call.resolve(value);
+        } else {
+            call.reject(new Error(value));
+        }
+    };
+
+    dioxus.commands = new Proxy({}, {
+        get(_, command) {
+            // Don't look like a promise to `await`
+            if (typeof command !== "string" || command === "then") return undefined;
+            return (...args) => dioxus.invoke(command, ...args);
+        },
+    });
+})();
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt;
+
+    struct Add;
+
+    impl Command for Add {
+        const NAME: &'static str = "add";
+        const TYPESCRIPT: &'static str = "add(a: number, b: number): Promise<number>";
+
+        fn call(args: Value) -> CommandFuture {
+            Box::pin(async move {
+                let (a, b): (i32, i32) = macro_helpers::deserialize_args(args)?;
+                macro_helpers::serialize(&(a + b))
+            })
+        }
+    }
+
+    struct Fail;
+
+    impl Command for Fail {
+        const NAME: &'static str = "fail";
+        const TYPESCRIPT: &'static str = "fail(): Promise<null>";
+
+        fn call(_: Value) -> CommandFuture {
+            Box::pin(async move { macro_helpers::serialize_result(Err::<(), _>("it broke")) })
+        }
+    }
+
+    fn call(registry: &CommandRegistry, name: &str, args: Value) -> Result<Value, CommandError> {
+        let window = unsafe { WindowId::dummy() };
+        registry.call(window, name, args).now_or_never().unwrap()
+    }
+
+    #[test]
+    fn registered_commands_are_dispatched() {
+        let registry = CommandRegistry::default();
+        assert!(matches!(
+            call(&registry, "add", serde_json::json!([1, 2])),
+            Err(CommandError::NotFound(_))
+        ));
+
+        registry.register::<Add>();
+        assert_eq!(
+            call(&registry, "add", serde_json::json!([1, 2])).unwrap(),
+            3
+        );
+        assert!(registry.declarations().contains(Add::TYPESCRIPT));
+
+        assert!(registry.remove("add"));
+        assert!(!registry.remove("add"));
+        assert!(!registry.declarations().contains(Add::TYPESCRIPT));
+    }
+
+    #[test]
+    fn window_commands_are_reference_counted() {
+        let registry = CommandRegistry::default();
+        let window = unsafe { WindowId::dummy() };
+
+        // Two components in the window register the same command
+        registry.register_in::<Add>(window);
+        registry.register_in::<Add>(window);
+
+        registry.release_in(window, "add");
+        assert_eq!(
+            call(&registry, "add", serde_json::json!([2, 2])).unwrap(),
+            4
+        );
+
+        registry.release_in(window, "add");
+        assert!(matches!(
+            call(&registry, "add", serde_json::json!([2, 2])),
+            Err(CommandError::NotFound(_))
+        ));
+
+        // Releasing a command that isn't registered does nothing
+        registry.release_in(window, "add");
+    }
+
+    #[test]
+    fn errors_reject_the_call() {
+        let registry = CommandRegistry::default();
+        registry.register::<Add>();
+        registry.register::<Fail>();
+
+        let err = call(&registry, "add", serde_json::json!(["one", 2])).unwrap_err();
+        assert!(matches!(err, CommandError::InvalidArguments(_)));
+        assert!(
+            resolve_script(7, Err(err)).starts_with("window.dioxus.__resolveCommand(7, false, ")
+        );
+
+        let err = call(&registry, "fail", Value::Null).unwrap_err();
+        assert_eq!(
+            resolve_script(8, Err(err)),
+            r#"window.dioxus.__resolveCommand(8, false, "it broke");"#
+        );
+
+        assert_eq!(
+            resolve_script(9, Ok(serde_json::json!(3))),
+            "window.dioxus.__resolveCommand(9, true, 3);"
+        );
+    }
+
+    #[test]
+    fn malformed_calls_keep_their_id() {
+        let call =
+            CommandCall::parse(serde_json::json!({ "id": 3, "command": "add", "args": [1, 2] }))
+                .unwrap();
+        assert_eq!((call.id, call.command.as_str()), (3, "add"));
+
+        let (id, err) =
+            CommandCall::parse(serde_json::json!({ "id": 4, "command": 5 })).unwrap_err();
+        assert_eq!(id, Some(4));
+        assert!(matches!(err, CommandError::InvalidCall(_)));
+
+        let (id, _) = CommandCall::parse(serde_json::json!("not a call")).unwrap_err();
+        assert_eq!(id, None);
+    }
+}
@@ -6,8 +6,10 @@ use tao::window::{Icon, WindowBuilder};
 use wry::http::{Request as HttpRequest, Response as HttpResponse};
 use wry::{RequestAsyncResponder, WebViewId};
 
+use crate::commands::{Command, CommandRegistry};
 use crate::ipc::UserWindowEvent;
 use crate::menubar::{default_menu_bar, DioxusMenu};
+use crate::navigation::{BlockedNavigation, BlockedNavigationHandler};
 
 type CustomEventHandler = Box<
     dyn 'static
@@ -64,10 +66,100 @@ pub struct Config {
     pub(crate) window_close_behavior: WindowCloseBehaviour,
     pub(crate) custom_event_handler: Option<CustomEventHandler>,
     pub(crate) disable_file_drop_handler: bool,
+    pub(crate) window_state: Option<String>,
+    pub(crate) single_instance: Option<String>,
+    pub(crate) commands: Vec<fn(&CommandRegistry)>,
+    pub(crate) command_declarations: Option<PathBuf>,
+    pub(crate) content_security_policy: Option<String>,
+    pub(crate) navigation_allowlist: Vec<String>,
+    pub(crate) blocked_navigation_handler: Option<BlockedNavigationHandler>,
+    #[cfg(all(
+        feature = "updater",
+        any(target_os = "windows", target_os = "linux", target_os = "macos")
+    ))]
+    pub(crate) updater: Option<crate::updater::UpdaterConfig>,
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub(crate) backends: SystemBackends,
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub(crate) crash_reporter: Option<crate::crash_report::CrashReporter>,
 }
 
 impl LaunchConfig for Config {}
 
+/// The backends that show dialogs and notifications and access the clipboard.
+///
+/// The desktop renderer provides the backends from the [`Config`] as a context to every window. The free functions
+/// like [`clipboard::write_clipboard`](crate::clipboard::write_clipboard) read them from the context, so you can
+/// provide your own backends to a plain [`VirtualDom`](dioxus_core::VirtualDom) to test components without a window:
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus::desktop::{clipboard::ClipboardBackend, SystemBackends};
+/// # fn app() -> Element { VNode::empty() }
+/// # fn test(clipboard: impl ClipboardBackend + 'static) {
+/// let mut dom = VirtualDom::new(app)
+///     .with_root_context(SystemBackends::default().with_clipboard(clipboard));
+/// dom.rebuild_in_place();
+/// # }
+/// ```
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+#[derive(Clone)]
+pub struct SystemBackends {
+    pub(crate) dialog: std::rc::Rc<dyn crate::dialog::DialogBackend>,
+    pub(crate) clipboard: std::rc::Rc<dyn crate::clipboard::ClipboardBackend>,
+    pub(crate) notification: std::rc::Rc<dyn crate::notification::NotificationBackend>,
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+impl Default for SystemBackends {
+    fn default() -> Self {
+        Self {
+            dialog: std::rc::Rc::new(crate::dialog::SystemDialogs),
+            clipboard: std::rc::Rc::new(crate::clipboard::SystemClipboard::default()),
+            notification: std::rc::Rc::new(crate::notification::SystemNotifications),
+        }
+    }
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+impl SystemBackends {
+    /// Show file dialogs with a different backend than the native dialogs of the OS
+    pub fn with_dialog(mut self, backend: impl crate::dialog::DialogBackend + 'static) -> Self {
+        self.dialog = std::rc::Rc::new(backend);
+        self
+    }
+
+    /// Access the clipboard with a different backend than the clipboard of the OS
+    pub fn with_clipboard(
+        mut self,
+        backend: impl crate::clipboard::ClipboardBackend + 'static,
+    ) -> Self {
+        self.clipboard = std::rc::Rc::new(backend);
+        self
+    }
+
+    /// Show notifications with a different backend than the notifications of the OS
+    pub fn with_notification(
+        mut self,
+        backend: impl crate::notification::NotificationBackend + 'static,
+    ) -> Self {
+        self.notification = std::rc::Rc::new(backend);
+        self
+    }
+
+    /// The backends provided to the current scope, or the backends of the OS outside of a scope that has them
+    pub(crate) fn current() -> Self {
+        thread_local! {
+            static DEFAULT: SystemBackends = SystemBackends::default();
+        }
+
+        dioxus_core::Runtime::try_current()
+            .and_then(|runtime| runtime.try_current_scope_id())
+            .and_then(|_| dioxus_core::try_consume_context::<Self>())
+            .unwrap_or_else(|| DEFAULT.with(Clone::clone))
+    }
+}
+
 pub(crate) type WryProtocol = (
     String,
     Box<dyn Fn(WebViewId, HttpRequest<Vec<u8>>) -> HttpResponse<Cow<'static, [u8]>> + 'static>,
@@ -111,6 +203,22 @@ impl Config {
             window_close_behavior: WindowCloseBehaviour::WindowCloses,
             custom_event_handler: None,
             disable_file_drop_handler: false,
+            window_state: None,
+            single_instance: None,
+            commands: Vec::new(),
+            command_declarations: None,
+            content_security_policy: None,
+            navigation_allowlist: Vec::new(),
+            blocked_navigation_handler: None,
+            #[cfg(all(
+                feature = "updater",
+                any(target_os = "windows", target_os = "linux", target_os = "macos")
+            ))]
+            updater: None,
+            #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+            backends: SystemBackends::default(),
+            #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+            crash_reporter: None,
         }
     }
 
@@ -141,6 +249,64 @@ impl Config {
         self
     }
 
+    /// Set the Content-Security-Policy the index of the window is served with.
+    ///
+    /// The policy limits what the page can load and run, so content injected into the page can't reach rust. Dioxus
+    /// adds what the app itself needs to the `script-src` and `connect-src` directives. The inline scripts in the index
+    /// and custom head are allowed with a nonce, while scripts injected later are blocked.
+    ///
+    /// [`document::eval`](dioxus_document::eval) and the head elements from `dioxus::document` run javascript with
+    /// `new Function`, which needs `'unsafe-eval'`. Styles set with the `style` attribute need `'unsafe-inline'`:
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::desktop::Config;
+    /// Config::new().with_content_security_policy(
+    ///     "default-src 'self'; script-src 'self' 'unsafe-eval'; style-src 'self' 'unsafe-inline'; img-src 'self' data:",
+    /// );
+    /// ```
+    pub fn with_content_security_policy(mut self, policy: impl Into<String>) -> Self {
+        self.content_security_policy = Some(policy.into());
+        self
+    }
+
+    /// Let the webview of the window navigate to these origins, like `https://dioxuslabs.com`.
+    ///
+    /// Pages on these origins are loaded in the window instead of the app, but they can't send messages to rust. Links
+    /// to these origins that open a new window are opened by the webview, and the window can navigate back to the app.
+    /// Navigations to any other url are passed to the
+    /// [blocked navigation handler](Config::with_blocked_navigation_handler).
+    pub fn with_navigation_allowlist(
+        mut self,
+        origins: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.navigation_allowlist
+            .extend(origins.into_iter().map(Into::into));
+        self
+    }
+
+    /// Handle navigations the webview of the window is not allowed to make.
+    ///
+    /// By default web and `mailto:` links are opened in the browser of the user. The handler replaces that behavior,
+    /// call [`BlockedNavigation::open_in_browser`] to keep it.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::desktop::Config;
+    /// Config::new().with_blocked_navigation_handler(|navigation| {
+    ///     if navigation.url().starts_with("https://dioxuslabs.com") {
+    ///         navigation.open_in_browser();
+    ///     } else {
+    ///         tracing::warn!("Blocked navigation to {}", navigation.url());
+    ///     }
+    /// });
+    /// ```
+    pub fn with_blocked_navigation_handler(
+        mut self,
+        handler: impl Fn(BlockedNavigation) + 'static,
+    ) -> Self {
+        self.blocked_navigation_handler = Some(std::rc::Rc::new(handler));
+        self
+    }
+
     /// Set the pre-rendered HTML content
     pub fn with_prerendered(mut self, content: String) -> Self {
         self.pre_rendered = Some(content);
@@ -164,6 +330,132 @@ impl Config {
         self
     }
 
+    /// Save the size, position, maximized state and monitor of the window and restore them the next time a window with
+    /// the same label is opened.
+    ///
+    /// The state is saved in the [data directory](Config::with_data_directory) when the window is moved, resized or
+    /// closed. If no data directory is set, the state is saved in the local data directory of the current user. The size
+    /// and position set with [`with_window`](Config::with_window) are only used the first time the window is opened.
+    ///
+    /// If the saved position is no longer visible on any monitor, the window is moved back on screen.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # use dioxus::desktop::Config;
+    /// # fn app() -> Element { VNode::empty() }
+    /// dioxus::LaunchBuilder::desktop()
+    ///     .with_cfg(Config::new().with_window_state("main"))
+    ///     .launch(app);
+    /// ```
+    pub fn with_window_state(mut self, label: impl Into<String>) -> Self {
+        self.window_state = Some(label.into());
+        self
+    }
+
+    /// Only allow one instance of the app with this id to run at a time.
+    ///
+    /// When the app is launched while another instance is already running, the new process forwards its command line
+    /// arguments to the running instance and exits. The running instance focuses its main window and you can handle
+    /// the arguments, including any links opened with a custom url scheme, with
+    /// [`use_second_instance_handler`](crate::use_second_instance_handler). If the first instance was opened with a
+    /// link, the handler receives its launch as well once the main window has rendered.
+    ///
+    /// The id should be unique to your app, like its bundle identifier.
+    ///
+    /// > Note: This only has an effect on Windows, macOS, and Linux.
+    pub fn with_single_instance(mut self, id: impl Into<String>) -> Self {
+        self.single_instance = Some(id.into());
+        self
+    }
+
+    /// Let javascript in every window call a function marked with [`#[command]`](crate::commands::command).
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # use dioxus::desktop::{commands::command, Config};
+    /// # fn app() -> Element { VNode::empty() }
+    /// #[command]
+    /// fn add(a: i32, b: i32) -> i32 {
+    ///     a + b
+    /// }
+    ///
+    /// dioxus::LaunchBuilder::desktop()
+    ///     .with_cfg(Config::new().with_command::<add>())
+    ///     .launch(app);
+    /// ```
+    pub fn with_command<C: Command>(mut self) -> Self {
+        self.commands.push(|registry| registry.register::<C>());
+        self
+    }
+
+    /// Write typescript declarations for the registered commands to this file.
+    ///
+    /// The file is rewritten whenever a command is registered or removed in debug builds, so editors can check the
+    /// calls to `window.dioxus.commands` in your javascript or typescript.
+    pub fn with_command_declarations(mut self, path: impl Into<PathBuf>) -> Self {
+        self.command_declarations = Some(path.into());
+        self
+    }
+
+    /// Check for updates to the bundled app with this config.
+    ///
+    /// If a previous run of the app downloaded an update, it is installed before any window opens. Check for new
+    /// updates with [`DesktopService::check_for_update`](crate::DesktopService::check_for_update).
+    #[cfg(all(
+        feature = "updater",
+        any(target_os = "windows", target_os = "linux", target_os = "macos")
+    ))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "updater")))]
+    pub fn with_updater(mut self, updater: crate::updater::UpdaterConfig) -> Self {
+        self.updater = Some(updater);
+        self
+    }
+
+    /// Show file dialogs with a different backend than the native dialogs of the OS.
+    ///
+    /// This is useful for tests, which can't click through a real dialog.
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn with_dialog_backend(
+        mut self,
+        backend: impl crate::dialog::DialogBackend + 'static,
+    ) -> Self {
+        self.backends = self.backends.with_dialog(backend);
+        self
+    }
+
+    /// Access the clipboard with a different backend than the clipboard of the OS.
+    ///
+    /// This is useful for tests, which shouldn't read or overwrite the clipboard of the user.
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn with_clipboard_backend(
+        mut self,
+        backend: impl crate::clipboard::ClipboardBackend + 'static,
+    ) -> Self {
+        self.backends = self.backends.with_clipboard(backend);
+        self
+    }
+
+    /// Show notifications with a different backend than the notifications of the OS.
+    ///
+    /// This is useful for tests, which can respond to notifications without a user.
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn with_notification_backend(
+        mut self,
+        backend: impl crate::notification::NotificationBackend + 'static,
+    ) -> Self {
+        self.backends = self.backends.with_notification(backend);
+        self
+    }
+
+    /// Write a crash report when the app panics and hand the reports to the app the next time it launches.
+    ///
+    /// See the [`crash_report`](crate::crash_report) module for an example.
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub fn with_crash_reporter(mut self, reporter: crate::crash_report::CrashReporter) -> Self {
+        self.crash_reporter = Some(reporter);
+        self
+    }
+
     /// Set the window as child
     pub fn with_as_child_window(mut self) -> Self {
         self.as_child_window = true;
@@ -331,3 +623,107 @@ impl Default for Config {
 //     file.write_all(icon.as_bytes()).unwrap();
 //     println!("({}, {})", icon.width(), icon.height())
 // }
+
+#[cfg(all(
+    test,
+    any(target_os = "windows", target_os = "linux", target_os = "macos")
+))]
+mod tests {
+    use super::SystemBackends;
+    use crate::{
+        clipboard::{self, ClipboardBackend, ClipboardContent, ClipboardFormat, ClipboardFuture},
+        dialog::{self, DialogBackend, DialogFuture, FileDialog},
+        notification::{
+            self, Notification, NotificationBackend, NotificationFuture, NotificationResponse,
+        },
+    };
+    use dioxus_core::{ScopeId, VNode, VirtualDom};
+    use futures_util::FutureExt;
+    use std::{cell::RefCell, path::PathBuf, rc::Rc};
+
+    #[derive(Default)]
+    struct MemoryClipboard(RefCell<Option<ClipboardContent>>);
+
+    impl ClipboardBackend for MemoryClipboard {
+        fn read(&self, format: ClipboardFormat) -> ClipboardFuture<Option<ClipboardContent>> {
+            let content = self
+                .0
+                .borrow()
+                .clone()
+                .filter(|content| content.format() == format);
+            Box::pin(async move { Ok(content) })
+        }
+
+        fn write(&self, content: ClipboardContent) -> ClipboardFuture<()> {
+            self.0.replace(Some(content));
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    struct PickFile(PathBuf);
+
+    impl DialogBackend for PickFile {
+        fn open(&self, _dialog: FileDialog) -> DialogFuture<Vec<PathBuf>> {
+            let file = self.0.clone();
+            Box::pin(async move { vec![file] })
+        }
+
+        fn save(&self, _dialog: FileDialog) -> DialogFuture<Option<PathBuf>> {
+            Box::pin(async { None })
+        }
+    }
+
+    struct PickFirstAction;
+
+    impl NotificationBackend for PickFirstAction {
+        fn show(&self, notification: &Notification) -> NotificationFuture {
+            let response = match notification.actions().first() {
+                Some(action) => NotificationResponse::Action(action.id().to_string()),
+                None => NotificationResponse::Dismissed,
+            };
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    #[test]
+    fn backends_are_read_from_the_context() {
+        let mut dom = VirtualDom::new(VNode::empty).with_root_context(
+            SystemBackends::default()
+                .with_clipboard(MemoryClipboard::default())
+                .with_dialog(PickFile(PathBuf::from("report.pdf")))
+                .with_notification(PickFirstAction),
+        );
+        dom.rebuild_in_place();
+
+        dom.in_scope(ScopeId::ROOT, || {
+            clipboard::write_clipboard("copied")
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+            let text = clipboard::read_clipboard_text().now_or_never().unwrap();
+            assert_eq!(text.unwrap().as_deref(), Some("copied"));
+
+            let files = dialog::open_file_dialog(FileDialog::new())
+                .now_or_never()
+                .unwrap();
+            assert_eq!(files, [PathBuf::from("report.pdf")]);
+            assert_eq!(
+                dialog::save_file_dialog(FileDialog::new()).now_or_never(),
+                Some(None)
+            );
+
+            let opened = Rc::new(RefCell::new(false));
+            let notification =
+                Notification::new("Download finished").with_action("open", "Open", {
+                    let opened = opened.clone();
+                    move || *opened.borrow_mut() = true
+                });
+            let response = notification::show_notification(notification)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+            assert_eq!(response, NotificationResponse::Action("open".to_string()));
+            assert!(*opened.borrow());
+        });
+    }
+}
@@ -0,0 +1,488 @@
+//! Crash reports for panics in desktop apps.
+//!
+//! When a panic on the main thread closes the app, the [`CrashReporter`] set with
+//! [`Config::with_crash_reporter`](crate::Config::with_crash_reporter) writes a [`CrashReport`] to disk. Panics while
+//! rendering a component are caught by the nearest error boundary and don't close the app, so they are not reported. The report
+//! holds the panic message and backtrace, the components that were running and the last messages the webview logged
+//! to the console. The next time the app launches, the reports are handed to a callback so you can ask the user to
+//! send them to you.
+//!
+//! ```rust, no_run
+//! use dioxus::desktop::{crash_report::CrashReporter, Config};
+//! use dioxus::prelude::*;
+//!
+//! fn main() {
+//!     let crash_reporter = CrashReporter::new()
+//!         .with_recovery_window(true)
+//!         .on_previous_crash(|report| {
+//!             spawn(async move {
+//!                 // Upload the report with your http client of choice and remove it once it is sent
+//!                 println!("{}", report.to_json());
+//!                 _ = report.remove();
+//!             });
+//!         });
+//!
+//!     dioxus::LaunchBuilder::desktop()
+//!         .with_cfg(Config::new().with_crash_reporter(crash_reporter))
+//!         .launch(app);
+//! }
+//!
+//! fn app() -> Element {
+//!     rsx! {
+//!         button { onclick: |_| panic!("Oh no"), "Crash" }
+//!     }
+//! }
+//! ```
+
+use crate::Config;
+use dioxus_core::{Element, Runtime, VirtualDom};
+use dioxus_core_macro::rsx;
+use dioxus_html as dioxus_elements;
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    collections::VecDeque,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tao::{dpi::LogicalSize, window::WindowBuilder};
+
+/// The environment variable the recovery window is launched with. It holds the path of the report.
+const RECOVERY_ENV: &str = "DIOXUS_CRASH_REPORT";
+
+/// The number of console messages that are kept for the next report
+const MAX_LOGS: usize = 100;
+
+static LOGS: Mutex<VecDeque<CrashLog>> = Mutex::new(VecDeque::new());
+static CAPTURING_LOGS: AtomicBool = AtomicBool::new(false);
+
+/// The folder reports are written to and whether to show the recovery window, once the reporter is installed
+static INSTALLED: Mutex<Option<(PathBuf, bool)>> = Mutex::new(None);
+
+/// The report of the last panic on the main thread. It is only written if the panic closes the app.
+static LAST_PANIC: Mutex<Option<CrashReport>> = Mutex::new(None);
+
+/// Writes crash reports when the app panics and hands the reports of previous runs to the app.
+///
+/// Reports are only written for panics on the main thread that close the app. Panics on other threads and panics while
+/// rendering a component don't close the app, so they are left to the default panic hook and error boundaries.
+#[derive(Clone, Default)]
+pub struct CrashReporter {
+    directory: Option<PathBuf>,
+    recovery_window: bool,
+    on_previous_crash: Option<Rc<dyn Fn(CrashReport)>>,
+}
+
+impl CrashReporter {
+    /// Create a crash reporter that writes reports to the `crash-reports` folder in the data directory of the app
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the folder the reports are written to
+    pub fn with_directory(mut self, path: impl Into<PathBuf>) -> Self {
+        self.directory = Some(path.into());
+        self
+    }
+
+    /// Show a window that tells the user the app crashed and lets them restart it. Defaults to `false`.
+    ///
+    /// The window runs in a new process, because the app that panicked can't open windows anymore.
+    pub fn with_recovery_window(mut self, recovery_window: bool) -> Self {
+        self.recovery_window = recovery_window;
+        self
+    }
+
+    /// Handle the reports of previous crashes when the app launches.
+    ///
+    /// The callback runs once for every report in the root scope of the main window, so it can spawn a task to upload
+    /// the report. Reports stay on disk and are handed to the callback again on the next launch until you
+    /// [remove](CrashReport::remove) them.
+    pub fn on_previous_crash(mut self, callback: impl Fn(CrashReport) + 'static) -> Self {
+        self.on_previous_crash = Some(Rc::new(callback));
+        self
+    }
+
+    /// The folder reports are written to
+    pub(crate) fn directory(&self, data_dir: Option<&Path>) -> Option<PathBuf> {
+        match (&self.directory, data_dir) {
+            (Some(dir), _) => Some(dir.clone()),
+            (None, Some(dir)) => Some(dir.join("crash-reports")),
+            (None, None) => {
+                crate::window_state::default_data_dir().map(|dir| dir.join("crash-reports"))
+            }
+        }
+    }
+
+    /// Capture a report for the panics on the current thread and start capturing the console of the webviews.
+    ///
+    /// The report is only written if the panic unwinds out of the event loop in [`catch_crash`].
+    pub(crate) fn install(&self, directory: PathBuf) {
+        let main_thread = std::thread::current().id();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+            if std::thread::current().id() != main_thread {
+                return;
+            }
+
+            let location = info.location().map(|location| location.to_string());
+            let report = CrashReport::capture(info.payload(), location);
+            if let Ok(mut last_panic) = LAST_PANIC.lock() {
+                *last_panic = Some(report);
+            }
+        }));
+
+        *INSTALLED.lock().unwrap() = Some((directory, self.recovery_window));
+        CAPTURING_LOGS.store(true, Ordering::Relaxed);
+    }
+
+    /// Hand the reports of previous runs to the callback
+    pub(crate) fn handle_previous_crashes(&self, directory: &Path, dom: &VirtualDom) {
+        let Some(callback) = &self.on_previous_crash else {
+            return;
+        };
+        for report in CrashReport::read_all(directory) {
+            dom.in_scope(dioxus_core::ScopeId::ROOT, || callback(report));
+        }
+    }
+}
+
+/// A report of a panic that closed the app
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct CrashReport {
+    /// The panic message
+    pub message: String,
+
+    /// The file, line and column the panic happened at
+    pub location: Option<String>,
+
+    /// The name of the thread that panicked
+    pub thread: Option<String>,
+
+    /// The backtrace of the panic. This is only captured if `RUST_BACKTRACE` is set or the app was built with debug
+    /// assertions.
+    pub backtrace: Option<String>,
+
+    /// The names of the components from the one that panicked up to the root of the app
+    pub component_stack: Vec<String>,
+
+    /// The last messages the webviews logged to the console before the panic
+    pub logs: Vec<CrashLog>,
+
+    /// The version of the app
+    pub app_version: Option<String>,
+
+    /// The operating system the app ran on
+    pub os: String,
+
+    /// The cpu architecture the app ran on
+    pub arch: String,
+
+    /// When the app panicked, in seconds since the unix epoch
+    pub timestamp: u64,
+
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+/// A message a webview logged to the console. This has the same shape as the logs the devtools of the web renderer
+/// forward to the CLI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashLog {
+    /// The console method that logged the message, like `log` or `error`
+    pub level: String,
+
+    /// The arguments that were logged
+    pub messages: Vec<String>,
+}
+
+impl CrashReport {
+    fn capture(payload: &(dyn Any + Send), location: Option<String>) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "<unknown panic>".to_string()
+        };
+
+        let backtrace = std::backtrace::Backtrace::capture();
+        let backtrace = (backtrace.status() == std::backtrace::BacktraceStatus::Captured)
+            .then(|| backtrace.to_string())
+            .or_else(|| {
+                cfg!(debug_assertions)
+                    .then(|| std::backtrace::Backtrace::force_capture().to_string())
+            });
+
+        let component_stack = Runtime::try_current()
+            .map(|runtime| {
+                runtime
+                    .component_stack()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let logs = match LOGS.try_lock() {
+            Ok(logs) => logs.iter().cloned().collect(),
+            Err(_) => Vec::new(),
+        };
+
+        Self {
+            message,
+            location,
+            thread: std::thread::current().name().map(str::to_string),
+            backtrace,
+            component_stack,
+            logs,
+            app_version: dioxus_cli_config::app_version(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            path: PathBuf::new(),
+        }
+    }
+
+    /// Write the report to a new file in the folder
+    fn write(&self, directory: &Path) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(directory)?;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let path = directory.join(format!("crash-{nanos}.json"));
+        std::fs::write(&path, self.to_json())?;
+        Ok(path)
+    }
+
+    fn read(path: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let mut report: Self = serde_json::from_str(&contents).ok()?;
+        report.path = path.to_path_buf();
+        Some(report)
+    }
+
+    /// Read the reports in the folder, oldest first
+    fn read_all(directory: &Path) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            return Vec::new();
+        };
+        let mut reports: Vec<Self> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Self::read(&path))
+            .collect();
+        reports.sort_by_key(|report| report.timestamp);
+        reports
+    }
+
+    /// The file the report is stored in
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Serialize the report to pretty printed json
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Delete the report so it is not handed to the app again
+    pub fn remove(&self) -> std::io::Result<()> {
+        std::fs::remove_file(&self.path)
+    }
+}
+
+/// The console message a webview sends over the `log` ipc method. It has the same shape as the `ClientMsg::Log` the
+/// web renderer sends to the devserver.
+#[derive(Deserialize)]
+enum ConsoleMsg {
+    Log(CrashLog),
+}
+
+/// Keep a console message the webview sent for the next crash report
+pub(crate) fn record_console_msg(params: serde_json::Value) {
+    match serde_json::from_value(params) {
+        Ok(ConsoleMsg::Log(log)) => record_log(log),
+        Err(err) => tracing::trace!("Ignoring an invalid console message: {err}"),
+    }
+}
+
+/// Keep a message the webview logged for the next crash report
+fn record_log(log: CrashLog) {
+    if !CAPTURING_LOGS.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(mut logs) = LOGS.lock() else {
+        return;
+    };
+    if logs.len() == MAX_LOGS {
+        logs.pop_front();
+    }
+    logs.push_back(log);
+}
+
+/// Check if the webviews should forward their console messages
+pub(crate) fn capturing_logs() -> bool {
+    CAPTURING_LOGS.load(Ordering::Relaxed)
+}
+
+/// Run a step of the event loop and write the report of the panic if it unwinds out of the step and closes the app.
+///
+/// Panics that are caught before they get here, like panics while rendering a component, are not reported.
+pub(crate) fn catch_crash<R>(f: impl FnOnce() -> R) -> R {
+    let payload = match std::panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => return result,
+        Err(payload) => payload,
+    };
+
+    let installed = INSTALLED
+        .lock()
+        .ok()
+        .and_then(|installed| installed.clone());
+    let report = LAST_PANIC.lock().ok().and_then(|mut report| report.take());
+    if let (Some((directory, recovery_window)), Some(report)) = (installed, report) {
+        match report.write(&directory) {
+            Ok(path) if recovery_window => launch_recovery_window(&path),
+            Ok(_) => {}
+            Err(err) => tracing::error!("Failed to write the crash report: {err}"),
+        }
+    }
+
+    std::panic::resume_unwind(payload)
+}
+
+/// Launch the app again with the report in the environment so it shows the recovery window
+fn launch_recovery_window(report: &Path) {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    let spawned = std::process::Command::new(exe)
+        .args(std::env::args_os().skip(1))
+        .env(RECOVERY_ENV, report)
+        .spawn();
+    if let Err(err) = spawned {
+        tracing::error!("Failed to open the crash recovery window: {err}");
+    }
+}
+
+/// Replace the app with the recovery window if this process was launched to show it
+pub(crate) fn recover(virtual_dom: VirtualDom, cfg: Config) -> (VirtualDom, Config) {
+    let Some(path) = std::env::var_os(RECOVERY_ENV) else {
+        return (virtual_dom, cfg);
+    };
+    let Some(report) = CrashReport::read(Path::new(&path)) else {
+        return (virtual_dom, cfg);
+    };
+
+    let title = dioxus_cli_config::app_title().unwrap_or_else(|| "The app".to_string());
+    let window = WindowBuilder::new()
+        .with_title(format!("{title} crashed"))
+        .with_inner_size(LogicalSize::new(520.0, 360.0));
+    let cfg = Config::new().with_window(window).with_menu(None);
+
+    (VirtualDom::new_with_props(recovery_window, report), cfg)
+}
+
+/// Tells the user that the app crashed and lets them restart it
+fn recovery_window(report: CrashReport) -> Element {
+    let title = dioxus_cli_config::app_title().unwrap_or_else(|| "The app".to_string());
+    let location = report.location.clone().unwrap_or_default();
+    let components = report.component_stack.join(" < ");
+
+    rsx! {
+        div { style: "font-family: sans-serif; padding: 16px;",
+            h2 { "{title} closed unexpectedly" }
+            p { "A report of the crash was saved. It will be offered to the app the next time it starts." }
+            details {
+                summary { "Details" }
+                pre { style: "white-space: pre-wrap;", "{report.message}\n\nat {location}\nin {components}" }
+            }
+            div { style: "display: flex; gap: 8px; justify-content: flex-end;",
+                button { onclick: move |_| close(true), "Restart" }
+                button { onclick: move |_| close(false), "Close" }
+            }
+        }
+    }
+}
+
+/// Close the recovery window and optionally start the app again
+fn close(restart: bool) {
+    if let Some(exe) = restart.then(std::env::current_exe).and_then(Result::ok) {
+        let spawned = std::process::Command::new(exe)
+            .args(std::env::args_os().skip(1))
+            .env_remove(RECOVERY_ENV)
+            .spawn();
+        if let Err(err) = spawned {
+            tracing::error!("Failed to restart the app: {err}");
+        }
+    }
+    std::process::exit(0);
+}
+
+#[test]
+fn test_reports_round_trip() {
+    let directory =
+        std::env::temp_dir().join(format!("dioxus-crash-reports-{}", std::process::id()));
+    let report = CrashReport {
+        message: "Oh no".to_string(),
+        location: Some("src/main.rs:1:1".to_string()),
+        thread: Some("main".to_string()),
+        backtrace: None,
+        component_stack: vec!["Child".to_string(), "app".to_string()],
+        logs: vec![CrashLog {
+            level: "error".to_string(),
+            messages: vec!["failed".to_string()],
+        }],
+        app_version: Some("0.1.0".to_string()),
+        os: "linux".to_string(),
+        arch: "x86_64".to_string(),
+        timestamp: 1,
+        path: PathBuf::new(),
+    };
+
+    let path = report.write(&directory).unwrap();
+    let reports = CrashReport::read_all(&directory);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].path(), path);
+    assert_eq!(reports[0].message, report.message);
+    assert_eq!(reports[0].logs, report.logs);
+
+    reports[0].remove().unwrap();
+    assert!(CrashReport::read_all(&directory).is_empty());
+    _ = std::fs::remove_dir(&directory);
+}
+
+#[test]
+fn test_only_panics_that_close_the_app_are_reported() {
+    let directory =
+        std::env::temp_dir().join(format!("dioxus-crash-panics-{}", std::process::id()));
+    CrashReporter::new().install(directory.clone());
+
+    // Panics that are caught inside the event loop, like panics while rendering, don't close the app
+    catch_crash(|| std::panic::catch_unwind(|| panic!("caught")).unwrap_err());
+    assert!(CrashReport::read_all(&directory).is_empty());
+
+    let escaped = std::panic::catch_unwind(|| catch_crash(|| panic!("escaped")));
+    assert!(escaped.is_err());
+    let reports = CrashReport::read_all(&directory);
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].message, "escaped");
+
+    _ = std::panic::take_hook();
+    *INSTALLED.lock().unwrap() = None;
+    CAPTURING_LOGS.store(false, Ordering::Relaxed);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
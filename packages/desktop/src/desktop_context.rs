@@ -1,8 +1,10 @@
 use crate::{
     app::SharedContext,
     assets::AssetHandlerRegistry,
+    commands::Command,
     file_upload::NativeFileHover,
     ipc::UserWindowEvent,
+    menubar::DioxusMenu,
     query::QueryEngine,
     shortcut::{HotKey, HotKeyState, ShortcutHandle, ShortcutRegistryError},
     webview::PendingWebview,
@@ -10,7 +12,7 @@ use crate::{
 };
 use dioxus_core::{Callback, VirtualDom};
 use std::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     future::{Future, IntoFuture},
     pin::Pin,
     rc::{Rc, Weak},
@@ -69,6 +71,9 @@ pub struct DesktopService {
     pub(crate) file_hover: NativeFileHover,
     pub(crate) close_behaviour: Rc<Cell<WindowCloseBehaviour>>,
 
+    // The menu bar must be kept alive as long as it is attached to the window
+    pub(crate) menu: RefCell<Option<DioxusMenu>>,
+
     #[cfg(target_os = "ios")]
     pub(crate) views: Rc<std::cell::RefCell<Vec<*mut objc::runtime::Object>>>,
 }
@@ -90,6 +95,7 @@ impl DesktopService {
         asset_handlers: AssetHandlerRegistry,
         file_hover: NativeFileHover,
         close_behaviour: WindowCloseBehaviour,
+        menu: Option<DioxusMenu>,
     ) -> Self {
         Self {
             window,
@@ -98,6 +104,7 @@ impl DesktopService {
             asset_handlers,
             file_hover,
             close_behaviour: Rc::new(Cell::new(close_behaviour)),
+            menu: RefCell::new(menu),
             query: Default::default(),
             #[cfg(target_os = "ios")]
             views: Default::default(),
@@ -193,6 +200,20 @@ impl DesktopService {
             .send_event(UserWindowEvent::CloseWindow(id));
     }
 
+    /// Replace the menu bar of the window. Passing `None` removes the menu bar.
+    ///
+    /// To build a menu bar that updates with your app state, use the [`WindowMenu`](crate::menu::WindowMenu) component instead.
+    pub fn set_menu(&self, menu: Option<DioxusMenu>) {
+        let mut current = self.menu.borrow_mut();
+        if let Some(old) = current.take() {
+            crate::menubar::remove_menu_bar(&old, &self.window);
+        }
+        if let Some(new) = &menu {
+            crate::menubar::init_menu_bar(new, &self.window);
+        }
+        *current = menu;
+    }
+
     /// change window to fullscreen
     pub fn set_fullscreen(&self, fullscreen: bool) {
         if let Some(handle) = &self.window.current_monitor() {
@@ -241,6 +262,163 @@ impl DesktopService {
         self.shared.event_handlers.remove(id)
     }
 
+    /// Let javascript in every window call a function marked with [`#[command]`](crate::commands::command).
+    ///
+    /// If a command with the same name is already registered, it is replaced.
+    pub fn register_command<C: Command>(&self) {
+        self.shared.commands.register::<C>()
+    }
+
+    /// Remove a command registered with [`Self::register_command`] or [`Config::with_command`]. Returns `true` if the
+    /// command was registered.
+    pub fn remove_command(&self, name: &str) -> bool {
+        self.shared.commands.remove(name)
+    }
+
+    /// Get the typescript declarations for the commands that are currently registered.
+    pub fn command_declarations(&self) -> String {
+        self.shared.commands.declarations()
+    }
+
+    /// Get the app context of this type that is shared by every window, if it was provided.
+    pub fn app_context<T: Clone + 'static>(&self) -> Option<T> {
+        self.shared.state.get()
+    }
+
+    /// Provide context that is shared by every window of the app, replacing any app context of the same type.
+    ///
+    /// Signals created in the closure are owned by the app instead of the current window. They stay alive after the
+    /// window closes, and writing to them reruns the components that read them in every window.
+    pub fn provide_app_context<T: Clone + 'static>(&self, init: impl FnOnce() -> T) -> T {
+        self.shared.state.provide(init)
+    }
+
+    /// Remove the app context of this type. Signals owned by the app are not dropped until the app exits.
+    pub fn remove_app_context<T: 'static>(&self) -> Option<T> {
+        self.shared.state.remove()
+    }
+
+    /// Check for a newer version of the app with the updater set in [`Config::with_updater`].
+    ///
+    /// Returns `None` if the app is up to date.
+    #[cfg(all(
+        feature = "updater",
+        any(target_os = "windows", target_os = "linux", target_os = "macos")
+    ))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "updater")))]
+    pub async fn check_for_update(
+        &self,
+    ) -> Result<Option<crate::updater::Update>, crate::updater::UpdateError> {
+        let Some(config) = self.shared.updater.clone() else {
+            return Err(crate::updater::UpdateError::NotConfigured);
+        };
+        crate::updater::check_for_update(&config).await
+    }
+
+    /// Show a dialog to pick files or folders to open.
+    ///
+    /// Returns an empty list if the user cancelled the dialog.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::desktop::{dialog::FileDialog, window};
+    /// # async fn open() {
+    /// let images = window()
+    ///     .open_file_dialog(FileDialog::new().with_filter("Images", &["png", "jpg"]).with_multiple(true))
+    ///     .await;
+    /// # }
+    /// ```
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub async fn open_file_dialog(
+        &self,
+        dialog: crate::dialog::FileDialog,
+    ) -> Vec<std::path::PathBuf> {
+        let backend = self.shared.backends.dialog.clone();
+        backend.open(dialog).await
+    }
+
+    /// Show a dialog to pick where to save a file.
+    ///
+    /// Returns `None` if the user cancelled the dialog.
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub async fn save_file_dialog(
+        &self,
+        dialog: crate::dialog::FileDialog,
+    ) -> Option<std::path::PathBuf> {
+        let backend = self.shared.backends.dialog.clone();
+        backend.save(dialog).await
+    }
+
+    /// Read the content of the clipboard in a format.
+    ///
+    /// Returns `None` if the clipboard is empty or has no content in that format.
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub async fn read_clipboard(
+        &self,
+        format: crate::clipboard::ClipboardFormat,
+    ) -> Result<Option<crate::clipboard::ClipboardContent>, crate::clipboard::ClipboardError> {
+        let backend = self.shared.backends.clipboard.clone();
+        backend.read(format).await
+    }
+
+    /// Read the text on the clipboard.
+    ///
+    /// Returns `None` if the clipboard is empty or has no text.
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub async fn read_clipboard_text(
+        &self,
+    ) -> Result<Option<String>, crate::clipboard::ClipboardError> {
+        let content = self
+            .read_clipboard(crate::clipboard::ClipboardFormat::Text)
+            .await?;
+        Ok(match content {
+            Some(crate::clipboard::ClipboardContent::Text(text)) => Some(text),
+            _ => None,
+        })
+    }
+
+    /// Replace the content of the clipboard with text, HTML or an image.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::desktop::window;
+    /// # async fn copy() {
+    /// window().write_clipboard("Hello from dioxus").await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub async fn write_clipboard(
+        &self,
+        content: impl Into<crate::clipboard::ClipboardContent>,
+    ) -> Result<(), crate::clipboard::ClipboardError> {
+        let backend = self.shared.backends.clipboard.clone();
+        backend.write(content.into()).await
+    }
+
+    /// Show a notification and wait for the user to respond to it.
+    ///
+    /// The callback of the action the user picked runs before this returns. If you don't need the response, spawn the
+    /// future instead of waiting for it.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// # use dioxus::desktop::{notification::Notification, window};
+    /// spawn(async move {
+    ///     let notification = Notification::new("Build finished")
+    ///         .with_action("show", "Show", || window().set_focus());
+    ///     _ = window().show_notification(notification).await;
+    /// });
+    /// ```
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    pub async fn show_notification(
+        &self,
+        notification: crate::notification::Notification,
+    ) -> Result<crate::notification::NotificationResponse, crate::notification::NotificationError>
+    {
+        let backend = self.shared.backends.notification.clone();
+        let response = backend.show(&notification).await?;
+        notification.respond(&response);
+        Ok(response)
+    }
+
     /// Create a global shortcut
     ///
     /// Linux: Only works on x11. See [this issue](https://github.com/tauri-apps/tao/issues/331) for more information.
@@ -0,0 +1,200 @@
+//! Native dialogs to open and save files.
+//!
+//! Show them with [`open_file_dialog`] and [`save_file_dialog`], or the methods of the same name on
+//! [`DesktopService`](crate::DesktopService). The dialogs are shown by a [`DialogBackend`], which you can replace with
+//! [`Config::with_dialog_backend`](crate::Config::with_dialog_backend) or [`SystemBackends`](crate::SystemBackends) to
+//! pick files in tests without a user.
+
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+};
+
+/// The future returned by a [`DialogBackend`]
+pub type DialogFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// Shows the file dialogs of the app.
+///
+/// The default backend shows the native dialogs of the OS.
+///
+/// ```rust, no_run
+/// use dioxus::desktop::dialog::{DialogBackend, DialogFuture, FileDialog};
+/// use std::path::PathBuf;
+///
+/// /// Picks the same file every time a dialog is opened
+/// struct PickFile(PathBuf);
+///
+/// impl DialogBackend for PickFile {
+///     fn open(&self, _dialog: FileDialog) -> DialogFuture<Vec<PathBuf>> {
+///         let file = self.0.clone();
+///         Box::pin(async move { vec![file] })
+///     }
+///
+///     fn save(&self, _dialog: FileDialog) -> DialogFuture<Option<PathBuf>> {
+///         let file = self.0.clone();
+///         Box::pin(async move { Some(file) })
+///     }
+/// }
+/// ```
+pub trait DialogBackend {
+    /// Show a dialog to pick files or folders. Returns an empty list if the user cancelled the dialog.
+    fn open(&self, dialog: FileDialog) -> DialogFuture<Vec<PathBuf>>;
+
+    /// Show a dialog to pick where to save a file. Returns `None` if the user cancelled the dialog.
+    fn save(&self, dialog: FileDialog) -> DialogFuture<Option<PathBuf>>;
+}
+
+/// Show a dialog to pick files or folders with the dialog backend of the current window.
+///
+/// Returns an empty list if the user cancelled the dialog.
+pub async fn open_file_dialog(dialog: FileDialog) -> Vec<PathBuf> {
+    let backend = crate::SystemBackends::current().dialog;
+    backend.open(dialog).await
+}
+
+/// Show a dialog to pick where to save a file with the dialog backend of the current window.
+///
+/// Returns `None` if the user cancelled the dialog.
+pub async fn save_file_dialog(dialog: FileDialog) -> Option<PathBuf> {
+    let backend = crate::SystemBackends::current().dialog;
+    backend.save(dialog).await
+}
+
+/// The options of a file dialog
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct FileDialog {
+    /// The title of the dialog
+    pub title: Option<String>,
+
+    /// The directory the dialog starts in
+    pub directory: Option<PathBuf>,
+
+    /// The file name the dialog starts with
+    pub file_name: Option<String>,
+
+    /// The types of files the user can pick. If this is empty, any file can be picked.
+    pub filters: Vec<FileFilter>,
+
+    /// Whether the user can pick more than one file or folder when opening
+    pub multiple: bool,
+
+    /// Whether the user picks folders instead of files when opening
+    pub folders: bool,
+}
+
+impl FileDialog {
+    /// Create a dialog with the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the title of the dialog
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the directory the dialog starts in
+    pub fn with_directory(mut self, directory: impl AsRef<Path>) -> Self {
+        self.directory = Some(directory.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set the file name the dialog starts with
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Add a type of file the user can pick
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::desktop::dialog::FileDialog;
+    /// let dialog = FileDialog::new()
+    ///     .with_filter("Images", &["png", "jpg"])
+    ///     .with_filter("Text", &["txt", "md"]);
+    /// ```
+    pub fn with_filter(mut self, name: impl Into<String>, extensions: &[&str]) -> Self {
+        self.filters.push(FileFilter {
+            name: name.into(),
+            extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Let the user pick more than one file or folder
+    pub fn with_multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    /// Pick folders instead of files
+    pub fn with_folders(mut self, folders: bool) -> Self {
+        self.folders = folders;
+        self
+    }
+}
+
+/// A named group of file extensions the user can pick from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFilter {
+    /// The name shown in the dialog
+    pub name: String,
+
+    /// The extensions of the files, without the leading dot
+    pub extensions: Vec<String>,
+}
+
+/// Shows the native dialogs of the OS
+pub(crate) struct SystemDialogs;
+
+impl SystemDialogs {
+    fn dialog(options: FileDialog) -> rfd::AsyncFileDialog {
+        let mut dialog = rfd::AsyncFileDialog::new();
+        if let Some(title) = options.title {
+            dialog = dialog.set_title(title);
+        }
+        if let Some(directory) = options.directory {
+            dialog = dialog.set_directory(directory);
+        }
+        if let Some(file_name) = options.file_name {
+            dialog = dialog.set_file_name(file_name);
+        }
+        for filter in options.filters {
+            dialog = dialog.add_filter(filter.name, &filter.extensions);
+        }
+        dialog
+    }
+}
+
+impl DialogBackend for SystemDialogs {
+    fn open(&self, options: FileDialog) -> DialogFuture<Vec<PathBuf>> {
+        let (multiple, folders) = (options.multiple, options.folders);
+        let dialog = Self::dialog(options);
+        Box::pin(async move {
+            let files = match (folders, multiple) {
+                (false, false) => dialog.pick_file().await.map(|file| vec![file]),
+                (false, true) => dialog.pick_files().await,
+                (true, false) => dialog.pick_folder().await.map(|folder| vec![folder]),
+                (true, true) => dialog.pick_folders().await,
+            };
+            files
+                .into_iter()
+                .flatten()
+                .map(|file| file.path().to_path_buf())
+                .collect()
+        })
+    }
+
+    fn save(&self, options: FileDialog) -> DialogFuture<Option<PathBuf>> {
+        let dialog = Self::dialog(options);
+        Box::pin(async move {
+            dialog
+                .save_file()
+                .await
+                .map(|file| file.path().to_path_buf())
+        })
+    }
+}
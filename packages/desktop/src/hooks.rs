@@ -1,12 +1,14 @@
 use std::rc::Rc;
 
 use crate::{
-    assets::*, ipc::UserWindowEvent, shortcut::IntoAccelerator, window, DesktopContext,
-    HotKeyState, ShortcutHandle, ShortcutRegistryError, WryEventHandler,
+    assets::*, commands::Command, ipc::UserWindowEvent, shortcut::IntoAccelerator, window,
+    DesktopContext, HotKeyState, ShortcutHandle, ShortcutRegistryError, WindowChannel,
+    WryEventHandler,
 };
 use dioxus_core::{consume_context, use_hook, use_hook_with_cleanup, Runtime};
 
 use dioxus_hooks::use_callback;
+use dioxus_signals::Signal;
 use tao::{event::Event, event_loop::EventLoopWindowTarget};
 use wry::RequestAsyncResponder;
 
@@ -85,10 +87,53 @@ pub fn use_tray_icon_event_handler(
     })
 }
 
+/// Register an event handler that runs when the app is launched again while
+/// [single instance mode](crate::Config::with_single_instance) is enabled.
+///
+/// The handler also runs for the launch of the first instance if it was opened with a link, and on macOS for every
+/// link the operating system asks the app to open.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus::desktop::use_second_instance_handler;
+/// fn App() -> Element {
+///     let mut opened = use_signal(Vec::new);
+///     use_second_instance_handler(move |launch| {
+///         // Links opened with the url schemes of the app are passed as arguments
+///         opened.extend(launch.urls().map(String::from));
+///     });
+///
+///     rsx! {
+///         for url in opened.iter() {
+///             p { "{url}" }
+///         }
+///     }
+/// }
+/// ```
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(target_os = "windows", target_os = "linux", target_os = "macos")))
+)]
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub fn use_second_instance_handler(
+    mut handler: impl FnMut(&crate::SecondInstance) + 'static,
+) -> WryEventHandler {
+    use_wry_event_handler(move |event, _| {
+        if let Event::UserEvent(UserWindowEvent::SecondInstance(launch)) = event {
+            handler(launch);
+        }
+    })
+}
+
 /// Provide a callback to handle asset loading yourself.
 ///
 /// The callback takes a path as requested by the web view, and it should return `Some(response)`
 /// if you want to load the asset, and `None` if you want to fallback on the default behavior.
+///
+/// To serve files with support for range requests and caching, respond with
+/// [`serve_file`](dioxus_asset_resolver::native::serve_file) or
+/// [`serve_reader`](dioxus_asset_resolver::native::serve_reader). They only read the part of the file the webview
+/// asked for, so video and audio elements can seek through large files.
 pub fn use_asset_handler(
     name: &str,
     mut handler: impl FnMut(AssetRequest, RequestAsyncResponder) + 'static,
@@ -110,6 +155,121 @@ pub fn use_asset_handler(
     );
 }
 
+/// Let javascript in the webview of this component call a function marked with [`#[command]`](crate::commands::command)
+/// while this component is mounted.
+///
+/// The command is only registered for the window the component is rendered in. If several components in the window
+/// register the same command, it stays registered until the last of them is unmounted. Use
+/// [`Config::with_command`](crate::Config::with_command) to register a command for every window.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus::desktop::{commands::command, use_command};
+/// #[command]
+/// fn greet(name: String) -> String {
+///     format!("Hello, {name}!")
+/// }
+///
+/// fn App() -> Element {
+///     use_command::<greet>();
+///     // `await window.dioxus.commands.greet("world")` returns "Hello, world!"
+///     rsx! {}
+/// }
+/// ```
+pub fn use_command<C: Command>() {
+    use_hook_with_cleanup(
+        || {
+            let desktop = window();
+            desktop
+                .shared
+                .commands
+                .register_in::<C>(desktop.window.id());
+            desktop
+        },
+        |desktop| {
+            desktop
+                .shared
+                .commands
+                .release_in(desktop.window.id(), C::NAME)
+        },
+    );
+}
+
+/// Get the app context of this type that is shared by every window, or provide it if no window has yet.
+///
+/// Signals created in the closure are owned by the app instead of the current window, so writing to them reruns the
+/// components that read them in every window.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus::desktop::use_app_context;
+/// #[derive(Clone, Copy)]
+/// struct Selection {
+///     color: Signal<String>,
+/// }
+///
+/// fn Palette() -> Element {
+///     let mut selection = use_app_context(|| Selection { color: Signal::new("red".to_string()) });
+///     rsx! {
+///         button { onclick: move |_| selection.color.set("blue".to_string()), "Blue" }
+///     }
+/// }
+///
+/// fn Inspector() -> Element {
+///     let selection = use_app_context(|| Selection { color: Signal::new("red".to_string()) });
+///     rsx! { "The selected color is {selection.color}" }
+/// }
+/// ```
+pub fn use_app_context<T: Clone + 'static>(init: impl FnOnce() -> T) -> T {
+    use_hook(|| window().shared.state.get_or_insert_with(init))
+}
+
+/// Get a signal that is shared by every window of the app. The signal is created the first time any window asks for a
+/// signal of this type.
+///
+/// This is a shorthand for [`use_app_context`] with a single signal. Use a newtype to share several signals that hold
+/// the same type.
+pub fn use_shared_signal<T: 'static>(init: impl FnOnce() -> T) -> Signal<T> {
+    use_app_context(|| Signal::new(init()))
+}
+
+/// Get a channel to send messages of this type to other windows and receive the messages they send to this window.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus::desktop::use_window_channel;
+/// #[derive(Clone)]
+/// enum Message {
+///     Refresh,
+/// }
+///
+/// fn App() -> Element {
+///     let channel = use_window_channel::<Message>();
+///     let mut refreshes = use_signal(|| 0);
+///
+///     let receiver = channel.clone();
+///     use_future(move || {
+///         let channel = receiver.clone();
+///         async move {
+///             while let Some((_from, Message::Refresh)) = channel.recv().await {
+///                 refreshes += 1;
+///             }
+///         }
+///     });
+///
+///     rsx! {
+///         button { onclick: move |_| _ = channel.broadcast(Message::Refresh), "Refresh other windows" }
+///         "Refreshed {refreshes} times"
+///     }
+/// }
+/// ```
+pub fn use_window_channel<M: 'static>() -> WindowChannel<M> {
+    use_hook(|| {
+        let window = window();
+        WindowChannel::new(window.id(), window.shared.clone())
+    })
+}
+
 /// Get a closure that executes any JavaScript in the WebView context.
 pub fn use_global_shortcut(
     accelerator: impl IntoAccelerator,
@@ -17,6 +17,10 @@ pub enum UserWindowEvent {
     #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
     TrayMenuEvent(tray_icon::menu::MenuEvent),
 
+    /// The app was launched again while single instance mode is enabled
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    SecondInstance(crate::single_instance::SecondInstance),
+
     /// Poll the virtualdom
     Poll(WindowId),
 
@@ -26,6 +30,9 @@ pub enum UserWindowEvent {
         msg: IpcMessage,
     },
 
+    /// The webview of a window tried to navigate somewhere it is not allowed to
+    BlockedNavigation(WindowId, crate::navigation::BlockedNavigation),
+
     /// Handle a hotreload event, basically telling us to update our templates
     #[cfg(all(feature = "devtools", debug_assertions))]
     HotReloadEvent(dioxus_devtools::DevserverMsg),
@@ -62,6 +69,8 @@ pub enum IpcMethod<'a> {
     Query,
     BrowserOpen,
     Initialize,
+    Command,
+    Log,
     Other(&'a str),
 }
 
@@ -73,6 +82,8 @@ impl IpcMessage {
             "query" => IpcMethod::Query,
             "browser_open" => IpcMethod::BrowserOpen,
             "initialize" => IpcMethod::Initialize,
+            "command" => IpcMethod::Command,
+            "log" => IpcMethod::Log,
             _ => IpcMethod::Other(&self.method),
         }
     }
@@ -7,16 +7,24 @@ use dioxus_core::*;
 use dioxus_document::eval;
 use std::any::Any;
 use tao::event::{Event, StartCause, WindowEvent};
+use tao::event_loop::{ControlFlow, EventLoopWindowTarget};
 
 /// Launch the WebView and run the event loop, with configuration and root props.
 ///
 /// This will block the main thread, and *must* be spawned on the main thread. This function does not assume any runtime
 /// and is equivalent to calling launch_with_props with the tokio feature disabled.
-pub fn launch_virtual_dom_blocking(virtual_dom: VirtualDom, mut desktop_config: Config) -> ! {
+pub fn launch_virtual_dom_blocking(virtual_dom: VirtualDom, desktop_config: Config) -> ! {
+    // If the app crashed and relaunched itself to show the recovery window, show that instead of the app
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    let (virtual_dom, desktop_config) = crate::crash_report::recover(virtual_dom, desktop_config);
+
+    let mut desktop_config = desktop_config;
     let mut custom_event_handler = desktop_config.custom_event_handler.take();
     let (event_loop, mut app) = App::new(desktop_config, virtual_dom);
 
-    event_loop.run(move |window_event, event_loop, control_flow| {
+    let mut handle_event = move |window_event: Event<'_, UserWindowEvent>,
+                                 event_loop: &EventLoopWindowTarget<UserWindowEvent>,
+                                 control_flow: &mut ControlFlow| {
         // Set the control flow and check if any events need to be handled in the app itself
         app.tick(&window_event);
 
@@ -27,12 +35,20 @@ pub fn launch_virtual_dom_blocking(virtual_dom: VirtualDom, mut desktop_config:
         match window_event {
             Event::NewEvents(StartCause::Init) => app.handle_start_cause_init(),
             Event::LoopDestroyed => app.handle_loop_destroyed(),
+            #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+            Event::Opened { urls } => {
+                app.handle_opened_urls(urls.into_iter().map(String::from).collect())
+            }
             Event::WindowEvent {
                 event, window_id, ..
             } => match event {
                 WindowEvent::CloseRequested => app.handle_close_requested(window_id),
                 WindowEvent::Destroyed { .. } => app.window_destroyed(window_id),
-                WindowEvent::Resized(new_size) => app.resize_window(window_id, new_size),
+                WindowEvent::Resized(new_size) => {
+                    app.resize_window(window_id, new_size);
+                    app.update_window_state(window_id);
+                }
+                WindowEvent::Moved(_) => app.update_window_state(window_id),
                 _ => {}
             },
 
@@ -40,6 +56,9 @@ pub fn launch_virtual_dom_blocking(virtual_dom: VirtualDom, mut desktop_config:
                 UserWindowEvent::Poll(id) => app.poll_vdom(id),
                 UserWindowEvent::NewWindow => app.handle_new_window(),
                 UserWindowEvent::CloseWindow(id) => app.handle_close_msg(id),
+                UserWindowEvent::BlockedNavigation(id, navigation) => {
+                    app.handle_blocked_navigation(id, navigation)
+                }
                 UserWindowEvent::Shutdown => app.control_flow = tao::event_loop::ControlFlow::Exit,
 
                 #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
@@ -54,6 +73,9 @@ pub fn launch_virtual_dom_blocking(virtual_dom: VirtualDom, mut desktop_config:
                 #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
                 UserWindowEvent::TrayIconEvent(evnt) => app.handle_tray_icon_event(evnt),
 
+                #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+                UserWindowEvent::SecondInstance(_) => app.handle_second_instance(),
+
                 #[cfg(all(feature = "devtools", debug_assertions))]
                 UserWindowEvent::HotReloadEvent(msg) => app.handle_hot_reload_msg(msg),
 
@@ -95,6 +117,8 @@ pub fn launch_virtual_dom_blocking(virtual_dom: VirtualDom, mut desktop_config:
                     IpcMethod::UserEvent => {}
                     IpcMethod::Query => app.handle_query_msg(msg, id),
                     IpcMethod::BrowserOpen => app.handle_browser_open(msg),
+                    IpcMethod::Command => app.handle_command_msg(msg, id),
+                    IpcMethod::Log => app.handle_log_msg(msg),
                     IpcMethod::Other(_) => {}
                 },
             },
@@ -102,9 +126,23 @@ pub fn launch_virtual_dom_blocking(virtual_dom: VirtualDom, mut desktop_config:
         }
 
         *control_flow = app.control_flow;
+    };
+
+    // Panics that escape a step of the event loop close the app, so they are the ones that get crash reports
+    event_loop.run(move |window_event, event_loop, control_flow| {
+        run_step(|| handle_event(window_event, event_loop, control_flow))
     })
 }
 
+/// Run a step of the event loop and write a crash report if a panic escapes it
+fn run_step(step: impl FnOnce()) {
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    crate::crash_report::catch_crash(step);
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    step();
+}
+
 /// Launches the WebView and runs the event loop, with configuration and root props.
 pub fn launch_virtual_dom(virtual_dom: VirtualDom, desktop_config: Config) -> ! {
     #[cfg(feature = "tokio_runtime")]
@@ -19,11 +19,14 @@ mod hooks;
 mod ipc;
 mod menubar;
 mod mobile;
+mod navigation;
 mod protocol;
 mod query;
+mod shared_state;
 mod shortcut;
 mod waker;
 mod webview;
+mod window_state;
 
 // mobile shortcut is only supported on mobile platforms
 #[cfg(any(target_os = "ios", target_os = "android"))]
@@ -32,6 +35,8 @@ mod mobile_shortcut;
 /// The main entrypoint for this crate
 pub mod launch;
 
+pub mod commands;
+
 // Reexport tao and wry, might want to re-export other important things
 pub use tao;
 pub use tao::dpi::{LogicalPosition, LogicalSize};
@@ -46,13 +51,47 @@ pub use muda;
 #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
 pub mod trayicon;
 
+// Single instance mode
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+mod single_instance;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub use single_instance::SecondInstance;
+
+// Self updates for bundled apps
+#[cfg(all(
+    feature = "updater",
+    any(target_os = "windows", target_os = "linux", target_os = "macos")
+))]
+#[cfg_attr(docsrs, doc(cfg(feature = "updater")))]
+pub mod updater;
+
+// File dialogs, the clipboard and notifications
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub mod clipboard;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub mod dialog;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub mod notification;
+
+// Crash reports for panics
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub mod crash_report;
+
+// Declarative menus for the menu bar and tray icon
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub mod menu;
+
 // Public exports
 pub use assets::AssetRequest;
 pub use config::{Config, WindowCloseBehaviour};
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+pub use config::SystemBackends;
 pub use desktop_context::{
     window, DesktopContext, DesktopService, PendingDesktopContext, WeakDesktopContext,
 };
 pub use event_handlers::WryEventHandler;
 pub use hooks::*;
+pub use navigation::BlockedNavigation;
+pub use shared_state::WindowChannel;
 pub use shortcut::{HotKeyState, ShortcutHandle, ShortcutRegistryError};
 pub use wry::RequestAsyncResponder;
@@ -0,0 +1,306 @@
+//! Declarative menus for the window menu bar and the tray icon.
+//!
+//! Menus are built from components in rsx. Each component creates a native menu item when it is mounted, removes it when
+//! it is unmounted, and updates the native item whenever the signals passed to its props change.
+//!
+//! ```rust, no_run
+//! # use dioxus::prelude::*;
+//! use dioxus::desktop::menu::*;
+//!
+//! fn App() -> Element {
+//!     let mut autosave = use_signal(|| true);
+//!     let mut saved = use_signal(|| 0);
+//!
+//!     rsx! {
+//!         WindowMenu {
+//!             Submenu { text: "File",
+//!                 MenuItem {
+//!                     text: "Save",
+//!                     accelerator: "CmdOrCtrl+S",
+//!                     onclick: move |_| saved += 1,
+//!                 }
+//!                 MenuSeparator {}
+//!                 CheckMenuItem {
+//!                     text: "Autosave",
+//!                     checked: autosave,
+//!                     onclick: move |checked| autosave.set(checked),
+//!                 }
+//!             }
+//!         }
+//!         TrayMenu {
+//!             MenuItem { text: "Saved {saved} times", enabled: false }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Items are added to their parent menu in the order they are first rendered. Items that are rendered conditionally after
+//! their parent menu has been created are added to the end of the menu.
+
+use crate::{ipc::UserWindowEvent, trayicon::DioxusTray, use_wry_event_handler, window};
+use dioxus_core::{
+    try_consume_context, use_drop, use_hook, use_hook_with_cleanup, Element, EventHandler, VNode,
+};
+use dioxus_core_macro::*;
+use dioxus_hooks::{use_context, use_context_provider, use_effect};
+use dioxus_signals::{ReadSignal, ReadableExt};
+use muda::{accelerator::Accelerator, CheckMenuItem as NativeCheckMenuItem, IsMenuItem, Menu};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tao::event::Event;
+
+/// Render a menu bar for the current window. This replaces the menu set with [`Config::with_menu`](crate::Config::with_menu).
+///
+/// The previous menu bar is removed while this component is mounted. When the component is unmounted, the window has no menu bar.
+#[component]
+pub fn WindowMenu(children: Element) -> Element {
+    let desktop = use_hook(window);
+    let menu = use_hook_with_cleanup(
+        {
+            let desktop = desktop.clone();
+            move || {
+                let menu = Menu::new();
+                desktop.set_menu(Some(menu.clone()));
+                menu
+            }
+        },
+        move |_| desktop.set_menu(None),
+    );
+    use_context_provider(|| MenuParent::Menu(menu));
+
+    children
+}
+
+/// Render the menu of the tray icon.
+///
+/// If a tray icon was created with [`init_tray_icon`](crate::trayicon::init_tray_icon) in a parent component, this menu
+/// replaces its menu. Otherwise, a tray icon with the default icon is created while this component is mounted.
+///
+/// When the component is unmounted, the menu is removed from the tray icon. A tray icon created by this component is
+/// removed as well.
+#[component]
+pub fn TrayMenu(children: Element) -> Element {
+    let (menu, tray, owns_tray) = use_hook(|| {
+        let menu = Menu::new();
+        match try_consume_context::<DioxusTray>() {
+            Some(tray) => {
+                tray.set_menu(Some(Box::new(menu.clone())));
+                (menu, tray, false)
+            }
+            None => {
+                let tray = crate::trayicon::init_tray_icon(menu.clone(), None);
+                (menu, tray, true)
+            }
+        }
+    });
+    use_context_provider(|| MenuParent::Menu(menu));
+    use_drop(move || {
+        tray.set_menu(None);
+        if owns_tray {
+            _ = tray.set_visible(false);
+        }
+    });
+
+    children
+}
+
+/// A submenu that contains other menu items.
+#[component]
+pub fn Submenu(
+    /// The text of the submenu.
+    text: ReadSignal<String>,
+    /// Whether the submenu can be opened.
+    #[props(default = true)]
+    enabled: ReadSignal<bool>,
+    children: Element,
+) -> Element {
+    let submenu = use_menu_item(|id| muda::Submenu::with_id(id, &*text.peek(), *enabled.peek()));
+
+    use_effect({
+        let submenu = submenu.clone();
+        move || submenu.set_text(&*text.read())
+    });
+    use_effect({
+        let submenu = submenu.clone();
+        move || submenu.set_enabled(enabled())
+    });
+    use_context_provider(|| MenuParent::Submenu(submenu));
+
+    children
+}
+
+/// A menu item that runs `onclick` when it is selected.
+#[component]
+pub fn MenuItem(
+    /// The text of the menu item.
+    text: ReadSignal<String>,
+    /// Whether the menu item can be selected.
+    #[props(default = true)]
+    enabled: ReadSignal<bool>,
+    /// The keyboard shortcut for the menu item, like `"CmdOrCtrl+S"`.
+    #[props(default)]
+    accelerator: ReadSignal<Option<String>>,
+    /// Called when the menu item is selected.
+    #[props(default)]
+    onclick: EventHandler,
+) -> Element {
+    let item = use_menu_item(|id| {
+        muda::MenuItem::with_id(
+            id,
+            &*text.peek(),
+            *enabled.peek(),
+            parse_accelerator(accelerator.peek().as_deref()),
+        )
+    });
+
+    use_effect({
+        let item = item.clone();
+        move || item.set_text(&*text.read())
+    });
+    use_effect({
+        let item = item.clone();
+        move || item.set_enabled(enabled())
+    });
+    use_effect({
+        let item = item.clone();
+        move || {
+            _ = item.set_accelerator(parse_accelerator(accelerator.read().as_deref()));
+        }
+    });
+    use_menu_event(item.id().clone(), move || onclick.call(()));
+
+    VNode::empty()
+}
+
+/// A menu item with a check mark.
+///
+/// The check mark always reflects the `checked` prop. Selecting the item calls `onclick` with the new checked state, but the
+/// check mark only changes once you update the signal passed to `checked`.
+#[component]
+pub fn CheckMenuItem(
+    /// The text of the menu item.
+    text: ReadSignal<String>,
+    /// Whether the menu item is checked.
+    checked: ReadSignal<bool>,
+    /// Whether the menu item can be selected.
+    #[props(default = true)]
+    enabled: ReadSignal<bool>,
+    /// The keyboard shortcut for the menu item, like `"CmdOrCtrl+S"`.
+    #[props(default)]
+    accelerator: ReadSignal<Option<String>>,
+    /// Called with the new checked state when the menu item is selected.
+    #[props(default)]
+    onclick: EventHandler<bool>,
+) -> Element {
+    let item = use_menu_item(|id| {
+        NativeCheckMenuItem::with_id(
+            id,
+            &*text.peek(),
+            *enabled.peek(),
+            *checked.peek(),
+            parse_accelerator(accelerator.peek().as_deref()),
+        )
+    });
+
+    use_effect({
+        let item = item.clone();
+        move || item.set_text(&*text.read())
+    });
+    use_effect({
+        let item = item.clone();
+        move || item.set_enabled(enabled())
+    });
+    use_effect({
+        let item = item.clone();
+        move || item.set_checked(checked())
+    });
+    use_effect({
+        let item = item.clone();
+        move || {
+            _ = item.set_accelerator(parse_accelerator(accelerator.read().as_deref()));
+        }
+    });
+    use_menu_event(item.id().clone(), {
+        let item = item.clone();
+        move || {
+            // The native menu toggles the check mark on its own. Reset it so the signal stays the source of truth
+            item.set_checked(*checked.peek());
+            onclick.call(!*checked.peek());
+        }
+    });
+
+    VNode::empty()
+}
+
+/// A separator between menu items.
+#[component]
+pub fn MenuSeparator() -> Element {
+    use_menu_item(|_| muda::PredefinedMenuItem::separator());
+
+    VNode::empty()
+}
+
+/// The native menu that items rendered below it are added to
+#[derive(Clone)]
+enum MenuParent {
+    Menu(Menu),
+    Submenu(muda::Submenu),
+}
+
+impl MenuParent {
+    fn append(&self, item: &dyn IsMenuItem) {
+        let result = match self {
+            MenuParent::Menu(menu) => menu.append(item),
+            MenuParent::Submenu(submenu) => submenu.append(item),
+        };
+        if let Err(err) = result {
+            tracing::error!("Failed to add menu item: {err}");
+        }
+    }
+
+    fn remove(&self, item: &dyn IsMenuItem) {
+        _ = match self {
+            MenuParent::Menu(menu) => menu.remove(item),
+            MenuParent::Submenu(submenu) => submenu.remove(item),
+        };
+    }
+}
+
+/// Create a native menu item with a unique id, add it to the parent menu, and remove it when the component is unmounted
+fn use_menu_item<I: IsMenuItem + Clone + 'static>(create: impl FnOnce(String) -> I) -> I {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    let parent = use_context::<MenuParent>();
+    use_hook_with_cleanup(
+        || {
+            let id = format!(
+                "dioxus-menu-item-{}",
+                NEXT_ID.fetch_add(1, Ordering::Relaxed)
+            );
+            let item = create(id);
+            parent.append(&item);
+            (parent, item)
+        },
+        |(parent, item)| parent.remove(&item),
+    )
+    .1
+}
+
+/// Run a handler when the menu item with this id is selected in the window menu or the tray menu
+fn use_menu_event(id: muda::MenuId, mut handler: impl FnMut() + 'static) {
+    use_wry_event_handler(move |event, _| match event {
+        Event::UserEvent(UserWindowEvent::MudaMenuEvent(event)) if event.id == id => handler(),
+        Event::UserEvent(UserWindowEvent::TrayMenuEvent(event)) if event.id == id => handler(),
+        _ => {}
+    });
+}
+
+fn parse_accelerator(accelerator: Option<&str>) -> Option<Accelerator> {
+    let accelerator = accelerator?;
+    match accelerator.parse() {
+        Ok(accelerator) => Some(accelerator),
+        Err(err) => {
+            tracing::error!("Invalid menu accelerator {accelerator:?}: {err}");
+            None
+        }
+    }
+}
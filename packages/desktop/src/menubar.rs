@@ -14,6 +14,15 @@ pub fn init_menu_bar(menu: &DioxusMenu, window: &Window) {
     }
 }
 
+/// Removes the menu bar from the window.
+#[allow(unused)]
+pub fn remove_menu_bar(menu: &DioxusMenu, window: &Window) {
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    {
+        desktop_platforms::remove_menu_bar(menu, window);
+    }
+}
+
 /// Creates a standard menu bar depending on the users platform. It may be used as a starting point
 /// to further customize the menu bar and pass it to a [`WindowBuilder`](tao::window::WindowBuilder).
 /// > Note: The default menu bar enables macOS shortcuts like cut/copy/paste.
@@ -54,6 +63,26 @@ mod desktop_platforms {
         }
     }
 
+    #[allow(unused)]
+    pub fn remove_menu_bar(menu: &Menu, window: &Window) {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            use tao::platform::windows::WindowExtWindows;
+            _ = menu.remove_for_hwnd(window.hwnd());
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            use tao::platform::unix::WindowExtUnix;
+            _ = menu.remove_for_gtk_window(window.gtk_window());
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            menu.remove_for_nsapp();
+        }
+    }
+
     pub fn default_menu_bar() -> Menu {
         let menu = Menu::new();
         // since it is uncommon on windows to have an "application menu"
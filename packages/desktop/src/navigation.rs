@@ -0,0 +1,292 @@
+//! Control where the webview of a window can navigate.
+//!
+//! The webview only loads the app by default. Navigations to the origins in
+//! [`Config::with_navigation_allowlist`](crate::Config::with_navigation_allowlist) are allowed, and every other
+//! navigation is blocked and handed to [`Config::with_blocked_navigation_handler`](crate::Config::with_blocked_navigation_handler).
+//! Only the app itself can send messages to rust, even after the webview navigated to an allowed origin.
+
+use dioxus_core::{ScopeId, VirtualDom};
+use std::{cell::Cell, rc::Rc};
+use wry::http::Uri;
+
+/// A navigation the webview of a window was not allowed to make
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedNavigation {
+    pub(crate) url: String,
+    pub(crate) new_window: bool,
+}
+
+impl BlockedNavigation {
+    /// The url the webview tried to load
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether the page tried to open the url in a new window, for example with a link with `target="_blank"`
+    pub fn is_new_window(&self) -> bool {
+        self.new_window
+    }
+
+    /// Open the url in the default browser of the user if it is a web or `mailto:` link.
+    ///
+    /// This is what happens to blocked navigations if no handler is set. Returns `false` if the url has a different
+    /// scheme or the browser could not be opened.
+    pub fn open_in_browser(&self) -> bool {
+        let is_link = ["http://", "https://", "mailto:"]
+            .iter()
+            .any(|scheme| self.url.starts_with(scheme));
+        is_link && webbrowser::open(&self.url).is_ok()
+    }
+}
+
+pub(crate) type BlockedNavigationHandler = Rc<dyn Fn(BlockedNavigation)>;
+
+/// Check if a url belongs to the app, which is served by the `dioxus` protocol from `dioxus://index.html`
+pub(crate) fn is_app_url(url: &str) -> bool {
+    let Ok(uri) = url.parse::<Uri>() else {
+        return false;
+    };
+    let (Some(scheme), Some(host)) = (uri.scheme_str(), uri.host()) else {
+        return false;
+    };
+    if uri.port().is_some() {
+        return false;
+    }
+
+    // Windows and android serve custom protocols from http(s)://<protocol>.<host>
+    #[cfg(any(windows, target_os = "android"))]
+    let is_app = (scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+        && host.eq_ignore_ascii_case("dioxus.index.html");
+    #[cfg(not(any(windows, target_os = "android")))]
+    let is_app = scheme.eq_ignore_ascii_case("dioxus") && host.eq_ignore_ascii_case("index.html");
+
+    is_app
+}
+
+/// The origins the webview of a window can navigate to and what happens with the navigations that are blocked
+#[derive(Clone, Default)]
+pub(crate) struct NavigationPolicy {
+    allowed_origins: Rc<Vec<Origin>>,
+    on_blocked: Option<BlockedNavigationHandler>,
+    // Whether the webview is showing the app instead of one of the allowed origins
+    showing_app: Rc<Cell<bool>>,
+}
+
+impl NavigationPolicy {
+    pub(crate) fn new(allowlist: &[String], on_blocked: Option<BlockedNavigationHandler>) -> Self {
+        let allowed_origins = allowlist
+            .iter()
+            .filter_map(|origin| {
+                let parsed = Origin::parse(origin);
+                if parsed.is_none() {
+                    tracing::warn!("Ignoring {origin:?} in the navigation allowlist because it is not an origin like \"https://example.com\"");
+                }
+                parsed
+            })
+            .collect();
+
+        Self {
+            allowed_origins: Rc::new(allowed_origins),
+            on_blocked,
+            showing_app: Default::default(),
+        }
+    }
+
+    /// Check if the url is on one of the allowed origins
+    pub(crate) fn allows(&self, url: &str) -> bool {
+        Origin::parse(url).is_some_and(|origin| self.allowed_origins.contains(&origin))
+    }
+
+    /// Check if the webview can navigate to a url.
+    ///
+    /// The app is loaded once and then updated with edits, so loading it again while it is showing would reset the page.
+    /// The webview can go back to the app after it navigated to an allowed origin.
+    pub(crate) fn allows_navigation(&self, url: &str) -> bool {
+        if is_app_url(url) {
+            return !self.showing_app.replace(true);
+        }
+
+        let allowed = self.allows(url);
+        if allowed {
+            self.showing_app.set(false);
+        }
+        allowed
+    }
+
+    /// Run the handler for a blocked navigation in the window, or open it in the browser if there is no handler
+    pub(crate) fn handle_blocked(&self, dom: &VirtualDom, navigation: BlockedNavigation) {
+        match &self.on_blocked {
+            Some(on_blocked) => dom.in_scope(ScopeId::ROOT, || on_blocked(navigation)),
+            None => {
+                if !navigation.open_in_browser() {
+                    tracing::debug!("Blocked navigation to {}", navigation.url);
+                }
+            }
+        }
+    }
+}
+
+/// The scheme, host and port of a url
+#[derive(Debug, PartialEq, Eq)]
+struct Origin {
+    scheme: String,
+    host: String,
+    port: Option<u16>,
+}
+
+impl Origin {
+    fn parse(url: &str) -> Option<Self> {
+        let uri: Uri = url.parse().ok()?;
+        let scheme = uri.scheme_str()?.to_ascii_lowercase();
+        let authority = uri.authority()?;
+        let default_port = match scheme.as_str() {
+            "http" | "ws" => Some(80),
+            "https" | "wss" => Some(443),
+            _ => None,
+        };
+
+        Some(Self {
+            host: authority.host().to_ascii_lowercase(),
+            port: authority.port_u16().or(default_port),
+            scheme,
+        })
+    }
+}
+
+/// Create a random nonce that marks the inline scripts of the app as trusted
+pub(crate) fn create_nonce() -> String {
+    use rand::{RngCore, SeedableRng};
+
+    let mut nonce = [0u8; 16];
+    rand::rngs::StdRng::from_os_rng().fill_bytes(&mut nonce);
+    base64::Engine::encode(&base64::engine::general_purpose::STANDARD, nonce)
+}
+
+/// Add the sources the app needs to a content security policy.
+///
+/// The app boots with inline scripts, which are allowed with a nonce so inline scripts injected into the page are still
+/// blocked. It also streams edits over a websocket on localhost and sends events back over the `dioxus` protocol.
+pub(crate) fn content_security_policy(policy: &str, nonce: &str, connect_sources: &str) -> String {
+    let directives: Vec<&str> = policy
+        .split(';')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .collect();
+    let find = |name: &str| {
+        directives.iter().find(|directive| {
+            directive
+                .split_whitespace()
+                .next()
+                .is_some_and(|directive| directive.eq_ignore_ascii_case(name))
+        })
+    };
+
+    // Directives fall back to default-src if they are not set, so extend a copy of it. 'none' can't be combined with
+    // other sources, so it is dropped.
+    let extend = |name: &str, source: &str, out: &mut Vec<String>| {
+        let Some(directive) = find(name).or_else(|| find("default-src")) else {
+            return;
+        };
+        let sources = directive
+            .split_whitespace()
+            .skip(1)
+            .filter(|source| *source != "'none'");
+        let directive = std::iter::once(name)
+            .chain(sources)
+            .chain(std::iter::once(source));
+        out.push(directive.collect::<Vec<_>>().join(" "));
+    };
+
+    let mut extended = Vec::new();
+    extend("script-src", &format!("'nonce-{nonce}'"), &mut extended);
+    extend("connect-src", connect_sources, &mut extended);
+
+    let mut policy: Vec<String> = directives
+        .iter()
+        .filter(|directive| {
+            let name = directive.split_whitespace().next().unwrap_or_default();
+            !name.eq_ignore_ascii_case("script-src") && !name.eq_ignore_ascii_case("connect-src")
+        })
+        .map(|directive| directive.to_string())
+        .collect();
+    policy.extend(extended);
+    policy.join("; ")
+}
+
+#[test]
+fn test_origins_match_with_default_ports() {
+    let policy = NavigationPolicy::new(
+        &[
+            "https://Example.com".to_string(),
+            "not an origin".to_string(),
+        ],
+        None,
+    );
+    assert!(policy.allows("https://example.com/docs?page=1"));
+    assert!(policy.allows("https://example.com:443/"));
+    assert!(!policy.allows("http://example.com/"));
+    assert!(!policy.allows("https://example.com.evil.com/"));
+    assert!(!policy.allows("mailto:hello@example.com"));
+}
+
+#[cfg(test)]
+#[cfg(any(windows, target_os = "android"))]
+const APP_URL: &str = "http://dioxus.index.html/";
+
+#[cfg(test)]
+#[cfg(not(any(windows, target_os = "android")))]
+const APP_URL: &str = "dioxus://index.html/";
+
+#[test]
+fn test_only_the_app_is_an_app_url() {
+    assert!(is_app_url(APP_URL));
+    assert!(is_app_url(&format!("{APP_URL}__events")));
+
+    assert!(!is_app_url("https://dioxus.evil.com/"));
+    assert!(!is_app_url("http://dioxus.index.html.evil.com/"));
+    assert!(!is_app_url("dioxus.example.com"));
+    assert!(!is_app_url("dioxus://evil.com/"));
+    assert!(!is_app_url("https://index.html/"));
+    assert!(!is_app_url("not a url"));
+
+    #[cfg(not(any(windows, target_os = "android")))]
+    assert!(!is_app_url("https://dioxus.index.html/"));
+}
+
+#[test]
+fn test_navigating_back_to_the_app() {
+    let policy = NavigationPolicy::new(&["https://example.com".to_string()], None);
+
+    // The app loads once and can't be reloaded while it is showing
+    assert!(policy.allows_navigation(APP_URL));
+    assert!(!policy.allows_navigation(APP_URL));
+    assert!(!policy.allows_navigation("https://evil.com/"));
+    assert!(!policy.allows_navigation("https://dioxus.evil.com/"));
+
+    // After navigating to an allowed origin the webview can go back to the app
+    assert!(policy.allows_navigation("https://example.com/docs"));
+    assert!(policy.allows_navigation(APP_URL));
+    assert!(!policy.allows_navigation(APP_URL));
+}
+
+#[test]
+fn test_csp_allows_the_app_scripts() {
+    let policy = content_security_policy(
+        "default-src 'self'; img-src *",
+        "abc",
+        "ws://127.0.0.1:1234",
+    );
+    assert_eq!(
+        policy,
+        "default-src 'self'; img-src *; script-src 'self' 'nonce-abc'; connect-src 'self' ws://127.0.0.1:1234"
+    );
+
+    let policy = content_security_policy("script-src 'self'", "abc", "ws://127.0.0.1:1234");
+    assert_eq!(policy, "script-src 'self' 'nonce-abc'");
+
+    let policy = content_security_policy("default-src 'none'", "abc", "ws://127.0.0.1:1234");
+    assert_eq!(
+        policy,
+        "default-src 'none'; script-src 'nonce-abc'; connect-src ws://127.0.0.1:1234"
+    );
+}
@@ -0,0 +1,266 @@
+//! Desktop notifications with actions.
+//!
+//! Show a notification with [`show_notification`] or
+//! [`DesktopService::show_notification`](crate::DesktopService::show_notification). The notifications are shown by a
+//! [`NotificationBackend`], which you can replace with
+//! [`Config::with_notification_backend`](crate::Config::with_notification_backend) or
+//! [`SystemBackends`](crate::SystemBackends) to answer notifications in tests.
+
+use std::{future::Future, pin::Pin, rc::Rc};
+
+/// The future returned by a [`NotificationBackend`]
+pub type NotificationFuture =
+    Pin<Box<dyn Future<Output = Result<NotificationResponse, NotificationError>>>>;
+
+/// Shows the notifications of the app.
+///
+/// The default backend shows the notifications of the OS.
+///
+/// ```rust, no_run
+/// use dioxus::desktop::notification::{
+///     Notification, NotificationBackend, NotificationFuture, NotificationResponse,
+/// };
+///
+/// /// Picks the first action of every notification
+/// struct PickFirstAction;
+///
+/// impl NotificationBackend for PickFirstAction {
+///     fn show(&self, notification: &Notification) -> NotificationFuture {
+///         let response = match notification.actions().first() {
+///             Some(action) => NotificationResponse::Action(action.id().to_string()),
+///             None => NotificationResponse::Dismissed,
+///         };
+///         Box::pin(async move { Ok(response) })
+///     }
+/// }
+/// ```
+pub trait NotificationBackend {
+    /// Show a notification and wait for the user to respond to it
+    fn show(&self, notification: &Notification) -> NotificationFuture;
+}
+
+/// Show a notification with the notification backend of the current window and wait for the user to respond to it.
+///
+/// The callback of the action the user picked runs before this returns.
+pub async fn show_notification(
+    notification: Notification,
+) -> Result<NotificationResponse, NotificationError> {
+    let backend = crate::SystemBackends::current().notification;
+    let response = backend.show(&notification).await?;
+    notification.respond(&response);
+    Ok(response)
+}
+
+/// How the user responded to a notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotificationResponse {
+    /// The user clicked the notification
+    Clicked,
+
+    /// The user picked the action with this id
+    Action(String),
+
+    /// The notification was closed without a response
+    Dismissed,
+}
+
+/// An error that occurred while showing a notification
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum NotificationError {
+    /// The OS failed to show the notification
+    #[error("Failed to show the notification: {0}")]
+    Failed(String),
+}
+
+/// A notification to show to the user
+///
+/// ```rust, no_run
+/// # use dioxus::desktop::notification::Notification;
+/// let notification = Notification::new("Download finished")
+///     .with_body("report.pdf was saved to your downloads")
+///     .with_action("open", "Open", || println!("Opening the file"))
+///     .on_click(|| println!("The notification was clicked"));
+/// ```
+#[derive(Clone, Default)]
+pub struct Notification {
+    title: String,
+    body: Option<String>,
+    icon: Option<String>,
+    actions: Vec<NotificationAction>,
+    on_click: Option<Rc<dyn Fn()>>,
+}
+
+impl std::fmt::Debug for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Notification")
+            .field("title", &self.title)
+            .field("body", &self.body)
+            .field("icon", &self.icon)
+            .field("actions", &self.actions)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Notification {
+    /// Create a notification with a title
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the text below the title
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Set the icon of the notification. This is the name of an icon from the icon theme or the path to an image.
+    ///
+    /// Only linux shows custom icons. Other platforms show the icon of the app.
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Add a button to the notification. The callback runs when the user picks it.
+    ///
+    /// Some platforms only show the first action or none at all, so don't rely on actions for anything important.
+    pub fn with_action(
+        mut self,
+        id: impl Into<String>,
+        label: impl Into<String>,
+        callback: impl Fn() + 'static,
+    ) -> Self {
+        self.actions.push(NotificationAction {
+            id: id.into(),
+            label: label.into(),
+            callback: Rc::new(callback),
+        });
+        self
+    }
+
+    /// Run a callback when the user clicks the notification
+    pub fn on_click(mut self, callback: impl Fn() + 'static) -> Self {
+        self.on_click = Some(Rc::new(callback));
+        self
+    }
+
+    /// The title of the notification
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The text below the title
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    /// The icon of the notification
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    /// The buttons of the notification
+    pub fn actions(&self) -> &[NotificationAction] {
+        &self.actions
+    }
+
+    /// Run the callback that belongs to the response
+    pub(crate) fn respond(&self, response: &NotificationResponse) {
+        match response {
+            NotificationResponse::Clicked => {
+                if let Some(on_click) = &self.on_click {
+                    on_click();
+                }
+            }
+            NotificationResponse::Action(id) => {
+                if let Some(action) = self.actions.iter().find(|action| &action.id == id) {
+                    (action.callback)();
+                }
+            }
+            NotificationResponse::Dismissed => {}
+        }
+    }
+}
+
+/// A button on a [`Notification`]
+#[derive(Clone)]
+pub struct NotificationAction {
+    id: String,
+    label: String,
+    callback: Rc<dyn Fn()>,
+}
+
+impl std::fmt::Debug for NotificationAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NotificationAction")
+            .field("id", &self.id)
+            .field("label", &self.label)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NotificationAction {
+    /// The id the response uses for this action
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The text on the button
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// Shows the notifications of the OS
+pub(crate) struct SystemNotifications;
+
+impl NotificationBackend for SystemNotifications {
+    fn show(&self, notification: &Notification) -> NotificationFuture {
+        let mut native = notify_rust::Notification::new();
+        if let Some(app_name) = dioxus_cli_config::app_title() {
+            native.appname(&app_name);
+        }
+        native.summary(&notification.title);
+        if let Some(body) = &notification.body {
+            native.body(body);
+        }
+        if let Some(icon) = &notification.icon {
+            native.icon(icon);
+        }
+
+        // Linux only reports clicks on the notification if it has a default action
+        native.action("default", "");
+        for action in &notification.actions {
+            native.action(&action.id, &action.label);
+        }
+
+        // Waiting for the response blocks, so we wait on another thread
+        let (tx, rx) = futures_channel::oneshot::channel();
+        std::thread::spawn(move || {
+            let response = native.show().map(|handle| {
+                let mut response = NotificationResponse::Dismissed;
+                handle.wait_for_action(|action| {
+                    response = match action {
+                        "default" => NotificationResponse::Clicked,
+                        "__closed" => NotificationResponse::Dismissed,
+                        action => NotificationResponse::Action(action.to_string()),
+                    }
+                });
+                response
+            });
+            _ = tx.send(response.map_err(|err| NotificationError::Failed(err.to_string())));
+        });
+
+        Box::pin(async move {
+            rx.await.unwrap_or_else(|_| {
+                Err(NotificationError::Failed(
+                    "The notification thread panicked".to_string(),
+                ))
+            })
+        })
+    }
+}
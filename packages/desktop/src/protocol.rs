@@ -1,4 +1,5 @@
 use crate::document::NATIVE_EVAL_JS;
+use crate::navigation::create_nonce;
 use crate::{assets::*, webview::WebviewEdits};
 use dioxus_interpreter_js::unified_bindings::SLEDGEHAMMER_JS;
 use dioxus_interpreter_js::NATIVE_JS;
@@ -37,6 +38,7 @@ pub(super) fn desktop_handler(
     custom_index: Option<String>,
     root_name: &str,
     headless: bool,
+    content_security_policy: Option<&str>,
 ) {
     // Try to serve the index file first
     if let Some(index_bytes) = index_request(
@@ -46,6 +48,7 @@ pub(super) fn desktop_handler(
         root_name,
         headless,
         edit_state,
+        content_security_policy,
     ) {
         return responder.respond(index_bytes);
     }
@@ -66,15 +69,52 @@ pub(super) fn desktop_handler(
         }
     }
 
-    match dioxus_asset_resolver::native::serve_asset(request.uri().path()) {
-        Ok(res) => responder.respond(res),
-        Err(_e) => responder.respond(
-            Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .body(String::from("Failed to serve asset").into_bytes())
-                .unwrap(),
-        ),
+    // Read the asset on another thread so large files don't block the event loop
+    spawn_blocking(
+        move || match dioxus_asset_resolver::native::serve_asset_request(&request) {
+            Ok(res) => responder.respond(res),
+            Err(_e) => responder.respond(
+                Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(String::from("Failed to serve asset").into_bytes())
+                    .unwrap(),
+            ),
+        },
+    );
+}
+
+/// Run blocking work like reading assets off the event loop.
+///
+/// We use the tokio blocking pool if the app runs inside tokio. Otherwise the work is queued on a few threads that are
+/// shared by every request, so a page that loads many assets at once doesn't spawn a thread for each of them.
+pub(crate) fn spawn_blocking(work: impl FnOnce() + Send + 'static) {
+    use std::sync::{mpsc, Arc, Mutex, OnceLock};
+
+    type Work = Box<dyn FnOnce() + Send>;
+    const WORKERS: usize = 4;
+    static POOL: OnceLock<mpsc::Sender<Work>> = OnceLock::new();
+
+    #[cfg(feature = "tokio_runtime")]
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        handle.spawn_blocking(work);
+        return;
     }
+
+    let pool = POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Work>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..WORKERS {
+            let receiver = receiver.clone();
+            std::thread::spawn(move || loop {
+                let Ok(work) = receiver.lock().unwrap().recv() else {
+                    return;
+                };
+                work();
+            });
+        }
+        sender
+    });
+    _ = pool.send(Box::new(work));
 }
 
 /// Build the index.html file we use for bootstrapping a new app
@@ -93,6 +133,7 @@ fn index_request(
     root_name: &str,
     headless: bool,
     edit_state: &WebviewEdits,
+    content_security_policy: Option<&str>,
 ) -> Option<Response<Vec<u8>>> {
     // If the request is for the root, we'll serve the index.html file.
     if request.uri().path() != "/" {
@@ -117,11 +158,33 @@ fn index_request(
         &module_loader(root_name, headless, edit_state),
     );
 
-    Response::builder()
+    let mut response = Response::builder()
         .header("Content-Type", "text/html")
-        .header("Access-Control-Allow-Origin", "*")
-        .body(index.into())
-        .ok()
+        .header("Access-Control-Allow-Origin", "*");
+
+    if let Some(policy) = content_security_policy {
+        // Only the scripts in the index are trusted. Scripts injected into the page later don't have the nonce.
+        let nonce = create_nonce();
+        index = index.replace("<script", &format!("<script nonce=\"{nonce}\""));
+
+        let edits_path = edit_state.wry_queue.edits_path();
+        let edits_origin = edits_path
+            .splitn(4, '/')
+            .take(3)
+            .collect::<Vec<_>>()
+            .join("/");
+        let events_origin = EVENTS_PATH.trim_end_matches("/__events");
+        response = response.header(
+            "Content-Security-Policy",
+            crate::navigation::content_security_policy(
+                policy,
+                &nonce,
+                &format!("{edits_origin} {events_origin}"),
+            ),
+        );
+    }
+
+    response.body(index.into()).ok()
 }
 
 /// Construct the inline script that boots up the page and bridges the webview with rust code.
@@ -137,6 +200,12 @@ fn module_loader(root_id: &str, headless: bool, edit_state: &WebviewEdits) -> St
     let edits_path = edit_state.wry_queue.edits_path();
     let expected_key = edit_state.wry_queue.required_server_key();
 
+    // Keep the console output of the page for crash reports
+    #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+    let forward_console = crate::crash_report::capturing_logs();
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    let forward_console = false;
+
     format!(
         r#"
 <script type="module">
@@ -149,6 +218,25 @@ fn module_loader(root_id: &str, headless: bool, edit_state: &WebviewEdits) -> St
     // The native interpreter extends the sledgehammer interpreter with a few extra methods that we use for IPC
     window.interpreter = new NativeInterpreter("{EVENTS_PATH}", {headless});
 
+    // Forward the console to rust with the log message the web renderer sends to the devserver
+    if ({forward_console}) {{
+        for (const level of ["debug", "log", "info", "warn", "error"]) {{
+            const original = console[level];
+            console[level] = function (...args) {{
+                original.apply(console, args);
+                const messages = args.map((arg) => {{
+                    if (typeof arg === "string") return arg;
+                    try {{
+                        return JSON.stringify(arg) ?? String(arg);
+                    }} catch {{
+                        return String(arg);
+                    }}
+                }});
+                window.ipc.postMessage(window.interpreter.serializeIpcMessage("log", {{ Log: {{ level, messages }} }}));
+            }};
+        }}
+    }}
+
     // Wait for the page to load before sending the initialize message
     window.onload = function() {{
         let root_element = window.document.getElementById("{root_id}");
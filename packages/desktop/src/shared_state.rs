@@ -0,0 +1,296 @@
+//! State and messages shared between the windows of the app.
+//!
+//! Every window runs its own [`VirtualDom`](dioxus_core::VirtualDom), so context provided in one window is not visible
+//! in the others. The app context lives outside of the windows instead. Signals stored in it are owned by the app, so
+//! they stay alive when the window that created them closes and writing to them reruns the components that read them
+//! in every window.
+
+use dioxus_core::with_owner;
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_util::StreamExt;
+use generational_box::{Owner, UnsyncStorage};
+use rustc_hash::FxHashMap;
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    hash::Hash,
+    rc::Rc,
+};
+use tao::window::WindowId;
+
+/// The app context and channels shared by every window
+#[derive(Default)]
+pub(crate) struct SharedState {
+    // Values created while providing app context are owned by the app instead of the window that provided them
+    owner: Owner<UnsyncStorage>,
+    contexts: RefCell<FxHashMap<TypeId, Box<dyn Any>>>,
+    channels: RefCell<FxHashMap<TypeId, Box<dyn Any>>>,
+}
+
+impl SharedState {
+    pub(crate) fn get<T: Clone + 'static>(&self) -> Option<T> {
+        self.contexts
+            .borrow()
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    }
+
+    pub(crate) fn get_or_insert_with<T: Clone + 'static>(&self, init: impl FnOnce() -> T) -> T {
+        if let Some(value) = self.get::<T>() {
+            return value;
+        }
+
+        // Don't hold the borrow while running init, it might read other app context
+        let value = self.with_owner(init);
+        self.insert(value.clone());
+        value
+    }
+
+    pub(crate) fn provide<T: Clone + 'static>(&self, init: impl FnOnce() -> T) -> T {
+        let value = self.with_owner(init);
+        self.insert(value.clone());
+        value
+    }
+
+    fn insert<T: Clone + 'static>(&self, value: T) {
+        self.contexts
+            .borrow_mut()
+            .insert(TypeId::of::<T>(), Box::new(value));
+    }
+
+    pub(crate) fn remove<T: 'static>(&self) -> Option<T> {
+        let value = self.contexts.borrow_mut().remove(&TypeId::of::<T>())?;
+        value.downcast().ok().map(|value| *value)
+    }
+
+    /// Run a closure with the app as the owner of any signals it creates
+    fn with_owner<O>(&self, f: impl FnOnce() -> O) -> O {
+        with_owner(self.owner.clone(), f)
+    }
+
+    // The channels are generic over the id of the windows so they can be tested without opening windows
+
+    fn subscribe<W: WindowKey, M: 'static>(&self, window: W) -> UnboundedReceiver<(W, M)> {
+        let (tx, rx) = unbounded();
+        self.channels
+            .borrow_mut()
+            .entry(TypeId::of::<(W, M)>())
+            .or_insert_with(|| Box::new(ChannelSubscribers::<W, M>::default()))
+            .downcast_mut::<ChannelSubscribers<W, M>>()
+            .expect("channels are keyed by the type of their messages")
+            .entry(window)
+            .or_default()
+            .push(tx);
+        rx
+    }
+
+    /// Send a message to the first receiver of a window. Returns `false` if no receiver in the window is open.
+    fn send<W: WindowKey, M: 'static>(&self, from: W, to: W, message: M) -> bool {
+        let mut message = Some(message);
+        self.for_each_subscriber::<W, M>(|window, sender| {
+            if *window != to {
+                return false;
+            }
+            let Some(msg) = message.take() else {
+                return false;
+            };
+            match sender.unbounded_send((from, msg)) {
+                Ok(()) => true,
+                Err(err) => {
+                    message = Some(err.into_inner().1);
+                    false
+                }
+            }
+        })
+    }
+
+    /// Send a message to every window except the sender. Returns `false` if no other window received it.
+    fn broadcast<W: WindowKey, M: Clone + 'static>(&self, from: W, message: M) -> bool {
+        self.for_each_subscriber::<W, M>(|window, sender| {
+            *window != from && sender.unbounded_send((from, message.clone())).is_ok()
+        })
+    }
+
+    /// Run a closure for each window that listens for messages of this type. Receivers that were dropped are removed.
+    fn for_each_subscriber<W: WindowKey, M: 'static>(
+        &self,
+        mut f: impl FnMut(&W, &UnboundedSender<(W, M)>) -> bool,
+    ) -> bool {
+        let mut channels = self.channels.borrow_mut();
+        let Some(subscribers) = channels
+            .get_mut(&TypeId::of::<(W, M)>())
+            .and_then(|subscribers| subscribers.downcast_mut::<ChannelSubscribers<W, M>>())
+        else {
+            return false;
+        };
+
+        let mut delivered = false;
+        subscribers.retain(|window, senders| {
+            senders.retain(|sender| !sender.is_closed());
+            for sender in senders.iter() {
+                delivered |= f(window, sender);
+            }
+            !senders.is_empty()
+        });
+        delivered
+    }
+}
+
+trait WindowKey: Copy + Eq + Hash + 'static {}
+
+impl<W: Copy + Eq + Hash + 'static> WindowKey for W {}
+
+type ChannelSubscribers<W, M> = FxHashMap<W, Vec<UnboundedSender<(W, M)>>>;
+
+/// A typed channel that windows use to send messages to each other.
+///
+/// Get one with [`use_window_channel`](crate::use_window_channel). Each window only receives messages of the type the
+/// channel was created with.
+pub struct WindowChannel<M: 'static> {
+    window: WindowId,
+    shared: Rc<crate::app::SharedContext>,
+    receiver: Rc<RefCell<UnboundedReceiver<(WindowId, M)>>>,
+}
+
+impl<M: 'static> Clone for WindowChannel<M> {
+    fn clone(&self) -> Self {
+        Self {
+            window: self.window,
+            shared: self.shared.clone(),
+            receiver: self.receiver.clone(),
+        }
+    }
+}
+
+impl<M: 'static> WindowChannel<M> {
+    pub(crate) fn new(window: WindowId, shared: Rc<crate::app::SharedContext>) -> Self {
+        let receiver = shared.state.subscribe::<WindowId, M>(window);
+        Self {
+            window,
+            shared,
+            receiver: Rc::new(RefCell::new(receiver)),
+        }
+    }
+
+    /// The id of the window this channel receives messages for.
+    pub fn window_id(&self) -> WindowId {
+        self.window
+    }
+
+    /// Send a message to a window. If several components in the window listen for messages of this type, the first
+    /// one that subscribed receives it.
+    ///
+    /// Returns `false` if the window doesn't listen for messages of this type or is closed.
+    pub fn send(&self, to: WindowId, message: M) -> bool {
+        self.shared.state.send(self.window, to, message)
+    }
+
+    /// Wait for the next message sent to this window. Returns the id of the window that sent it along with the message.
+    pub async fn recv(&self) -> Option<(WindowId, M)> {
+        std::future::poll_fn(|cx| self.receiver.borrow_mut().poll_next_unpin(cx)).await
+    }
+}
+
+impl<M: Clone + 'static> WindowChannel<M> {
+    /// Send a message to every other window that listens for messages of this type.
+    ///
+    /// Returns `false` if no other window received the message.
+    pub fn broadcast(&self, message: M) -> bool {
+        self.shared.state.broadcast(self.window, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus::prelude::*;
+    use dioxus_core::Mutation;
+
+    #[derive(Clone, Copy)]
+    struct Count(Signal<i32>);
+
+    /// A window that reads the shared signal the way `use_shared_signal` does
+    #[component]
+    fn Window() -> Element {
+        let state = consume_context::<Rc<SharedState>>();
+        let count = use_hook(|| state.get_or_insert_with(|| Count(Signal::new(0))));
+        rsx! { "{count.0}" }
+    }
+
+    fn open_window(state: &Rc<SharedState>) -> VirtualDom {
+        let mut dom = VirtualDom::new(Window).with_root_context(state.clone());
+        dom.rebuild_in_place();
+        dom
+    }
+
+    #[test]
+    fn writes_in_one_window_rerun_the_others() {
+        let state = Rc::new(SharedState::default());
+        let mut first = open_window(&state);
+        let mut second = open_window(&state);
+
+        let Count(mut count) = state.get::<Count>().unwrap();
+        first.in_runtime(|| count.set(1));
+
+        for window in [&mut first, &mut second] {
+            window.process_events();
+            let edits = window.render_immediate_to_vec().edits;
+            assert!(matches!(&edits[..], [Mutation::SetText { value, .. }] if value == "1"));
+        }
+
+        // The signal belongs to the app, so it outlives the window that created it
+        drop(first);
+        assert_eq!(*count.peek(), 1);
+        count.set(2);
+        second.process_events();
+        let edits = second.render_immediate_to_vec().edits;
+        assert!(matches!(&edits[..], [Mutation::SetText { value, .. }] if value == "2"));
+    }
+
+    // Windows in the channel tests are identified by numbers, since tao can't create distinct window ids without an event loop
+    const FIRST: u32 = 1;
+    const SECOND: u32 = 2;
+
+    #[test]
+    fn messages_are_delivered_to_the_right_window() {
+        let state = SharedState::default();
+        let mut first = state.subscribe::<u32, &str>(FIRST);
+        let mut second = state.subscribe::<u32, &str>(SECOND);
+        let mut numbers = state.subscribe::<u32, i32>(SECOND);
+
+        assert!(state.send(FIRST, SECOND, "hello"));
+        assert_eq!(second.try_next().unwrap(), Some((FIRST, "hello")));
+        assert!(first.try_next().is_err());
+        assert!(numbers.try_next().is_err());
+
+        assert!(state.broadcast(SECOND, "everyone"));
+        assert_eq!(first.try_next().unwrap(), Some((SECOND, "everyone")));
+        assert!(second.try_next().is_err());
+
+        assert!(!state.send(FIRST, 3, "nobody"));
+        assert!(!state.send(FIRST, SECOND, 7u8));
+    }
+
+    #[test]
+    fn closed_windows_unsubscribe() {
+        let state = SharedState::default();
+        let first = state.subscribe::<u32, &str>(FIRST);
+        let second = state.subscribe::<u32, &str>(SECOND);
+
+        drop(second);
+        assert!(!state.send(FIRST, SECOND, "hello"));
+        assert!(!state.broadcast(FIRST, "hello"));
+        assert_eq!(
+            state.channels.borrow()[&TypeId::of::<(u32, &str)>()]
+                .downcast_ref::<ChannelSubscribers<u32, &str>>()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            [&FIRST]
+        );
+
+        drop(first);
+        assert!(!state.broadcast(SECOND, "hello"));
+    }
+}
@@ -0,0 +1,297 @@
+//! Make sure only one instance of the app runs at a time.
+//!
+//! This is enabled with [`Config::with_single_instance`](crate::Config::with_single_instance). The first instance of
+//! the app listens on a local socket. When the app is launched again, the new process sends its command line arguments
+//! to the running instance and exits.
+//!
+//! On unix, the socket is only accessible to the current user and connections from other users are ignored.
+
+use crate::ipc::UserWindowEvent;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+use tao::event_loop::EventLoopProxy;
+
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(windows)]
+use uds_windows::{UnixListener, UnixStream};
+
+/// A launch of the app that was forwarded to the instance that is already running.
+///
+/// Listen for these with [`use_second_instance_handler`](crate::use_second_instance_handler).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecondInstance {
+    /// The command line arguments of the new process, starting with the name of the program.
+    pub args: Vec<String>,
+
+    /// The working directory of the new process.
+    pub cwd: Option<PathBuf>,
+}
+
+impl SecondInstance {
+    /// The launch of the current process
+    pub(crate) fn current() -> Self {
+        Self {
+            args: std::env::args().collect(),
+            cwd: std::env::current_dir().ok(),
+        }
+    }
+
+    /// A launch for links the operating system asked the running app to open
+    pub(crate) fn from_urls(urls: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            args: std::env::args().take(1).chain(urls).collect(),
+            cwd: None,
+        }
+    }
+
+    /// The links like `my-app://settings` that were passed to the new process.
+    ///
+    /// When a link to a url scheme registered with `url_schemes` in the `[bundle]` section of `Dioxus.toml` is opened,
+    /// the operating system launches the app with the link as an argument on Windows and Linux. On macOS, the link is
+    /// sent to the running app instead, and dioxus forwards it to the handler the same way.
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        self.args
+            .iter()
+            .skip(1)
+            .map(String::as_str)
+            .filter(|arg| is_url(arg))
+    }
+}
+
+/// The socket the first instance listens on. The socket file is removed when this is dropped or the app exits.
+pub(crate) struct SingleInstance {
+    path: PathBuf,
+}
+
+impl SingleInstance {
+    /// Remove the socket so the next launch doesn't try to connect to this instance
+    pub(crate) fn release(&self) {
+        _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Become the only running instance of the app with this id. If another instance is already running, the arguments
+/// of this process are sent to it and this process exits.
+pub(crate) fn acquire(id: &str, proxy: EventLoopProxy<UserWindowEvent>) -> Option<SingleInstance> {
+    let path = socket_path(id);
+
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(_) => {
+            if forward_launch(&path).is_ok() {
+                std::process::exit(0);
+            }
+
+            // Nobody is listening on the socket, so it was left behind by an instance that didn't shut down cleanly
+            _ = std::fs::remove_file(&path);
+            match UnixListener::bind(&path) {
+                Ok(listener) => listener,
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to listen for other instances on {}: {err}",
+                        path.display()
+                    );
+                    return None;
+                }
+            }
+        }
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(err) = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)) {
+            tracing::warn!(
+                "Failed to restrict the permissions of {}: {err}",
+                path.display()
+            );
+        }
+    }
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+
+            #[cfg(unix)]
+            if !is_current_user(&stream) {
+                tracing::warn!("Ignoring a connection from another user");
+                continue;
+            }
+
+            match read_launch(&mut stream) {
+                Ok(launch) => {
+                    if proxy
+                        .send_event(UserWindowEvent::SecondInstance(launch))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+                Err(err) => tracing::warn!("Ignoring invalid message from another instance: {err}"),
+            }
+        }
+    });
+
+    Some(SingleInstance { path })
+}
+
+/// Send the arguments of this process to the running instance
+fn forward_launch(path: &Path) -> std::io::Result<()> {
+    // Never send the arguments to a socket another user created in a shared directory
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if std::fs::metadata(path)?.uid() != unsafe { libc::geteuid() } {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "the socket belongs to another user",
+            ));
+        }
+    }
+
+    let mut stream = UnixStream::connect(path)?;
+    write_launch(&mut stream, &SecondInstance::current())
+}
+
+/// Send a launch as json. The sender closes the connection after the message, so it doesn't need a delimiter.
+fn write_launch(mut writer: impl Write, launch: &SecondInstance) -> std::io::Result<()> {
+    writer.write_all(serde_json::to_string(launch)?.as_bytes())
+}
+
+/// Read a launch another instance sent until it closed the connection
+fn read_launch(mut reader: impl Read) -> std::io::Result<SecondInstance> {
+    let mut message = String::new();
+    reader.read_to_string(&mut message)?;
+    Ok(serde_json::from_str(&message)?)
+}
+
+/// Check if the process on the other end of the socket runs as the same user as this process
+#[cfg(unix)]
+fn is_current_user(stream: &UnixStream) -> bool {
+    use std::os::fd::AsRawFd;
+
+    let fd = stream.as_raw_fd();
+    let uid = unsafe { libc::geteuid() };
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let mut cred = libc::ucred {
+            pid: 0,
+            uid: 0,
+            gid: 0,
+        };
+        let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                &mut cred as *mut libc::ucred as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        result == 0 && cred.uid == uid
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    {
+        let (mut peer_uid, mut peer_gid) = (0, 0);
+        let result = unsafe { libc::getpeereid(fd, &mut peer_uid, &mut peer_gid) };
+        result == 0 && peer_uid == uid
+    }
+}
+
+fn socket_path(id: &str) -> PathBuf {
+    // The id is used as the file name, so we replace anything that could escape the directory
+    let file_name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(format!("{file_name}.sock"))
+}
+
+/// Check if an argument looks like a link with a url scheme
+fn is_url(arg: &str) -> bool {
+    let Some((scheme, _)) = arg.split_once("://") else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+#[test]
+fn test_urls_need_a_valid_scheme() {
+    assert!(is_url("my-app://settings"));
+    assert!(is_url("https://dioxuslabs.com/learn"));
+    assert!(is_url("web+app.v2://open?file=a"));
+
+    assert!(!is_url("--flag"));
+    assert!(!is_url("/home/me/notes.md"));
+    assert!(!is_url("C:\\Users\\me\\notes.md"));
+    assert!(!is_url("://missing-scheme"));
+    assert!(!is_url("2fa://starts-with-a-digit"));
+    assert!(!is_url("my app://has-a-space"));
+}
+
+#[test]
+fn test_forwarded_launches_round_trip() {
+    let launch = SecondInstance {
+        args: vec![
+            "my-app".to_string(),
+            "--open".to_string(),
+            "notes with spaces.md".to_string(),
+            "my-app://settings?tab=\"general\"".to_string(),
+            "ünïcødé".to_string(),
+        ],
+        cwd: Some(PathBuf::from("/home/me/projects")),
+    };
+
+    let mut message = Vec::new();
+    write_launch(&mut message, &launch).unwrap();
+    assert_eq!(read_launch(message.as_slice()).unwrap(), launch);
+    assert_eq!(
+        launch.urls().collect::<Vec<_>>(),
+        ["my-app://settings?tab=\"general\""]
+    );
+
+    // The program name is never a url, even if the app was launched through one
+    let launch = SecondInstance {
+        args: vec!["my-app://program".to_string(), "my-app://link".to_string()],
+        cwd: None,
+    };
+    let mut message = Vec::new();
+    write_launch(&mut message, &launch).unwrap();
+    let decoded = read_launch(message.as_slice()).unwrap();
+    assert_eq!(decoded.urls().collect::<Vec<_>>(), ["my-app://link"]);
+    assert_eq!(decoded.cwd, None);
+}
+
+#[test]
+fn test_invalid_launches_are_rejected() {
+    assert!(read_launch(&b""[..]).is_err());
+    assert!(read_launch(&b"{\"args\": [\"my-app\"]"[..]).is_err());
+    assert!(read_launch(&b"{\"args\": \"my-app\", \"cwd\": null}"[..]).is_err());
+    assert!(read_launch(&[0xff, 0xfe][..]).is_err());
+}
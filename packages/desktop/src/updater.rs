@@ -0,0 +1,761 @@
+//! Update bundled apps in place.
+//!
+//! `dx bundle` writes a `latest.json` manifest next to the bundles when `[bundle.updater]` is set in `Dioxus.toml`. The
+//! manifest lists the installer for each platform along with its signature, and is signed itself with the same key.
+//! Upload the manifest, its `.sig` file and the installers to a static file host, then point the app at the manifest:
+//!
+//! ```rust, no_run
+//! use dioxus::desktop::{updater::UpdaterConfig, Config};
+//! # use dioxus::prelude::*;
+//! # fn app() -> Element { VNode::empty() }
+//!
+//! dioxus::LaunchBuilder::desktop()
+//!     .with_cfg(Config::new().with_updater(UpdaterConfig::new(
+//!         "https://releases.example.com/my-app/latest.json",
+//!         // The public key `dx bundle` printed when it created the signing key
+//!         "ZmFrZSBwdWJsaWMga2V5IGZvciB0aGUgZXhhbXBsZSE=",
+//!     )))
+//!     .launch(app);
+//! ```
+//!
+//! Downloading an update with [`Update::download_and_stage`] verifies it and stages it for the next launch. The staged
+//! update is installed before any window opens the next time the app starts, or right away if you call [`restart`].
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// The largest manifest or signature we download
+const MAX_MANIFEST_LEN: u64 = 1024 * 1024;
+
+/// The largest installer we download
+const MAX_ARTIFACT_LEN: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Where and how the app checks for updates. Pass it to [`Config::with_updater`](crate::Config::with_updater).
+#[derive(Debug, Clone)]
+pub struct UpdaterConfig {
+    endpoint: String,
+    public_key: String,
+    current_version: Option<String>,
+    staging_dir: Option<PathBuf>,
+}
+
+impl UpdaterConfig {
+    /// Check the manifest at this url for updates. The public key is the base64 encoded key `dx bundle` printed when it
+    /// created the key that signs the updates.
+    pub fn new(endpoint: impl Into<String>, public_key: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            public_key: public_key.into(),
+            current_version: None,
+            staging_dir: None,
+        }
+    }
+
+    /// Set the version of the running app.
+    ///
+    /// Apps bundled with `dx bundle` know their version from `Cargo.toml`, so you only need this if you build the app
+    /// another way.
+    pub fn with_current_version(mut self, version: impl Into<String>) -> Self {
+        self.current_version = Some(version.into());
+        self
+    }
+
+    /// Set the directory updates are downloaded to before they are installed.
+    ///
+    /// Defaults to an `updates` folder in the local data directory of the app.
+    pub fn with_staging_directory(mut self, path: impl Into<PathBuf>) -> Self {
+        self.staging_dir = Some(path.into());
+        self
+    }
+
+    fn current_version(&self) -> Result<semver::Version, UpdateError> {
+        let version = self
+            .current_version
+            .clone()
+            .or_else(dioxus_cli_config::app_version)
+            .ok_or(UpdateError::UnknownVersion)?;
+        Ok(semver::Version::parse(&version)?)
+    }
+
+    fn staging_dir(&self) -> Result<PathBuf, UpdateError> {
+        match &self.staging_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => crate::window_state::default_data_dir()
+                .map(|dir| dir.join("updates"))
+                .ok_or_else(|| {
+                    UpdateError::Io(std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        "Failed to find the local data directory",
+                    ))
+                }),
+        }
+    }
+
+    /// Resolve an artifact url from the manifest. Relative urls are relative to the manifest.
+    fn resolve(&self, url: &str) -> String {
+        if url.contains("://") {
+            return url.to_string();
+        }
+        match self.endpoint.rsplit_once('/') {
+            Some((base, _)) => format!("{base}/{url}"),
+            None => url.to_string(),
+        }
+    }
+}
+
+/// An error that occurred while checking for or installing an update.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum UpdateError {
+    /// The request for the manifest or installer failed.
+    #[error("Request failed: {0}")]
+    Http(String),
+
+    /// The manifest is not valid json or doesn't match the format `dx bundle` writes.
+    #[error("Invalid update manifest: {0}")]
+    InvalidManifest(#[from] serde_json::Error),
+
+    /// The public key in the [`UpdaterConfig`] is not a base64 encoded ed25519 key.
+    #[error("Invalid public key")]
+    InvalidPublicKey,
+
+    /// The signature of the manifest or installer doesn't match the public key.
+    #[error("The signature of {0} is not valid")]
+    InvalidSignature(String),
+
+    /// A version is not valid semver.
+    #[error("Invalid version: {0}")]
+    InvalidVersion(#[from] semver::Error),
+
+    /// The version of the running app is not known. Set it with [`UpdaterConfig::with_current_version`].
+    #[error("The version of the running app is not known")]
+    UnknownVersion,
+
+    /// The app was launched without [`Config::with_updater`](crate::Config::with_updater).
+    #[error("No updater is configured")]
+    NotConfigured,
+
+    /// The manifest has no installer for this platform.
+    #[error("The update has no installer for {0}")]
+    UnsupportedPlatform(String),
+
+    /// The staged update points to an installer outside of the staging directory.
+    #[error("The staged installer {0} is outside of the staging directory")]
+    UntrustedPath(PathBuf),
+
+    /// Reading or writing the staged update failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// The manifest `dx bundle` writes with the latest version of the app.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateManifest {
+    /// The version of the update.
+    pub version: String,
+
+    /// The release notes of the update.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+
+    /// The installers for each platform, keyed by `<os>-<arch>` like `linux-x86_64` or `macos-aarch64`.
+    pub platforms: BTreeMap<String, UpdateArtifact>,
+}
+
+/// The installer of an update for a single platform.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateArtifact {
+    /// The url to download the installer from. Relative urls are relative to the manifest.
+    pub url: String,
+
+    /// The base64 encoded ed25519 signature of the installer.
+    pub signature: String,
+
+    /// The kind of installer.
+    pub format: ArtifactFormat,
+}
+
+/// The kinds of installers the updater can install.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ArtifactFormat {
+    /// A linux AppImage that replaces the running AppImage.
+    AppImage,
+
+    /// A windows NSIS installer that runs silently.
+    Nsis,
+
+    /// A windows MSI installer that runs with a progress bar.
+    Msi,
+
+    /// A gzipped tarball of a macOS `.app` bundle that replaces the running bundle.
+    App,
+}
+
+/// The platform key of the running app in the manifest, like `linux-x86_64`.
+pub fn current_platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// An update that is newer than the running app.
+#[derive(Debug, Clone)]
+pub struct Update {
+    config: UpdaterConfig,
+    version: String,
+    notes: Option<String>,
+    artifact: UpdateArtifact,
+}
+
+impl Update {
+    /// The version of the update.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The release notes of the update.
+    pub fn notes(&self) -> Option<&str> {
+        self.notes.as_deref()
+    }
+
+    /// Download the installer, verify its signature and stage it to be installed the next time the app starts.
+    ///
+    /// Call [`restart`] afterwards to install the update right away.
+    pub async fn download_and_stage(&self) -> Result<(), UpdateError> {
+        let update = self.clone();
+        run_blocking(move || update.download_and_stage_blocking()).await
+    }
+
+    fn download_and_stage_blocking(&self) -> Result<(), UpdateError> {
+        let url = self.config.resolve(&self.artifact.url);
+        let installer = get(&url, MAX_ARTIFACT_LEN)?;
+        verify(
+            &self.config.public_key,
+            &installer,
+            &self.artifact.signature,
+            &url,
+        )?;
+
+        let dir = self.config.staging_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        let file_name = url
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty() && !name.contains(['\\', '?', '#']))
+            .unwrap_or("update");
+        let path = dir.join(file_name);
+        write_atomic(&path, &installer)?;
+
+        let staged = StagedUpdate {
+            version: self.version.clone(),
+            format: self.artifact.format,
+            path,
+            signature: self.artifact.signature.clone(),
+        };
+        write_atomic(&dir.join(STAGED_FILE), &serde_json::to_vec(&staged)?)?;
+        Ok(())
+    }
+}
+
+/// Check the manifest for a version that is newer than the running app.
+///
+/// Returns `None` if the app is up to date. The signature of the manifest is checked before it is parsed.
+pub async fn check_for_update(config: &UpdaterConfig) -> Result<Option<Update>, UpdateError> {
+    let config = config.clone();
+    run_blocking(move || check_for_update_blocking(config)).await
+}
+
+fn check_for_update_blocking(config: UpdaterConfig) -> Result<Option<Update>, UpdateError> {
+    let manifest = get(&config.endpoint, MAX_MANIFEST_LEN)?;
+    let signature = get(&format!("{}.sig", config.endpoint), MAX_MANIFEST_LEN)?;
+    verify(
+        &config.public_key,
+        &manifest,
+        &String::from_utf8_lossy(&signature),
+        &config.endpoint,
+    )?;
+
+    let mut manifest: UpdateManifest = serde_json::from_slice(&manifest)?;
+    if semver::Version::parse(&manifest.version)? <= config.current_version()? {
+        return Ok(None);
+    }
+
+    let platform = current_platform();
+    let artifact = manifest
+        .platforms
+        .remove(&platform)
+        .ok_or(UpdateError::UnsupportedPlatform(platform))?;
+
+    Ok(Some(Update {
+        config,
+        version: manifest.version,
+        notes: manifest.notes,
+        artifact,
+    }))
+}
+
+/// Restart the app. If an update is staged, it is installed before the app starts again.
+pub fn restart() -> ! {
+    let exe = running_executable();
+    if let Err(err) = std::process::Command::new(&exe)
+        .args(std::env::args_os().skip(1))
+        .spawn()
+    {
+        tracing::error!("Failed to restart {}: {err}", exe.display());
+    }
+    std::process::exit(0)
+}
+
+const STAGED_FILE: &str = "staged.json";
+
+/// An update that was downloaded and verified, waiting to be installed
+#[derive(Serialize, Deserialize)]
+struct StagedUpdate {
+    version: String,
+    format: ArtifactFormat,
+    path: PathBuf,
+    signature: String,
+}
+
+/// Install the update that was staged by a previous run of the app, then start the new version.
+///
+/// This is called before the event loop starts, so no windows are open yet.
+pub(crate) fn install_staged_update(config: &UpdaterConfig) {
+    let staged = match take_staged_update(config) {
+        Ok(Some(staged)) => staged,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::error!("Ignoring the staged update: {err}");
+            return;
+        }
+    };
+
+    tracing::info!("Installing update to version {}", staged.version);
+    match install(&staged) {
+        Ok(()) => restart(),
+        Err(err) => {
+            tracing::error!("Failed to install update {}: {err}", staged.version);
+            _ = std::fs::remove_file(&staged.path);
+        }
+    }
+}
+
+/// Read the update a previous run staged and check that it is safe to install.
+///
+/// Anything that can write to the data directory can write the marker file, so the installer must be inside the
+/// staging directory and still be signed with the public key of the app. Returns `None` if nothing newer is staged.
+fn take_staged_update(config: &UpdaterConfig) -> Result<Option<StagedUpdate>, UpdateError> {
+    let dir = config.staging_dir()?;
+    let marker = dir.join(STAGED_FILE);
+    let Ok(contents) = std::fs::read(&marker) else {
+        return Ok(None);
+    };
+
+    // Remove the marker first so a broken update can't stop the app from starting
+    _ = std::fs::remove_file(&marker);
+    let mut staged = serde_json::from_slice::<StagedUpdate>(&contents)?;
+
+    let path = staged.path.canonicalize()?;
+    if !path.starts_with(dir.canonicalize()?) {
+        return Err(UpdateError::UntrustedPath(path));
+    }
+    staged.path = path;
+
+    let is_newer = match (
+        semver::Version::parse(&staged.version),
+        config.current_version(),
+    ) {
+        (Ok(staged), Ok(current)) => staged > current,
+        _ => false,
+    };
+    if !is_newer {
+        _ = std::fs::remove_file(&staged.path);
+        return Ok(None);
+    }
+
+    let installer = std::fs::read(&staged.path)?;
+    let name = staged.path.display().to_string();
+    if let Err(err) = verify(&config.public_key, &installer, &staged.signature, &name) {
+        _ = std::fs::remove_file(&staged.path);
+        return Err(err);
+    }
+
+    Ok(Some(staged))
+}
+
+fn install(staged: &StagedUpdate) -> Result<(), UpdateError> {
+    match staged.format {
+        #[cfg(target_os = "linux")]
+        ArtifactFormat::AppImage => {
+            use std::os::unix::fs::PermissionsExt;
+
+            let target = std::env::var_os("APPIMAGE")
+                .map(PathBuf::from)
+                .ok_or_else(|| {
+                    UpdateError::UnsupportedPlatform("apps that are not an AppImage".into())
+                })?;
+            let new = target.with_extension("new");
+            std::fs::copy(&staged.path, &new)?;
+            std::fs::set_permissions(&new, std::fs::Permissions::from_mode(0o755))?;
+            std::fs::rename(&new, &target)?;
+            _ = std::fs::remove_file(&staged.path);
+            Ok(())
+        }
+
+        // The installers replace the app themselves, so we hand off to them and exit
+        #[cfg(target_os = "windows")]
+        ArtifactFormat::Nsis => {
+            std::process::Command::new(&staged.path).arg("/S").spawn()?;
+            std::process::exit(0)
+        }
+        #[cfg(target_os = "windows")]
+        ArtifactFormat::Msi => {
+            std::process::Command::new("msiexec")
+                .arg("/i")
+                .arg(&staged.path)
+                .arg("/passive")
+                .spawn()?;
+            std::process::exit(0)
+        }
+
+        #[cfg(target_os = "macos")]
+        ArtifactFormat::App => {
+            let bundle = running_executable()
+                .ancestors()
+                .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+                .map(Path::to_path_buf)
+                .ok_or_else(|| {
+                    UpdateError::UnsupportedPlatform("apps that are not a .app bundle".into())
+                })?;
+            let parent = bundle.parent().unwrap_or(Path::new("/"));
+
+            // Unpack next to the old bundle so we can swap them with a rename
+            let unpacked = parent.join(".dioxus-update");
+            _ = std::fs::remove_dir_all(&unpacked);
+            let archive = std::fs::File::open(&staged.path)?;
+            tar::Archive::new(flate2::read::GzDecoder::new(archive)).unpack(&unpacked)?;
+            let new_bundle = std::fs::read_dir(&unpacked)?
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+                .ok_or_else(|| {
+                    UpdateError::UnsupportedPlatform("archives without a .app bundle".into())
+                })?;
+
+            let old_bundle = bundle.with_extension("app.old");
+            _ = std::fs::remove_dir_all(&old_bundle);
+            std::fs::rename(&bundle, &old_bundle)?;
+            if let Err(err) = std::fs::rename(&new_bundle, &bundle) {
+                _ = std::fs::rename(&old_bundle, &bundle);
+                return Err(err.into());
+            }
+            _ = std::fs::remove_dir_all(&old_bundle);
+            _ = std::fs::remove_dir_all(&unpacked);
+            _ = std::fs::remove_file(&staged.path);
+            Ok(())
+        }
+
+        format => Err(UpdateError::UnsupportedPlatform(format!(
+            "{format:?} installers on {}",
+            current_platform()
+        ))),
+    }
+}
+
+/// The path of the program the user launched. For AppImages this is the AppImage instead of the unpacked executable.
+fn running_executable() -> PathBuf {
+    #[cfg(target_os = "linux")]
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return PathBuf::from(appimage);
+    }
+    std::env::current_exe()
+        .unwrap_or_else(|_| PathBuf::from(std::env::args_os().next().unwrap_or_default()))
+}
+
+/// Check a base64 encoded ed25519 signature of some data
+fn verify(public_key: &str, data: &[u8], signature: &str, name: &str) -> Result<(), UpdateError> {
+    let public_key: [u8; 32] = BASE64_STANDARD
+        .decode(public_key.trim())
+        .ok()
+        .and_then(|key| key.try_into().ok())
+        .ok_or(UpdateError::InvalidPublicKey)?;
+    let public_key =
+        VerifyingKey::from_bytes(&public_key).map_err(|_| UpdateError::InvalidPublicKey)?;
+
+    let invalid = || UpdateError::InvalidSignature(name.to_string());
+    let signature: [u8; 64] = BASE64_STANDARD
+        .decode(signature.trim())
+        .ok()
+        .and_then(|signature| signature.try_into().ok())
+        .ok_or_else(invalid)?;
+
+    public_key
+        .verify_strict(data, &Signature::from_bytes(&signature))
+        .map_err(|_| invalid())
+}
+
+fn get(url: &str, limit: u64) -> Result<Vec<u8>, UpdateError> {
+    let mut response = ureq::get(url)
+        .call()
+        .map_err(|err| UpdateError::Http(format!("{url}: {err}")))?;
+    response
+        .body_mut()
+        .with_config()
+        .limit(limit)
+        .read_to_vec()
+        .map_err(|err| UpdateError::Http(format!("{url}: {err}")))
+}
+
+/// Write a file so readers never see it half written
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let temp = path.with_extension("part");
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(temp, path)
+}
+
+/// Run blocking network and file io on another thread so it doesn't freeze the windows
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, UpdateError> + Send + 'static,
+) -> Result<T, UpdateError> {
+    let (tx, rx) = futures_channel::oneshot::channel();
+    std::thread::spawn(move || _ = tx.send(f()));
+    rx.await
+        .map_err(|_| UpdateError::Io(std::io::Error::other("The update thread panicked")))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn public_key() -> String {
+        BASE64_STANDARD.encode(signing_key().verifying_key().as_bytes())
+    }
+
+    fn sign(data: &[u8]) -> String {
+        BASE64_STANDARD.encode(signing_key().sign(data).to_bytes())
+    }
+
+    /// Serve files over http on a local port until the test ends and return the base url
+    fn serve(files: Vec<(&'static str, Vec<u8>)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(&stream);
+                _ = reader.read_line(&mut request_line);
+                // Skip the headers
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|len| len > 2) {
+                    line.clear();
+                }
+
+                let path = request_line.split(' ').nth(1).unwrap_or_default();
+                let response = match files.iter().find(|(name, _)| *name == path) {
+                    Some((_, body)) => {
+                        let mut response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        response.extend_from_slice(body);
+                        response
+                    }
+                    None => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+                _ = stream.write_all(&response);
+            }
+        });
+        base
+    }
+
+    fn manifest(version: &str, installer: &[u8]) -> Vec<u8> {
+        serde_json::to_vec(&UpdateManifest {
+            version: version.to_string(),
+            notes: Some("Bug fixes".to_string()),
+            platforms: BTreeMap::from([(
+                current_platform(),
+                UpdateArtifact {
+                    url: "app.AppImage".to_string(),
+                    signature: sign(installer),
+                    format: ArtifactFormat::AppImage,
+                },
+            )]),
+        })
+        .unwrap()
+    }
+
+    fn config(base: &str, staging: &Path) -> UpdaterConfig {
+        UpdaterConfig::new(format!("{base}/latest.json"), public_key())
+            .with_current_version("1.0.0")
+            .with_staging_directory(staging)
+    }
+
+    fn staging_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dioxus-updater-{name}-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn signed_updates_are_accepted() {
+        let installer = b"new version".to_vec();
+        let manifest = manifest("1.1.0", &installer);
+        let base = serve(vec![
+            ("/latest.json.sig", sign(&manifest).into_bytes()),
+            ("/latest.json", manifest),
+            ("/app.AppImage", installer.clone()),
+        ]);
+        let staging = staging_dir("accept");
+        let config = config(&base, &staging);
+
+        let update = check_for_update_blocking(config.clone()).unwrap().unwrap();
+        assert_eq!(update.version(), "1.1.0");
+        assert_eq!(update.notes(), Some("Bug fixes"));
+
+        update.download_and_stage_blocking().unwrap();
+        let staged = take_staged_update(&config).unwrap().unwrap();
+        assert_eq!(std::fs::read(&staged.path).unwrap(), installer);
+
+        // The marker is consumed, so the update is only installed once
+        assert!(take_staged_update(&config).unwrap().is_none());
+        std::fs::remove_dir_all(staging).unwrap();
+    }
+
+    #[test]
+    fn unsigned_updates_are_rejected() {
+        let installer = b"new version".to_vec();
+        let manifest = manifest("1.1.0", &installer);
+
+        // The manifest was changed after it was signed
+        let mut tampered = manifest.clone();
+        tampered.extend_from_slice(b" ");
+        let base = serve(vec![
+            ("/latest.json.sig", sign(&manifest).into_bytes()),
+            ("/latest.json", tampered),
+        ]);
+        let staging = staging_dir("reject-manifest");
+        assert!(matches!(
+            check_for_update_blocking(config(&base, &staging)),
+            Err(UpdateError::InvalidSignature(_))
+        ));
+
+        // The installer doesn't match the signature in the manifest
+        let base = serve(vec![
+            ("/latest.json.sig", sign(&manifest).into_bytes()),
+            ("/latest.json", manifest),
+            ("/app.AppImage", b"malicious version".to_vec()),
+        ]);
+        let update = check_for_update_blocking(config(&base, &staging))
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            update.download_and_stage_blocking(),
+            Err(UpdateError::InvalidSignature(_))
+        ));
+        std::fs::remove_dir_all(staging).unwrap();
+    }
+
+    #[test]
+    fn staged_updates_are_verified_again() {
+        let staging = staging_dir("restage");
+        let config = config("http://127.0.0.1:0", &staging);
+        let stage = |path: &Path, signature: String| {
+            let staged = StagedUpdate {
+                version: "2.0.0".to_string(),
+                format: ArtifactFormat::AppImage,
+                path: path.to_path_buf(),
+                signature,
+            };
+            std::fs::write(
+                staging.join(STAGED_FILE),
+                serde_json::to_vec(&staged).unwrap(),
+            )
+            .unwrap();
+        };
+
+        // The installer was replaced after it was downloaded
+        let installer = staging.join("app.AppImage");
+        std::fs::write(&installer, b"replaced").unwrap();
+        stage(&installer, sign(b"downloaded"));
+        assert!(matches!(
+            take_staged_update(&config),
+            Err(UpdateError::InvalidSignature(_))
+        ));
+        assert!(!installer.exists());
+
+        // Installers outside of the staging directory are never trusted, even if they are signed
+        let outside = staging.with_extension("outside");
+        std::fs::write(&outside, b"downloaded").unwrap();
+        stage(&outside, sign(b"downloaded"));
+        assert!(matches!(
+            take_staged_update(&config),
+            Err(UpdateError::UntrustedPath(_))
+        ));
+        assert!(outside.exists());
+        std::fs::remove_file(outside).unwrap();
+        std::fs::remove_dir_all(staging).unwrap();
+    }
+
+    #[test]
+    fn only_newer_versions_are_updates() {
+        let installer = b"new version".to_vec();
+        let staging = staging_dir("versions");
+        for (version, is_update) in [
+            ("0.9.0", false),
+            ("1.0.0", false),
+            ("1.0.0-beta.1", false),
+            ("1.0.1", true),
+            ("2.0.0-alpha.1", true),
+        ] {
+            let manifest = manifest(version, &installer);
+            let base = serve(vec![
+                ("/latest.json.sig", sign(&manifest).into_bytes()),
+                ("/latest.json", manifest),
+            ]);
+            let update = check_for_update_blocking(config(&base, &staging)).unwrap();
+            assert_eq!(update.is_some(), is_update, "{version}");
+        }
+
+        // Staged updates that are not newer than the running app are discarded
+        let installer_path = staging.join("app.AppImage");
+        std::fs::write(&installer_path, &installer).unwrap();
+        let staged = StagedUpdate {
+            version: "1.0.0".to_string(),
+            format: ArtifactFormat::AppImage,
+            path: installer_path.clone(),
+            signature: sign(&installer),
+        };
+        std::fs::write(
+            staging.join(STAGED_FILE),
+            serde_json::to_vec(&staged).unwrap(),
+        )
+        .unwrap();
+        let config = config("http://127.0.0.1:0", &staging);
+        assert!(take_staged_update(&config).unwrap().is_none());
+        assert!(!installer_path.exists());
+        std::fs::remove_dir_all(staging).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 use crate::element::DesktopElement;
 use crate::file_upload::DesktopFileDragEvent;
-use crate::menubar::DioxusMenu;
+use crate::navigation::{is_app_url, BlockedNavigation, NavigationPolicy};
+use crate::window_state::WindowStateTracker;
 use crate::PendingDesktopContext;
 use crate::WindowCloseBehaviour;
 use crate::{
@@ -16,7 +17,6 @@ use dioxus_history::{History, MemoryHistory};
 use dioxus_hooks::to_owned;
 use dioxus_html::{HtmlEvent, PlatformEventData};
 use futures_util::{pin_mut, FutureExt};
-use std::sync::atomic::AtomicBool;
 use std::{cell::OnceCell, time::Duration};
 use std::{rc::Rc, task::Waker};
 use wry::{DragDropEvent, RequestAsyncResponder, WebContext, WebViewBuilder, WebViewId};
@@ -153,17 +153,12 @@ pub(crate) struct WebviewInstance {
     pub edits: WebviewEdits,
     pub desktop_context: DesktopContext,
     pub waker: Waker,
+    pub window_state: Option<WindowStateTracker>,
+    pub navigation: NavigationPolicy,
 
     // Wry assumes the webcontext is alive for the lifetime of the webview.
     // We need to keep the webcontext alive, otherwise the webview will crash
     _web_context: WebContext,
-
-    // Same with the menu.
-    // Currently it's a DioxusMenu because 1) we don't touch it and 2) we support a number of platforms
-    // like ios where muda does not give us a menu type. It sucks but alas.
-    //
-    // This would be a good thing for someone looking to contribute to fix.
-    _menu: Option<DioxusMenu>,
 }
 
 impl WebviewInstance {
@@ -197,6 +192,15 @@ impl WebviewInstance {
             ));
         }
 
+        // Restore the size and position the window had the last time it was open
+        let window_state = cfg
+            .window_state
+            .as_deref()
+            .and_then(|label| WindowStateTracker::new(label, cfg.data_dir.as_deref()));
+        if let Some(window_state) = &window_state {
+            window = window_state.restore(window, &shared.target);
+        }
+
         let window = window.build(&shared.target).unwrap();
 
         // https://developer.apple.com/documentation/appkit/nswindowcollectionbehavior/nswindowcollectionbehaviormanaged
@@ -227,6 +231,7 @@ impl WebviewInstance {
                 cfg.custom_head,
                 cfg.custom_index,
                 cfg.root_name,
+                cfg.content_security_policy,
                 asset_handlers,
                 edits
             ];
@@ -240,6 +245,7 @@ impl WebviewInstance {
                     custom_index.clone(),
                     &root_name,
                     headless,
+                    content_security_policy.as_deref(),
                 )
             }
         };
@@ -248,6 +254,15 @@ impl WebviewInstance {
             let window_id = window.id();
             to_owned![shared.proxy];
             move |payload: wry::http::Request<String>| {
+                // Pages from other origins the window navigated to can't talk to rust
+                let origin = payload.uri().to_string();
+                if !is_app_url(&origin) {
+                    tracing::warn!(
+                        "Ignoring a message from {origin}, which is not part of the app"
+                    );
+                    return;
+                }
+
                 // defer the event to the main thread
                 let body = payload.into_body();
                 if let Ok(msg) = serde_json::from_str(&body) {
@@ -292,7 +307,47 @@ impl WebviewInstance {
             }
         };
 
-        let page_loaded = AtomicBool::new(false);
+        let navigation = NavigationPolicy::new(
+            &cfg.navigation_allowlist,
+            cfg.blocked_navigation_handler.take(),
+        );
+
+        let navigation_handler = {
+            to_owned![navigation, shared.proxy];
+            let window_id = window.id();
+            move |url: String| {
+                // We only want to serve the index file, assets and the allowed origins
+                if navigation.allows_navigation(&url) {
+                    return true;
+                }
+
+                // Everything else is blocked and handled on the main thread
+                let navigation = BlockedNavigation {
+                    url,
+                    new_window: false,
+                };
+                _ = proxy.send_event(UserWindowEvent::BlockedNavigation(window_id, navigation));
+                false
+            }
+        };
+
+        // Only the allowed origins can open in a new window. The app creates its own windows with `new_window`
+        let new_window_handler = {
+            to_owned![navigation, shared.proxy];
+            let window_id = window.id();
+            move |url: String| {
+                if navigation.allows(&url) {
+                    return true;
+                }
+
+                let navigation = BlockedNavigation {
+                    url,
+                    new_window: true,
+                };
+                _ = proxy.send_event(UserWindowEvent::BlockedNavigation(window_id, navigation));
+                false
+            }
+        };
 
         let mut webview = WebViewBuilder::new_with_web_context(&mut web_context)
             .with_bounds(wry::Rect {
@@ -305,26 +360,8 @@ impl WebviewInstance {
             .with_transparent(cfg.window.window.transparent)
             .with_url("dioxus://index.html/")
             .with_ipc_handler(ipc_handler)
-            .with_navigation_handler(move |var| {
-                // We don't want to allow any navigation
-                // We only want to serve the index file and assets
-                if var.starts_with("dioxus://")
-                    || var.starts_with("http://dioxus.")
-                    || var.starts_with("https://dioxus.")
-                {
-                    // After the page has loaded once, don't allow any more navigation
-                    let page_loaded = page_loaded.swap(true, std::sync::atomic::Ordering::SeqCst);
-                    !page_loaded
-                } else {
-                    if var.starts_with("http://")
-                        || var.starts_with("https://")
-                        || var.starts_with("mailto:")
-                    {
-                        _ = webbrowser::open(&var);
-                    }
-                    false
-                }
-            }) // prevent all navigations
+            .with_navigation_handler(navigation_handler)
+            .with_new_window_req_handler(new_window_handler)
             .with_asynchronous_custom_protocol(String::from("dioxus"), request_handler);
 
         // Enable https scheme on android, needed for secure context API, like the geolocation API
@@ -370,6 +407,8 @@ impl WebviewInstance {
         }
         "#;
 
+        webview = webview.with_initialization_script(crate::commands::COMMANDS_SCRIPT);
+
         if cfg.disable_context_menu {
             // in release mode, we don't want to show the dev tool or reload menus
             webview = webview.with_initialization_script(INITIALIZATION_SCRIPT)
@@ -421,6 +460,7 @@ impl WebviewInstance {
             asset_handlers,
             file_hover,
             WindowCloseBehaviour::WindowCloses,
+            menu,
         ));
 
         // Provide the desktop context to the virtual dom and edit handler
@@ -431,6 +471,8 @@ impl WebviewInstance {
             provide_context(desktop_context.clone());
             provide_context(provider);
             provide_context(history_provider);
+            #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+            provide_context(shared.backends.clone());
         });
 
         WebviewInstance {
@@ -438,7 +480,8 @@ impl WebviewInstance {
             edits,
             waker: tao_waker(shared.proxy.clone(), desktop_context.window.id()),
             desktop_context,
-            _menu: menu,
+            window_state,
+            navigation,
             _web_context: web_context,
         }
     }
@@ -0,0 +1,412 @@
+//! Save the size, position and maximized state of windows across launches.
+//!
+//! This is enabled per window with [`Config::with_window_state`](crate::Config::with_window_state). The state of each
+//! window is stored as json in `<data directory>/window-state/<label>.json`.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tao::{
+    dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
+    event_loop::EventLoopWindowTarget,
+    monitor::MonitorHandle,
+    window::{Window, WindowBuilder},
+};
+
+use crate::ipc::UserWindowEvent;
+
+/// How often the state is written to disk while the window is being moved or resized
+const SAVE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How much of a restored window needs to be on a monitor before we move it back on screen
+const MIN_VISIBLE: i64 = 64;
+
+/// The outer position and inner size of a window.
+///
+/// These are logical pixels on macOS and physical pixels everywhere else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct WindowBounds {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+impl WindowBounds {
+    /// Read the current bounds of a window
+    pub(crate) fn from_window(window: &Window) -> Option<Self> {
+        let position = window.outer_position().ok()?;
+        let (x, y) = if cfg!(target_os = "macos") {
+            let position = position.to_logical::<i32>(window.scale_factor());
+            (position.x, position.y)
+        } else {
+            (position.x, position.y)
+        };
+
+        let (width, height) = if cfg!(target_os = "macos") {
+            let size = window.outer_size();
+            let size = size.to_logical::<u32>(window.scale_factor());
+            // This is to work around a bug in how tao handles inner_size on macOS
+            // We *want* to use inner_size, but that's currently broken, so we use outer_size instead and then an adjustment
+            //
+            // https://github.com/tauri-apps/tao/issues/889
+            let adjustment = if window.is_decorated() { 28 } else { 0 };
+            (size.width, size.height.saturating_sub(adjustment))
+        } else {
+            let size = window.inner_size();
+            (size.width, size.height)
+        };
+
+        Some(Self {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// The area a monitor covers on the desktop
+    fn from_monitor(monitor: &MonitorHandle) -> Self {
+        let (position, size) = (monitor.position(), monitor.size());
+        if cfg!(target_os = "macos") {
+            let position = position.to_logical::<i32>(monitor.scale_factor());
+            let size = size.to_logical::<u32>(monitor.scale_factor());
+            Self {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            }
+        } else {
+            Self {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            }
+        }
+    }
+
+    pub(crate) fn position(&self) -> Position {
+        if cfg!(target_os = "macos") {
+            LogicalPosition::new(self.x, self.y).into()
+        } else {
+            PhysicalPosition::new(self.x, self.y).into()
+        }
+    }
+
+    pub(crate) fn size(&self) -> Size {
+        if cfg!(target_os = "macos") {
+            LogicalSize::new(self.width, self.height).into()
+        } else {
+            PhysicalSize::new(self.width, self.height).into()
+        }
+    }
+
+    /// Move the bounds onto the fallback area if they are not visible on any of the monitor areas
+    fn keep_on_screen(self, monitors: &[Self], fallback: Option<&Self>) -> Self {
+        if monitors.iter().any(|area| self.is_visible_in(area)) {
+            return self;
+        }
+        match fallback {
+            Some(area) => self.clamp_to(area),
+            None => self,
+        }
+    }
+
+    /// Check if enough of the window is inside the area that the user can grab it
+    fn is_visible_in(&self, area: &Self) -> bool {
+        let overlap = |start: i32, len: u32, area_start: i32, area_len: u32| {
+            let end = (start as i64 + len as i64).min(area_start as i64 + area_len as i64);
+            end - (start as i64).max(area_start as i64)
+        };
+        let width = overlap(self.x, self.width, area.x, area.width);
+        let height = overlap(self.y, self.height, area.y, area.height);
+        width >= MIN_VISIBLE.min(self.width as i64) && height >= MIN_VISIBLE.min(self.height as i64)
+    }
+
+    /// Shrink and move the bounds so they fit inside the area
+    fn clamp_to(self, area: &Self) -> Self {
+        let width = self.width.min(area.width);
+        let height = self.height.min(area.height);
+        let clamp = |start: i32, len: u32, area_start: i32, area_len: u32| {
+            let max = area_start as i64 + (area_len - len) as i64;
+            (start as i64).clamp(area_start as i64, max) as i32
+        };
+        Self {
+            x: clamp(self.x, width, area.x, area.width),
+            y: clamp(self.y, height, area.y, area.height),
+            width,
+            height,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SavedWindowState {
+    #[serde(flatten)]
+    bounds: WindowBounds,
+    maximized: bool,
+    monitor: Option<String>,
+}
+
+/// Tracks the state of a single window and writes it to disk when it changes
+pub(crate) struct WindowStateTracker {
+    path: PathBuf,
+    state: RefCell<Option<SavedWindowState>>,
+    dirty: Cell<bool>,
+    last_save: Cell<Option<Instant>>,
+}
+
+impl WindowStateTracker {
+    /// Create a tracker for the window with this label. Returns `None` if there is no directory to store the state in.
+    pub(crate) fn new(label: &str, data_dir: Option<&Path>) -> Option<Self> {
+        let data_dir = match data_dir {
+            Some(dir) => dir.to_path_buf(),
+            None => default_data_dir()?,
+        };
+
+        // The label is used as the file name, so we replace anything that could escape the directory
+        let file_name: String = label
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let path = data_dir
+            .join("window-state")
+            .join(format!("{file_name}.json"));
+
+        let state = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|state| serde_json::from_str(&state).ok());
+
+        Some(Self {
+            path,
+            state: RefCell::new(state),
+            dirty: Cell::new(false),
+            last_save: Cell::new(None),
+        })
+    }
+
+    /// Apply the saved state to the window builder before the window is created
+    ///
+    /// If the window would end up off screen because a monitor was disconnected or its resolution changed, it is moved
+    /// back onto the monitor it was last on or the primary monitor.
+    pub(crate) fn restore(
+        &self,
+        mut window: WindowBuilder,
+        target: &EventLoopWindowTarget<UserWindowEvent>,
+    ) -> WindowBuilder {
+        let Some(state) = self.state.borrow().clone() else {
+            return window;
+        };
+
+        let monitors: Vec<_> = target.available_monitors().collect();
+        let fallback = monitors
+            .iter()
+            .find(|monitor| monitor.name().is_some() && monitor.name() == state.monitor)
+            .cloned()
+            .or_else(|| target.primary_monitor())
+            .or_else(|| monitors.first().cloned())
+            .map(|monitor| WindowBounds::from_monitor(&monitor));
+        let areas: Vec<_> = monitors.iter().map(WindowBounds::from_monitor).collect();
+        let bounds = state.bounds.keep_on_screen(&areas, fallback.as_ref());
+
+        window = window
+            .with_position(bounds.position())
+            .with_inner_size(bounds.size());
+        if state.maximized {
+            window = window.with_maximized(true);
+        }
+        window
+    }
+
+    /// Read the current state of the window. The state is written to disk at most every [`SAVE_INTERVAL`] while the
+    /// window changes, and any remaining changes are written by [`Self::save`] when the window closes.
+    pub(crate) fn update(&self, window: &Window) {
+        // Minimized windows report a position far off screen on some platforms
+        if window.is_minimized() {
+            return;
+        }
+
+        let maximized = window.is_maximized();
+        let mut state = self.state.borrow_mut();
+        let bounds = match (&*state, maximized) {
+            // Keep the bounds from before the window was maximized so it restores to that size when unmaximized
+            (Some(state), true) => state.bounds,
+            _ => match WindowBounds::from_window(window) {
+                Some(bounds) => bounds,
+                None => return,
+            },
+        };
+        let new_state = SavedWindowState {
+            bounds,
+            maximized,
+            monitor: window.current_monitor().and_then(|monitor| monitor.name()),
+        };
+        if state.as_ref() == Some(&new_state) {
+            return;
+        }
+        *state = Some(new_state);
+        drop(state);
+        self.dirty.set(true);
+
+        let save_now = match self.last_save.get() {
+            Some(last_save) => last_save.elapsed() >= SAVE_INTERVAL,
+            None => true,
+        };
+        if save_now {
+            self.save();
+        }
+    }
+
+    /// Write the state to disk if it changed since the last save
+    pub(crate) fn save(&self) {
+        if !self.dirty.replace(false) {
+            return;
+        }
+        self.last_save.set(Some(Instant::now()));
+
+        let Some(state) = &*self.state.borrow() else {
+            return;
+        };
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| {
+                let state = serde_json::to_string(state)?;
+                std::fs::write(&self.path, state)
+            });
+        if let Err(err) = result {
+            tracing::error!(
+                "Failed to save window state to {}: {err}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+impl Drop for WindowStateTracker {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+/// The directory we store state in if the app didn't set one with [`Config::with_data_directory`](crate::Config::with_data_directory)
+pub(crate) fn default_data_dir() -> Option<PathBuf> {
+    let name = dioxus_cli_config::product_name().or_else(|| {
+        let exe = std::env::current_exe().ok()?;
+        Some(exe.file_stem()?.to_string_lossy().into_owned())
+    })?;
+    Some(dirs::data_local_dir()?.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(x: i32, y: i32, width: u32, height: u32) -> WindowBounds {
+        WindowBounds {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    const MONITOR: WindowBounds = WindowBounds {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+
+    #[test]
+    fn visibility() {
+        // Fully inside the monitor
+        assert!(bounds(100, 100, 800, 600).is_visible_in(&MONITOR));
+
+        // Partially visible with enough left on screen to grab the window
+        assert!(bounds(1920 - MIN_VISIBLE as i32, 100, 800, 600).is_visible_in(&MONITOR));
+        assert!(bounds(-700, -500, 800, 600).is_visible_in(&MONITOR));
+
+        // Partially visible, but not enough to grab the window
+        assert!(!bounds(1920 - 10, 100, 800, 600).is_visible_in(&MONITOR));
+        assert!(!bounds(100, -590, 800, 600).is_visible_in(&MONITOR));
+
+        // Completely off screen
+        assert!(!bounds(5000, 100, 800, 600).is_visible_in(&MONITOR));
+        assert!(!bounds(-900, 100, 800, 600).is_visible_in(&MONITOR));
+
+        // Windows smaller than the minimum only need to be fully visible
+        assert!(bounds(1900, 100, 20, 20).is_visible_in(&MONITOR));
+        assert!(!bounds(1910, 100, 20, 20).is_visible_in(&MONITOR));
+    }
+
+    #[test]
+    fn clamping() {
+        // Bounds that already fit are unchanged
+        let inside = bounds(100, 100, 800, 600);
+        assert_eq!(inside.clamp_to(&MONITOR), inside);
+
+        // Off screen windows are moved back to the nearest edge
+        assert_eq!(
+            bounds(5000, -300, 800, 600).clamp_to(&MONITOR),
+            bounds(1120, 0, 800, 600)
+        );
+        assert_eq!(
+            bounds(-900, 2000, 800, 600).clamp_to(&MONITOR),
+            bounds(0, 480, 800, 600)
+        );
+
+        // Windows larger than the monitor are shrunk to fit
+        assert_eq!(
+            bounds(-50, 10, 3000, 2000).clamp_to(&MONITOR),
+            bounds(0, 0, 1920, 1080)
+        );
+
+        // Monitors that don't start at the origin
+        let left = bounds(-1280, 200, 1280, 1024);
+        assert_eq!(
+            bounds(-5000, 0, 800, 600).clamp_to(&left),
+            bounds(-1280, 200, 800, 600)
+        );
+    }
+
+    #[test]
+    fn multiple_monitors() {
+        let left = bounds(-1280, 0, 1280, 1024);
+        let monitors = [MONITOR, left];
+
+        // A window on the second monitor stays where it is
+        let on_left = bounds(-1000, 100, 800, 600);
+        assert_eq!(on_left.keep_on_screen(&monitors, Some(&MONITOR)), on_left);
+
+        // A window spanning both monitors stays where it is
+        let spanning = bounds(-400, 100, 800, 600);
+        assert_eq!(spanning.keep_on_screen(&monitors, Some(&MONITOR)), spanning);
+
+        // A window on a monitor that was disconnected moves onto the fallback monitor
+        assert_eq!(
+            bounds(2500, 100, 800, 600).keep_on_screen(&monitors, Some(&MONITOR)),
+            bounds(1120, 100, 800, 600)
+        );
+        assert_eq!(
+            on_left.keep_on_screen(&[MONITOR], Some(&MONITOR)),
+            bounds(0, 100, 800, 600)
+        );
+
+        // Without any monitor to fall back to, the bounds are left alone
+        let off_screen = bounds(5000, 100, 800, 600);
+        assert_eq!(off_screen.keep_on_screen(&[], None), off_screen);
+    }
+}
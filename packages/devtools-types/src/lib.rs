@@ -25,6 +25,9 @@ pub enum DevserverMsg {
 
     /// The program is shutting down completely - maybe toss up a splash screen or something?
     Shutdown,
+
+    /// The devserver wants a snapshot of the reactive graph, which the client answers with a [`ClientMsg::ReactiveGraph`]
+    RequestReactiveGraph,
 }
 
 /// A message the client sends from the frontend to the devserver
@@ -37,6 +40,47 @@ pub enum ClientMsg {
         level: String,
         messages: Vec<String>,
     },
+
+    /// A snapshot of the app's signals, memos and their subscribers, sent in response to a
+    /// [`DevserverMsg::RequestReactiveGraph`]. Only debug builds with the `reactive-graph` feature record the reactive graph.
+    ReactiveGraph(ReactiveGraphSnapshot),
+}
+
+/// A serializable snapshot of the reactive graph of an app
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReactiveGraphSnapshot {
+    pub nodes: Vec<ReactiveNodeSnapshot>,
+}
+
+/// A signal or memo in a [`ReactiveGraphSnapshot`]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReactiveNodeSnapshot {
+    pub id: u64,
+    pub is_memo: bool,
+    pub type_name: String,
+    pub created_at: String,
+    pub origin_scope: ScopeSnapshot,
+    pub writes: u64,
+    pub last_written_at: Option<String>,
+    pub subscribers: Vec<ReactiveSubscriberSnapshot>,
+
+    /// Every scope that rerenders when this node is written to, including scopes that read it through memos
+    pub dirtied_scopes: Vec<ScopeSnapshot>,
+}
+
+/// A reactive context that is subscribed to a [`ReactiveNodeSnapshot`]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ReactiveSubscriberSnapshot {
+    pub created_at: Option<String>,
+    pub rerendered_scope: Option<ScopeSnapshot>,
+    pub memo: Option<u64>,
+}
+
+/// A scope and the name of the component it renders, if it is still mounted
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScopeSnapshot {
+    pub id: usize,
+    pub name: Option<String>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
@@ -0,0 +1,148 @@
+use crate::DevserverMsg;
+use std::panic::AssertUnwindSafe;
+use std::time::Instant;
+
+/// Run a list of tests, re-running them whenever `dx test` hotpatches the process.
+///
+/// This is a test harness for test targets with `harness = false`. Unlike the default libtest harness which exits
+/// after running the tests once, this harness stays alive when launched by `dx test` so changes to the tests and the
+/// code they call are hotpatched into the running process and the tests re-run in milliseconds.
+///
+/// Outside of `dx test`, like with `cargo test`, the tests run once and the process exits with an error if any of
+/// them failed.
+///
+/// ```toml
+/// [[test]]
+/// name = "components"
+/// harness = false
+/// ```
+///
+/// ```rust, ignore
+/// fn main() {
+///     dioxus_devtools::run_tests(&[
+///         ("renders_title", renders_title),
+///         ("counts_clicks", counts_clicks),
+///     ]);
+/// }
+/// ```
+///
+/// Arguments passed to the binary filter the tests by name, like with libtest.
+pub fn run_tests(tests: &[(&'static str, fn())]) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let tests = select_tests(tests, &args);
+
+    let Some(endpoint) = dioxus_cli_config::devserver_ws_endpoint() else {
+        if !run_once(&tests) {
+            std::process::exit(101);
+        }
+        return;
+    };
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    crate::connect_at(endpoint, move |msg| {
+        if let DevserverMsg::HotReload(hot_reload_msg) = msg {
+            if let Some(jumptable) = hot_reload_msg.jump_table {
+                if hot_reload_msg.for_pid == Some(std::process::id()) {
+                    unsafe { subsecond::apply_patch(jumptable).unwrap() };
+                    _ = tx.send(());
+                }
+            }
+        }
+    });
+
+    run_once(&tests);
+    while rx.recv().is_ok() {
+        // Patches often arrive in bursts when multiple files are saved at once
+        while rx.try_recv().is_ok() {}
+        println!();
+        run_once(&tests);
+    }
+}
+
+/// The tests whose names contain any of the filters in the arguments. Flags like `--nocapture` are ignored.
+fn select_tests<'a>(
+    tests: &'a [(&'static str, fn())],
+    args: &[String],
+) -> Vec<&'a (&'static str, fn())> {
+    let filters: Vec<&String> = args.iter().filter(|arg| !arg.starts_with('-')).collect();
+    tests
+        .iter()
+        .filter(|(name, _)| filters.is_empty() || filters.iter().any(|f| name.contains(f.as_str())))
+        .collect()
+}
+
+/// Run the tests, going through the jump table so the latest version of each test is used
+fn run_once(tests: &[&(&'static str, fn())]) -> bool {
+    let started = Instant::now();
+    println!("running {} tests", tests.len());
+
+    let mut failed = vec![];
+    for (name, test) in tests {
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| subsecond::call(*test)));
+        match result {
+            Ok(()) => println!("test {name} ... ok"),
+            Err(_) => {
+                println!("test {name} ... FAILED");
+                failed.push(*name);
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        println!("\nfailures:");
+        for name in failed.iter() {
+            println!("    {name}");
+        }
+    }
+
+    println!(
+        "\ntest result: {}. {} passed; {} failed; finished in {:.2}s",
+        if failed.is_empty() { "ok" } else { "FAILED" },
+        tests.len() - failed.len(),
+        failed.len(),
+        started.elapsed().as_secs_f32()
+    );
+
+    failed.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passes() {}
+
+    fn fails() {
+        panic!("expected failure");
+    }
+
+    const TESTS: &[(&str, fn())] = &[
+        ("renders_title", passes),
+        ("renders_footer", passes),
+        ("counts_clicks", fails),
+    ];
+
+    #[test]
+    fn tests_are_filtered_by_name() {
+        let names = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            select_tests(TESTS, &args)
+                .into_iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(&["--nocapture"]).len(), 3);
+        assert_eq!(names(&["renders"]), ["renders_title", "renders_footer"]);
+        assert_eq!(
+            names(&["title", "clicks", "--nocapture"]),
+            ["renders_title", "counts_clicks"]
+        );
+    }
+
+    #[test]
+    fn failing_tests_fail_the_run() {
+        assert!(run_once(&select_tests(&TESTS[..2], &[])));
+        assert!(!run_once(&select_tests(TESTS, &[])));
+    }
+}
@@ -1,11 +1,16 @@
 use dioxus_core::internal::HotReloadedTemplate;
 use dioxus_core::{ScopeId, VirtualDom};
-use dioxus_signals::{GlobalKey, Signal, WritableExt};
+use dioxus_signals::{GlobalKey, ReactiveGraph, ReactiveNodeKind, Signal, WritableExt};
 
 pub use dioxus_devtools_types::*;
 pub use subsecond;
 use subsecond::PatchError;
 
+#[cfg(not(target_family = "wasm"))]
+mod harness;
+#[cfg(not(target_family = "wasm"))]
+pub use harness::*;
+
 /// Applies template and literal changes to the VirtualDom
 ///
 /// Assets need to be handled by the renderer.
@@ -54,6 +59,48 @@ pub fn try_apply_changes(dom: &VirtualDom, msg: &HotReloadMsg) -> Result<(), Pat
     })
 }
 
+/// Take a snapshot of the signals and memos in the VirtualDom to send to the devserver as a [`ClientMsg::ReactiveGraph`].
+///
+/// The reactive graph is only recorded in debug builds with the `reactive-graph` feature. Otherwise the snapshot is always empty.
+pub fn reactive_graph(dom: &VirtualDom) -> ReactiveGraphSnapshot {
+    let runtime = dom.runtime();
+    let scope = |id: ScopeId| ScopeSnapshot {
+        id: id.0,
+        name: runtime.scope_name(id).map(str::to_string),
+    };
+
+    let graph = ReactiveGraph::snapshot_of(&runtime);
+    let nodes = graph
+        .nodes
+        .iter()
+        .map(|node| ReactiveNodeSnapshot {
+            id: node.id,
+            is_memo: node.kind == ReactiveNodeKind::Memo,
+            type_name: node.type_name.to_string(),
+            created_at: node.created_at.to_string(),
+            origin_scope: scope(node.origin_scope),
+            writes: node.writes,
+            last_written_at: node.last_written_at.map(|location| location.to_string()),
+            subscribers: node
+                .subscribers
+                .iter()
+                .map(|subscriber| ReactiveSubscriberSnapshot {
+                    created_at: subscriber.created_at.map(|location| location.to_string()),
+                    rerendered_scope: subscriber.rerendered_scope.map(scope),
+                    memo: subscriber.memo,
+                })
+                .collect(),
+            dirtied_scopes: graph
+                .dirtied_scopes(node.id)
+                .into_iter()
+                .map(scope)
+                .collect(),
+        })
+        .collect();
+
+    ReactiveGraphSnapshot { nodes }
+}
+
 /// Connect to the devserver and handle its messages with a callback.
 ///
 /// This doesn't use any form of security or protocol, so it's not safe to expose to the internet.
@@ -100,16 +147,56 @@ pub fn connect_at(endpoint: String, mut callback: impl FnMut(DevserverMsg) + Sen
             Err(_) => return,
         };
 
-        while let Ok(msg) = websocket.read() {
-            if let tungstenite::Message::Text(text) = msg {
-                if let Ok(msg) = serde_json::from_str(&text) {
-                    callback(msg);
+        // Reads time out so messages queued with `send` go out even while the devserver is quiet
+        if let tungstenite::stream::MaybeTlsStream::Plain(stream) = websocket.get_mut() {
+            _ = stream.set_read_timeout(Some(std::time::Duration::from_millis(100)));
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        *CLIENT_SENDER.lock().unwrap() = Some(tx);
+
+        loop {
+            for msg in rx.try_iter() {
+                let Ok(text) = serde_json::to_string(&msg) else {
+                    continue;
+                };
+                if websocket.send(tungstenite::Message::text(text)).is_err() {
+                    return;
+                }
+            }
+
+            match websocket.read() {
+                Ok(tungstenite::Message::Text(text)) => {
+                    if let Ok(msg) = serde_json::from_str(&text) {
+                        callback(msg);
+                    }
                 }
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(err))
+                    if matches!(
+                        err.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) => {}
+                Err(_) => return,
             }
         }
     });
 }
 
+/// Send a message to the devserver over the connection opened by [`connect`]. Messages sent before the connection is
+/// open are dropped.
+#[cfg(not(target_family = "wasm"))]
+pub fn send(msg: ClientMsg) {
+    if let Some(tx) = CLIENT_SENDER.lock().unwrap().as_ref() {
+        _ = tx.send(msg);
+    }
+}
+
+/// The queue of messages for the devserver connection opened by [`connect`]
+#[cfg(not(target_family = "wasm"))]
+static CLIENT_SENDER: std::sync::Mutex<Option<std::sync::mpsc::Sender<ClientMsg>>> =
+    std::sync::Mutex::new(None);
+
 /// Run this asynchronous future to completion.
 ///
 /// Whenever your code changes, the future is dropped and a new one is created using the new function.
@@ -219,6 +219,10 @@ pub mod prelude {
     #[cfg_attr(docsrs, doc(cfg(feature = "router")))]
     pub use dioxus_router;
 
+    #[cfg(any(feature = "desktop", feature = "mobile"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "desktop", feature = "mobile"))))]
+    pub use dioxus_desktop;
+
     #[cfg(feature = "router")]
     #[cfg_attr(docsrs, doc(cfg(feature = "router")))]
     #[doc(inline)]
@@ -78,6 +78,9 @@ pub use use_reactive::*;
 mod use_resource;
 pub use use_resource::*;
 
+mod use_async_memo;
+pub use use_async_memo::*;
+
 mod use_effect;
 pub use use_effect::*;
 
@@ -102,5 +105,11 @@ pub use use_after_suspense_resolved::*;
 mod use_action;
 pub use use_action::*;
 
+mod use_query;
+pub use use_query::*;
+
+mod use_transition;
+pub use use_transition::*;
+
 mod use_waker;
 pub use use_waker::*;
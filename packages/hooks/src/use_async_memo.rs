@@ -0,0 +1,358 @@
+use crate::{use_callback, use_signal};
+use dioxus_core::{
+    spawn, use_hook, Callback, IntoAttributeValue, IntoDynNode, ReactiveContext, RenderError,
+    Subscribers, SuspendedFuture, Task,
+};
+use dioxus_signals::*;
+use futures_util::{
+    future::{self, Either},
+    pin_mut, FutureExt, StreamExt,
+};
+use std::{cell::Cell, future::Future, ops::Deref, rc::Rc, time::Duration};
+
+/// Create a memo that is computed by a future. The future is rerun every time a signal it reads changes.
+///
+/// Unlike [`crate::use_resource`], the async memo keeps the last value it computed while a new value is being computed, and
+/// any computation that is still running when a dependency changes is cancelled so stale results are never written.
+///
+/// Signals read anywhere inside the future, including after an `.await`, are tracked as dependencies.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # async fn search(query: &str) -> Vec<String> { vec![] }
+/// fn Search() -> Element {
+///     let mut query = use_signal(String::new);
+///     // Every time the query changes, the old search is cancelled and a new one starts. The old results are shown until the new results are ready.
+///     let results = use_async_memo(move || async move { search(&query()).await });
+///
+///     rsx! {
+///         input { oninput: move |event| query.set(event.value()) }
+///         if results.pending() {
+///             "Searching..."
+///         }
+///         for result in results().unwrap_or_default() {
+///             div { "{result}" }
+///         }
+///     }
+/// }
+/// ```
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+#[doc = include_str!("../docs/moving_state_around.md")]
+#[track_caller]
+pub fn use_async_memo<T, F>(future: impl FnMut() -> F + 'static) -> AsyncMemo<T>
+where
+    T: PartialEq + 'static,
+    F: Future<Output = T> + 'static,
+{
+    use_async_memo_with_caller(
+        AsyncMemoOptions::new(),
+        future,
+        std::panic::Location::caller(),
+    )
+}
+
+/// Create an async memo with options that control when the future is rerun after a dependency changes.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use std::time::Duration;
+/// # async fn search(query: &str) -> Vec<String> { vec![] }
+/// fn Search() -> Element {
+///     let mut query = use_signal(String::new);
+///     // Wait until the user stops typing for 300ms before searching
+///     let results = use_async_memo_with_options(
+///         AsyncMemoOptions::new().debounce(Duration::from_millis(300)),
+///         move || async move { search(&query()).await },
+///     );
+///
+///     rsx! {
+///         input { oninput: move |event| query.set(event.value()) }
+///         for result in results().unwrap_or_default() {
+///             div { "{result}" }
+///         }
+///     }
+/// }
+/// ```
+#[track_caller]
+pub fn use_async_memo_with_options<T, F>(
+    options: AsyncMemoOptions,
+    future: impl FnMut() -> F + 'static,
+) -> AsyncMemo<T>
+where
+    T: PartialEq + 'static,
+    F: Future<Output = T> + 'static,
+{
+    use_async_memo_with_caller(options, future, std::panic::Location::caller())
+}
+
+fn use_async_memo_with_caller<T, F>(
+    options: AsyncMemoOptions,
+    mut future: impl FnMut() -> F + 'static,
+    location: &'static std::panic::Location<'static>,
+) -> AsyncMemo<T>
+where
+    T: PartialEq + 'static,
+    F: Future<Output = T> + 'static,
+{
+    let mut value = use_signal(|| None);
+    let mut state = use_signal(|| AsyncMemoState::Pending);
+    let (rc, changed) = use_hook(|| {
+        let (rc, changed) = ReactiveContext::new_with_origin(location);
+        (rc, Rc::new(Cell::new(Some(changed))))
+    });
+
+    let cb = use_callback(move |_| {
+        state.set(AsyncMemoState::Pending);
+
+        // Create the user's future, subscribing to anything read before the first await
+        let fut = rc.reset_and_run_in(&mut future);
+
+        spawn(async move {
+            let fut = fut;
+            pin_mut!(fut);
+
+            // Run each poll in the reactive context so reads after an await are tracked too
+            let res = future::poll_fn(|cx| {
+                rc.run_in(|| {
+                    tracing::trace_span!("polling async memo", location = %location)
+                        .in_scope(|| fut.poll_unpin(cx))
+                })
+            })
+            .await;
+
+            // Only notify subscribers if the value actually changed
+            if value.peek().as_ref() != Some(&res) {
+                value.set(Some(res));
+            }
+            state.set(AsyncMemoState::Ready);
+        })
+    });
+
+    let mut task = use_hook(|| Signal::new(cb(())));
+
+    use_hook(|| {
+        let mut changed = changed.take().unwrap();
+        spawn(async move {
+            while changed.next().await.is_some() {
+                match options.schedule {
+                    AsyncMemoSchedule::Immediate => {
+                        // Stop the old computation and start a new one
+                        task.peek().cancel();
+                        task.set(cb(()));
+                    }
+                    AsyncMemoSchedule::Debounce(delay) => {
+                        // The running computation is already stale, so stop it before waiting for the dependencies to settle
+                        task.peek().cancel();
+                        state.set(AsyncMemoState::Pending);
+                        loop {
+                            let sleep = futures_timer::Delay::new(delay);
+                            match future::select(sleep, changed.next()).await {
+                                Either::Left(_) => break,
+                                Either::Right((Some(_), _)) => continue,
+                                Either::Right((None, _)) => return,
+                            }
+                        }
+
+                        task.set(cb(()));
+                    }
+                    AsyncMemoSchedule::Throttle(interval) => loop {
+                        task.peek().cancel();
+                        task.set(cb(()));
+                        futures_timer::Delay::new(interval).await;
+
+                        // Every change during the interval is combined into one rerun that reads the latest values
+                        let mut changed_during_interval = false;
+                        while let Ok(Some(())) = changed.try_next() {
+                            changed_during_interval = true;
+                        }
+                        if !changed_during_interval {
+                            break;
+                        }
+                    },
+                }
+            }
+        })
+    });
+
+    AsyncMemo {
+        task,
+        value,
+        state,
+        callback: cb,
+    }
+}
+
+/// Options for [`use_async_memo_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AsyncMemoOptions {
+    schedule: AsyncMemoSchedule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum AsyncMemoSchedule {
+    #[default]
+    Immediate,
+    Debounce(Duration),
+    Throttle(Duration),
+}
+
+impl AsyncMemoOptions {
+    /// Create options that rerun the future as soon as a dependency changes.
+    pub const fn new() -> Self {
+        Self {
+            schedule: AsyncMemoSchedule::Immediate,
+        }
+    }
+
+    /// Wait until the dependencies have not changed for `delay` before rerunning the future.
+    ///
+    /// Any computation that is running when a dependency changes is cancelled immediately.
+    pub const fn debounce(mut self, delay: Duration) -> Self {
+        self.schedule = AsyncMemoSchedule::Debounce(delay);
+        self
+    }
+
+    /// Rerun the future at most once every `interval`. Changes during the interval are combined into one rerun at the end of the interval.
+    pub const fn throttle(mut self, interval: Duration) -> Self {
+        self.schedule = AsyncMemoSchedule::Throttle(interval);
+        self
+    }
+}
+
+/// The state of an [`AsyncMemo`]'s computation.
+#[derive(Clone, Copy, PartialEq, Hash, Eq, Debug)]
+pub enum AsyncMemoState {
+    /// The value is being computed or is waiting for a debounce or throttle timer. The previous value is still available if there is one.
+    Pending,
+
+    /// The value is up to date with its dependencies.
+    Ready,
+}
+
+/// A handle to a memo computed by a future created with [`use_async_memo`].
+///
+/// Reading the memo returns `None` until the first computation finishes. After that, the memo always holds the latest finished value,
+/// even while a new value is being computed.
+#[derive(Debug)]
+pub struct AsyncMemo<T: 'static> {
+    value: Signal<Option<T>>,
+    task: Signal<Task>,
+    state: Signal<AsyncMemoState>,
+    callback: Callback<(), Task>,
+}
+
+impl<T> AsyncMemo<T> {
+    /// Cancel the running computation and start a new one, even if no dependencies changed.
+    pub fn restart(&mut self) {
+        self.task.peek().cancel();
+        let new_task = self.callback.call(());
+        self.task.set(new_task);
+    }
+
+    /// Get the current state of the computation. This method returns a [`ReadSignal`] which can be read to get the current state or passed to other hooks and components.
+    pub fn state(&self) -> ReadSignal<AsyncMemoState> {
+        self.state.into()
+    }
+
+    /// Check if a new value is being computed. Reading this subscribes to the state of the memo.
+    pub fn pending(&self) -> bool {
+        self.state.cloned() == AsyncMemoState::Pending
+    }
+
+    /// Get the latest value of the memo. This method returns a [`ReadSignal`] which can be read to get the current value or passed to other hooks and components.
+    pub fn value(&self) -> ReadSignal<Option<T>> {
+        self.value.into()
+    }
+
+    /// Get a handle to the task that is computing the current value.
+    pub fn task(&self) -> Task {
+        self.task.cloned()
+    }
+
+    /// Suspend rendering until the first value is ready.
+    ///
+    /// Once a value has been computed, this never suspends again. Later computations keep showing the previous value until they finish.
+    pub fn suspend(&self) -> std::result::Result<MappedSignal<T, Signal<Option<T>>>, RenderError> {
+        // Read the task so the component rerenders and suspends on the new task if the computation restarts before it finishes
+        let task = self.task.cloned();
+        if self.value.read().is_some() {
+            Ok(self.value.map(|v| v.as_ref().unwrap()))
+        } else {
+            Err(RenderError::Suspended(SuspendedFuture::new(task)))
+        }
+    }
+}
+
+impl<T> PartialEq for AsyncMemo<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+            && self.state == other.state
+            && self.task == other.task
+            && self.callback == other.callback
+    }
+}
+
+impl<T> Clone for AsyncMemo<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for AsyncMemo<T> {}
+
+impl<T> From<AsyncMemo<T>> for ReadSignal<Option<T>> {
+    fn from(val: AsyncMemo<T>) -> Self {
+        val.value.into()
+    }
+}
+
+impl<T> Readable for AsyncMemo<T> {
+    type Target = Option<T>;
+    type Storage = UnsyncStorage;
+
+    #[track_caller]
+    fn try_read_unchecked(
+        &self,
+    ) -> Result<ReadableRef<'static, Self>, generational_box::BorrowError> {
+        self.value.try_read_unchecked()
+    }
+
+    #[track_caller]
+    fn try_peek_unchecked(
+        &self,
+    ) -> Result<ReadableRef<'static, Self>, generational_box::BorrowError> {
+        self.value.try_peek_unchecked()
+    }
+
+    fn subscribers(&self) -> Subscribers {
+        self.value.subscribers()
+    }
+}
+
+impl<T> IntoAttributeValue for AsyncMemo<T>
+where
+    T: Clone + IntoAttributeValue,
+{
+    fn into_value(self) -> dioxus_core::AttributeValue {
+        self.with(|f| f.clone().into_value())
+    }
+}
+
+impl<T> IntoDynNode for AsyncMemo<T>
+where
+    T: Clone + IntoDynNode,
+{
+    fn into_dyn_node(self) -> dioxus_core::DynamicNode {
+        self().into_dyn_node()
+    }
+}
+
+/// Allow calling a signal with signal() syntax
+///
+/// Currently only limited to copy types, though could probably specialize for string/arc/rc
+impl<T: Clone> Deref for AsyncMemo<T> {
+    type Target = dyn Fn() -> Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { ReadableExt::deref_impl(self) }
+    }
+}
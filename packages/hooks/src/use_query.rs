@@ -0,0 +1,856 @@
+use crate::{use_callback, use_effect, use_root_context, Action};
+use dioxus_core::{
+    spawn_forever, use_drop, use_hook, Callback, CapturedError, IntoDynNode, RenderError, ScopeId,
+    Subscribers, SuspendedFuture, Task,
+};
+use dioxus_signals::*;
+use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures_util::StreamExt;
+use std::{
+    any::Any, cell::RefCell, collections::HashMap, future::Future, ops::Deref, rc::Rc,
+    time::Duration,
+};
+
+/// A key that identifies a query in the [`QueryClient`] cache.
+///
+/// Keys are made of segments. Invalidating a key also invalidates every key that starts with the same segments, so
+/// invalidating `"todos"` invalidates both `["todos", "1"]` and `["todos", "2"]`.
+///
+/// ```rust
+/// # use dioxus::prelude::*;
+/// let all_todos = QueryKey::from("todos");
+/// let todo = QueryKey::from(["todos", "1"]);
+/// assert!(todo.starts_with(&all_todos));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct QueryKey(Rc<[String]>);
+
+impl QueryKey {
+    /// Create a new key from a list of segments.
+    pub fn new(segments: impl IntoIterator<Item = impl ToString>) -> Self {
+        Self(segments.into_iter().map(|s| s.to_string()).collect())
+    }
+
+    /// Get the segments of the key.
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Check if the key starts with all of the segments in the prefix.
+    pub fn starts_with(&self, prefix: &QueryKey) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+}
+
+impl From<&str> for QueryKey {
+    fn from(key: &str) -> Self {
+        Self::new([key])
+    }
+}
+
+impl From<String> for QueryKey {
+    fn from(key: String) -> Self {
+        Self::new([key])
+    }
+}
+
+impl<T: ToString, const N: usize> From<[T; N]> for QueryKey {
+    fn from(segments: [T; N]) -> Self {
+        Self::new(segments)
+    }
+}
+
+impl<T: ToString> From<Vec<T>> for QueryKey {
+    fn from(segments: Vec<T>) -> Self {
+        Self::new(segments)
+    }
+}
+
+/// Options that control how long a query is cached and when it is refetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryOptions {
+    stale_time: Duration,
+    cache_time: Duration,
+    refetch_on_window_focus: bool,
+    refetch_on_reconnect: bool,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueryOptions {
+    /// Create the default options. Data is stale as soon as it is fetched, unused data is kept for five minutes, and stale
+    /// queries are refetched when the window is focused or the network reconnects.
+    pub const fn new() -> Self {
+        Self {
+            stale_time: Duration::ZERO,
+            cache_time: Duration::from_secs(5 * 60),
+            refetch_on_window_focus: true,
+            refetch_on_reconnect: true,
+        }
+    }
+
+    /// Set how long fetched data is considered fresh. Fresh data is never refetched when a new component uses the query,
+    /// the window is focused or the network reconnects. Use [`Duration::MAX`] to keep data fresh until it is invalidated.
+    pub const fn stale_time(mut self, stale_time: Duration) -> Self {
+        self.stale_time = stale_time;
+        self
+    }
+
+    /// Set how long data is kept in the cache after the last component using the query is dropped. Use [`Duration::MAX`]
+    /// to keep data in the cache forever.
+    pub const fn cache_time(mut self, cache_time: Duration) -> Self {
+        self.cache_time = cache_time;
+        self
+    }
+
+    /// Set if stale queries are refetched when the window is focused.
+    pub const fn refetch_on_window_focus(mut self, refetch: bool) -> Self {
+        self.refetch_on_window_focus = refetch;
+        self
+    }
+
+    /// Set if stale queries are refetched when the network reconnects.
+    pub const fn refetch_on_reconnect(mut self, refetch: bool) -> Self {
+        self.refetch_on_reconnect = refetch;
+        self
+    }
+}
+
+/// A cache of queries shared by every component in the app. Queries with the same key share their data and only one
+/// request for a key is in flight at a time.
+///
+/// Get the client for the current app with [`use_query_client`].
+#[derive(Clone)]
+pub struct QueryClient {
+    inner: Rc<RefCell<QueryCache>>,
+    events: UnboundedSender<QueryEvent>,
+}
+
+impl Default for QueryClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for QueryClient {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+struct QueryCache {
+    entries: HashMap<QueryKey, QueryEntry>,
+    default_options: QueryOptions,
+    // The id of the next component that uses a query
+    next_observer: usize,
+}
+
+struct QueryEntry {
+    data: Box<dyn QueryData>,
+    fetching: Signal<bool>,
+    stale: bool,
+    task: Option<Task>,
+    options: QueryOptions,
+    // The components that use this query with their fetchers, oldest first. The fetcher of the newest one is used to
+    // refetch the query in the background.
+    observers: Vec<(usize, RefetchQuery)>,
+    // Incremented every time the data is written so old stale timers can be ignored
+    data_generation: u64,
+    // Incremented every time an observer is dropped so old cache timers can be ignored
+    observer_generation: u64,
+    // Set when the data was written with `set_query_data` before any observer attached. The data stays fresh until one does.
+    hydrated: bool,
+}
+
+type RefetchQuery = Rc<dyn Fn(&QueryClient, &QueryKey)>;
+
+trait QueryData {
+    fn as_any(&self) -> &dyn Any;
+
+    fn manually_drop(&self);
+}
+
+impl<T: 'static> QueryData for Signal<Option<T>> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn manually_drop(&self) {
+        Signal::manually_drop(self)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum QueryEvent {
+    WindowFocused,
+    Reconnected,
+}
+
+impl QueryClient {
+    /// Create a new query client with the default [`QueryOptions`]. This must be called inside the dioxus runtime.
+    pub fn new() -> Self {
+        Self::with_options(QueryOptions::new())
+    }
+
+    /// Create a new query client with the options used by queries that don't set their own. This must be called inside the dioxus runtime.
+    pub fn with_options(default_options: QueryOptions) -> Self {
+        let (events, rx) = futures_channel::mpsc::unbounded();
+        let client = Self {
+            inner: Rc::new(RefCell::new(QueryCache {
+                entries: HashMap::new(),
+                default_options,
+                next_observer: 0,
+            })),
+            events,
+        };
+
+        spawn_forever(client.clone().handle_events(rx));
+
+        #[cfg(target_arch = "wasm32")]
+        listen_for_window_events(client.events.clone());
+
+        client
+    }
+
+    /// Get the options used by queries that don't set their own.
+    pub fn default_options(&self) -> QueryOptions {
+        self.inner.borrow().default_options
+    }
+
+    /// Get the cached data for a query without subscribing to it.
+    pub fn get_query_data<T: Clone + 'static>(&self, key: impl Into<QueryKey>) -> Option<T> {
+        let key = key.into();
+        let inner = self.inner.borrow();
+        let entry = inner.entries.get(&key)?;
+        let data = Self::downcast::<T>(&key, entry);
+        let value = data.peek().clone();
+        value
+    }
+
+    /// Write data into the cache for a query and mark it as fresh.
+    ///
+    /// If no component uses the query yet, the data stays fresh until the first component starts using it, even if the
+    /// stale time is zero. From then on it goes stale after the stale time of that component's query. This lets you
+    /// update the cache after a mutation or hydrate the cache with data loaded on the server without fetching it again:
+    ///
+    /// ```rust, ignore
+    /// # use dioxus::prelude::*;
+    /// # fn load_user() -> String { todo!() }
+    /// # async fn fetch_user() -> String { todo!() }
+    /// fn User() -> Element {
+    ///     let client = use_query_client();
+    ///     // Load the user on the server and send it to the client with the rest of the html
+    ///     let initial = use_server_cached(load_user);
+    ///     use_hook(|| client.set_query_data("user", initial));
+    ///     // The query uses the hydrated data instead of fetching it again
+    ///     let user = use_query("user", fetch_user);
+    ///
+    ///     rsx! { "{user:?}" }
+    /// }
+    /// ```
+    pub fn set_query_data<T: 'static>(&self, key: impl Into<QueryKey>, value: T) {
+        let key = key.into();
+        let options = self.default_options();
+        let mut data = self.entry::<T>(&key, options).0;
+        data.set(Some(value));
+        self.mark_fresh(&key);
+        if let Some(entry) = self.inner.borrow_mut().entries.get_mut(&key) {
+            if entry.observers.is_empty() {
+                entry.stale = false;
+                entry.hydrated = true;
+            }
+        }
+    }
+
+    /// Mark every query that starts with the key as stale. Queries that are currently used by a component are refetched in the background.
+    pub fn invalidate(&self, prefix: impl Into<QueryKey>) {
+        let prefix = prefix.into();
+        let refetch: Vec<_> = {
+            let mut inner = self.inner.borrow_mut();
+            inner
+                .entries
+                .iter_mut()
+                .filter(|(key, _)| key.starts_with(&prefix))
+                .filter_map(|(key, entry)| {
+                    entry.stale = true;
+                    let (_, refetch) = entry.observers.last()?;
+                    Some((key.clone(), refetch.clone()))
+                })
+                .collect()
+        };
+
+        for (key, refetch) in refetch {
+            refetch(self, &key);
+        }
+    }
+
+    /// Remove every query that starts with the key from the cache. Queries that are still used by a component are
+    /// [invalidated](QueryClient::invalidate) instead, so those components keep sharing one cache entry.
+    pub fn remove(&self, prefix: impl Into<QueryKey>) {
+        let prefix = prefix.into();
+        let removed: Vec<_> = {
+            let mut inner = self.inner.borrow_mut();
+            let keys: Vec<_> = inner
+                .entries
+                .iter()
+                .filter(|(key, entry)| key.starts_with(&prefix) && entry.observers.is_empty())
+                .map(|(key, _)| key.clone())
+                .collect();
+            keys.into_iter()
+                .filter_map(|key| inner.entries.remove(&key))
+                .collect()
+        };
+
+        for entry in removed {
+            Self::drop_entry(entry);
+        }
+
+        self.invalidate(prefix);
+    }
+
+    /// Refetch stale queries that opted into [`QueryOptions::refetch_on_window_focus`].
+    ///
+    /// On the web this is called automatically when the window is focused. Other renderers can call it when their window gains focus.
+    pub fn window_focused(&self) {
+        _ = self.events.unbounded_send(QueryEvent::WindowFocused);
+    }
+
+    /// Refetch stale queries that opted into [`QueryOptions::refetch_on_reconnect`].
+    ///
+    /// On the web this is called automatically when the browser goes back online. Other renderers can call it when the network reconnects.
+    pub fn reconnected(&self) {
+        _ = self.events.unbounded_send(QueryEvent::Reconnected);
+    }
+
+    async fn handle_events(self, mut rx: UnboundedReceiver<QueryEvent>) {
+        while let Some(event) = rx.next().await {
+            let refetch: Vec<_> = {
+                let inner = self.inner.borrow();
+                inner
+                    .entries
+                    .iter()
+                    .filter(|(_, entry)| {
+                        entry.stale
+                            && match event {
+                                QueryEvent::WindowFocused => entry.options.refetch_on_window_focus,
+                                QueryEvent::Reconnected => entry.options.refetch_on_reconnect,
+                            }
+                    })
+                    .filter_map(|(key, entry)| {
+                        let (_, refetch) = entry.observers.last()?;
+                        Some((key.clone(), refetch.clone()))
+                    })
+                    .collect()
+            };
+
+            for (key, refetch) in refetch {
+                refetch(&self, &key);
+            }
+        }
+    }
+
+    fn downcast<T: 'static>(key: &QueryKey, entry: &QueryEntry) -> Signal<Option<T>> {
+        match entry.data.as_any().downcast_ref::<Signal<Option<T>>>() {
+            Some(data) => *data,
+            None => panic!(
+                "The query {key:?} was used with a different type than {}. Each query key must always hold the same type.",
+                std::any::type_name::<T>()
+            ),
+        }
+    }
+
+    /// Get or create the signals for a query. The signals are owned by the root scope so they outlive the components that use them.
+    fn entry<T: 'static>(
+        &self,
+        key: &QueryKey,
+        options: QueryOptions,
+    ) -> (Signal<Option<T>>, Signal<bool>) {
+        let mut inner = self.inner.borrow_mut();
+        let entry = inner
+            .entries
+            .entry(key.clone())
+            .or_insert_with(|| QueryEntry {
+                data: Box::new(Signal::<Option<T>>::new_in_scope(None, ScopeId::ROOT)),
+                fetching: Signal::new_in_scope(false, ScopeId::ROOT),
+                stale: true,
+                task: None,
+                options,
+                observers: Vec::new(),
+                data_generation: 0,
+                observer_generation: 0,
+                hydrated: false,
+            });
+        (Self::downcast(key, entry), entry.fetching)
+    }
+
+    /// Get a unique id for a component that uses queries
+    fn observer_id(&self) -> usize {
+        let mut inner = self.inner.borrow_mut();
+        inner.next_observer += 1;
+        inner.next_observer
+    }
+
+    fn attach<T, F>(
+        &self,
+        key: &QueryKey,
+        observer: usize,
+        options: QueryOptions,
+        fetcher: Callback<(), F>,
+    ) where
+        T: 'static,
+        F: Future<Output = T> + 'static,
+    {
+        self.entry::<T>(key, options);
+        let (should_fetch, hydrated) = {
+            let mut inner = self.inner.borrow_mut();
+            let entry = inner.entries.get_mut(key).unwrap();
+            entry.options = options;
+            entry.observers.push((
+                observer,
+                Rc::new(move |client: &QueryClient, key: &QueryKey| {
+                    client.fetch::<T, F>(key, fetcher)
+                }),
+            ));
+            (entry.stale, std::mem::take(&mut entry.hydrated))
+        };
+
+        if should_fetch {
+            self.fetch::<T, F>(key, fetcher);
+        } else if hydrated {
+            // Hydrated data was only fresh until now. Restart the stale timer with the options of this query
+            self.mark_fresh(key);
+        }
+    }
+
+    fn detach(&self, key: &QueryKey, observer: usize) {
+        let mut inner = self.inner.borrow_mut();
+        let Some(entry) = inner.entries.get_mut(key) else {
+            return;
+        };
+        // The fetcher of the component is dropped with it, so it must not be used to refetch the query anymore
+        entry.observers.retain(|(id, _)| *id != observer);
+        entry.observer_generation += 1;
+        if !entry.observers.is_empty() || entry.options.cache_time == Duration::MAX {
+            return;
+        }
+
+        let generation = entry.observer_generation;
+        let cache_time = entry.options.cache_time;
+        let client = self.clone();
+        let key = key.clone();
+        spawn_forever(async move {
+            futures_timer::Delay::new(cache_time).await;
+            let removed = {
+                let mut inner = client.inner.borrow_mut();
+                match inner.entries.get(&key) {
+                    Some(entry)
+                        if entry.observers.is_empty()
+                            && entry.observer_generation == generation =>
+                    {
+                        inner.entries.remove(&key)
+                    }
+                    _ => None,
+                }
+            };
+            if let Some(entry) = removed {
+                Self::drop_entry(entry);
+            }
+        });
+    }
+
+    fn fetch<T, F>(&self, key: &QueryKey, fetcher: Callback<(), F>)
+    where
+        T: 'static,
+        F: Future<Output = T> + 'static,
+    {
+        let (mut data, mut fetching) = {
+            let inner = self.inner.borrow();
+            let Some(entry) = inner.entries.get(key) else {
+                return;
+            };
+            // Deduplicate requests by reusing the request that is already in flight
+            if entry.task.is_some() {
+                return;
+            }
+            (Self::downcast::<T>(key, entry), entry.fetching)
+        };
+
+        fetching.set(true);
+        let future = fetcher.call(());
+        let client = self.clone();
+        let task_key = key.clone();
+        let task = spawn_forever(async move {
+            let value = future.await;
+            if let Some(entry) = client.inner.borrow_mut().entries.get_mut(&task_key) {
+                entry.task = None;
+            }
+            data.set(Some(value));
+            fetching.set(false);
+            client.mark_fresh(&task_key);
+        });
+
+        if let Some(entry) = self.inner.borrow_mut().entries.get_mut(key) {
+            entry.task = Some(task);
+        }
+    }
+
+    fn mark_fresh(&self, key: &QueryKey) {
+        let mut inner = self.inner.borrow_mut();
+        let Some(entry) = inner.entries.get_mut(key) else {
+            return;
+        };
+        entry.hydrated = false;
+        entry.data_generation += 1;
+        entry.stale = entry.options.stale_time.is_zero();
+        if entry.stale || entry.options.stale_time == Duration::MAX {
+            return;
+        }
+
+        let generation = entry.data_generation;
+        let stale_time = entry.options.stale_time;
+        let client = self.clone();
+        let key = key.clone();
+        spawn_forever(async move {
+            futures_timer::Delay::new(stale_time).await;
+            if let Some(entry) = client.inner.borrow_mut().entries.get_mut(&key) {
+                if entry.data_generation == generation {
+                    entry.stale = true;
+                }
+            }
+        });
+    }
+
+    fn task(&self, key: &QueryKey) -> Option<Task> {
+        self.inner.borrow().entries.get(key)?.task
+    }
+
+    fn drop_entry(entry: QueryEntry) {
+        if let Some(task) = entry.task {
+            task.cancel();
+        }
+        entry.data.manually_drop();
+        entry.fetching.manually_drop();
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn listen_for_window_events(events: UnboundedSender<QueryEvent>) {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+
+    for (name, event) in [
+        ("focus", QueryEvent::WindowFocused),
+        ("online", QueryEvent::Reconnected),
+    ] {
+        let events = events.clone();
+        let listener = Closure::<dyn FnMut()>::new(move || {
+            _ = events.unbounded_send(event);
+        });
+        _ = window.add_event_listener_with_callback(name, listener.as_ref().unchecked_ref());
+        // The client lives as long as the app, so the listener is never removed
+        listener.forget();
+    }
+}
+
+/// Get the [`QueryClient`] for the current app. If no client was provided with `use_context_provider` or `provide_root_context`, a client with the default options is created at the root of the app.
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+pub fn use_query_client() -> QueryClient {
+    use_root_context(QueryClient::new)
+}
+
+/// Fetch data with a future and cache it in the [`QueryClient`] under a key.
+///
+/// Every component that uses the same key shares the same data and the same in-flight request. The query is refetched
+/// when the key changes, when the data is [invalidated](QueryClient::invalidate), and in the background when stale data
+/// is used by a new component, the window is focused or the network reconnects.
+///
+/// Unlike [`crate::use_resource`], the future does not subscribe to signals it reads. Include any value the request depends on in the key instead.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # async fn fetch_todo(id: u32) -> String { todo!() }
+/// #[component]
+/// fn Todo(id: u32) -> Element {
+///     // Every Todo component with the same id shares one request
+///     let todo = use_query(["todos".to_string(), id.to_string()], move || fetch_todo(id));
+///
+///     rsx! {
+///         match todo() {
+///             Some(todo) => rsx! { "{todo}" },
+///             None => rsx! { "Loading..." },
+///         }
+///     }
+/// }
+/// ```
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+pub fn use_query<T, F>(key: impl Into<QueryKey>, fetcher: impl FnMut() -> F + 'static) -> Query<T>
+where
+    T: 'static,
+    F: Future<Output = T> + 'static,
+{
+    let client = use_query_client();
+    let options = client.default_options();
+    use_query_with_client(client, key.into(), options, fetcher)
+}
+
+/// Fetch data with a future and cache it under a key with custom [`QueryOptions`]. See [`use_query`] for more details.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use std::time::Duration;
+/// # async fn fetch_settings() -> String { todo!() }
+/// fn Settings() -> Element {
+///     // Settings rarely change, so keep them fresh for a minute
+///     let settings = use_query_with_options(
+///         "settings",
+///         QueryOptions::new().stale_time(Duration::from_secs(60)),
+///         fetch_settings,
+///     );
+///
+///     rsx! { "{settings:?}" }
+/// }
+/// ```
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+pub fn use_query_with_options<T, F>(
+    key: impl Into<QueryKey>,
+    options: QueryOptions,
+    fetcher: impl FnMut() -> F + 'static,
+) -> Query<T>
+where
+    T: 'static,
+    F: Future<Output = T> + 'static,
+{
+    let client = use_query_client();
+    use_query_with_client(client, key.into(), options, fetcher)
+}
+
+fn use_query_with_client<T, F>(
+    client: QueryClient,
+    key: QueryKey,
+    options: QueryOptions,
+    mut fetcher: impl FnMut() -> F + 'static,
+) -> Query<T>
+where
+    T: 'static,
+    F: Future<Output = T> + 'static,
+{
+    let fetcher = use_callback(move |_| fetcher());
+    let mut observer = use_hook(|| {
+        CopyValue::new(QueryObserver {
+            client: client.clone(),
+            id: client.observer_id(),
+            key: None,
+        })
+    });
+
+    // Move to the new query if the key changed since the last render
+    let changed = observer.peek().key.as_ref() != Some(&key);
+    if changed {
+        let old_key = observer.write().key.replace(key.clone());
+        let id = observer.peek().id;
+        if let Some(old_key) = old_key {
+            client.detach(&old_key, id);
+        }
+        client.attach::<T, F>(&key, id, options, fetcher);
+    }
+
+    use_drop(move || {
+        if let Ok(observer) = observer.try_peek() {
+            if let Some(key) = &observer.key {
+                observer.client.detach(key, observer.id);
+            }
+        }
+    });
+
+    let (data, fetching) = client.entry::<T>(&key, options);
+
+    Query {
+        data,
+        fetching,
+        observer,
+    }
+}
+
+/// Invalidate queries every time an action finishes successfully.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # async fn fetch_todos() -> Vec<String> { todo!() }
+/// # async fn add_todo(todo: String) -> Result<()> { todo!() }
+/// fn Todos() -> Element {
+///     let todos = use_query("todos", fetch_todos);
+///     let mut add = use_action(add_todo);
+///     // Refetch the list of todos after a new todo is added
+///     use_invalidate_on_success(add, "todos");
+///
+///     rsx! {
+///         button { onclick: move |_| add.call("Buy milk".to_string()), "Add todo" }
+///         for todo in todos().unwrap_or_default() {
+///             div { "{todo}" }
+///         }
+///     }
+/// }
+/// ```
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+pub fn use_invalidate_on_success<I: 'static, O: 'static>(
+    action: Action<I, O>,
+    prefix: impl Into<QueryKey>,
+) {
+    let client = use_query_client();
+    let prefix = prefix.into();
+    let mut latest_prefix = use_hook(|| CopyValue::new(prefix.clone()));
+    if *latest_prefix.peek() != prefix {
+        latest_prefix.set(prefix);
+    }
+
+    use_effect(move || {
+        if let Some(Ok(_)) = action.result() {
+            client.invalidate(latest_prefix.cloned());
+        }
+    });
+}
+
+struct QueryObserver {
+    client: QueryClient,
+    id: usize,
+    key: Option<QueryKey>,
+}
+
+/// A handle to a cached query created with [`use_query`].
+///
+/// Reading the query returns `None` until the first request finishes. After that, the query keeps its data while it is refetched.
+pub struct Query<T: 'static> {
+    data: Signal<Option<T>>,
+    fetching: Signal<bool>,
+    observer: CopyValue<QueryObserver>,
+}
+
+impl<T> Query<T> {
+    /// Get the cached data for the query. This method returns a [`ReadSignal`] which can be read to get the current value or passed to other hooks and components.
+    pub fn value(&self) -> ReadSignal<Option<T>> {
+        self.data.into()
+    }
+
+    /// Check if a request for the query is in flight. Reading this subscribes to the fetching state of the query.
+    pub fn fetching(&self) -> bool {
+        self.fetching.cloned()
+    }
+
+    /// Get the key of the query.
+    pub fn key(&self) -> QueryKey {
+        self.observer.read().key.clone().unwrap()
+    }
+
+    /// Refetch the query in the background. If a request for the query is already in flight, no new request is made.
+    pub fn refetch(&self) {
+        let observer = self.observer.read();
+        if let Some(key) = &observer.key {
+            observer.client.invalidate(key.clone());
+        }
+    }
+
+    /// Suspend rendering until the first request for the query finishes.
+    ///
+    /// Once the query has data, this never suspends again. Refetches keep showing the cached data until they finish.
+    pub fn suspend(&self) -> std::result::Result<MappedSignal<T, Signal<Option<T>>>, RenderError> {
+        if self.data.read().is_some() {
+            return Ok(self.data.map(|v| v.as_ref().unwrap()));
+        }
+
+        let observer = self.observer.read();
+        let Some(key) = observer.key.as_ref() else {
+            return Err(RenderError::Error(CapturedError::from_display(
+                "The query was suspended before it was attached to a key",
+            )));
+        };
+        let task = match observer.client.task(key) {
+            Some(task) => task,
+            // The request finished in the same frame but data was removed from the cache. Start a new request to wait on.
+            None => {
+                observer.client.invalidate(key.clone());
+                observer.client.task(key).ok_or_else(|| {
+                    RenderError::Error(CapturedError::from_display(format!(
+                        "The query {key:?} has no data and no request could be started for it"
+                    )))
+                })?
+            }
+        };
+        Err(RenderError::Suspended(SuspendedFuture::new(task)))
+    }
+}
+
+impl<T> PartialEq for Query<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.fetching == other.fetching
+    }
+}
+
+impl<T> Clone for Query<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Query<T> {}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Query<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.data.fmt(f)
+    }
+}
+
+impl<T> From<Query<T>> for ReadSignal<Option<T>> {
+    fn from(val: Query<T>) -> Self {
+        val.data.into()
+    }
+}
+
+impl<T> Readable for Query<T> {
+    type Target = Option<T>;
+    type Storage = UnsyncStorage;
+
+    #[track_caller]
+    fn try_read_unchecked(
+        &self,
+    ) -> Result<ReadableRef<'static, Self>, generational_box::BorrowError> {
+        self.data.try_read_unchecked()
+    }
+
+    #[track_caller]
+    fn try_peek_unchecked(
+        &self,
+    ) -> Result<ReadableRef<'static, Self>, generational_box::BorrowError> {
+        self.data.try_peek_unchecked()
+    }
+
+    fn subscribers(&self) -> Subscribers {
+        self.data.subscribers()
+    }
+}
+
+impl<T> IntoDynNode for Query<T>
+where
+    T: Clone + IntoDynNode,
+{
+    fn into_dyn_node(self) -> dioxus_core::DynamicNode {
+        self().into_dyn_node()
+    }
+}
+
+/// Allow calling a signal with signal() syntax
+///
+/// Currently only limited to copy types, though could probably specialize for string/arc/rc
+impl<T: Clone> Deref for Query<T> {
+    type Target = dyn Fn() -> Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { ReadableExt::deref_impl(self) }
+    }
+}
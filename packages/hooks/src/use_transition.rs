@@ -0,0 +1,70 @@
+use crate::use_signal;
+use dioxus_core::{after_transitions, start_transition};
+use dioxus_signals::*;
+
+/// Create a handle that runs updates as low priority transitions and tracks if any transition is still rendering.
+///
+/// Components that rerender because of an update inside [`Transition::start`] keep showing their previous output until
+/// all urgent work, like updating the value of an input, has been rendered.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # #[component]
+/// # fn Table(filter: ReadSignal<String>) -> Element { VNode::empty() }
+/// fn App() -> Element {
+///     let mut input = use_signal(String::new);
+///     let mut filter = use_signal(String::new);
+///     let mut transition = use_transition();
+///
+///     rsx! {
+///         input {
+///             value: "{input}",
+///             oninput: move |event| {
+///                 input.set(event.value());
+///                 // Filtering the table is slow, so we render it after the input
+///                 transition.start(move || filter.set(event.value()));
+///             }
+///         }
+///         if transition.is_pending() {
+///             "Updating..."
+///         }
+///         Table { filter }
+///     }
+/// }
+/// ```
+#[doc = include_str!("../docs/rules_of_hooks.md")]
+#[track_caller]
+pub fn use_transition() -> Transition {
+    let pending = use_signal(|| false);
+    Transition { pending }
+}
+
+/// A handle to start transitions created with [`use_transition`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    pending: Signal<bool>,
+}
+
+impl Transition {
+    /// Run a closure as a low priority transition. Any components the closure marks dirty will only rerender once there is
+    /// no more urgent work.
+    ///
+    /// If a transition is started while a previous transition is still rendering, the old transition is replaced and the
+    /// handle stays pending until the newest transition finishes.
+    pub fn start(&mut self, f: impl FnOnce()) {
+        self.pending.set(true);
+        start_transition(f);
+        let mut pending = self.pending;
+        after_transitions(move || pending.set(false));
+    }
+
+    /// Check if a transition is still rendering. Reading this subscribes to the state of the transition.
+    pub fn is_pending(&self) -> bool {
+        self.pending.cloned()
+    }
+
+    /// Get a [`ReadSignal`] that is `true` while a transition is rendering.
+    pub fn pending(&self) -> ReadSignal<bool> {
+        self.pending.into()
+    }
+}
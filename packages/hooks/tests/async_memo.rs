@@ -0,0 +1,169 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use dioxus::prelude::*;
+
+#[tokio::test]
+async fn async_memo_cancels_stale_computations() {
+    #[derive(Default)]
+    struct State {
+        count: Option<Signal<i32>>,
+        memo: Option<AsyncMemo<i32>>,
+        finished: Vec<i32>,
+    }
+
+    fn app() -> Element {
+        let state = use_context::<Rc<RefCell<State>>>();
+        let count = use_signal(|| 0);
+        let memo = use_async_memo({
+            let state = state.clone();
+            move || {
+                let state = state.clone();
+                async move {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    // Reads after an await are tracked too
+                    let value = count() * 2;
+                    state.borrow_mut().finished.push(value);
+                    value
+                }
+            }
+        });
+        state.borrow_mut().count = Some(count);
+        state.borrow_mut().memo = Some(memo);
+
+        rsx! { "{memo:?}" }
+    }
+
+    let state = Rc::new(RefCell::new(State::default()));
+    let mut dom = VirtualDom::new(app).with_root_context(state.clone());
+    dom.rebuild_in_place();
+
+    let race = async {
+        let memo = state.borrow().memo.unwrap();
+        let mut count = state.borrow().count.unwrap();
+
+        while memo.peek().is_none() {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+        assert_eq!(*memo.peek(), Some(0));
+        dom.in_scope(ScopeId::APP, || {
+            assert_eq!(*memo.state().peek(), AsyncMemoState::Ready)
+        });
+
+        // Change the dependency twice. The first recomputation should be cancelled before it finishes
+        dom.in_runtime(|| count.set(1));
+        dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        tokio::time::timeout(Duration::from_millis(10), dom.wait_for_work())
+            .await
+            .ok();
+        dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        dom.in_runtime(|| count.set(2));
+
+        // The previous value is kept while the new value is computed
+        assert_eq!(*memo.peek(), Some(0));
+
+        while *memo.peek() != Some(4) {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+    };
+
+    tokio::select! {
+        _ = race => {},
+        _ = tokio::time::sleep(Duration::from_millis(1000)) => panic!("timed out")
+    };
+
+    assert_eq!(state.borrow().finished, vec![0, 4]);
+}
+
+#[derive(Default)]
+struct ScheduleState {
+    count: Option<Signal<i32>>,
+    memo: Option<AsyncMemo<i32>>,
+    runs: Vec<std::time::Instant>,
+}
+
+/// Render an app with an async memo that records every time its future runs
+fn scheduled_memo_dom(options: AsyncMemoOptions) -> (VirtualDom, Rc<RefCell<ScheduleState>>) {
+    fn app() -> Element {
+        let state = use_context::<Rc<RefCell<ScheduleState>>>();
+        let options = use_context::<AsyncMemoOptions>();
+        let count = use_signal(|| 0);
+        let memo = use_async_memo_with_options(options, {
+            let state = state.clone();
+            move || {
+                state.borrow_mut().runs.push(std::time::Instant::now());
+                async move { count() }
+            }
+        });
+        state.borrow_mut().count = Some(count);
+        state.borrow_mut().memo = Some(memo);
+
+        rsx! { "{memo:?}" }
+    }
+
+    let state = Rc::new(RefCell::new(ScheduleState::default()));
+    let mut dom = VirtualDom::new(app)
+        .with_root_context(state.clone())
+        .with_root_context(options);
+    dom.rebuild_in_place();
+    (dom, state)
+}
+
+/// Run the dom for a while, handling every update in the meantime
+async fn run_for(dom: &mut VirtualDom, duration: Duration) {
+    _ = tokio::time::timeout(duration, async {
+        loop {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn async_memo_debounce_collapses_bursts() {
+    let (mut dom, state) =
+        scheduled_memo_dom(AsyncMemoOptions::new().debounce(Duration::from_millis(200)));
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.borrow().runs.len(), 1);
+
+    // Every change lands well within the debounce delay of the previous one
+    let mut count = state.borrow().count.unwrap();
+    for value in 1..=5 {
+        dom.in_runtime(|| count.set(value));
+        run_for(&mut dom, Duration::from_millis(10)).await;
+    }
+    assert_eq!(state.borrow().runs.len(), 1);
+
+    run_for(&mut dom, Duration::from_millis(500)).await;
+    let memo = state.borrow().memo.unwrap();
+    assert_eq!(*memo.peek(), Some(5));
+    assert_eq!(state.borrow().runs.len(), 2);
+}
+
+#[tokio::test]
+async fn async_memo_throttle_runs_once_per_interval() {
+    let interval = Duration::from_millis(100);
+    let (mut dom, state) = scheduled_memo_dom(AsyncMemoOptions::new().throttle(interval));
+    run_for(&mut dom, Duration::from_millis(50)).await;
+    assert_eq!(state.borrow().runs.len(), 1);
+
+    // Keep changing the dependency for a few intervals
+    let mut count = state.borrow().count.unwrap();
+    for value in 1..=20 {
+        dom.in_runtime(|| count.set(value));
+        run_for(&mut dom, Duration::from_millis(10)).await;
+    }
+    run_for(&mut dom, interval * 3).await;
+
+    let memo = state.borrow().memo.unwrap();
+    assert_eq!(*memo.peek(), Some(20));
+
+    // The first change reruns the future right away and the rest are combined into one rerun per interval
+    let runs = state.borrow().runs.clone();
+    assert!(runs.len() > 2 && runs.len() < 10, "{} runs", runs.len());
+    for pair in runs[1..].windows(2) {
+        assert!(pair[1] - pair[0] >= interval);
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    cell::{Cell, RefCell},
+    time::Duration,
+};
+
+use dioxus::prelude::*;
+
+#[tokio::test]
+async fn queries_are_deduplicated_and_invalidated() {
+    thread_local! {
+        static FETCHES: Cell<usize> = const { Cell::new(0) };
+        static CLIENT: RefCell<Option<QueryClient>> = const { RefCell::new(None) };
+        static RENDERED: RefCell<Vec<Option<usize>>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn app() -> Element {
+        let client = use_query_client();
+        use_hook(|| CLIENT.with(|cell| *cell.borrow_mut() = Some(client.clone())));
+
+        rsx! {
+            Child {}
+            Child {}
+        }
+    }
+
+    #[component]
+    fn Child() -> Element {
+        let todos = use_query(["todos", "list"], || async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            FETCHES.with(|fetches| {
+                fetches.set(fetches.get() + 1);
+                fetches.get()
+            })
+        });
+        RENDERED.with(|rendered| rendered.borrow_mut().push(todos()));
+
+        rsx! { "{todos:?}" }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+
+    let race = async {
+        // Both children share one request
+        while RENDERED.with(|rendered| rendered.borrow().iter().filter(|r| r.is_some()).count()) < 2
+        {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+        assert_eq!(FETCHES.with(Cell::get), 1);
+        assert!(RENDERED.with(|rendered| rendered.borrow().iter().flatten().all(|r| *r == 1)));
+
+        // Invalidating a prefix of the key refetches the query once for both children
+        let client = CLIENT.with(|cell| cell.borrow().clone().unwrap());
+        dom.in_runtime(|| client.invalidate("todos"));
+        while client.get_query_data::<usize>(["todos", "list"]) != Some(2) {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+        assert_eq!(FETCHES.with(Cell::get), 2);
+    };
+
+    tokio::select! {
+        _ = race => {},
+        _ = tokio::time::sleep(Duration::from_millis(1000)) => panic!("timed out")
+    };
+}
+
+#[tokio::test]
+async fn removing_an_observed_query_refetches_it() {
+    thread_local! {
+        static FETCHES: Cell<usize> = const { Cell::new(0) };
+        static CLIENT: RefCell<Option<QueryClient>> = const { RefCell::new(None) };
+    }
+
+    fn app() -> Element {
+        let client = use_query_client();
+        use_hook(|| CLIENT.with(|cell| *cell.borrow_mut() = Some(client.clone())));
+        let count = use_query("count", || async move {
+            FETCHES.with(|fetches| {
+                fetches.set(fetches.get() + 1);
+                fetches.get()
+            })
+        });
+
+        rsx! { "{count:?}" }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    let client = CLIENT.with(|cell| cell.borrow().clone().unwrap());
+
+    let race = async {
+        while client.get_query_data::<usize>("count") != Some(1) {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+
+        // The component still uses the query, so it stays in the cache and is fetched again
+        dom.in_runtime(|| client.remove("count"));
+        assert_eq!(client.get_query_data::<usize>("count"), Some(1));
+        while client.get_query_data::<usize>("count") != Some(2) {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+
+        // Unused queries are evicted
+        dom.in_runtime(|| client.set_query_data("unused", 1usize));
+        dom.in_runtime(|| client.remove("unused"));
+        assert_eq!(client.get_query_data::<usize>("unused"), None);
+    };
+
+    tokio::select! {
+        _ = race => {},
+        _ = tokio::time::sleep(Duration::from_millis(1000)) => panic!("timed out")
+    };
+}
+
+#[tokio::test]
+async fn hydrated_queries_are_not_fetched_again() {
+    thread_local! {
+        static FETCHES: Cell<usize> = const { Cell::new(0) };
+        static RENDERED: RefCell<Vec<Option<usize>>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn app() -> Element {
+        let client = use_query_client();
+        use_hook(|| client.set_query_data("user", 7usize));
+        let user = use_query("user", || async move {
+            FETCHES.with(|fetches| fetches.set(fetches.get() + 1));
+            0usize
+        });
+        RENDERED.with(|rendered| rendered.borrow_mut().push(user()));
+
+        rsx! { "{user:?}" }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    tokio::time::timeout(Duration::from_millis(100), dom.wait_for_work())
+        .await
+        .ok();
+    dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+
+    assert_eq!(FETCHES.with(Cell::get), 0);
+    assert_eq!(RENDERED.with(|rendered| rendered.borrow()[0]), Some(7));
+}
+
+#[tokio::test]
+async fn invalidating_after_the_newest_observer_unmounts() {
+    thread_local! {
+        static FETCHES: Cell<usize> = const { Cell::new(0) };
+        static CLIENT: RefCell<Option<QueryClient>> = const { RefCell::new(None) };
+        static SHOW_SECOND: RefCell<Option<Signal<bool>>> = const { RefCell::new(None) };
+    }
+
+    fn app() -> Element {
+        let client = use_query_client();
+        use_hook(|| CLIENT.with(|cell| *cell.borrow_mut() = Some(client.clone())));
+        let show_second = use_signal(|| true);
+        use_hook(|| SHOW_SECOND.with(|cell| *cell.borrow_mut() = Some(show_second)));
+
+        rsx! {
+            Child {}
+            if show_second() {
+                Child {}
+            }
+        }
+    }
+
+    #[component]
+    fn Child() -> Element {
+        let count = use_query("count", || async move {
+            FETCHES.with(|fetches| {
+                fetches.set(fetches.get() + 1);
+                fetches.get()
+            })
+        });
+
+        rsx! { "{count:?}" }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    let client = CLIENT.with(|cell| cell.borrow().clone().unwrap());
+
+    let race = async {
+        while client.get_query_data::<usize>("count") != Some(1) {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+
+        // Unmount the child that attached last, which drops its fetcher
+        let mut show_second = SHOW_SECOND.with(|cell| cell.borrow().unwrap());
+        dom.in_runtime(|| show_second.set(false));
+        dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+
+        // The query is refetched with the fetcher of the child that is still mounted
+        dom.in_runtime(|| client.invalidate("count"));
+        while client.get_query_data::<usize>("count") != Some(2) {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+
+        // Focusing the window refetches the stale query with the same fetcher
+        dom.in_runtime(|| client.window_focused());
+        while client.get_query_data::<usize>("count") != Some(3) {
+            dom.wait_for_work().await;
+            dom.render_immediate(&mut dioxus::dioxus_core::NoOpMutations);
+        }
+    };
+
+    tokio::select! {
+        _ = race => {},
+        _ = tokio::time::sleep(Duration::from_millis(1000)) => panic!("timed out")
+    };
+}
@@ -94,6 +94,7 @@ macro_rules! mod_methods {
         $mod:ident;
         $fn:ident;
         $fn_html_to_rsx:ident;
+        $docs:ident;
         $(
             $(#[$attr:meta])*
             $name:ident $(: $(no-$alias:ident)? $js_name:literal)? $(in $ns:literal)?;
@@ -122,6 +123,16 @@ macro_rules! mod_methods {
             None
         }
 
+        #[cfg(feature = "element-docs")]
+        pub(crate) const $docs: &[crate::docs::AttributeDoc] = &[
+            $(
+                crate::docs::AttributeDoc {
+                    name: stringify!($name),
+                    meta: &[$(stringify!($attr)),*],
+                },
+            )*
+        ];
+
         #[cfg(feature = "html-to-rsx")]
         #[doc = "Converts an HTML attribute to an RSX attribute"]
         pub(crate) fn $fn_html_to_rsx(html: &str) -> Option<&'static str> {
@@ -215,6 +226,7 @@ mod_methods! {
     global_attributes;
     map_global_attributes;
     map_html_global_attributes_to_rsx;
+    GLOBAL_ATTRIBUTE_DOCS;
 
     #[deprecated(note = "This attribute does nothing. For most renderers, you should prefer calling [`dioxus_core::Event::prevent_default`] on the event instead. For liveview, you can use `\"onclick\": (evt) => evt.prevent_default()` to prevent the default action for this element.")]
     /// This attribute has been deprecated in favor of [`dioxus_core::Event::prevent_default`]
@@ -1745,6 +1757,7 @@ mod_methods! {
     svg_attributes;
     map_svg_attributes;
     map_html_svg_attributes_to_rsx;
+    SVG_ATTRIBUTE_DOCS;
 
     /// Prevent the default action for this element. This attribute is only recommended in the LiveView renderer
     /// which does not support the prevent default method on events.
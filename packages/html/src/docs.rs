@@ -0,0 +1,133 @@
+//! Documentation for every element and attribute, for editor tooling like `dx lsp` that needs to offer completions
+//! and hover docs without access to rustdoc.
+
+/// An element with the attributes that are specific to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElementDoc {
+    /// The name of the element as it is written in rsx
+    pub name: &'static str,
+    /// The namespace of the element, if it isn't an html element
+    pub namespace: Option<&'static str>,
+    /// The attributes that are specific to this element. Global or svg attributes are also valid
+    pub attributes: &'static [AttributeDoc],
+    pub(crate) meta: &'static [&'static str],
+}
+
+impl ElementDoc {
+    /// The doc comment of the element
+    pub fn docs(&self) -> String {
+        docs_from_meta(self.meta)
+    }
+
+    /// Is this an svg element? Svg elements accept the svg attributes instead of the global html attributes.
+    pub fn is_svg(&self) -> bool {
+        self.namespace == Some("http://www.w3.org/2000/svg")
+    }
+
+    /// All attributes that are valid on this element, including the global or svg attributes
+    pub fn all_attributes(&self) -> impl Iterator<Item = &'static AttributeDoc> {
+        let shared = match self.is_svg() {
+            true => svg_attributes(),
+            false => global_attributes(),
+        };
+        self.attributes.iter().chain(shared)
+    }
+
+    /// Find an attribute that is valid on this element by its rsx name
+    pub fn attribute(&self, name: &str) -> Option<&'static AttributeDoc> {
+        self.all_attributes().find(|attr| attr.rsx_name() == name)
+    }
+}
+
+/// An attribute of an element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttributeDoc {
+    /// The name of the attribute as it is written in rsx. Attributes that are keywords are raw identifiers like `r#type`
+    pub name: &'static str,
+    pub(crate) meta: &'static [&'static str],
+}
+
+impl AttributeDoc {
+    /// The name of the attribute without the raw identifier prefix
+    pub fn rsx_name(&self) -> &'static str {
+        self.name.trim_start_matches("r#")
+    }
+
+    /// The doc comment of the attribute
+    pub fn docs(&self) -> String {
+        docs_from_meta(self.meta)
+    }
+
+    /// Is the attribute deprecated?
+    pub fn is_deprecated(&self) -> bool {
+        self.meta.iter().any(|meta| meta.starts_with("deprecated"))
+    }
+}
+
+/// Every html, svg and mathml element
+pub fn elements() -> &'static [ElementDoc] {
+    crate::elements::ELEMENT_DOCS
+}
+
+/// Find an element by its rsx name
+pub fn element(name: &str) -> Option<&'static ElementDoc> {
+    elements().iter().find(|el| el.name == name)
+}
+
+/// The attributes that are valid on every html element
+pub fn global_attributes() -> &'static [AttributeDoc] {
+    crate::attribute_groups::GLOBAL_ATTRIBUTE_DOCS
+}
+
+/// The attributes that are valid on every svg element
+pub fn svg_attributes() -> &'static [AttributeDoc] {
+    crate::attribute_groups::SVG_ATTRIBUTE_DOCS
+}
+
+/// Join the `doc = "..."` attributes captured by the element macros back into markdown
+fn docs_from_meta(meta: &[&str]) -> String {
+    let mut docs = String::new();
+    for meta in meta {
+        let Some(literal) = meta.strip_prefix("doc").map(str::trim_start) else {
+            continue;
+        };
+        let Some(literal) = literal.strip_prefix('=').map(str::trim) else {
+            continue;
+        };
+        let Some(line) = parse_str_literal(literal) else {
+            continue;
+        };
+        // Doc comments keep the space after `///`
+        docs.push_str(line.strip_prefix(' ').unwrap_or(&line));
+        docs.push('\n');
+    }
+    docs.trim_end().to_string()
+}
+
+/// Parse a (possibly raw) string literal as it was written by `stringify!`
+fn parse_str_literal(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let inner = raw[hashes..].strip_prefix('"')?;
+        let inner = inner.strip_suffix(&raw[..hashes])?.strip_suffix('"')?;
+        return Some(inner.to_string());
+    }
+
+    let inner = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            '0' => out.push('\0'),
+            other => out.push(other),
+        }
+    }
+    Some(out)
+}
@@ -482,6 +482,19 @@ macro_rules! impl_map_global_attributes {
     };
 }
 
+#[cfg(feature = "element-docs")]
+macro_rules! element_docs_namespace {
+    (None) => {
+        None
+    };
+    ($namespace:literal) => {
+        Some($namespace)
+    };
+    ([$_:literal, $namespace:tt]) => {
+        Some($namespace)
+    };
+}
+
 macro_rules! builder_constructors {
     (
         $(
@@ -526,6 +539,25 @@ macro_rules! builder_constructors {
             }
         }
 
+        #[cfg(feature = "element-docs")]
+        pub(crate) const ELEMENT_DOCS: &[crate::docs::ElementDoc] = &[
+            $(
+                crate::docs::ElementDoc {
+                    name: stringify!($name),
+                    namespace: element_docs_namespace!($namespace),
+                    attributes: &[
+                        $(
+                            crate::docs::AttributeDoc {
+                                name: stringify!($fil),
+                                meta: &[$(stringify!($attr_method)),*],
+                            },
+                        )*
+                    ],
+                    meta: &[$(stringify!($attr)),*],
+                },
+            )*
+        ];
+
         #[cfg(feature = "html-to-rsx")]
         pub fn map_html_attribute_to_rsx(html: &str) -> Option<&'static str> {
             $(
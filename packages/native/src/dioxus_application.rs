@@ -78,6 +78,14 @@ impl DioxusNativeApplication {
                 dioxus_devtools::DevserverMsg::FullReloadStart => {}
                 dioxus_devtools::DevserverMsg::FullReloadFailed => {}
                 dioxus_devtools::DevserverMsg::FullReloadCommand => {}
+                dioxus_devtools::DevserverMsg::RequestReactiveGraph => {
+                    for window in self.inner.windows.values_mut() {
+                        let doc = window.downcast_doc_mut::<DioxusDocument>();
+                        dioxus_devtools::send(dioxus_devtools::ClientMsg::ReactiveGraph(
+                            dioxus_devtools::reactive_graph(&doc.vdom),
+                        ));
+                    }
+                }
                 _ => {}
             },
 
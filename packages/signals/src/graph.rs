@@ -0,0 +1,327 @@
+//! Debug-only introspection of the reactive graph.
+//!
+//! With the `reactive-graph` feature enabled, every [`Signal`](crate::Signal) and [`Memo`](crate::Memo) in a debug build
+//! registers itself in a registry when it is created and removes itself when it is dropped. [`ReactiveGraph::snapshot`]
+//! reads the entries of one runtime from that registry to show what each value is subscribed to, which scopes it will
+//! rerender when it is written to, and how often it has been written to.
+//!
+//! Without the feature or in release builds nothing is recorded and snapshots are always empty.
+
+use dioxus_core::{Runtime, ScopeId};
+use std::{collections::HashSet, panic::Location};
+
+#[cfg(all(debug_assertions, feature = "reactive-graph"))]
+use {
+    dioxus_core::ReactiveContext,
+    std::sync::{Arc, Mutex},
+};
+
+/// The kind of reactive value a [`ReactiveNode`] describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReactiveNodeKind {
+    /// A [`Signal`](crate::Signal) created directly or with `use_signal`.
+    Signal,
+    /// A [`Memo`](crate::Memo) created directly or with `use_memo`.
+    Memo,
+}
+
+/// A reactive context that is subscribed to a [`ReactiveNode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReactiveSubscriber {
+    /// The location the reactive context was created at.
+    pub created_at: Option<&'static Location<'static>>,
+    /// The scope that owns the reactive context.
+    pub origin_scope: ScopeId,
+    /// The scope that is rerendered when the reactive context is marked dirty if this is the reactive context of a component.
+    pub rerendered_scope: Option<ScopeId>,
+    /// The id of the memo that is recomputed when the reactive context is marked dirty if this is the reactive context of a memo.
+    pub memo: Option<u64>,
+}
+
+/// A snapshot of a signal or memo in the reactive graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReactiveNode {
+    /// A unique id for the node. Ids are never reused while the program is running.
+    pub id: u64,
+    /// The kind of value this node describes.
+    pub kind: ReactiveNodeKind,
+    /// The name of the type stored in the value.
+    pub type_name: &'static str,
+    /// The location the value was created at.
+    pub created_at: &'static Location<'static>,
+    /// The scope the value was created in.
+    pub origin_scope: ScopeId,
+    /// The number of times the value has been written to.
+    pub writes: u64,
+    /// The location of the last write to the value.
+    pub last_written_at: Option<&'static Location<'static>>,
+    /// The reactive contexts that are currently subscribed to the value.
+    pub subscribers: Vec<ReactiveSubscriber>,
+}
+
+/// A snapshot of every live signal and memo along with their subscribers.
+///
+/// ```rust, no_run
+/// # use dioxus::prelude::*;
+/// # use dioxus::core::current_scope_id;
+/// # use dioxus_signals::ReactiveGraph;
+/// fn log_rerender_sources() {
+///     let graph = ReactiveGraph::snapshot();
+///     for node in graph.sources_of(current_scope_id()) {
+///         println!("{:?} created at {} was written {} times", node.kind, node.created_at, node.writes);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReactiveGraph {
+    /// Every live signal and memo, sorted by id.
+    pub nodes: Vec<ReactiveNode>,
+}
+
+impl ReactiveGraph {
+    /// Take a snapshot of the reactive graph of the current runtime. This is empty outside of a runtime, in release
+    /// builds and without the `reactive-graph` feature.
+    pub fn snapshot() -> Self {
+        Runtime::try_current()
+            .map(|runtime| Self::snapshot_of(&runtime))
+            .unwrap_or_default()
+    }
+
+    /// Take a snapshot of the signals and memos that were created in the given runtime. This is empty in release builds
+    /// and without the `reactive-graph` feature.
+    #[allow(unused_variables)]
+    pub fn snapshot_of(runtime: &Runtime) -> Self {
+        #[cfg(all(debug_assertions, feature = "reactive-graph"))]
+        {
+            registry::snapshot(registry::runtime_key(runtime))
+        }
+        #[cfg(not(all(debug_assertions, feature = "reactive-graph")))]
+        {
+            Self::default()
+        }
+    }
+
+    /// Get the node with the given id.
+    pub fn node(&self, id: u64) -> Option<&ReactiveNode> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+            .map(|index| &self.nodes[index])
+    }
+
+    /// Get every scope that will be rerendered when the node is written to, including scopes that read the node through memos.
+    pub fn dirtied_scopes(&self, id: u64) -> Vec<ScopeId> {
+        let mut scopes = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue = vec![id];
+        while let Some(id) = queue.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            let Some(node) = self.node(id) else {
+                continue;
+            };
+            for subscriber in &node.subscribers {
+                if let Some(scope) = subscriber.rerendered_scope {
+                    if !scopes.contains(&scope) {
+                        scopes.push(scope);
+                    }
+                }
+                if let Some(memo) = subscriber.memo {
+                    queue.push(memo);
+                }
+            }
+        }
+        scopes
+    }
+
+    /// Get every node that will rerender the given scope when it is written to. This is useful for answering why a component rerendered.
+    pub fn sources_of(&self, scope: ScopeId) -> impl Iterator<Item = &ReactiveNode> + '_ {
+        self.nodes
+            .iter()
+            .filter(move |node| self.dirtied_scopes(node.id).contains(&scope))
+    }
+}
+
+/// The handle a signal keeps to its entry in the reactive graph. Dropping the handle removes the entry.
+#[cfg(all(debug_assertions, feature = "reactive-graph"))]
+pub(crate) struct GraphNode {
+    id: u64,
+    // Writes are counted on the node itself so writing to a signal never touches the shared registry
+    writes: Arc<parking_lot::Mutex<registry::Writes>>,
+}
+
+#[cfg(all(debug_assertions, feature = "reactive-graph"))]
+impl GraphNode {
+    pub(crate) fn new() -> Self {
+        Self {
+            id: registry::next_id(),
+            writes: Default::default(),
+        }
+    }
+
+    pub(crate) fn register(
+        &self,
+        type_name: &'static str,
+        created_at: &'static Location<'static>,
+        origin_scope: ScopeId,
+        subscribers: Arc<Mutex<HashSet<ReactiveContext>>>,
+    ) {
+        registry::insert(
+            self.id,
+            registry::Record {
+                runtime: Runtime::try_current().map(|runtime| registry::runtime_key(&runtime)),
+                kind: ReactiveNodeKind::Signal,
+                type_name,
+                created_at,
+                origin_scope,
+                subscribers,
+                memo_context: None,
+                writes: self.writes.clone(),
+            },
+        );
+    }
+
+    pub(crate) fn mark_as_memo(&self, context: ReactiveContext) {
+        registry::update(self.id, |record| {
+            record.kind = ReactiveNodeKind::Memo;
+            record.memo_context = Some(context);
+        });
+    }
+
+    pub(crate) fn record_write(&self, location: &'static Location<'static>) {
+        let mut writes = self.writes.lock();
+        writes.count += 1;
+        writes.last_written_at = Some(location);
+    }
+}
+
+#[cfg(all(debug_assertions, feature = "reactive-graph"))]
+impl Drop for GraphNode {
+    fn drop(&mut self) {
+        registry::remove(self.id);
+    }
+}
+
+#[cfg(all(debug_assertions, feature = "reactive-graph"))]
+mod registry {
+    use super::*;
+    use rustc_hash::FxHashMap;
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    };
+
+    #[derive(Default)]
+    pub(super) struct Writes {
+        pub(super) count: u64,
+        pub(super) last_written_at: Option<&'static Location<'static>>,
+    }
+
+    #[derive(Clone)]
+    pub(super) struct Record {
+        /// The runtime the value was created in. Values created outside of a runtime don't show up in any snapshot
+        pub(super) runtime: Option<usize>,
+        pub(super) kind: ReactiveNodeKind,
+        pub(super) type_name: &'static str,
+        pub(super) created_at: &'static Location<'static>,
+        pub(super) origin_scope: ScopeId,
+        pub(super) subscribers: Arc<Mutex<HashSet<ReactiveContext>>>,
+        pub(super) memo_context: Option<ReactiveContext>,
+        pub(super) writes: Arc<parking_lot::Mutex<Writes>>,
+    }
+
+    /// Runtimes are identified by their address. Signals are dropped with the scopes of their runtime, so the entries of
+    /// a runtime are gone before another runtime can be allocated at the same address.
+    pub(super) fn runtime_key(runtime: &Runtime) -> usize {
+        runtime as *const Runtime as usize
+    }
+
+    fn records() -> &'static Mutex<FxHashMap<u64, Record>> {
+        static RECORDS: OnceLock<Mutex<FxHashMap<u64, Record>>> = OnceLock::new();
+        RECORDS.get_or_init(Default::default)
+    }
+
+    pub(super) fn next_id() -> u64 {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub(super) fn insert(id: u64, record: Record) {
+        if let Ok(mut records) = records().lock() {
+            records.insert(id, record);
+        }
+    }
+
+    pub(super) fn update(id: u64, f: impl FnOnce(&mut Record)) {
+        if let Ok(mut records) = records().lock() {
+            if let Some(record) = records.get_mut(&id) {
+                f(record);
+            }
+        }
+    }
+
+    pub(super) fn remove(id: u64) {
+        // Take the record out before dropping it so the subscriber list is never dropped while the registry is locked
+        let record = records()
+            .lock()
+            .ok()
+            .and_then(|mut records| records.remove(&id));
+        drop(record);
+    }
+
+    pub(super) fn snapshot(runtime: usize) -> ReactiveGraph {
+        // Copy the records out of the registry first. Locking a subscriber list while holding the registry lock could
+        // deadlock with a signal that is being created or dropped while its subscribers are being notified
+        let copied: Vec<(u64, Record)> = match records().lock() {
+            Ok(records) => records
+                .iter()
+                .filter(|(_, record)| record.runtime == Some(runtime))
+                .map(|(id, record)| (*id, record.clone()))
+                .collect(),
+            Err(_) => return ReactiveGraph::default(),
+        };
+
+        // The key type is mutable, but the hash is stable through mutations because we hash by id
+        #[allow(clippy::mutable_key_type)]
+        let memos: FxHashMap<ReactiveContext, u64> = copied
+            .iter()
+            .filter_map(|(id, record)| record.memo_context.map(|context| (context, *id)))
+            .collect();
+
+        let mut nodes: Vec<_> = copied
+            .into_iter()
+            .map(|(id, record)| {
+                let subscribers = record
+                    .subscribers
+                    .lock()
+                    .map(|subscribers| {
+                        subscribers
+                            .iter()
+                            .map(|context| ReactiveSubscriber {
+                                created_at: context.created_at(),
+                                origin_scope: context.origin_scope(),
+                                rerendered_scope: context.rerendered_scope(),
+                                memo: memos.get(context).copied(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let writes = record.writes.lock();
+                ReactiveNode {
+                    id,
+                    kind: record.kind,
+                    type_name: record.type_name,
+                    created_at: record.created_at,
+                    origin_scope: record.origin_scope,
+                    writes: writes.count,
+                    last_written_at: writes.last_written_at,
+                    subscribers,
+                }
+            })
+            .collect();
+        nodes.sort_by_key(|node| node.id);
+
+        ReactiveGraph { nodes }
+    }
+}
@@ -44,3 +44,9 @@ pub mod warnings;
 
 mod boxed;
 pub use boxed::*;
+
+mod graph;
+pub use graph::*;
+
+mod sync_handle;
+pub use sync_handle::*;
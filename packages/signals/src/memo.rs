@@ -8,8 +8,8 @@ use std::{
 };
 
 use dioxus_core::{
-    current_scope_id, spawn_isomorphic, IntoAttributeValue, IntoDynNode, ReactiveContext, ScopeId,
-    Subscribers,
+    current_scope_id, in_transition, spawn_isomorphic, start_transition, IntoAttributeValue,
+    IntoDynNode, ReactiveContext, ScopeId, Subscribers,
 };
 use futures_util::StreamExt;
 use generational_box::{AnyStorage, BorrowResult, UnsyncStorage};
@@ -47,12 +47,21 @@ impl<T> Memo<T> {
         T: PartialEq + 'static,
     {
         let dirty = Arc::new(AtomicBool::new(false));
+        // If the memo was only marked dirty inside transitions, the recompute should only mark subscribers dirty in a transition too
+        let transition = Arc::new(AtomicBool::new(false));
+        let urgent = Arc::new(AtomicBool::new(false));
         let (tx, mut rx) = futures_channel::mpsc::unbounded();
 
         let callback = {
             let dirty = dirty.clone();
+            let transition = transition.clone();
+            let urgent = urgent.clone();
             move || {
                 dirty.store(true, std::sync::atomic::Ordering::Relaxed);
+                match in_transition() {
+                    true => transition.store(true, std::sync::atomic::Ordering::Relaxed),
+                    false => urgent.store(true, std::sync::atomic::Ordering::Relaxed),
+                }
                 let _ = tx.unbounded_send(());
             }
         };
@@ -67,6 +76,8 @@ impl<T> Memo<T> {
             callback: recompute,
         });
         let state: Signal<T> = Signal::new_with_caller(value, location);
+        #[cfg(all(debug_assertions, feature = "reactive-graph"))]
+        state.inner.peek().graph_node.mark_as_memo(rc);
 
         let memo = Memo {
             inner: state,
@@ -77,7 +88,13 @@ impl<T> Memo<T> {
             while rx.next().await.is_some() {
                 // Remove any pending updates
                 while rx.try_next().is_ok() {}
-                memo.recompute();
+                let transition = transition.swap(false, std::sync::atomic::Ordering::Relaxed);
+                let urgent = urgent.swap(false, std::sync::atomic::Ordering::Relaxed);
+                if transition && !urgent {
+                    start_transition(|| memo.recompute());
+                } else {
+                    memo.recompute();
+                }
             }
         });
 
@@ -2,7 +2,9 @@ use crate::{
     default_impl, fmt_impls, read::*, write::*, write_impls, CopyValue, Global, GlobalMemo,
     GlobalSignal, Memo, ReadableRef, WritableRef,
 };
-use dioxus_core::{IntoAttributeValue, IntoDynNode, ReactiveContext, ScopeId, Subscribers};
+use dioxus_core::{
+    current_scope_id, IntoAttributeValue, IntoDynNode, ReactiveContext, ScopeId, Subscribers,
+};
 use generational_box::{BorrowResult, Storage, SyncStorage, UnsyncStorage};
 use std::{collections::HashSet, ops::Deref, sync::Arc, sync::Mutex};
 
@@ -24,9 +26,36 @@ pub type SyncSignal<T> = Signal<T, SyncStorage>;
 /// The data stored for tracking in a signal.
 pub struct SignalData<T> {
     pub(crate) subscribers: Arc<Mutex<HashSet<ReactiveContext>>>,
+    #[cfg(all(debug_assertions, feature = "reactive-graph"))]
+    pub(crate) graph_node: crate::graph::GraphNode,
     pub(crate) value: T,
 }
 
+impl<T> SignalData<T> {
+    /// Create the data of a new signal and register it in the debug reactive graph
+    #[allow(unused_variables)]
+    fn new(
+        value: T,
+        caller: &'static std::panic::Location<'static>,
+        origin_scope: impl FnOnce() -> ScopeId,
+    ) -> Self {
+        let data = Self {
+            subscribers: Default::default(),
+            #[cfg(all(debug_assertions, feature = "reactive-graph"))]
+            graph_node: crate::graph::GraphNode::new(),
+            value,
+        };
+        #[cfg(all(debug_assertions, feature = "reactive-graph"))]
+        data.graph_node.register(
+            std::any::type_name::<T>(),
+            caller,
+            origin_scope(),
+            data.subscribers.clone(),
+        );
+        data
+    }
+}
+
 impl<T: 'static> Signal<T> {
     /// Creates a new [`Signal`]. Signals are a Copy state management solution with automatic dependency tracking.
     ///
@@ -147,12 +176,7 @@ impl<T, S: Storage<SignalData<T>>> Signal<T, S> {
     where
         T: 'static,
     {
-        Self {
-            inner: CopyValue::<SignalData<T>, S>::new_maybe_sync(SignalData {
-                subscribers: Default::default(),
-                value,
-            }),
-        }
+        Self::new_with_caller(value, std::panic::Location::caller())
     }
 
     /// Creates a new Signal with an explicit caller. Signals are a Copy state management solution with automatic dependency tracking.
@@ -174,10 +198,7 @@ impl<T, S: Storage<SignalData<T>>> Signal<T, S> {
     {
         Self {
             inner: CopyValue::new_with_caller(
-                SignalData {
-                    subscribers: Default::default(),
-                    value,
-                },
+                SignalData::new(value, caller, current_scope_id),
                 caller,
             ),
         }
@@ -190,10 +211,7 @@ impl<T, S: Storage<SignalData<T>>> Signal<T, S> {
     {
         Self {
             inner: CopyValue::leak_with_caller(
-                SignalData {
-                    subscribers: Default::default(),
-                    value,
-                },
+                SignalData::new(value, caller, current_scope_id),
                 caller,
             ),
         }
@@ -215,10 +233,7 @@ impl<T, S: Storage<SignalData<T>>> Signal<T, S> {
     ) -> Self {
         Self {
             inner: CopyValue::<SignalData<T>, S>::new_maybe_sync_in_scope_with_caller(
-                SignalData {
-                    subscribers: Default::default(),
-                    value,
-                },
+                SignalData::new(value, caller, || owner),
                 owner,
                 caller,
             ),
@@ -252,7 +267,7 @@ impl<T, S: Storage<SignalData<T>>> Signal<T, S> {
         self.inner.origin_scope()
     }
 
-    fn update_subscribers(&self)
+    pub(crate) fn update_subscribers(&self)
     where
         T: 'static,
     {
@@ -532,6 +547,10 @@ impl<T: 'static, S: Storage<SignalData<T>> + 'static> Drop for SignalSubscriberD
                 "Write on signal at {} finished, updating subscribers",
                 self.origin
             );
+            #[cfg(feature = "reactive-graph")]
+            if let Ok(data) = self.signal.inner.value.try_read() {
+                data.graph_node.record_write(self.origin);
+            }
         }
         self.signal.update_subscribers();
     }
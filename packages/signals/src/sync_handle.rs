@@ -0,0 +1,208 @@
+use crate::Signal;
+use dioxus_core::{provide_root_context, spawn_forever, try_consume_context};
+use futures_channel::mpsc::UnboundedSender;
+use futures_util::StreamExt;
+use generational_box::{BorrowError, BorrowMutError, GenerationalBoxId, SyncStorage};
+use rustc_hash::FxHashMap;
+use std::sync::{Arc, Mutex};
+
+impl<T: Send + Sync + 'static> Signal<T, SyncStorage> {
+    /// Create a [`SyncSignalHandle`] that can be sent to other threads to write to this signal.
+    ///
+    /// Writes through the handle update the value immediately, but subscribers are notified on the thread that owns the
+    /// [`VirtualDom`](dioxus_core::VirtualDom). All writes that happen before the runtime gets a chance to process them are batched into
+    /// a single update, so a worker that writes thousands of times per frame only causes one rerender.
+    ///
+    /// This must be called inside the dioxus runtime, typically in a hook or event handler.
+    ///
+    /// ```rust, no_run
+    /// # use dioxus::prelude::*;
+    /// fn App() -> Element {
+    ///     let progress = use_signal_sync(|| 0);
+    ///
+    ///     rsx! {
+    ///         button {
+    ///             onclick: move |_| {
+    ///                 let handle = progress.sync_handle();
+    ///                 // Run the heavy computation on a background thread and report progress back to the UI
+    ///                 std::thread::spawn(move || {
+    ///                     for i in 0..=100 {
+    ///                         std::thread::sleep(std::time::Duration::from_millis(10));
+    ///                         handle.set(i);
+    ///                     }
+    ///                 });
+    ///             },
+    ///             "Start"
+    ///         }
+    ///         "{progress}%"
+    ///     }
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn sync_handle(&self) -> SyncSignalHandle<T> {
+        SyncSignalHandle {
+            signal: *self,
+            batch: UpdateBatch::current(),
+        }
+    }
+}
+
+/// A `Send + Sync` handle to a [`SyncSignal`](crate::SyncSignal) that can be written to from any thread. Create one with [`Signal::sync_handle`].
+///
+/// Unlike writing to the signal directly, the handle never runs subscriber callbacks on the calling thread. Instead, it queues one
+/// update per signal and wakes the runtime that owns the signal, which notifies subscribers before it renders the next frame.
+pub struct SyncSignalHandle<T: 'static> {
+    signal: Signal<T, SyncStorage>,
+    batch: UpdateBatch,
+}
+
+impl<T: Send + Sync + 'static> SyncSignalHandle<T> {
+    /// Set the value of the signal.
+    ///
+    /// # Panics
+    /// This method panics if the signal has been dropped or is currently borrowed on another thread.
+    #[track_caller]
+    pub fn set(&self, value: T) {
+        self.with_mut(|v| *v = value);
+    }
+
+    /// Run a closure with a mutable reference to the value of the signal.
+    ///
+    /// The signal is locked while the closure runs, so keep the closure short to avoid blocking the UI thread.
+    ///
+    /// # Panics
+    /// This method panics if the signal has been dropped or is currently borrowed on another thread.
+    #[track_caller]
+    pub fn with_mut<O>(&self, f: impl FnOnce(&mut T) -> O) -> O {
+        self.try_with_mut(f).unwrap()
+    }
+
+    /// Try to run a closure with a mutable reference to the value of the signal. Returns an error if the signal has been dropped
+    /// or is currently borrowed.
+    #[track_caller]
+    pub fn try_with_mut<O>(&self, f: impl FnOnce(&mut T) -> O) -> Result<O, BorrowMutError> {
+        let mut data = self.signal.inner.value.try_write()?;
+        let out = f(&mut data.value);
+        #[cfg(all(debug_assertions, feature = "reactive-graph"))]
+        data.graph_node.record_write(std::panic::Location::caller());
+        drop(data);
+
+        self.batch.queue(self.signal);
+
+        Ok(out)
+    }
+
+    /// Run a closure with a reference to the current value of the signal. This does not subscribe to the signal.
+    ///
+    /// # Panics
+    /// This method panics if the signal has been dropped or is currently borrowed mutably.
+    #[track_caller]
+    pub fn with<O>(&self, f: impl FnOnce(&T) -> O) -> O {
+        self.try_with(f).unwrap()
+    }
+
+    /// Try to run a closure with a reference to the current value of the signal. Returns an error if the signal has been dropped
+    /// or is currently borrowed mutably.
+    #[track_caller]
+    pub fn try_with<O>(&self, f: impl FnOnce(&T) -> O) -> Result<O, BorrowError> {
+        let data = self.signal.inner.value.try_read()?;
+        Ok(f(&data.value))
+    }
+
+    /// Get a clone of the current value of the signal. This does not subscribe to the signal.
+    ///
+    /// # Panics
+    /// This method panics if the signal has been dropped or is currently borrowed mutably.
+    #[track_caller]
+    pub fn cloned(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Get the signal this handle writes to. The signal can only be read and written with subscriber tracking on the thread that owns the runtime.
+    pub fn signal(&self) -> Signal<T, SyncStorage> {
+        self.signal
+    }
+}
+
+impl<T> Clone for SyncSignalHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            signal: self.signal,
+            batch: self.batch.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for SyncSignalHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SyncSignalHandle")
+            .field("signal", &self.signal.inner.value)
+            .finish()
+    }
+}
+
+/// The signals that were written to from other threads since the runtime last notified subscribers.
+///
+/// One batch is shared by every handle in a runtime. It is drained by a task in the root scope, so subscribers are always
+/// notified on the thread that owns the runtime.
+#[derive(Clone)]
+struct UpdateBatch {
+    inner: Arc<UpdateBatchInner>,
+}
+
+struct UpdateBatchInner {
+    pending: Mutex<FxHashMap<GenerationalBoxId, Box<dyn FnOnce() + Send>>>,
+    wake: UnboundedSender<()>,
+}
+
+impl UpdateBatch {
+    fn current() -> Self {
+        if let Some(batch) = try_consume_context::<Self>() {
+            return batch;
+        }
+
+        let (wake, mut rx) = futures_channel::mpsc::unbounded();
+        let batch = Self {
+            inner: Arc::new(UpdateBatchInner {
+                pending: Default::default(),
+                wake,
+            }),
+        };
+
+        spawn_forever({
+            let batch = batch.clone();
+            async move {
+                while rx.next().await.is_some() {
+                    let pending = std::mem::take(&mut *batch.inner.pending.lock().unwrap());
+                    for notify in pending.into_values() {
+                        notify();
+                    }
+                }
+            }
+        });
+
+        provide_root_context(batch)
+    }
+
+    fn queue<T: Send + Sync + 'static>(&self, signal: Signal<T, SyncStorage>) {
+        let mut pending = self.inner.pending.lock().unwrap();
+        // Only the first write in a batch needs to wake the runtime
+        let wake = pending.is_empty();
+        pending.entry(signal.inner.value.id()).or_insert_with(|| {
+            Box::new(move || {
+                // The signal may have been dropped by the time the runtime processes the batch
+                if signal.inner.value.try_read().is_ok() {
+                    signal.update_subscribers();
+                }
+            })
+        });
+        drop(pending);
+
+        if wake {
+            _ = self.inner.wake.unbounded_send(());
+        }
+    }
+}
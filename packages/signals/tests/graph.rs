@@ -0,0 +1,100 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_core::{generation, NoOpMutations};
+use dioxus_signals::*;
+
+#[test]
+fn graph_tracks_subscribers_and_writes() {
+    fn app() -> Element {
+        let mut count = use_context_provider(|| Signal::new(0));
+        let doubled = use_memo(move || count() * 2);
+
+        if generation() == 0 {
+            count += 1;
+        }
+
+        rsx! {
+            Child { doubled }
+        }
+    }
+
+    #[component]
+    fn Child(doubled: Memo<i32>) -> Element {
+        rsx! { "{doubled}" }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    dom.render_immediate(&mut NoOpMutations);
+
+    dom.in_runtime(|| {
+        let graph = ReactiveGraph::snapshot();
+        let count = graph
+            .nodes
+            .iter()
+            .find(|node| node.kind == ReactiveNodeKind::Signal && node.type_name == "i32")
+            .expect("the signal should be in the graph");
+        let doubled = graph
+            .nodes
+            .iter()
+            .find(|node| node.kind == ReactiveNodeKind::Memo)
+            .expect("the memo should be in the graph");
+
+        assert_eq!(count.created_at.file(), file!());
+        assert_eq!(count.writes, 1);
+
+        // The signal is read by the memo, and the memo is read by the child component
+        assert!(count
+            .subscribers
+            .iter()
+            .any(|subscriber| subscriber.memo == Some(doubled.id)));
+        let child = doubled
+            .subscribers
+            .iter()
+            .find_map(|subscriber| subscriber.rerendered_scope)
+            .expect("the child should rerender when the memo changes");
+
+        assert!(graph.dirtied_scopes(count.id).contains(&child));
+        assert!(graph.sources_of(child).any(|node| node.id == count.id));
+    });
+
+    let runtime = dom.runtime();
+    drop(dom);
+
+    assert!(ReactiveGraph::snapshot_of(&runtime).nodes.is_empty());
+}
+
+#[test]
+fn graph_is_scoped_to_the_runtime() {
+    fn one_signal() -> Element {
+        let count = use_signal(|| 0u8);
+        rsx! { "{count}" }
+    }
+
+    fn two_signals() -> Element {
+        let count = use_signal(|| 0u16);
+        let other = use_signal(|| 0u16);
+        rsx! { "{count}{other}" }
+    }
+
+    let mut first = VirtualDom::new(one_signal);
+    first.rebuild_in_place();
+    let mut second = VirtualDom::new(two_signals);
+    second.rebuild_in_place();
+
+    let first_graph = ReactiveGraph::snapshot_of(&first.runtime());
+    let second_graph = second.in_runtime(ReactiveGraph::snapshot);
+
+    let count = |graph: &ReactiveGraph, type_name: &str| {
+        graph
+            .nodes
+            .iter()
+            .filter(|node| node.type_name == type_name)
+            .count()
+    };
+    assert_eq!(count(&first_graph, "u8"), 1);
+    assert_eq!(count(&first_graph, "u16"), 0);
+    assert_eq!(count(&second_graph, "u8"), 0);
+    assert_eq!(count(&second_graph, "u16"), 2);
+}
@@ -0,0 +1,46 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+use std::{cell::Cell, time::Duration};
+
+use dioxus::prelude::*;
+use dioxus_core::NoOpMutations;
+use dioxus_signals::*;
+
+#[tokio::test]
+async fn writes_from_other_threads_are_batched() {
+    thread_local! {
+        static RENDERS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn app() -> Element {
+        let count = use_context_provider(|| Signal::new_maybe_sync(0));
+        use_context_provider(|| count.sync_handle());
+        RENDERS.with(|renders| renders.set(renders.get() + 1));
+
+        rsx! { "{count}" }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    assert_eq!(RENDERS.with(Cell::get), 1);
+
+    let handle = dom.in_scope(ScopeId::APP, consume_context::<SyncSignalHandle<i32>>);
+    std::thread::spawn(move || {
+        for _ in 0..100 {
+            handle.with_mut(|count| *count += 1);
+        }
+    })
+    .join()
+    .unwrap();
+
+    tokio::select! {
+        _ = dom.wait_for_work() => {},
+        _ = tokio::time::sleep(Duration::from_millis(1000)) => panic!("timed out")
+    };
+    dom.render_immediate(&mut NoOpMutations);
+
+    // All hundred writes are applied in a single rerender
+    assert_eq!(RENDERS.with(Cell::get), 2);
+    let count = dom.in_scope(ScopeId::APP, consume_context::<Signal<i32, SyncStorage>>);
+    assert_eq!(*count.peek(), 100);
+}
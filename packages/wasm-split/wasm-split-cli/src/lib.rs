@@ -18,6 +18,9 @@ use wasmparser::{
     SymbolInfo,
 };
 
+mod sizes;
+pub use sizes::{FunctionSize, ModuleSizes};
+
 pub const MAKE_LOAD_JS: &str = include_str!("./__wasm_split.js");
 
 /// A parsed wasm module with additional metadata and functionality for splitting and patching.
@@ -0,0 +1,131 @@
+//! Measure the parts of a wasm module for size reports like `dx analyze`.
+
+use crate::{parse_bytes_to_data_segment, ModuleWithRelocations, Node};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use walrus::Module;
+use wasmparser::{Payload, SymbolInfo};
+
+/// The sizes of the sections, functions and data of a wasm module
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ModuleSizes {
+    /// The size of every section, with custom sections prefixed with `custom:`
+    pub sections: BTreeMap<String, u64>,
+
+    /// The size of every local function, including the length prefix of its body
+    pub functions: Vec<FunctionSize>,
+
+    /// The size of the data every symbol owns. This is only known for modules linked with `--emit-relocs`, see
+    /// [`ModuleSizes::with_data_symbols`].
+    pub data: BTreeMap<String, u64>,
+}
+
+/// The size of the body of a local function
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionSize {
+    /// The index of the function in the module
+    pub index: usize,
+
+    /// The name of the function from the name section, if the module has one
+    pub name: Option<String>,
+
+    /// The size of the body
+    pub size: u64,
+}
+
+impl ModuleSizes {
+    /// Measure the sections and functions of a module
+    pub fn new(bytes: &[u8]) -> Result<Self> {
+        let mut sections = BTreeMap::new();
+        for payload in wasmparser::Parser::new(0).parse_all(bytes) {
+            let payload = payload?;
+            let Some((id, range)) = payload.as_section() else {
+                continue;
+            };
+            let section = match &payload {
+                Payload::CustomSection(reader) => format!("custom:{}", reader.name()),
+                _ => section_name(id).to_string(),
+            };
+            *sections.entry(section).or_default() += range.len() as u64;
+        }
+
+        let module = Module::from_buffer(bytes)?;
+        let functions = module
+            .funcs
+            .iter_local()
+            .map(|(id, local)| FunctionSize {
+                index: id.index(),
+                name: module.funcs.get(id).name.clone(),
+                size: local.original_range.as_ref().map_or(0, |range| range.len()) as u64,
+            })
+            .collect();
+
+        Ok(Self {
+            sections,
+            functions,
+            data: BTreeMap::new(),
+        })
+    }
+
+    /// Attribute the data of the module to the symbols in the `linking` section of the linker output.
+    ///
+    /// Statics with a rust symbol name own their data. Anonymous data, like string literals, is attributed to the
+    /// function that references it through a relocation, or keeps its own name if nothing references it. Modules that
+    /// were not linked with `--emit-relocs` have no symbols, so their data stays unattributed.
+    pub fn with_data_symbols(mut self, linked: &[u8]) -> Result<Self> {
+        let raw = parse_bytes_to_data_segment(linked)?;
+        if raw.data_symbols.is_empty() {
+            return Ok(self);
+        }
+
+        // Without the relocations we can't tell who uses the anonymous data, so it keeps its own name
+        let module = ModuleWithRelocations::new(linked).ok();
+        for symbol in raw.data_symbols.values() {
+            let SymbolInfo::Data { name, .. } = raw.symbols[symbol.index] else {
+                continue;
+            };
+
+            let is_rust_symbol = name.starts_with("_ZN") || name.starts_with("_R");
+            let user = module
+                .as_ref()
+                .filter(|_| !is_rust_symbol)
+                .and_then(|module| {
+                    module
+                        .parents
+                        .get(&Node::DataSymbol(symbol.index))?
+                        .iter()
+                        .filter_map(|parent| match parent {
+                            Node::Function(id) => module.module.funcs.get(*id).name.clone(),
+                            Node::DataSymbol(_) => None,
+                        })
+                        .min()
+                });
+
+            *self
+                .data
+                .entry(user.unwrap_or_else(|| name.to_string()))
+                .or_default() += symbol.symbol_size as u64;
+        }
+
+        Ok(self)
+    }
+}
+
+fn section_name(id: u8) -> &'static str {
+    match id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "datacount",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
@@ -3,9 +3,10 @@
 //! This sets up a websocket connection to the devserver and handles messages from it.
 //! We also set up a little recursive timer that will attempt to reconnect if the connection is lost.
 
-use dioxus_devtools::{DevserverMsg, HotReloadMsg};
+use dioxus_devtools::{ClientMsg, DevserverMsg};
 use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 use js_sys::JsString;
+use std::cell::RefCell;
 use std::fmt::Display;
 use std::time::Duration;
 use wasm_bindgen::prelude::*;
@@ -21,7 +22,24 @@ const POLL_INTERVAL_SCALE_FACTOR: i32 = 2;
 const TOAST_TIMEOUT: Duration = Duration::from_secs(5);
 const TOAST_TIMEOUT_LONG: Duration = Duration::from_secs(3600); // Duration::MAX is too long for JS.
 
-pub(crate) fn init(config: &crate::Config) -> UnboundedReceiver<HotReloadMsg> {
+thread_local! {
+    /// The current connection to the devserver, which is replaced when we reconnect
+    static SOCKET: RefCell<Option<WebSocket>> = const { RefCell::new(None) };
+}
+
+/// Send a message to the devserver if we are connected to it
+pub(crate) fn send(msg: &ClientMsg) {
+    SOCKET.with_borrow(|socket| {
+        if let (Some(socket), Ok(text)) = (socket, serde_json::to_string(msg)) {
+            if socket.ready_state() == WebSocket::OPEN {
+                _ = socket.send_with_str(&text);
+            }
+        }
+    });
+}
+
+/// Set up the connection to the devserver. Messages that need the VirtualDom are forwarded to the main loop.
+pub(crate) fn init(config: &crate::Config) -> UnboundedReceiver<DevserverMsg> {
     // Create the tx/rx pair that we'll use for the top-level future in the dioxus loop
     let (tx, rx) = unbounded();
 
@@ -41,21 +59,26 @@ pub(crate) fn init(config: &crate::Config) -> UnboundedReceiver<HotReloadMsg> {
     rx
 }
 
-fn make_ws(tx: UnboundedSender<HotReloadMsg>, poll_interval: i32, reload: bool) {
+fn make_ws(tx: UnboundedSender<DevserverMsg>, poll_interval: i32, reload: bool) {
     // Get the location of the devserver, using the current location plus the /_dioxus path
     // The idea here being that the devserver is always located on the /_dioxus behind a proxy
+    //
+    // When multiple apps share one port, the devserver of each app is under the route of the app instead
     let location = web_sys::window().unwrap().location();
     let url = format!(
-        "{protocol}//{host}/_dioxus?build_id={build_id}",
+        "{protocol}//{host}{devtools_path}?build_id={build_id}",
         protocol = match location.protocol().unwrap() {
             prot if prot == "https:" => "wss:",
             _ => "ws:",
         },
         host = location.host().unwrap(),
+        devtools_path =
+            dioxus_cli_config::web_devtools_path().unwrap_or_else(|| "/_dioxus".to_string()),
         build_id = dioxus_cli_config::build_id(),
     );
 
     let ws = WebSocket::new(&url).unwrap();
+    SOCKET.set(Some(ws.clone()));
 
     // Set the onmessage handler to bounce messages off to the main dioxus loop
     let tx_ = tx.clone();
@@ -70,7 +93,10 @@ fn make_ws(tx: UnboundedSender<HotReloadMsg>, poll_interval: i32, reload: bool)
             let string = Box::leak(string.into_boxed_str());
 
             match serde_json::from_str::<DevserverMsg>(string) {
-                Ok(DevserverMsg::HotReload(hr)) => _ = tx_.unbounded_send(hr),
+                Ok(msg @ DevserverMsg::HotReload(_)) => _ = tx_.unbounded_send(msg),
+
+                // Snapshotting the reactive graph needs the VirtualDom, so the main loop answers this
+                Ok(msg @ DevserverMsg::RequestReactiveGraph) => _ = tx_.unbounded_send(msg),
 
                 // todo: we want to throw a screen here that shows the user that the devserver has disconnected
                 // Would be nice to do that with dioxus itself or some html/css
@@ -276,7 +302,7 @@ pub(crate) fn invalidate_browser_asset_cache() {
 /// Initialize required devtools for dioxus-playground.
 ///
 /// This listens for window message events from other Windows (such as window.top when this is running in an iframe).
-fn playground(tx: UnboundedSender<HotReloadMsg>) {
+fn playground(tx: UnboundedSender<DevserverMsg>) {
     let window = web_sys::window().expect("this code should be running in a web context");
 
     let binding = Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| {
@@ -284,10 +310,10 @@ fn playground(tx: UnboundedSender<HotReloadMsg>) {
             return;
         };
         let string: String = text.into();
-        let Ok(hr_msg) = serde_json::from_str::<HotReloadMsg>(&string) else {
+        let Ok(hr_msg) = serde_json::from_str(&string) else {
             return;
         };
-        _ = tx.unbounded_send(hr_msg);
+        _ = tx.unbounded_send(DevserverMsg::HotReload(hr_msg));
     });
 
     let callback = binding.as_ref().unchecked_ref();
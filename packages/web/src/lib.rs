@@ -78,7 +78,7 @@ pub async fn run(mut virtual_dom: VirtualDom, web_config: Config) -> ! {
             let mut timeout = gloo_timers::future::TimeoutFuture::new(100).fuse();
             futures_util::select! {
                 msg = hotreload_rx.next() => {
-                    if let Some(msg) = msg {
+                    if let Some(dioxus_devtools::DevserverMsg::HotReload(msg)) = msg {
                         if msg.for_build_id == Some(dioxus_cli_config::build_id()) {
                             dioxus_devtools::apply_changes(&virtual_dom, &msg);
                         }
@@ -217,7 +217,14 @@ pub async fn run(mut virtual_dom: VirtualDom, web_config: Config) -> ! {
         }
 
         #[cfg(all(feature = "devtools", debug_assertions))]
-        if let Some(hr_msg) = template {
+        if let Some(dioxus_devtools::DevserverMsg::RequestReactiveGraph) = template {
+            devtools::send(&dioxus_devtools::ClientMsg::ReactiveGraph(
+                dioxus_devtools::reactive_graph(&virtual_dom),
+            ));
+        }
+
+        #[cfg(all(feature = "devtools", debug_assertions))]
+        if let Some(dioxus_devtools::DevserverMsg::HotReload(hr_msg)) = template {
             // Replace all templates
             dioxus_devtools::apply_changes(&virtual_dom, &hr_msg);
 